@@ -0,0 +1,114 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tokio::fs;
+use crate::utils::{clear_folder, folder_size, sha256_bytes};
+
+/// Verified artifacts named by their sha256, shared between installs and profiles so reinstalling doesn't download them again
+#[derive(Clone, Debug)]
+pub struct ArtifactCache {
+    folder: PathBuf
+}
+
+impl ArtifactCache {
+    pub fn new(folder: PathBuf) -> Self {
+        Self {
+            folder,
+        }
+    }
+
+    fn path(&self, hash: &str) -> Option<PathBuf> {
+        // Hash comes from manifest and becomes a file name
+        if hash.is_empty() || !hash.chars().all(|x| x.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        Some(self.folder.join(hash.to_lowercase()))
+    }
+
+    /// Cached data if it still matches the hash, files that don't are removed
+    pub async fn get(&self, hash: &str) -> Option<Vec<u8>> {
+        let path = self.path(hash)?;
+        let data = fs::read(&path).await.ok()?;
+
+        if !sha256_bytes(&data).eq_ignore_ascii_case(hash) {
+            fs::remove_file(&path).await.ok();
+            return None;
+        }
+
+        // Pruning goes by modification time, so used artifacts stay around longer
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            file.set_modified(SystemTime::now()).ok();
+        }
+
+        Some(data)
+    }
+
+    /// Data has to be verified against the hash already
+    pub async fn insert(&self, hash: &str, data: &[u8]) -> io::Result<()> {
+        let Some(path) = self.path(hash) else {
+            return Ok(());
+        };
+
+        if path.exists() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.folder).await?;
+        fs::write(path, data).await
+    }
+
+    pub async fn size(&self) -> io::Result<u64> {
+        if !self.folder.exists() {
+            return Ok(0);
+        }
+
+        folder_size(self.folder.clone()).await
+    }
+
+    /// Removes least recently used artifacts until the cache fits into max size, returns how much was freed
+    pub async fn prune(&self, max_size: u64) -> io::Result<u64> {
+        if !self.folder.exists() {
+            return Ok(0);
+        }
+
+        let mut files = vec![];
+        let mut directory = fs::read_dir(&self.folder).await?;
+
+        while let Some(entry) = directory.next_entry().await? {
+            let metadata = entry.metadata().await?;
+
+            if metadata.is_file() {
+                files.push((metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), metadata.len(), entry.path()));
+            }
+        }
+
+        files.sort_by_key(|(modified, ..)| *modified);
+
+        let mut size: u64 = files.iter().map(|(_, len, _)| len).sum();
+        let mut freed = 0;
+
+        for (_, len, path) in files {
+            if size <= max_size {
+                break;
+            }
+
+            fs::remove_file(path).await?;
+            size -= len;
+            freed += len;
+        }
+
+        Ok(freed)
+    }
+
+    /// Removes everything, returns how much was freed
+    pub async fn clear(&self) -> io::Result<u64> {
+        let freed = self.size().await?;
+
+        if self.folder.exists() {
+            clear_folder(&self.folder).await?;
+        }
+
+        Ok(freed)
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::Path;
+use reqwest::{Client, RequestBuilder};
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use tokio::fs;
+use tokio::process::Command;
+use crate::config::{CloudSyncSettings, Config, SyncTarget};
+use crate::install::ModInstallOperations;
+use crate::launch::LaunchProfile;
+use crate::lockfile::{LockedMod, Lockfile};
+use crate::manager::{OperationStatus, QueueItem};
+use crate::manifest::GUID;
+
+/// Name of the file the setup is kept in, both in gists and repositories
+pub const SYNC_FILE_NAME: &str = "neos-mod-organizer.json";
+
+const GIST_API: &str = "https://api.github.com/gists";
+
+/// Part of the setup that follows the user across PCs
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SyncSnapshot {
+    #[serde(default)]
+    pub launch_profiles: Vec<LaunchProfile>,
+    #[serde(default)]
+    pub lockfile: Lockfile
+}
+
+impl SyncSnapshot {
+    /// What was last synced, nothing if it never was
+    pub async fn load_base() -> Result<SyncSnapshot, SyncError> {
+        let path = Config::cloud_sync_base_path();
+
+        if !path.exists() {
+            return Ok(SyncSnapshot::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    pub async fn save_base(&self) -> Result<(), SyncError> {
+        let path = Config::cloud_sync_base_path();
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string(self)?).await?)
+    }
+}
+
+/// Entry both sides changed since the last sync, each in their own way. None means it was removed
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncConflict {
+    Mod {
+        mod_id: GUID,
+        local: Option<LockedMod>,
+        remote: Option<LockedMod>
+    },
+    Profile {
+        name: String,
+        local: Option<LaunchProfile>,
+        remote: Option<LaunchProfile>
+    }
+}
+
+impl SyncConflict {
+    /// Puts the chosen side into the merged snapshot, merging leaves the local one there
+    pub fn resolve(&self, snapshot: &mut SyncSnapshot, keep_local: bool) {
+        match self {
+            SyncConflict::Mod { mod_id, local, remote } => {
+                match if keep_local { local } else { remote } {
+                    Some(locked) => snapshot.lockfile.mods.insert(mod_id.clone(), locked.clone()),
+                    None => snapshot.lockfile.mods.remove(mod_id)
+                };
+            }
+            SyncConflict::Profile { name, local, remote } => {
+                let chosen = if keep_local { local } else { remote };
+                let index = snapshot.launch_profiles.iter().position(|x| &x.name == name);
+
+                match (index, chosen) {
+                    (Some(index), Some(profile)) => snapshot.launch_profiles[index] = profile.clone(),
+                    (Some(index), None) => { snapshot.launch_profiles.remove(index); }
+                    (None, Some(profile)) => snapshot.launch_profiles.push(profile.clone()),
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+}
+
+/// Three-way merge of entries by key, a side that didn't change since base takes the other side's change
+fn merge_entries<V: Clone + PartialEq>(base: &BTreeMap<String, V>, local: &BTreeMap<String, V>, remote: &BTreeMap<String, V>) -> (BTreeMap<String, V>, Vec<(String, Option<V>, Option<V>)>) {
+    let mut merged = BTreeMap::new();
+    let mut conflicts = vec![];
+
+    let mut keys: Vec<&String> = base.keys().chain(local.keys()).chain(remote.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        let (base, local, remote) = (base.get(key), local.get(key), remote.get(key));
+
+        let value = match () {
+            _ if local == remote || remote == base => local,
+            _ if local == base => remote,
+            _ => {
+                conflicts.push((key.clone(), local.cloned(), remote.cloned()));
+                local
+            }
+        };
+
+        if let Some(value) = value {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+
+    (merged, conflicts)
+}
+
+fn profiles_by_name(profiles: &[LaunchProfile]) -> BTreeMap<String, LaunchProfile> {
+    profiles.iter()
+        .map(|x| (x.name.clone(), x.clone()))
+        .collect()
+}
+
+/// Changes made on both sides since base end up together, entries changed differently on both are returned as conflicts
+pub fn merge_snapshots(base: &SyncSnapshot, local: &SyncSnapshot, remote: &SyncSnapshot) -> (SyncSnapshot, Vec<SyncConflict>) {
+    let (mods, mod_conflicts) = merge_entries(&base.lockfile.mods, &local.lockfile.mods, &remote.lockfile.mods);
+
+    let (mut profiles, profile_conflicts) = merge_entries(
+        &profiles_by_name(&base.launch_profiles),
+        &profiles_by_name(&local.launch_profiles),
+        &profiles_by_name(&remote.launch_profiles)
+    );
+
+    // Local order is kept, profiles that came from the other side go after
+    let launch_profiles = local.launch_profiles.iter()
+        .chain(remote.launch_profiles.iter())
+        .filter_map(|x| profiles.remove(&x.name))
+        .collect();
+
+    let conflicts = mod_conflicts.into_iter()
+        .map(|(mod_id, local, remote)| SyncConflict::Mod { mod_id, local, remote })
+        .chain(profile_conflicts.into_iter().map(|(name, local, remote)| SyncConflict::Profile { name, local, remote }))
+        .collect();
+
+    let merged = SyncSnapshot {
+        launch_profiles,
+        lockfile: Lockfile { mods },
+    };
+
+    (merged, conflicts)
+}
+
+/// Sync that pulled changes to installed mods, base is only saved once the install was synced to them
+pub struct PendingSync {
+    pub base: SyncSnapshot,
+    operations: Vec<ModInstallOperations>,
+    /// Queue batch the operations went into, none until user applies them
+    batch: Option<u64>
+}
+
+impl PendingSync {
+    pub fn new(base: SyncSnapshot, operations: Vec<ModInstallOperations>) -> Self {
+        Self {
+            base,
+            operations,
+            batch: None,
+        }
+    }
+
+    /// Takes the batch as the one applying the sync, if it's made of the same operations
+    pub fn claim_batch(&mut self, operations: &[ModInstallOperations], batch: u64) -> bool {
+        if self.batch.is_some() || self.operations != operations {
+            return false;
+        }
+
+        self.batch = Some(batch);
+        true
+    }
+
+    /// Whether all operations of the sync went through, None while they're not applied or still going
+    pub fn outcome<'a>(&self, queue: impl IntoIterator<Item = &'a QueueItem>) -> Option<bool> {
+        let batch = self.batch?;
+        let items: Vec<&QueueItem> = queue.into_iter().filter(|x| x.batch == batch).collect();
+
+        if items.iter().any(|x| !x.status.is_finished()) {
+            return None;
+        }
+
+        Some(items.iter().all(|x| x.status == OperationStatus::Done))
+    }
+}
+
+/// Setup that's synced at the target, None if nothing was pushed there yet
+pub async fn pull_snapshot(client: &Client, settings: &CloudSyncSettings) -> Result<Option<SyncSnapshot>, SyncError> {
+    let content = match &settings.target {
+        SyncTarget::Disabled => return Ok(None),
+        SyncTarget::Gist { id } => pull_gist(client, &settings.token, id).await?,
+        SyncTarget::Git { url, branch } => pull_git(&Config::cloud_sync_repo_path(), url, branch).await?
+    };
+
+    Ok(match content {
+        Some(content) => Some(serde_json::from_str(&content)?),
+        None => None
+    })
+}
+
+/// Replaces the setup at the target, gets ID of the gist if a new one had to be made
+pub async fn push_snapshot(client: &Client, settings: &CloudSyncSettings, snapshot: &SyncSnapshot) -> Result<Option<String>, SyncError> {
+    let content = serde_json::to_string_pretty(snapshot)?;
+
+    match &settings.target {
+        SyncTarget::Disabled => Ok(None),
+        SyncTarget::Gist { id } => push_gist(client, &settings.token, id, content).await,
+        SyncTarget::Git { branch, .. } => {
+            push_git(&Config::cloud_sync_repo_path(), branch, content).await?;
+            Ok(None)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GistFile {
+    #[serde(default)]
+    content: String,
+    /// Content of big files is cut off, the rest has to be fetched from raw URL
+    #[serde(default)]
+    truncated: bool,
+    raw_url: Option<String>
+}
+
+#[derive(Deserialize)]
+struct Gist {
+    id: String,
+    #[serde(default)]
+    files: HashMap<String, GistFile>
+}
+
+fn gist_request(request: RequestBuilder, token: &str) -> RequestBuilder {
+    let request = request.header("Accept", "application/vnd.github+json");
+
+    if token.is_empty() {
+        request
+    } else {
+        request.bearer_auth(token)
+    }
+}
+
+async fn send_gist_request(request: RequestBuilder) -> Result<Gist, SyncError> {
+    let response = request.send().await?;
+
+    if !response.status().is_success() {
+        return Err(SyncError::Status(response.status().as_u16()));
+    }
+
+    Ok(serde_json::from_slice(&response.bytes().await?)?)
+}
+
+async fn pull_gist(client: &Client, token: &str, id: &str) -> Result<Option<String>, SyncError> {
+    if id.is_empty() {
+        return Ok(None);
+    }
+
+    let gist = send_gist_request(gist_request(client.get(format!("{}/{}", GIST_API, id)), token)).await?;
+
+    let Some(file) = gist.files.get(SYNC_FILE_NAME) else {
+        return Ok(None);
+    };
+
+    match (&file.raw_url, file.truncated) {
+        (Some(raw_url), true) => Ok(Some(gist_request(client.get(raw_url), token).send().await?.error_for_status()?.text().await?)),
+        _ => Ok(Some(file.content.clone()))
+    }
+}
+
+async fn push_gist(client: &Client, token: &str, id: &str, content: String) -> Result<Option<String>, SyncError> {
+    if token.is_empty() {
+        return Err(SyncError::MissingToken);
+    }
+
+    let files = json!({ SYNC_FILE_NAME: { "content": content } });
+
+    if id.is_empty() {
+        let body = json!({
+            "description": "Neos Mod Organizer setup",
+            "public": false,
+            "files": files
+        });
+
+        let gist = send_gist_request(gist_request(client.post(GIST_API), token).json(&body)).await?;
+        return Ok(Some(gist.id));
+    }
+
+    send_gist_request(gist_request(client.patch(format!("{}/{}", GIST_API, id)), token).json(&json!({ "files": files }))).await?;
+
+    Ok(None)
+}
+
+/// Runs git in the repository, output is returned only if it succeeded. Git isn't allowed to ask for credentials, nobody would see the prompt
+async fn git(repo: &Path, args: &[&str]) -> Result<String, SyncError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(SyncError::Git(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Brings the local clone to what the branch has on the remote, anything local is thrown away
+async fn pull_git(repo: &Path, url: &str, branch: &str) -> Result<Option<String>, SyncError> {
+    if !repo.join(".git").exists() {
+        fs::create_dir_all(repo).await?;
+        git(repo, &["init"]).await?;
+        git(repo, &["remote", "add", "origin", url]).await?;
+    } else {
+        git(repo, &["remote", "set-url", "origin", url]).await?;
+    }
+
+    git(repo, &["fetch", "origin"]).await?;
+
+    let remote_branch = format!("origin/{}", branch);
+
+    // Repository might be empty, or the branch wasn't pushed yet
+    if git(repo, &["rev-parse", "--verify", "--quiet", &remote_branch]).await.is_err() {
+        return Ok(None);
+    }
+
+    git(repo, &["checkout", "--force", "-B", branch, &remote_branch]).await?;
+
+    let path = repo.join(SYNC_FILE_NAME);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    Ok(Some(fs::read_to_string(path).await?))
+}
+
+/// Commits the file on top of what was last pulled and pushes it, a push that got rejected means someone synced in between
+async fn push_git(repo: &Path, branch: &str, content: String) -> Result<(), SyncError> {
+    fs::write(repo.join(SYNC_FILE_NAME), content).await?;
+
+    git(repo, &["checkout", "-B", branch]).await?;
+    git(repo, &["add", SYNC_FILE_NAME]).await?;
+
+    if !git(repo, &["status", "--porcelain"]).await?.trim().is_empty() {
+        git(repo, &["-c", "user.name=Neos Mod Organizer", "-c", "user.email=neos-mod-organizer@localhost", "commit", "-m", "Sync setup"]).await?;
+    }
+
+    git(repo, &["push", "origin", &format!("HEAD:refs/heads/{}", branch)]).await?;
+
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum SyncError {
+    NetworkError(reqwest::Error),
+    /// GitHub answered with something other than success
+    Status(u16),
+    /// Pushing to a gist needs a token
+    MissingToken,
+    /// Git failed, with what it printed
+    Git(String),
+    FileError(io::Error),
+    ParseError(serde_json::Error)
+}
+
+impl Display for SyncError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncError::NetworkError(e) => write!(f, "{}", e),
+            SyncError::Status(status) => write!(f, "GitHub answered with status {}", status),
+            SyncError::MissingToken => write!(f, "Pushing to a gist needs a GitHub token"),
+            SyncError::Git(message) => write!(f, "Git failed: {}", message),
+            SyncError::FileError(e) => write!(f, "{}", e),
+            SyncError::ParseError(e) => write!(f, "Synced setup couldn't be read: {}", e)
+        }
+    }
+}
+
+impl Error for SyncError {}
+
+impl From<reqwest::Error> for SyncError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::NetworkError(value)
+    }
+}
+
+impl From<io::Error> for SyncError {
+    fn from(value: io::Error) -> Self {
+        Self::FileError(value)
+    }
+}
+
+impl From<serde_json::Error> for SyncError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::ParseError(value)
+    }
+}
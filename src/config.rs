@@ -1,23 +1,112 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::{env, io};
-use std::path::{PathBuf};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
 use dirs::config_dir;
+use reqwest::{Certificate, Client, ClientBuilder, Proxy, Url};
 use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+use strum_macros::{Display, EnumIter};
 use tokio::task::{JoinError, spawn_blocking};
-use crate::launch::LaunchOptions;
+use crate::install::ModConflict;
+use crate::launch::{LaunchOptions, LaunchProfile};
+use crate::locale::default_language;
+use crate::manifest::{Category, GUID};
+use crate::ui::manager::ManagerTabs;
+use crate::version::Version;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Config {
+    /// Configs from before versioning existed are version 0, see CONFIG_MIGRATIONS
+    #[serde(default)]
+    pub config_version: u32,
     pub neos_exe_location: PathBuf,
     #[serde(default)]
     pub launch_options: LaunchOptions,
-    #[serde(default = "default_scan_locations")]
-    pub scan_locations: Vec<PathBuf>,
+    /// Secondary launch profiles, can be launched while main instance is running
+    #[serde(default)]
+    pub launch_profiles: Vec<LaunchProfile>,
+    /// Mods that stay enabled when direct conflicts get disabled for a launch, earlier ones win
+    #[serde(default)]
+    pub conflict_preference: Vec<GUID>,
+    /// Conflicts user chose to live with, they aren't shown as problems
+    #[serde(default)]
+    pub suppressed_conflicts: Vec<SuppressedConflict>,
+    /// Order mods get loaded in, plugins are passed to the game in this order
+    #[serde(default)]
+    pub load_order: Vec<GUID>,
+    /// Mods that are kept at the top of the mod list
+    #[serde(default)]
+    pub pinned_mods: Vec<GUID>,
+    #[serde(default)]
+    pub install_profile: InstallProfile,
     #[serde(default = "default_manifest_links")]
-    pub manifest_links: Vec<String>
+    pub manifest_links: Vec<String>,
+    /// Trust settings of manifest sources by URL, sources without an entry use SourceTrust::for_url
+    #[serde(default)]
+    pub manifest_trust: HashMap<String, SourceTrust>,
+    #[serde(default)]
+    pub window_state: WindowState,
+    /// Multiplier on top of the display's own scaling
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Configs from before the tour existed are considered to have seen it
+    #[serde(default = "default_tour_completed")]
+    pub tour_completed: bool,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub trash: TrashSettings,
+    #[serde(default)]
+    pub artifact_cache: ArtifactCacheSettings,
+    #[serde(default)]
+    pub maintenance: MaintenanceSettings,
+    #[serde(default)]
+    pub toasts: ToastSettings,
+    /// Minimizing hides the window, organizer stays reachable from the tray icon
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+    /// Show a system notification when background manifest refresh finds updates
+    #[serde(default = "default_update_notifications")]
+    pub update_notifications: bool,
+    /// Channel of mods that don't have their own in mod_update_channels
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    #[serde(default)]
+    pub mod_update_channels: HashMap<GUID, UpdateChannel>,
+    /// Updates user skipped, mod is offered updates again once something newer than the skipped version comes out
+    #[serde(default)]
+    pub skipped_updates: HashMap<GUID, Version>,
+    /// Pending updates get applied when game is launched, before it starts
+    #[serde(default)]
+    pub update_before_launch: bool,
+    #[serde(default)]
+    pub cloud_sync: CloudSyncSettings
+}
+
+/// Conflict that's ignored, only this exact one, so it shows up again if versions involved change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SuppressedConflict {
+    pub conflict: ModConflict,
+    pub reason: String
 }
 
+/// Window geometry and last opened tab, restored on startup
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct WindowState {
+    pub size: Option<(f32, f32)>,
+    pub position: Option<(f32, f32)>,
+    #[serde(default)]
+    pub last_tab: ManagerTabs
+}
+
+/// Neos keeps these folders next to Neos.exe under Steam, standalone and Proton alike,
+/// so every install type starts from the same list. Per type defaults aren't done until a layout that differs turns up
 pub fn default_scan_locations() -> Vec<PathBuf> {
     vec![
         PathBuf::from("/Libraries"),
@@ -26,6 +115,513 @@ pub fn default_scan_locations() -> Vec<PathBuf> {
     ]
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Display, EnumIter)]
+pub enum InstallType {
+    Unknown,
+    Steam,
+    Standalone,
+    Proton
+}
+
+impl InstallType {
+    pub fn detect(neos_exe_location: &Path) -> Self {
+        let path = neos_exe_location.to_string_lossy().to_lowercase();
+
+        if path.contains("steamapps") {
+            if cfg!(target_os = "windows") {
+                InstallType::Steam
+            } else {
+                InstallType::Proton
+            }
+        } else {
+            InstallType::Standalone
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScanLocation {
+    pub path: PathBuf,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool
+}
+
+impl ScanLocation {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            enabled: true,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Where artifacts go when manifest doesn't specify install_location, relative to Neos install folder
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DefaultLocations {
+    pub mods: PathBuf,
+    pub libraries: PathBuf,
+    /// Plugins are loaded by the game with -LoadAssembly instead of NML
+    #[serde(default = "default_plugins_location")]
+    pub plugins: PathBuf
+}
+
+fn default_plugins_location() -> PathBuf {
+    PathBuf::from("/Libraries")
+}
+
+impl DefaultLocations {
+    pub fn for_category(&self, category: Category) -> PathBuf {
+        match category {
+            Category::Libraries => self.libraries.clone(),
+            Category::Plugins => self.plugins.clone(),
+            _ => self.mods.clone()
+        }
+    }
+}
+
+impl Default for DefaultLocations {
+    fn default() -> Self {
+        Self {
+            mods: PathBuf::from("/nml_mods"),
+            libraries: PathBuf::from("/nml_libs"),
+            plugins: default_plugins_location(),
+        }
+    }
+}
+
+/// Locations to scan for mods, relative to Neos install folder
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InstallProfile {
+    pub install_type: InstallType,
+    pub scan_locations: Vec<ScanLocation>,
+    #[serde(default)]
+    pub default_locations: DefaultLocations
+}
+
+impl InstallProfile {
+    pub fn for_install_type(install_type: InstallType) -> Self {
+        Self {
+            install_type,
+            scan_locations: default_scan_locations().into_iter()
+                .map(ScanLocation::new)
+                .collect(),
+            default_locations: Default::default(),
+        }
+    }
+
+    pub fn detect(neos_exe_location: &Path) -> Self {
+        Self::for_install_type(InstallType::detect(neos_exe_location))
+    }
+
+    pub fn enabled_locations(&self) -> impl Iterator<Item = &PathBuf> {
+        self.scan_locations.iter()
+            .filter(|x| x.enabled)
+            .map(|x| &x.path)
+    }
+}
+
+impl Default for InstallProfile {
+    fn default() -> Self {
+        Self::for_install_type(InstallType::Unknown)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default, Display, EnumIter)]
+pub enum ProxyKind {
+    #[default]
+    #[strum(serialize = "HTTP")]
+    Http,
+    #[strum(serialize = "SOCKS5")]
+    Socks5
+}
+
+impl ProxyKind {
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            ProxyKind::Http => "http",
+            ProxyKind::Socks5 => "socks5",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ProxySettings {
+    pub enabled: bool,
+    pub kind: ProxyKind,
+    /// Host and port, like "proxy.local:8080"
+    pub address: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String
+}
+
+impl ProxySettings {
+    pub fn url(&self) -> Result<Url, ConfigError> {
+        let address = self.address.trim();
+
+        // Letting people paste full proxy URLs, scheme still comes from the kind
+        let address = address.split_once("://").map_or(address, |(_, x)| x);
+
+        let mut url = Url::parse(&format!("{}://{}", self.kind.scheme(), address))
+            .map_err(|_| ConfigError::InvalidProxy(self.address.clone()))?;
+
+        if !self.username.is_empty() {
+            url.set_username(&self.username).map_err(|_| ConfigError::InvalidProxy(self.address.clone()))?;
+            url.set_password(Some(&self.password)).map_err(|_| ConfigError::InvalidProxy(self.address.clone()))?;
+        }
+
+        Ok(url)
+    }
+}
+
+/// Which versions count as latest when looking for updates and picking versions to install
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default, EnumIter)]
+pub enum UpdateChannel {
+    /// Prereleases are only picked when nothing else fits, or the mod is on a prerelease already
+    #[default]
+    Stable,
+    /// Versions with suffixes count as latest like any other
+    Prerelease
+}
+
+impl UpdateChannel {
+    pub fn title_key(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "update_channel.stable",
+            UpdateChannel::Prerelease => "update_channel.prerelease",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct SourceTrust {
+    /// Mods from trusted sources are installed without asking
+    pub trusted: bool,
+    /// Hex encoded ed25519 key, manifest is rejected if its signature doesn't match
+    pub public_key: Option<String>
+}
+
+impl SourceTrust {
+    /// Default manifests are trusted out of the box, anything else user has to vouch for
+    pub fn for_url(url: &str) -> Self {
+        Self {
+            trusted: default_manifest_links().iter().any(|x| x == url),
+            public_key: None,
+        }
+    }
+}
+
+/// Where launch profiles and the lockfile get synced to, so the setup follows the user across PCs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CloudSyncSettings {
+    #[serde(default)]
+    pub target: SyncTarget,
+    /// GitHub token with gist scope, only needed for gists
+    #[serde(default)]
+    pub token: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum SyncTarget {
+    #[default]
+    Disabled,
+    /// GitHub Gist, a secret one is made on first push if there's no ID yet
+    Gist {
+        id: String
+    },
+    /// Any repository git can push to, git's own credentials are used
+    Git {
+        url: String,
+        branch: String
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrashSettings {
+    /// Uninstalled mods are kept in trash instead of being deleted right away
+    pub enabled: bool,
+    /// Days after which trashed mods get deleted for good
+    pub retention_days: u32
+}
+
+impl Default for TrashSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: 30,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArtifactCacheSettings {
+    /// Installed artifacts are kept, so installing them again doesn't need a download
+    pub enabled: bool,
+    /// Least recently used artifacts are removed once cache grows past this
+    pub max_size_mb: u64
+}
+
+impl ArtifactCacheSettings {
+    pub fn max_size_bytes(&self) -> u64 {
+        self.max_size_mb * 1024 * 1024
+    }
+}
+
+impl Default for ArtifactCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: 1024,
+        }
+    }
+}
+
+/// How many days things are kept in folders that clean up deletes from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MaintenanceSettings {
+    /// Downloads left over from installs that never finished
+    pub staging_retention_days: u32,
+    /// Backups that let history entries be undone
+    pub backup_retention_days: u32,
+    /// Artifacts that weren't installed again in a while
+    pub cache_retention_days: u32
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            staging_retention_days: 7,
+            backup_retention_days: 30,
+            cache_retention_days: 90,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ToastSettings {
+    /// Success toasts still end up in notification center when hidden
+    pub show_success: bool,
+    /// Seconds regular notifications stay on screen
+    pub duration: f32,
+    /// Seconds errors and other long notifications stay on screen
+    pub long_duration: f32
+}
+
+impl Default for ToastSettings {
+    fn default() -> Self {
+        Self {
+            show_success: true,
+            duration: 5.0,
+            long_duration: 30.0,
+        }
+    }
+}
+
+pub const USER_AGENT: &str = concat!("neos-mod-organizer/", env!("CARGO_PKG_VERSION"));
+
+/// Giving up on servers that don't answer at all
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Whole request including the body, artifacts can be big on slow connections
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long unused connections are kept around for reuse
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Client builder with settings every client should have
+fn base_client_builder() -> ClientBuilder {
+    Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+}
+
+/// Client without any custom network settings, until actual settings are loaded
+pub fn default_client() -> Client {
+    base_client_builder().build().unwrap_or_default()
+}
+
+/// Settings for the client used by all downloads
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct NetworkSettings {
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    /// PEM file with extra root certificates, for proxies that intercept TLS
+    #[serde(default)]
+    pub ca_bundle: Option<PathBuf>,
+    /// Download speed limit in KiB/s
+    #[serde(default)]
+    pub bandwidth_limit: Option<u32>,
+    #[serde(default)]
+    pub mirrors: Vec<MirrorRule>
+}
+
+/// Rewrites URLs starting with prefix to go through a mirror instead
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MirrorRule {
+    pub prefix: String,
+    pub replacement: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool
+}
+
+impl MirrorRule {
+    pub fn new(prefix: String, replacement: String) -> Self {
+        Self {
+            prefix,
+            replacement,
+            enabled: true,
+        }
+    }
+
+    pub fn apply(&self, url: &str) -> Option<String> {
+        if !self.enabled || self.prefix.is_empty() {
+            return None;
+        }
+
+        url.strip_prefix(&self.prefix).map(|rest| format!("{}{}", self.replacement, rest))
+    }
+}
+
+/// URLs to try in order, mirrors go first and original URL is the last resort
+pub fn mirrored_urls(mirrors: &[MirrorRule], url: &str) -> Vec<String> {
+    let mut urls: Vec<String> = vec![];
+
+    for mirrored in mirrors.iter().filter_map(|x| x.apply(url)) {
+        if !urls.contains(&mirrored) {
+            urls.push(mirrored);
+        }
+    }
+
+    if !urls.iter().any(|x| x == url) {
+        urls.push(url.to_string());
+    }
+
+    urls
+}
+
+impl NetworkSettings {
+    pub fn bandwidth_limit_bytes(&self) -> Option<u64> {
+        self.bandwidth_limit.map(|x| x as u64 * 1024)
+    }
+
+    pub fn build_client(&self) -> Result<Client, ConfigError> {
+        let mut builder = base_client_builder();
+
+        if self.proxy.enabled && !self.proxy.address.trim().is_empty() {
+            builder = builder.proxy(Proxy::all(self.proxy.url()?)?);
+        }
+
+        if let Some(path) = &self.ca_bundle {
+            let bundle = std::fs::read_to_string(path)?;
+
+            // Certificate only reads the first one from PEM, so bundles have to be split up
+            for certificate in bundle.split_inclusive("-----END CERTIFICATE-----").filter(|x| x.contains("-----BEGIN CERTIFICATE-----")) {
+                builder = builder.add_root_certificate(Certificate::from_pem(certificate.trim().as_bytes())?);
+            }
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// Marker file next to the exe that turns on portable mode, same as running with --portable
+pub const PORTABLE_MARKER: &str = "portable.txt";
+
+fn portable_data_folder() -> Option<PathBuf> {
+    let exe_folder = env::current_exe().ok()?.parent()?.to_path_buf();
+
+    let flag = env::args().skip(1).any(|x| x == "--portable");
+
+    if flag || exe_folder.join(PORTABLE_MARKER).exists() {
+        Some(exe_folder.join("data"))
+    } else {
+        None
+    }
+}
+
+/// Version of config layout, bumped along with a new step in CONFIG_MIGRATIONS
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Steps that upgrade config JSON, step at index N turns version N into N + 1
+const CONFIG_MIGRATIONS: [fn(&mut Value); CONFIG_VERSION as usize] = [
+    migrate_v0_scan_locations
+];
+
+/// Runs migrations config needs, returns version it was migrated from, or None if it's up to date.
+/// Configs from newer versions are left alone, fields this version doesn't know about are ignored
+pub fn migrate_config_json(config: &mut Value) -> Option<u32> {
+    let version = config.get("config_version")
+        .and_then(|x| x.as_u64())
+        .unwrap_or(0) as u32;
+
+    if version >= CONFIG_VERSION {
+        return None;
+    }
+
+    for step in &CONFIG_MIGRATIONS[version as usize..] {
+        step(config);
+    }
+
+    if let Some(config) = config.as_object_mut() {
+        config.insert("config_version".to_string(), Value::from(CONFIG_VERSION));
+    }
+
+    Some(version)
+}
+
+/// Scan locations used to be a plain list of paths before install profiles existed
+fn migrate_v0_scan_locations(config: &mut Value) {
+    let Some(config) = config.as_object_mut() else {
+        return;
+    };
+
+    let Some(Value::Array(locations)) = config.remove("scan_locations") else {
+        return;
+    };
+
+    let locations: Vec<Value> = locations.into_iter()
+        .map(|path| json!({ "path": path, "enabled": true }))
+        .collect();
+
+    let profile = config.entry("install_profile")
+        .or_insert_with(|| json!({ "install_type": "Unknown" }));
+
+    if let Some(profile) = profile.as_object_mut() {
+        profile.insert("scan_locations".to_string(), Value::Array(locations));
+    }
+}
+
+pub fn default_tour_completed() -> bool {
+    true
+}
+
+pub fn default_ui_scale() -> f32 {
+    1.0
+}
+
+/// Range UI scale is kept within, anything outside of it makes the window unusable
+pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.75..=3.0;
+
+/// Brings UI scale from hand edited config back into range
+pub fn clamp_ui_scale(scale: f32) -> f32 {
+    if scale.is_finite() {
+        scale.clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end())
+    } else {
+        default_ui_scale()
+    }
+}
+
+pub fn default_update_notifications() -> bool {
+    true
+}
+
 pub fn default_manifest_links() -> Vec<String> {
     vec![
         format!("https://raw.githubusercontent.com/neos-modding-group/neos-mod-manifest/master/manifest.json")
@@ -33,16 +629,263 @@ pub fn default_manifest_links() -> Vec<String> {
 }
 
 impl Config {
-    pub fn config_path() -> PathBuf {
-        let mut dir = config_dir().map(|mut d| {
-            d.push("neos-mod-organizer"); d
-        }).unwrap_or_else(|| env::current_dir().expect("where tf am i?"));
+    pub fn source_trust(&self, url: &str) -> SourceTrust {
+        self.manifest_trust.get(url).cloned().unwrap_or_else(|| SourceTrust::for_url(url))
+    }
 
-        dir.push("config.json");
+    /// Which of two conflicting mods gets disabled, mods in preference list beat ones that aren't, second one loses otherwise
+    pub fn conflict_loser<'a>(&self, first: &'a GUID, second: &'a GUID) -> &'a GUID {
+        let rank = |guid: &GUID| self.conflict_preference.iter().position(|x| x == guid).unwrap_or(usize::MAX);
+
+        if rank(second) < rank(first) {
+            first
+        } else {
+            second
+        }
+    }
 
+    pub fn suppression_of(&self, conflict: &ModConflict) -> Option<&SuppressedConflict> {
+        self.suppressed_conflicts.iter().find(|x| x.conflict == *conflict)
+    }
+
+    pub fn is_conflict_suppressed(&self, conflict: &ModConflict) -> bool {
+        self.suppression_of(conflict).is_some()
+    }
+
+    /// Sorts mods by load order, mods that aren't in it go last sorted by GUID
+    pub fn sort_by_load_order(&self, mods: &mut [GUID]) {
+        mods.sort_by_cached_key(|guid| (self.load_order.iter().position(|x| x == guid).unwrap_or(usize::MAX), guid.clone()));
+    }
+
+    /// Source is trusted if user said so or its signature was checked, plain HTTP is never trusted
+    pub fn is_source_trusted(&self, url: &str) -> bool {
+        let trust = self.source_trust(url);
+        url.starts_with("https://") && (trust.trusted || trust.public_key.is_some())
+    }
+
+    /// Channel the mod follows, its own if it was set or the global one
+    pub fn update_channel_for(&self, guid: &str) -> UpdateChannel {
+        self.mod_update_channels.get(guid).copied().unwrap_or(self.update_channel)
+    }
+
+    /// Version was skipped, or something newer than it was
+    pub fn is_update_skipped(&self, guid: &str, version: &Version) -> bool {
+        self.skipped_updates.get(guid).map_or(false, |x| version <= x)
+    }
+
+    pub fn is_pinned(&self, guid: &str) -> bool {
+        self.pinned_mods.iter().any(|x| x == guid)
+    }
+
+    /// Pins the mod, or unpins it if it already was
+    pub fn toggle_pinned(&mut self, guid: &str) {
+        if self.is_pinned(guid) {
+            self.pinned_mods.retain(|x| x != guid);
+        } else {
+            self.pinned_mods.push(guid.to_string());
+        }
+    }
+
+    pub fn new(neos_exe_location: PathBuf) -> Self {
+        Self {
+            config_version: CONFIG_VERSION,
+            install_profile: InstallProfile::detect(&neos_exe_location),
+            neos_exe_location,
+            launch_options: Default::default(),
+            launch_profiles: vec![],
+            conflict_preference: vec![],
+            suppressed_conflicts: vec![],
+            load_order: vec![],
+            pinned_mods: vec![],
+            manifest_links: default_manifest_links(),
+            manifest_trust: Default::default(),
+            window_state: Default::default(),
+            ui_scale: default_ui_scale(),
+            language: default_language(),
+            tour_completed: false,
+            network: Default::default(),
+            trash: Default::default(),
+            artifact_cache: Default::default(),
+            maintenance: Default::default(),
+            toasts: Default::default(),
+            minimize_to_tray: false,
+            update_notifications: default_update_notifications(),
+            update_channel: Default::default(),
+            mod_update_channels: Default::default(),
+            skipped_updates: Default::default(),
+            update_before_launch: false,
+            cloud_sync: Default::default(),
+        }
+    }
+
+    /// Fills in things that are detected instead of being stored
+    fn detect_missing(&mut self) {
+        if self.install_profile.install_type == InstallType::Unknown {
+            self.install_profile.install_type = InstallType::detect(&self.neos_exe_location);
+        }
+    }
+
+    /// Parses config, migrating it first if it was written by an older version, also returns version it was migrated from
+    fn parse_config(str: &str) -> Result<(Config, Option<u32>), ConfigError> {
+        let mut value: Value = serde_json::from_str(str)?;
+        let migrated_from = migrate_config_json(&mut value);
+
+        let mut config: Config = serde_json::from_value(value)?;
+        config.detect_missing();
+        config.ui_scale = clamp_ui_scale(config.ui_scale);
+
+        Ok((config, migrated_from))
+    }
+
+    /// Where config of provided version is copied before it gets migrated
+    pub fn config_backup_path(version: u32) -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name(format!("config.v{}.json.bak", version));
+        path
+    }
+
+    /// Folder everything organizer writes goes into and whether it's portable mode, decided once on startup
+    fn data_location() -> &'static (PathBuf, bool) {
+        static DATA_LOCATION: OnceLock<(PathBuf, bool)> = OnceLock::new();
+
+        DATA_LOCATION.get_or_init(|| {
+            // Tests keep away from the user's real data
+            if cfg!(test) {
+                return (env::temp_dir().join("nmo_test_data"), true);
+            }
+
+            if let Some(folder) = portable_data_folder() {
+                return (folder, true);
+            }
+
+            let folder = config_dir().map(|mut d| {
+                d.push("neos-mod-organizer"); d
+            }).unwrap_or_else(|| env::current_dir().expect("where tf am i?"));
+
+            (folder, false)
+        })
+    }
+
+    /// In portable mode, that's "data" folder next to the exe
+    pub fn data_folder() -> PathBuf {
+        Self::data_location().0.clone()
+    }
+
+    pub fn is_portable() -> bool {
+        Self::data_location().1
+    }
+
+    pub fn config_path() -> PathBuf {
+        let mut dir = Self::data_folder();
+        dir.push("config.json");
         dir
     }
 
+    pub fn log_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("organizer.log");
+        path
+    }
+
+    pub fn mod_map_cache_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("mod_map.json");
+        path
+    }
+
+    pub fn readme_cache_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("readmes.json");
+        path
+    }
+
+    /// When each manifest version was first seen, for the What's new feed
+    pub fn known_versions_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("known_versions.json");
+        path
+    }
+
+    pub fn history_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("history.json");
+        path
+    }
+
+    /// Folder where files of uninstalled mods are kept, so uninstalls can be undone
+    pub fn backups_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("backups");
+        path
+    }
+
+    pub fn trash_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("trash");
+        path
+    }
+
+    /// Artifacts kept after installing, named by their hash
+    pub fn artifact_cache_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("artifact_cache");
+        path
+    }
+
+    /// Names and versions of files that aren't in any manifest
+    pub fn local_mods_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("local_mods.json");
+        path
+    }
+
+    /// Notes user wrote about installed mods
+    pub fn notes_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("notes.json");
+        path
+    }
+
+    /// Operations that were still queued when the organizer closed
+    pub fn queue_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("queue.json");
+        path
+    }
+
+    /// Exact versions of installed mods, written after the organizer changes the install
+    pub fn lockfile_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("mods.lock.json");
+        path
+    }
+
+    /// What was last synced to the cloud, changes are told apart from it when merging
+    pub fn cloud_sync_base_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("cloud_sync_base.json");
+        path
+    }
+
+    /// Clone of the sync repository when syncing with git
+    pub fn cloud_sync_repo_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("cloud_sync_repo");
+        path
+    }
+
+    /// Folder where downloaded artifacts wait until they're written, named by their hash
+    pub fn staging_path() -> PathBuf {
+        let mut path = Self::config_path();
+        path.set_file_name("staging");
+        path
+    }
+
+    /// When config file was last written, None if it can't be read
+    pub async fn config_modified_time() -> Option<SystemTime> {
+        tokio::fs::metadata(Self::config_path()).await.ok()?.modified().ok()
+    }
+
     pub fn config_exists(path: &PathBuf) -> bool {
         path.try_exists().expect("Can't access config")
     }
@@ -56,7 +899,14 @@ impl Config {
 
         let str = std::fs::read_to_string(path)?;
 
-        Ok(serde_json::from_str(&str)?)
+        let (config, migrated_from) = Self::parse_config(&str)?;
+
+        if let Some(version) = migrated_from {
+            std::fs::write(Self::config_backup_path(version), &str)?;
+            config.save_config_sync()?;
+        }
+
+        Ok(config)
     }
 
     pub async fn load_config() -> Result<Config, ConfigError> {
@@ -68,7 +918,16 @@ impl Config {
 
         let str = tokio::fs::read_to_string(path).await?;
 
-        Ok(spawn_blocking(move || serde_json::from_str(&str)).await??)
+        let (config, migrated_from, str) = spawn_blocking(move || {
+            Self::parse_config(&str).map(|(config, migrated_from)| (config, migrated_from, str))
+        }).await??;
+
+        if let Some(version) = migrated_from {
+            tokio::fs::write(Self::config_backup_path(version), &str).await?;
+            config.save_config().await?;
+        }
+
+        Ok(config)
     }
 
     pub fn save_config_sync(&self) -> Result<(), ConfigError> {
@@ -95,7 +954,9 @@ pub enum ConfigError {
     MissingConfig,
     IOError(io::Error),
     JSONError(serde_json::Error),
-    JoinError(JoinError)
+    JoinError(JoinError),
+    InvalidProxy(String),
+    NetworkError(reqwest::Error)
 }
 
 impl Display for ConfigError {
@@ -118,6 +979,12 @@ impl From<serde_json::Error> for ConfigError {
     }
 }
 
+impl From<reqwest::Error> for ConfigError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::NetworkError(value)
+    }
+}
+
 impl From<JoinError> for ConfigError {
     fn from(value: JoinError) -> Self {
         Self::JoinError(value)
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use crate::config::Config;
+use crate::install::InstallError;
+use crate::manifest::{GUID, Mod};
+use crate::version::Version;
+
+/// How many releases the feed shows at most
+pub const FEED_LENGTH: usize = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedEntry {
+    pub mod_id: GUID,
+    pub version: Version,
+    /// Seconds since unix epoch
+    pub first_seen: u64
+}
+
+/// When each version was first seen in manifests, since manifests don't say when versions were released
+#[derive(Serialize, Deserialize, Default)]
+pub struct KnownVersions {
+    pub first_seen: HashMap<GUID, HashMap<Version, u64>>
+}
+
+impl KnownVersions {
+    pub async fn load() -> Result<KnownVersions, InstallError> {
+        let path = Config::known_versions_path();
+
+        if !path.exists() {
+            return Ok(KnownVersions::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    pub async fn save(&self) -> Result<(), InstallError> {
+        let path = Config::known_versions_path();
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string(self)?).await?)
+    }
+
+    /// Remembers versions that weren't seen before, returns whether anything was added.
+    /// Versions that were already out when tracking started get 0, so only the newest version of a new mod shows up in the feed
+    pub fn record(&mut self, mod_list: &HashMap<GUID, Mod>, now: u64) -> bool {
+        let first_run = self.first_seen.is_empty();
+        let mut changed = false;
+
+        for (mod_id, mod_info) in mod_list {
+            let new_mod = !self.first_seen.contains_key(mod_id);
+            let latest = mod_info.versions.keys().max();
+            let known = self.first_seen.entry(mod_id.clone()).or_default();
+
+            for version in mod_info.versions.keys() {
+                if known.contains_key(version) {
+                    continue;
+                }
+
+                let timestamp = if first_run || (new_mod && Some(version) != latest) { 0 } else { now };
+
+                known.insert(version.clone(), timestamp);
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    /// Newest releases first, versions from before tracking started are left out
+    pub fn recent_releases(&self, limit: usize) -> Vec<FeedEntry> {
+        let mut entries: Vec<FeedEntry> = self.first_seen.iter()
+            .flat_map(|(mod_id, versions)| versions.iter().map(move |(version, first_seen)| FeedEntry {
+                mod_id: mod_id.clone(),
+                version: version.clone(),
+                first_seen: *first_seen,
+            }))
+            .filter(|x| x.first_seen > 0)
+            .collect();
+
+        entries.sort_by(|a, b| b.first_seen.cmp(&a.first_seen)
+            .then_with(|| a.mod_id.cmp(&b.mod_id))
+            .then_with(|| b.version.cmp(&a.version)));
+
+        entries.truncate(limit);
+
+        entries
+    }
+}
@@ -0,0 +1,212 @@
+use crate::config::{Config, UpdateChannel};
+use crate::install::{IDVersion, ModConflict, ModInstall, ModInstallOperations, VirtualInstall};
+use crate::manifest::{GUID, ManifestMods};
+use crate::resolver::{resolve_install_mod, ResolveResult};
+use crate::version::{Version, VersionReq};
+
+/// What a fix does, mostly for showing it to the user
+#[derive(Clone, Debug, PartialEq)]
+pub enum FixKind {
+    /// Installed mod is replaced with a newer version
+    Update(GUID, Version),
+    /// Installed mod is replaced with an older version
+    Downgrade(GUID, Version),
+    /// Mod that isn't installed gets installed
+    Install(GUID, Version),
+    Uninstall(IDVersion),
+    /// Mod is installed again, so files it's missing come back
+    Reinstall(IDVersion),
+    /// Other installed versions of the mod are uninstalled
+    KeepOnly(IDVersion)
+}
+
+/// Change that was checked to clear the conflict without bringing new ones that are just as bad
+#[derive(Clone, Debug)]
+pub struct ConflictFix {
+    pub kind: FixKind,
+    pub operations: Vec<ModInstallOperations>
+}
+
+/// Fixes for the conflict, changing versions goes before uninstalling anything
+pub async fn suggest_fixes(install: &VirtualInstall, conflict: &ModConflict, mod_list: &ManifestMods, config: &Config) -> Vec<ConflictFix> {
+    let current_conflicts = install.check_for_conflicts(mod_list);
+    let mut fixes: Vec<ConflictFix> = vec![];
+
+    let clears = |fix: ConflictFix| {
+        let mut install = install.clone();
+        let current_conflicts = &current_conflicts;
+
+        async move {
+            install.perform_operations(&fix.operations).await.ok()?;
+
+            let conflicts = install.check_for_conflicts(mod_list);
+
+            let breaks_something = conflicts.iter()
+                .any(|x| x.severity() >= conflict.severity() && !current_conflicts.contains(x) && !config.is_conflict_suppressed(x));
+
+            (!conflicts.contains(conflict) && !breaks_something).then_some(fix)
+        }
+    };
+
+    for candidates in candidate_fixes(install, conflict, mod_list, config) {
+        // Candidates in a group are ordered from the best one, only the first one that works is suggested
+        for candidate in candidates {
+            if fixes.iter().any(|x| x.kind == candidate.kind) {
+                break;
+            }
+
+            if let Some(fix) = clears(candidate).await {
+                fixes.push(fix);
+                break;
+            }
+        }
+    }
+
+    fixes
+}
+
+/// Candidates of a group, made one at a time as they're checked since each of them takes resolving
+type Candidates<'a> = Box<dyn Iterator<Item = ConflictFix> + Send + 'a>;
+
+fn single<'a>(fix: ConflictFix) -> Candidates<'a> {
+    Box::new(std::iter::once(fix))
+}
+
+/// Groups of fixes that might work for the conflict, they're checked later
+fn candidate_fixes<'a>(install: &'a VirtualInstall, conflict: &'a ModConflict, mod_list: &'a ManifestMods, config: &'a Config) -> Vec<Candidates<'a>> {
+    let map = install.mod_map();
+    let uninstall = |id: &IDVersion| single(ConflictFix {
+        kind: FixKind::Uninstall(id.clone()),
+        operations: vec![ModInstallOperations::UninstallMod(id.clone())],
+    });
+
+    match conflict {
+        ModConflict::VersionConflict(guid) => {
+            let versions = map.installed_versions(guid);
+
+            versions.iter().rev()
+                .map(|keep| single(ConflictFix {
+                    kind: FixKind::KeepOnly((guid.clone(), (*keep).clone())),
+                    operations: versions.iter()
+                        .filter(|x| *x != keep)
+                        .map(|x| ModInstallOperations::UninstallMod((guid.clone(), (*x).clone())))
+                        .collect(),
+                }))
+                .collect()
+        }
+
+        ModConflict::DirectConflict { this, conflict_with } => {
+            let mut groups = version_changes(&this.0, install, mod_list, config);
+            groups.extend(version_changes(&conflict_with.0, install, mod_list, config));
+            groups.push(uninstall(this));
+            groups.push(uninstall(conflict_with));
+            groups
+        }
+
+        ModConflict::DependencyMissing { this, needs: (dependency_id, requirement) } => {
+            let install_dependency = std::iter::once_with(move || {
+                let ResolveResult::Ok(operations) = resolve_install_mod(dependency_id, requirement, map, mod_list, |x| config.update_channel_for(x)) else {
+                    return None;
+                };
+
+                let version = operations.iter().find_map(|x| match x {
+                    ModInstallOperations::InstallMod((id, version)) if id == dependency_id => Some(version.clone()),
+                    _ => None
+                })?;
+
+                Some(ConflictFix {
+                    kind: FixKind::Install(dependency_id.clone(), version),
+                    operations,
+                })
+            }).flatten();
+
+            let mut groups: Vec<Candidates> = vec![Box::new(install_dependency)];
+            groups.extend(version_changes(&this.0, install, mod_list, config));
+            groups.push(uninstall(this));
+            groups
+        }
+
+        ModConflict::DependencyMismatch { this, needs: (dependency_id, _), .. } => {
+            let mut groups = version_changes(dependency_id, install, mod_list, config);
+            groups.extend(version_changes(&this.0, install, mod_list, config));
+            groups.push(uninstall(this));
+            groups
+        }
+
+        ModConflict::IncompleteInstall { this, .. } => {
+            vec![single(ConflictFix {
+                kind: FixKind::Reinstall(this.clone()),
+                operations: vec![
+                    ModInstallOperations::UninstallMod(this.clone()),
+                    ModInstallOperations::InstallMod(this.clone())
+                ],
+            })]
+        }
+
+        ModConflict::FileConflict { this, already_exists } => {
+            let mut groups = version_changes(&this.0, install, mod_list, config);
+            groups.push(uninstall(this));
+
+            // Whoever else has a file there
+            for (mod_id, version, file) in map.iter_versions() {
+                if *mod_id != this.0 && file.files.iter().any(|x| x.file_path.ends_with(already_exists)) {
+                    groups.push(uninstall(&(mod_id.clone(), version.clone())));
+                }
+            }
+
+            groups
+        }
+
+        ModConflict::IncompatibleRequirements { requirements, .. } => {
+            let mut groups = vec![];
+
+            for (dependent, _) in requirements {
+                groups.extend(version_changes(&dependent.0, install, mod_list, config));
+            }
+
+            for (dependent, _) in requirements {
+                groups.push(uninstall(dependent));
+            }
+
+            groups
+        }
+    }
+}
+
+/// Other versions of an installed mod from the manifest, newest updates first and then closest downgrades first
+fn version_changes<'a>(mod_id: &'a str, install: &'a VirtualInstall, mod_list: &'a ManifestMods, config: &'a Config) -> Vec<Candidates<'a>> {
+    let map = install.mod_map();
+    let (Some(installed), Some(mod_info)) = (map.installed_version(mod_id), mod_list.get(mod_id)) else {
+        return vec![];
+    };
+
+    let channel = config.update_channel_for(mod_id);
+    let mut versions: Vec<&Version> = mod_info.versions.keys()
+        .filter(|x| *x != installed)
+        .filter(|x| channel == UpdateChannel::Prerelease || !x.is_prerelease() || installed.is_prerelease())
+        .collect();
+
+    versions.sort_by(|a, b| b.cmp(a));
+
+    let change_to = move |version: &Version| {
+        let ResolveResult::Ok(operations) = resolve_install_mod(mod_id, &VersionReq::exact(version), map, mod_list, |x| config.update_channel_for(x)) else {
+            return None;
+        };
+
+        let kind = if version > installed {
+            FixKind::Update(mod_id.to_string(), version.clone())
+        } else {
+            FixKind::Downgrade(mod_id.to_string(), version.clone())
+        };
+
+        Some(ConflictFix { kind, operations })
+    };
+
+    let (updates, downgrades): (Vec<&Version>, Vec<&Version>) = versions.into_iter()
+        .partition(|x| *x > installed);
+
+    vec![
+        Box::new(updates.into_iter().filter_map(change_to)),
+        Box::new(downgrades.into_iter().filter_map(change_to))
+    ]
+}
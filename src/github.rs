@@ -0,0 +1,88 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::Deserialize;
+use crate::transport::{Transport, TransportError};
+use crate::version::Version;
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct GitHubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct GitHubRelease {
+    pub tag_name: String,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub assets: Vec<GitHubAsset>
+}
+
+impl GitHubRelease {
+    /// Assets that can be installed as mods
+    pub fn dll_assets(&self) -> impl Iterator<Item = &GitHubAsset> {
+        self.assets.iter().filter(|x| x.name.to_lowercase().ends_with(".dll"))
+    }
+}
+
+/// Version the tag stands for, tags usually have "v" in front of it
+pub fn version_from_tag(tag: &str) -> Option<Version> {
+    Version::from_str(tag.trim().trim_start_matches(['v', 'V'])).ok()
+}
+
+/// Owner and name of the repository from its link, anything after them is ignored
+pub fn parse_repo_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let path = ["https://github.com/", "http://github.com/", "github.com/", "https://www.github.com/"].iter()
+        .find_map(|prefix| url.strip_prefix(prefix))?;
+
+    let mut parts = path.split(['/', '?', '#']).filter(|x| !x.is_empty());
+    let owner = parts.next()?;
+    let repo = parts.next()?.trim_end_matches(".git");
+
+    let valid = |x: &str| !x.is_empty() && x.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) && x != "." && x != "..";
+
+    (valid(owner) && valid(repo)).then(|| (owner.to_string(), repo.to_string()))
+}
+
+/// Releases of the repository, newest first as GitHub lists them
+pub async fn fetch_releases(transport: &dyn Transport, owner: &str, repo: &str) -> Result<Vec<GitHubRelease>, GitHubError> {
+    let url = format!("https://api.github.com/repos/{}/{}/releases", owner, repo);
+    let body = transport.get(&url, &mut None).await?;
+
+    Ok(serde_json::from_slice(&body)?)
+}
+
+#[derive(Debug)]
+pub enum GitHubError {
+    NetworkError(TransportError),
+    ParseError(serde_json::Error)
+}
+
+impl Display for GitHubError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitHubError::NetworkError(e) => write!(f, "{}", e),
+            GitHubError::ParseError(e) => write!(f, "Unexpected answer from GitHub: {}", e)
+        }
+    }
+}
+
+impl Error for GitHubError {}
+
+impl From<TransportError> for GitHubError {
+    fn from(value: TransportError) -> Self {
+        Self::NetworkError(value)
+    }
+}
+
+impl From<serde_json::Error> for GitHubError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::ParseError(value)
+    }
+}
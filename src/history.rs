@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use crate::config::Config;
+use crate::install::{InstallError, ModInstallOperations};
+
+/// How many entries are kept in history file, backups of older ones get removed
+const MAX_HISTORY_ENTRIES: usize = 200;
+
+/// Only this many of the latest entries can be undone
+pub const UNDOABLE_ENTRIES: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryFile {
+    pub path: PathBuf,
+    /// Copy of the file made before it was removed
+    pub backup: Option<PathBuf>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    pub id: u64,
+    /// Seconds since unix epoch
+    pub timestamp: u64,
+    pub operation: ModInstallOperations,
+    pub files: Vec<HistoryFile>,
+    #[serde(default)]
+    pub undone: bool
+}
+
+impl HistoryEntry {
+    /// Checks if files needed to undo the operation are still around
+    pub fn is_recoverable(&self) -> bool {
+        if self.undone {
+            return false;
+        }
+
+        match self.operation {
+            ModInstallOperations::UninstallMod(_) => self.files.iter()
+                .all(|x| !x.path.exists() && x.backup.as_ref().map_or(false, |x| x.exists())),
+            _ => self.files.iter().all(|x| x.path.exists())
+        }
+    }
+
+    /// Where backups of this entry's files go
+    pub fn backup_folder(&self) -> PathBuf {
+        let mut path = Config::backups_path();
+        path.push(self.id.to_string());
+        path
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>
+}
+
+impl History {
+    pub async fn load() -> Result<History, InstallError> {
+        let path = Config::history_path();
+
+        if !path.exists() {
+            return Ok(History::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    pub async fn save(&self) -> Result<(), InstallError> {
+        let path = Config::history_path();
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string(self)?).await?)
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.entries.last().map_or(1, |x| x.id + 1)
+    }
+
+    /// Makes an entry with the next free id, doesn't add it yet
+    pub fn new_entry(&self, operation: ModInstallOperations) -> HistoryEntry {
+        HistoryEntry {
+            id: self.next_id(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs()),
+            operation,
+            files: vec![],
+            undone: false,
+        }
+    }
+
+    pub async fn push(&mut self, entry: HistoryEntry) {
+        self.entries.push(entry);
+
+        while self.entries.len() > MAX_HISTORY_ENTRIES {
+            let removed = self.entries.remove(0);
+            fs::remove_dir_all(removed.backup_folder()).await.ok();
+        }
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut HistoryEntry> {
+        self.entries.iter_mut().find(|x| x.id == id)
+    }
+
+    /// Only the latest entries can be undone
+    pub fn is_undoable(&self, id: u64) -> bool {
+        self.entries.iter()
+            .rev()
+            .take(UNDOABLE_ENTRIES)
+            .any(|x| x.id == id && x.is_recoverable())
+    }
+}
+
+/// Renames the file, falls back to copying when it's on another drive
+pub async fn move_file(from: &Path, to: &Path) -> Result<(), InstallError> {
+    if let Some(folder) = to.parent() {
+        fs::create_dir_all(folder).await?;
+    }
+
+    if fs::rename(from, to).await.is_err() {
+        fs::copy(from, to).await?;
+        fs::remove_file(from).await?;
+    }
+
+    Ok(())
+}
+
+/// Puts backed up files back where they were
+pub async fn restore_backup(entry: &HistoryEntry) -> Result<(), InstallError> {
+    for file in &entry.files {
+        let Some(backup) = &file.backup else {
+            return Err(InstallError::FileNotFound);
+        };
+
+        move_file(backup, &file.path).await?;
+    }
+
+    fs::remove_dir_all(entry.backup_folder()).await.ok();
+
+    Ok(())
+}
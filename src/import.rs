@@ -0,0 +1,319 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use regex::Regex;
+use crate::install::is_disabled_path;
+use crate::launch::LaunchOptions;
+
+/// Steam app id of NeosVR, launch options in Steam config are stored under it
+const NEOS_APP_ID: &str = "740250";
+
+/// Place launch arguments were found at, like Steam or a shortcut
+#[derive(Debug, Clone)]
+pub struct ArgumentSource {
+    pub name: String,
+    pub arguments: String
+}
+
+/// Mods and launch options left behind by manual setups or other managers
+#[derive(Default, Debug)]
+pub struct ExistingSetup {
+    pub enabled_mods: usize,
+    pub disabled_mods: usize,
+    pub mod_loader_installed: bool,
+    /// Launch options set in Steam and shortcuts to Neos
+    pub argument_sources: Vec<ArgumentSource>
+}
+
+impl ExistingSetup {
+    pub fn is_empty(&self) -> bool {
+        self.enabled_mods == 0 && self.disabled_mods == 0 && !self.mod_loader_installed && self.argument_sources.is_empty()
+    }
+
+    /// Launch options from the source, mods get loaded if mod loader is there
+    pub fn launch_options(&self, source: usize) -> Option<LaunchOptions> {
+        let source = self.argument_sources.get(source)?;
+
+        let mut options = LaunchOptions::parse_arguments(&source.arguments);
+        options.use_mods |= self.mod_loader_installed;
+
+        Some(options)
+    }
+}
+
+/// Looks around Neos folder for signs of an existing mod setup
+pub fn detect_existing_setup(neos_exe_location: &Path) -> ExistingSetup {
+    let Some(neos_folder) = neos_exe_location.parent() else {
+        return ExistingSetup::default();
+    };
+
+    let mut setup = ExistingSetup {
+        mod_loader_installed: neos_folder.join("Libraries").join("NeosModLoader.dll").exists(),
+        ..Default::default()
+    };
+
+    if let Some(arguments) = find_steam_launch_options(neos_exe_location) {
+        setup.argument_sources.push(ArgumentSource {
+            name: "Steam".to_string(),
+            arguments,
+        });
+    }
+
+    for (path, shortcut) in find_neos_shortcuts() {
+        if let Some(arguments) = shortcut.arguments.filter(|x| !x.trim().is_empty()) {
+            setup.argument_sources.push(ArgumentSource {
+                name: path.file_stem().map_or_else(|| path.to_string_lossy().to_string(), |x| x.to_string_lossy().to_string()),
+                arguments,
+            });
+        }
+    }
+
+    for folder in ["nml_mods", "nml_libs"] {
+        let Ok(entries) = fs::read_dir(neos_folder.join(folder)) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            if is_disabled_path(&path) {
+                setup.disabled_mods += 1;
+            } else if path.extension().map_or(false, |x| x.eq_ignore_ascii_case("dll")) {
+                setup.enabled_mods += 1;
+            }
+        }
+    }
+
+    setup
+}
+
+/// Places Steam could be installed at, Steam folder Neos is in comes first
+fn steam_folders(neos_exe_location: &Path) -> Vec<PathBuf> {
+    let mut folders = vec![];
+
+    if let Some(steamapps) = neos_exe_location.ancestors().find(|x| x.file_name().map_or(false, |x| x.eq_ignore_ascii_case("steamapps"))) {
+        if let Some(steam) = steamapps.parent() {
+            folders.push(steam.to_path_buf());
+        }
+    }
+
+    if cfg!(target_os = "windows") {
+        folders.push(PathBuf::from("C:\\Program Files (x86)\\Steam"));
+    } else if let Some(home) = dirs::home_dir() {
+        folders.push(home.join(".steam").join("steam"));
+        folders.push(home.join(".local").join("share").join("Steam"));
+    }
+
+    folders
+}
+
+fn find_steam_launch_options(neos_exe_location: &Path) -> Option<String> {
+    for steam in steam_folders(neos_exe_location) {
+        let Ok(users) = fs::read_dir(steam.join("userdata")) else {
+            continue;
+        };
+
+        for user in users.flatten() {
+            let Ok(vdf) = fs::read_to_string(user.path().join("config").join("localconfig.vdf")) else {
+                continue;
+            };
+
+            if let Some(options) = extract_launch_options(&vdf, NEOS_APP_ID) {
+                return Some(options);
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds LaunchOptions of the app in Steam's localconfig.vdf, None if they're not set
+pub fn extract_launch_options(vdf: &str, app_id: &str) -> Option<String> {
+    let app_start = Regex::new(&format!(r#""{}"\s*\{{"#, app_id)).unwrap().find(vdf)?.end();
+
+    // Launch options are a direct value of the app block, so looking only until the block closes
+    let mut depth = 1;
+    let mut app_end = vdf.len();
+
+    for (index, character) in vdf[app_start..].char_indices() {
+        match character {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    app_end = app_start + index;
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let options = Regex::new(r#""LaunchOptions"\s*"((?:[^"\\]|\\.)*)""#).unwrap()
+        .captures(&vdf[app_start..app_end])?
+        .get(1)?
+        .as_str()
+        .replace("\\\"", "\"")
+        .replace("\\\\", "\\");
+
+    Some(options).filter(|x| !x.trim().is_empty())
+}
+
+/// Folders people usually keep shortcuts in
+fn shortcut_folders() -> Vec<PathBuf> {
+    let mut folders = vec![];
+
+    if let Some(desktop) = dirs::desktop_dir() {
+        folders.push(desktop);
+    }
+
+    if let Some(data) = dirs::data_dir() {
+        folders.push(data.join("Microsoft").join("Windows").join("Start Menu").join("Programs"));
+    }
+
+    folders
+}
+
+/// Shortcuts in usual places that point at Neos.exe
+pub fn find_neos_shortcuts() -> Vec<(PathBuf, Shortcut)> {
+    let mut shortcuts = vec![];
+
+    for folder in shortcut_folders() {
+        let Ok(entries) = fs::read_dir(folder) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            if !path.extension().map_or(false, |x| x.eq_ignore_ascii_case("lnk")) {
+                continue;
+            }
+
+            let Some(shortcut) = fs::read(&path).ok().and_then(|x| parse_shortcut(&x)) else {
+                continue;
+            };
+
+            if shortcut.target.as_ref().map_or(false, |x| x.to_lowercase().ends_with("neos.exe")) {
+                shortcuts.push((path, shortcut));
+            }
+        }
+    }
+
+    shortcuts
+}
+
+/// Parts of a Windows shortcut (.lnk) that matter for launching Neos
+#[derive(Debug, Default, PartialEq)]
+pub struct Shortcut {
+    pub target: Option<String>,
+    pub working_dir: Option<String>,
+    pub arguments: Option<String>
+}
+
+const LINK_HEADER_SIZE: usize = 0x4C;
+
+const HAS_TARGET_ID_LIST: u32 = 0x1;
+const HAS_LINK_INFO: u32 = 0x2;
+const HAS_NAME: u32 = 0x4;
+const HAS_RELATIVE_PATH: u32 = 0x8;
+const HAS_WORKING_DIR: u32 = 0x10;
+const HAS_ARGUMENTS: u32 = 0x20;
+const IS_UNICODE: u32 = 0x80;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Null terminated string starting at offset, UTF-16 or single byte
+fn read_terminated(data: &[u8], offset: usize, unicode: bool) -> Option<String> {
+    let data = data.get(offset..)?;
+
+    if unicode {
+        let units: Vec<u16> = data.chunks_exact(2)
+            .map(|x| u16::from_le_bytes([x[0], x[1]]))
+            .take_while(|x| *x != 0)
+            .collect();
+
+        Some(String::from_utf16_lossy(&units))
+    } else {
+        let bytes: Vec<u8> = data.iter().copied().take_while(|x| *x != 0).collect();
+
+        Some(String::from_utf8_lossy(&bytes).to_string())
+    }
+}
+
+/// Reads the parts of the shell link format needed to get target and arguments, None if data isn't a valid link
+pub fn parse_shortcut(data: &[u8]) -> Option<Shortcut> {
+    if read_u32(data, 0)? as usize != LINK_HEADER_SIZE {
+        return None;
+    }
+
+    let flags = read_u32(data, 0x14)?;
+    let unicode = flags & IS_UNICODE != 0;
+    let mut offset = LINK_HEADER_SIZE;
+    let mut shortcut = Shortcut::default();
+
+    if flags & HAS_TARGET_ID_LIST != 0 {
+        offset += 2 + read_u16(data, offset)? as usize;
+    }
+
+    if flags & HAS_LINK_INFO != 0 {
+        let info = data.get(offset..offset + read_u32(data, offset)? as usize)?;
+        let header_size = read_u32(info, 4)?;
+        let has_local_path = read_u32(info, 8)? & 0x1 != 0;
+
+        if has_local_path {
+            // Newer links have unicode version of the path after the regular header fields
+            shortcut.target = if header_size >= 0x24 {
+                read_terminated(info, read_u32(info, 0x1C)? as usize, true)
+            } else {
+                read_terminated(info, read_u32(info, 0x10)? as usize, false)
+            };
+        }
+
+        offset += info.len();
+    }
+
+    let mut read_string = |flag: u32| -> Option<Option<String>> {
+        if flags & flag == 0 {
+            return Some(None);
+        }
+
+        let length = read_u16(data, offset)? as usize;
+        offset += 2;
+
+        let string = if unicode {
+            let units: Vec<u16> = data.get(offset..offset + length * 2)?
+                .chunks_exact(2)
+                .map(|x| u16::from_le_bytes([x[0], x[1]]))
+                .collect();
+
+            offset += length * 2;
+            String::from_utf16_lossy(&units)
+        } else {
+            let bytes = data.get(offset..offset + length)?;
+
+            offset += length;
+            String::from_utf8_lossy(bytes).to_string()
+        };
+
+        Some(Some(string))
+    };
+
+    read_string(HAS_NAME)?;
+    let relative_path = read_string(HAS_RELATIVE_PATH)?;
+    shortcut.working_dir = read_string(HAS_WORKING_DIR)?;
+    shortcut.arguments = read_string(HAS_ARGUMENTS)?;
+
+    // Links without link info still usually have a relative path to the target
+    if shortcut.target.is_none() {
+        shortcut.target = relative_path;
+    }
+
+    Some(shortcut)
+}
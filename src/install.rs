@@ -1,22 +1,168 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::error::Error;
 use std::{io, path};
 use std::fmt::{Display, Formatter};
+use std::ops::Deref;
 use std::path::{Path, PathBuf, StripPrefixError};
 use std::sync::Arc;
+use std::time::UNIX_EPOCH;
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
-use crate::manifest::{GlobalModList, GUID, ManifestMods, Mod, ModVersion};
+use crate::manifest::{Artifact, Category, GlobalModList, GUID, ManifestMods, Mod, ModVersion};
 use crate::version::{Version, VersionReq};
+use reqwest::Client;
 use serde::{Serialize, Deserialize};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::RwLock;
-use crate::config::Config;
-use crate::utils::{append_relative_path, find_filename_from_url, get_all_files_of_extension, sha256_file};
+use crate::cache::ArtifactCache;
+use crate::config::{Config, default_client, DefaultLocations, mirrored_urls, MirrorRule, NetworkSettings};
+use crate::transport::{BodySink, Transport, TransportError};
+use crate::utils::{append_relative_path, detect_assembly_version, find_filename_from_url, get_all_files_of_extension, is_safe_file_name, is_safe_relative_path, is_within, sha256_bytes, TokenBucket};
 
 pub type IDVersion = (String, Version);
 pub type IDVersionReq = (String, VersionReq);
 
-pub type ModMap = HashMap<GUID, HashMap<Version, ModFile>>;
+/// Installed mods, by GUID and then by version. Reading goes through Deref,
+/// changes go through methods so versions that ran out of files don't linger
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(transparent)]
+pub struct ModMap(HashMap<GUID, HashMap<Version, ModFile>>);
+
+impl ModMap {
+    /// Newest installed version of the mod
+    pub fn installed_version(&self, mod_id: &str) -> Option<&Version> {
+        self.0.get(mod_id).and_then(|x| x.keys().max())
+    }
+
+    /// Installed versions of the mod, oldest first
+    pub fn installed_versions(&self, mod_id: &str) -> Vec<&Version> {
+        let mut versions: Vec<&Version> = self.0.get(mod_id).map_or_else(Vec::new, |x| x.keys().collect());
+        versions.sort();
+        versions
+    }
+
+    pub fn is_installed(&self, mod_id: &str, version: &Version) -> bool {
+        self.file(mod_id, version).is_some()
+    }
+
+    /// Whether all files of the version are enabled, false if it's not installed
+    pub fn is_enabled(&self, mod_id: &str, version: &Version) -> bool {
+        self.file(mod_id, version).map_or(false, |x| x.is_enabled())
+    }
+
+    pub fn file(&self, mod_id: &str, version: &Version) -> Option<&ModFile> {
+        self.0.get(mod_id).and_then(|x| x.get(version))
+    }
+
+    pub fn file_mut(&mut self, mod_id: &str, version: &Version) -> Option<&mut ModFile> {
+        self.0.get_mut(mod_id).and_then(|x| x.get_mut(version))
+    }
+
+    /// Amount of installed versions across all mods
+    pub fn version_count(&self) -> usize {
+        self.0.values().map(|x| x.len()).sum()
+    }
+
+    /// Amount of files across all installed versions
+    pub fn total_files(&self) -> usize {
+        self.iter_versions().map(|(_, _, file)| file.files.len()).sum()
+    }
+
+    /// Every installed version, in no particular order
+    pub fn iter_versions(&self) -> impl Iterator<Item = (&GUID, &Version, &ModFile)> {
+        self.0.iter()
+            .flat_map(|(mod_id, versions)| versions.iter().map(move |(version, file)| (mod_id, version, file)))
+    }
+
+    /// Every installed version, sorted by GUID and then by version
+    pub fn iter_sorted(&self) -> impl Iterator<Item = (&GUID, &Version, &ModFile)> {
+        let mut versions: Vec<_> = self.iter_versions().collect();
+        versions.sort_by(|a, b| a.0.cmp(b.0).then_with(|| a.1.cmp(b.1)));
+        versions.into_iter()
+    }
+
+    /// Every installed version as ID and version pairs, sorted
+    pub fn id_versions(&self) -> Vec<IDVersion> {
+        self.iter_sorted()
+            .map(|(mod_id, version, _)| (mod_id.clone(), version.clone()))
+            .collect()
+    }
+
+    pub fn insert(&mut self, mod_id: &str, version: &Version, file: ModFile) {
+        self.0.entry(mod_id.to_string()).or_default().insert(version.clone(), file);
+    }
+
+    /// Removes the version, and the mod too if that was its last version
+    pub fn remove(&mut self, mod_id: &str, version: &Version) -> Option<ModFile> {
+        let versions = self.0.get_mut(mod_id)?;
+        let file = versions.remove(version);
+
+        if versions.is_empty() {
+            self.0.remove(mod_id);
+        }
+
+        file
+    }
+
+    /// What changed going from this map to the other one
+    pub fn diff(&self, other: &ModMap) -> ModMapDiff {
+        let mut diff = ModMapDiff::default();
+
+        for (mod_id, version, file) in self.iter_sorted() {
+            match other.file(mod_id, version) {
+                None => diff.removed.push((mod_id.clone(), version.clone())),
+                Some(other_file) if other_file != file => diff.changed.push((mod_id.clone(), version.clone())),
+                Some(_) => {}
+            }
+        }
+
+        for (mod_id, version, _) in other.iter_sorted() {
+            if !self.is_installed(mod_id, version) {
+                diff.added.push((mod_id.clone(), version.clone()));
+            }
+        }
+
+        diff
+    }
+}
+
+impl Deref for ModMap {
+    type Target = HashMap<GUID, HashMap<Version, ModFile>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> IntoIterator for &'a ModMap {
+    type Item = (&'a GUID, &'a HashMap<Version, ModFile>);
+    type IntoIter = std::collections::hash_map::Iter<'a, GUID, HashMap<Version, ModFile>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl From<HashMap<GUID, HashMap<Version, ModFile>>> for ModMap {
+    fn from(value: HashMap<GUID, HashMap<Version, ModFile>>) -> Self {
+        Self(value)
+    }
+}
+
+/// Versions that appeared, disappeared, or had their files change between two mod maps, each sorted
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ModMapDiff {
+    pub added: Vec<IDVersion>,
+    pub removed: Vec<IDVersion>,
+    pub changed: Vec<IDVersion>
+}
+
+impl ModMapDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
 pub struct ModFile {
@@ -28,10 +174,53 @@ pub struct ModFileArtifact {
     pub file_path: PathBuf,
     pub file_hash: String,
     pub disabled: bool,
+    /// File size in bytes, used to tell if the file changed since last scan and for disk usage
+    #[serde(default)]
+    pub size: u64,
+    /// Modification time in seconds since unix epoch, used to tell if the file changed since last scan
+    #[serde(default)]
+    pub modified: u64,
+    /// Assembly version read from the dll itself, if it has one
+    #[serde(default)]
+    pub assembly_version: Option<String>,
+}
+
+/// How many of the version's artifacts are actually there
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct ArtifactStatus {
+    pub present: usize,
+    pub expected: usize,
+    pub enabled: usize,
+}
+
+impl ArtifactStatus {
+    pub fn is_complete(&self) -> bool {
+        self.present >= self.expected
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled == self.present
+    }
+
+    /// Some of the files are enabled while others aren't
+    pub fn is_partially_enabled(&self) -> bool {
+        self.enabled > 0 && self.enabled < self.present
+    }
+}
+
+/// Folder the artifact should be installed to, override takes priority over manifest's install_location
+pub fn artifact_install_location(artifact: &Artifact, category: Category, location_override: Option<&Path>, defaults: &DefaultLocations) -> PathBuf {
+    location_override.map(|x| x.to_path_buf())
+        .or_else(|| artifact.install_location.clone())
+        .unwrap_or_else(|| defaults.for_category(category))
 }
 
 impl ModFile {
-    pub fn new(mod_id: &str, version: &Version, mods: &ManifestMods) -> Self {
+    pub fn new(mod_id: &str, version: &Version, mods: &ManifestMods, defaults: &DefaultLocations) -> Self {
+        Self::new_at(mod_id, version, mods, None, defaults)
+    }
+
+    pub fn new_at(mod_id: &str, version: &Version, mods: &ManifestMods, location_override: Option<&Path>, defaults: &DefaultLocations) -> Self {
         let files = if let Some(mod_info) = mods.get(mod_id) {
             let version_info = mod_info.versions.get(&version);
 
@@ -39,10 +228,15 @@ impl ModFile {
                 x.artifacts.iter()
                     .filter_map(|x| {
                         let filename = x.filename.clone()
-                            .or_else(|| find_filename_from_url(&x.url, ".dll"))?;
+                            .or_else(|| find_filename_from_url(&x.url, ".dll"))
+                            .filter(|x| is_safe_file_name(x))?;
+
+                        let mut location = artifact_install_location(x, mod_info.install_category(), location_override, defaults);
 
-                        let mut location = x.install_location.clone()
-                            .unwrap_or_else(|| PathBuf::from("/nml_mods"));
+                        // Actual install refuses these, so they shouldn't show up in previews either
+                        if !is_safe_relative_path(&location) {
+                            return None;
+                        }
 
                         location.push(filename);
 
@@ -50,6 +244,9 @@ impl ModFile {
                             file_path: location,
                             file_hash: x.sha256.clone(),
                             disabled: false,
+                            size: 0,
+                            modified: 0,
+                            assembly_version: None,
                         })
                     })
                     .collect()
@@ -62,9 +259,149 @@ impl ModFile {
             files,
         }
     }
+
+    pub fn is_enabled(&self) -> bool {
+        self.files.iter().all(|x| !x.disabled)
+    }
+
+    /// Disk space taken by the files, as of last scan
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|x| x.size).sum()
+    }
+
+    /// Latest modification time among the files, 0 if unknown
+    pub fn installed_at(&self) -> u64 {
+        self.files.iter().map(|x| x.modified).max().unwrap_or(0)
+    }
+
+    /// First assembly version found among the files
+    pub fn assembly_version(&self) -> Option<String> {
+        self.files.iter().find_map(|x| x.assembly_version.clone())
+    }
+
+    /// Expected is amount of artifacts the version has in the manifest
+    pub fn status(&self, expected: usize) -> ArtifactStatus {
+        ArtifactStatus {
+            present: self.files.len(),
+            expected,
+            enabled: self.files.iter().filter(|x| !x.disabled).count(),
+        }
+    }
+}
+
+/// Artifact that was downloaded, but not yet written to disk
+pub struct DownloadedArtifact {
+    pub path: PathBuf,
+    pub url: String,
+    /// Mirror the artifact came from, if it wasn't downloaded from the original URL
+    pub mirror: Option<String>,
+    pub expected_hash: String,
+    pub data: Vec<u8>
+}
+
+/// Checks downloaded data against hashes from manifest
+pub fn verify_artifacts(artifacts: &[DownloadedArtifact]) -> Result<(), InstallError> {
+    for artifact in artifacts {
+        let hash = sha256_bytes(&artifact.data);
+
+        if !hash.eq_ignore_ascii_case(&artifact.expected_hash) {
+            return Err(InstallError::HashMismatch {
+                url: artifact.url.clone(),
+                expected: artifact.expected_hash.clone(),
+                found: hash,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Path the file gets when disabled, `Mod.dll` becomes `Mod.dll.disabled`
+pub fn disabled_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".disabled");
+    PathBuf::from(path)
+}
+
+/// Disabled files keep their whole name with .disabled added, so `Mod.dll.disabled` is a disabled `Mod.dll`
+pub fn is_disabled_path(path: &Path) -> bool {
+    path.extension().map_or(false, |x| x.eq_ignore_ascii_case("disabled"))
+}
+
+/// Path the disabled file gets when enabled again, only the trailing .disabled is taken off
+pub fn enabled_path(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+
+    if is_disabled_path(&path) {
+        path.set_extension("");
+    }
+
+    path
+}
+
+/// Renames all artifacts of the version, if any of them fails, already renamed ones are put back
+async fn set_artifacts_disabled(file: &mut ModFile, disabled: bool) -> Result<(), InstallError> {
+    let mut renamed: Vec<(usize, PathBuf)> = vec![];
+
+    for (index, artifact) in file.files.iter().enumerate() {
+        if artifact.disabled == disabled {
+            continue;
+        }
+
+        let target = if disabled {
+            disabled_path(&artifact.file_path)
+        } else {
+            enabled_path(&artifact.file_path)
+        };
+
+        if let Err(e) = fs::rename(&artifact.file_path, &target).await {
+            for (index, target) in renamed.into_iter().rev() {
+                fs::rename(target, &file.files[index].file_path).await.ok();
+            }
+
+            return Err(e.into());
+        }
+
+        renamed.push((index, target));
+    }
+
+    for (index, target) in renamed {
+        let artifact = &mut file.files[index];
+        artifact.file_path = target;
+        artifact.disabled = disabled;
+    }
+
+    Ok(())
 }
 
-#[derive(Clone, Debug)]
+/// Removes all artifacts of the version, files are moved aside first so a failure doesn't leave the mod half removed
+async fn remove_artifacts(file: &ModFile) -> Result<(), InstallError> {
+    let mut staged: Vec<(&Path, PathBuf)> = vec![];
+
+    for artifact in &file.files {
+        let mut staging = artifact.file_path.as_os_str().to_owned();
+        staging.push(".uninstalling");
+        let staging = PathBuf::from(staging);
+
+        if let Err(e) = fs::rename(&artifact.file_path, &staging).await {
+            for (original, staging) in staged.into_iter().rev() {
+                fs::rename(staging, original).await.ok();
+            }
+
+            return Err(e.into());
+        }
+
+        staged.push((artifact.file_path.as_path(), staging));
+    }
+
+    for (_, staging) in staged {
+        fs::remove_file(staging).await?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum ModConflict {
     /// Multiple versions of a single mod are found
     VersionConflict(GUID),
@@ -98,28 +435,101 @@ pub enum ModConflict {
     FileConflict {
         this: IDVersion,
         already_exists: PathBuf
+    },
+
+    /// No version of the mod fits all of the mods that depend on it at once
+    IncompatibleRequirements {
+        this: GUID,
+        requirements: Vec<(IDVersion, VersionReq)>
     }
 }
 
-#[derive(Clone, Debug)]
+/// How bad a conflict is, errors keep the game from launching until they're dealt with or suppressed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConflictSeverity {
+    Warning,
+    Error
+}
+
+impl ModConflict {
+    /// Mismatched dependency versions and missing extra files often work fine, the rest usually breaks things
+    pub fn severity(&self) -> ConflictSeverity {
+        match self {
+            ModConflict::DependencyMismatch { .. }
+            | ModConflict::IncompleteInstall { .. }
+            | ModConflict::IncompatibleRequirements { .. } => ConflictSeverity::Warning,
+            ModConflict::VersionConflict(_)
+            | ModConflict::DirectConflict { .. }
+            | ModConflict::DependencyMissing { .. }
+            | ModConflict::FileConflict { .. } => ConflictSeverity::Error
+        }
+    }
+
+    /// Mod the conflict was found for
+    pub fn mod_id(&self) -> &GUID {
+        match self {
+            ModConflict::VersionConflict(guid) | ModConflict::IncompatibleRequirements { this: guid, .. } => guid,
+            ModConflict::DirectConflict { this, .. }
+            | ModConflict::DependencyMissing { this, .. }
+            | ModConflict::DependencyMismatch { this, .. }
+            | ModConflict::IncompleteInstall { this, .. }
+            | ModConflict::FileConflict { this, .. } => &this.0
+        }
+    }
+
+    /// Mod is either the one the conflict was found for or the one it conflicts with
+    pub fn involves(&self, guid: &str) -> bool {
+        match self {
+            ModConflict::DirectConflict { conflict_with, .. } if conflict_with.0 == guid => true,
+            ModConflict::IncompatibleRequirements { requirements, .. } if requirements.iter().any(|((x, _), _)| x == guid) => true,
+            _ => self.mod_id() == guid
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum ModInstallOperations {
     InstallMod(IDVersion),
-    UninstallMod(IDVersion)
+    /// Same as InstallMod, but artifacts go to the provided folder instead of the default one
+    InstallModAt(IDVersion, PathBuf),
+    UninstallMod(IDVersion),
+    /// Enables all artifacts of the version
+    EnableMod(IDVersion),
+    /// Disables all artifacts of the version
+    DisableMod(IDVersion)
+}
+
+impl ModInstallOperations {
+    /// Changes install destination of the operation, does nothing for uninstalls
+    pub fn with_location_override(self, location: Option<PathBuf>) -> Self {
+        match (self, location) {
+            (ModInstallOperations::InstallMod(id) | ModInstallOperations::InstallModAt(id, _), Some(location)) => ModInstallOperations::InstallModAt(id, location),
+            (ModInstallOperations::InstallModAt(id, _), None) => ModInstallOperations::InstallMod(id),
+            (op, _) => op
+        }
+    }
 }
 
 #[async_trait::async_trait]
 pub trait ModInstall {
     fn mod_map(&self) -> &ModMap;
+    fn default_locations(&self) -> &DefaultLocations;
     async fn perform_operations(&mut self, operations: &[ModInstallOperations]) -> Result<(), InstallError>;
 
     fn check_for_conflicts(&self, mods: &ManifestMods) -> Vec<ModConflict> {
         let mut conflicts = vec![];
 
         let map = self.mod_map();
-        let mut install_files: HashSet<PathBuf> = HashSet::new();
+        let mut install_files: HashMap<PathBuf, &GUID> = HashMap::new();
+        let mut dependency_requirements: HashMap<&GUID, Vec<(IDVersion, VersionReq)>> = HashMap::new();
 
         for (file_guid, mod_files) in map {
-            if mod_files.len() > 1 { // If there's more than one version of a single mod installed, then version conflict
+            // If there's more than one version of a single mod enabled, then version conflict. Disabled ones don't get loaded
+            let enabled_versions = mod_files.values()
+                .filter(|x| x.files.is_empty() || x.files.iter().any(|x| !x.disabled))
+                .count();
+
+            if enabled_versions > 1 {
                 conflicts.push(ModConflict::VersionConflict(file_guid.clone()));
             }
 
@@ -131,16 +541,21 @@ pub trait ModInstall {
                                 .or_else(|| find_filename_from_url(&artifact.url, ".dll"))
                                 .unwrap_or_else(|| "unknown.dll".to_string());
 
-                            let mut filepath = artifact.install_location.clone().unwrap_or_else(|| PathBuf::from("/nml_mods"));
+                            let mut filepath = artifact_install_location(artifact, mod_info.install_category(), None, self.default_locations());
                             filepath.push(&filename);
 
-                            if install_files.contains(&filepath) { // If there's already a file at the path, file conflict
-                                conflicts.push(ModConflict::FileConflict {
-                                    this: (file_guid.clone(), file_version.clone()),
-                                    already_exists: filepath
-                                })
-                            } else { // If there's not, add the file path to hash set
-                                install_files.insert(filepath);
+                            match install_files.get(&filepath) {
+                                // Versions of the same mod sharing a path are already reported as version conflict
+                                Some(owner) if *owner == file_guid => {}
+                                Some(_) => { // If there's already a file of another mod at the path, file conflict
+                                    conflicts.push(ModConflict::FileConflict {
+                                        this: (file_guid.clone(), file_version.clone()),
+                                        already_exists: filepath
+                                    })
+                                }
+                                None => { // If there's not, remember who owns the path
+                                    install_files.insert(filepath, file_guid);
+                                }
                             }
 
                             if !file.files.iter().any(|x| x.file_hash == artifact.sha256) {
@@ -153,6 +568,10 @@ pub trait ModInstall {
 
                         if let Some(mod_dependencies) = &version.dependencies { // If there's defined dependencies for this version
                             for (dependency_guid, dependency_info) in mod_dependencies { // For each found dependency
+                                dependency_requirements.entry(dependency_guid)
+                                    .or_default()
+                                    .push(((file_guid.clone(), file_version.clone()), dependency_info.version.clone()));
+
                                 if let Some(found_files) = map.get(dependency_guid) { // If dependency is installed
                                     if !found_files.iter().any(|(v, _)| { // If all versions don't match the requirement
                                         return dependency_info.version.matches(v);
@@ -196,14 +615,92 @@ pub trait ModInstall {
             }
         }
 
+        // Each dependent might be fine with some version, but not with the same one
+        let mut dependency_requirements: Vec<(&GUID, Vec<(IDVersion, VersionReq)>)> = dependency_requirements.into_iter()
+            .filter(|(_, x)| x.len() > 1)
+            .collect();
+
+        dependency_requirements.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (dependency_guid, mut requirements) in dependency_requirements {
+            let known_versions: Vec<&Version> = mods.get(dependency_guid).map_or_else(Vec::new, |x| x.versions.keys().collect()).into_iter()
+                .chain(map.get(dependency_guid).into_iter().flat_map(|x| x.keys()))
+                .collect();
+
+            let fits = |requirement: &VersionReq| known_versions.iter().any(|x| requirement.matches(x));
+
+            let merged = requirements.iter()
+                .map(|(_, x)| x.clone())
+                .reduce(|a, b| a.intersect(&b));
+
+            if known_versions.is_empty() || !requirements.iter().all(|(_, x)| fits(x)) || merged.map_or(true, |x| fits(&x)) {
+                continue;
+            }
+
+            requirements.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            conflicts.push(ModConflict::IncompatibleRequirements {
+                this: dependency_guid.clone(),
+                requirements,
+            });
+        }
+
         conflicts
     }
 }
 
+/// Known mod file found in the game folder, but outside of enabled scan locations
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrayMod {
+    pub mod_id: GUID,
+    pub version: Version,
+    pub file_path: PathBuf,
+    /// Folder the artifact belongs in according to manifest, relative to Neos folder
+    pub expected_location: PathBuf
+}
+
+/// File picked by user to install, it doesn't have to be in any manifest
+#[derive(Clone, Debug, PartialEq)]
+pub struct SideloadFile {
+    /// Path or URL the file came from
+    pub source: String,
+    pub file_name: String,
+    pub size: u64,
+    pub hash: String,
+    /// Manifest version that has an artifact with the same hash
+    pub identified: Option<IDVersion>
+}
+
+/// Download that's still coming in, kept in staging so it can be resumed
+struct PartialFile {
+    file: fs::File
+}
+
+#[async_trait]
+impl BodySink for PartialFile {
+    async fn restart(&mut self) -> io::Result<()> {
+        // Opened for appending, so writes go to the start again
+        self.file.set_len(0).await
+    }
+
+    async fn write(&mut self, chunk: &[u8]) -> io::Result<()> {
+        self.file.write_all(chunk).await
+    }
+}
+
 pub struct ActualInstall {
     location: PathBuf,
     installed_mods: ModMap,
     manifest_mods: GlobalModList,
+    transport: Arc<dyn Transport>,
+    /// Bytes per second, None means downloads aren't limited
+    bandwidth_limit: Option<u64>,
+    mirrors: Vec<MirrorRule>,
+    default_locations: DefaultLocations,
+    /// Downloads are kept here until written, so an interrupted install doesn't download them again
+    staging: Option<PathBuf>,
+    /// Written artifacts are kept here, None when cache is turned off
+    cache: Option<ArtifactCache>,
 }
 
 impl ActualInstall {
@@ -212,55 +709,478 @@ impl ActualInstall {
             location: location.as_ref().to_path_buf(),
             installed_mods: Default::default(),
             manifest_mods: global_mods,
+            transport: Arc::new(default_client()),
+            bandwidth_limit: None,
+            mirrors: vec![],
+            default_locations: Default::default(),
+            staging: None,
+            cache: None,
         }
     }
 
-    pub async fn rescan_mods(&mut self, config: Arc<Config>) -> Result<(), InstallError> {
+    /// Quick check if files in scan locations changed since the last scan, only looks at file list and sizes
+    pub async fn has_external_changes(&self, config: &Config) -> Result<bool, InstallError> {
+        let mut known: HashMap<&Path, u64> = self.installed_mods.iter_versions()
+            .flat_map(|(_, _, x)| x.files.iter())
+            .map(|x| (x.file_path.as_path(), x.size))
+            .collect();
+
+        for scan_location in config.install_profile.enabled_locations() {
+            let mut location = self.location.clone();
+            append_relative_path(&mut location, scan_location)?;
+
+            if !location.exists() {
+                continue;
+            }
+
+            for file in get_all_files_of_extension(location, &["dll", "disabled"]).await? {
+                let size = fs::metadata(&file).await?.len();
+
+                if known.remove(file.as_path()) != Some(size) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        // Anything left wasn't found on disk anymore
+        Ok(!known.is_empty())
+    }
+
+    pub fn location(&self) -> &Path {
+        &self.location
+    }
+
+    /// Client, speed limit and mirrors used for downloading artifacts
+    pub fn apply_network_settings(&mut self, client: Client, settings: &NetworkSettings) {
+        self.set_transport(Arc::new(client));
+        self.bandwidth_limit = settings.bandwidth_limit_bytes();
+        self.mirrors = settings.mirrors.clone();
+    }
+
+    /// What artifacts get downloaded through, tests use it to answer without network
+    pub fn set_transport(&mut self, transport: Arc<dyn Transport>) {
+        self.transport = transport;
+    }
+
+    pub fn set_staging_folder(&mut self, folder: PathBuf) {
+        self.staging = Some(folder);
+    }
+
+    pub fn set_artifact_cache(&mut self, cache: Option<ArtifactCache>) {
+        self.cache = cache;
+    }
+
+    /// Cached copy of the artifact, or a finished download left in staging by an earlier attempt
+    async fn read_local(&self, hash: &str) -> Option<Vec<u8>> {
+        if let Some(data) = match &self.cache {
+            Some(cache) => cache.get(hash).await,
+            None => None
+        } {
+            return Some(data);
+        }
+
+        self.read_staged(hash).await
+    }
+
+    fn staged_path(&self, hash: &str) -> Option<PathBuf> {
+        // Hash comes from manifest and becomes a file name
+        if hash.is_empty() || !hash.chars().all(|x| x.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let mut path = self.staging.clone()?;
+        path.push(hash.to_lowercase());
+        Some(path)
+    }
+
+    /// Data of the artifact if it was downloaded before, staged files that don't match the hash are removed
+    async fn read_staged(&self, hash: &str) -> Option<Vec<u8>> {
+        let path = self.staged_path(hash)?;
+        let data = fs::read(&path).await.ok()?;
+
+        if sha256_bytes(&data).eq_ignore_ascii_case(hash) {
+            Some(data)
+        } else {
+            fs::remove_file(&path).await.ok();
+            None
+        }
+    }
+
+    /// Puts data of an artifact that came from elsewhere into staging, installing it then doesn't download anything
+    pub async fn stage_artifact(&self, hash: &str, data: &[u8]) -> Result<(), InstallError> {
+        let Some(path) = self.staged_path(hash) else {
+            return Err(InstallError::InvalidArtifact(hash.to_string()));
+        };
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, data).await?)
+    }
+
+    /// Downloads into a partial file in staging, picking up where an earlier attempt stopped, the file is staged once it's complete
+    async fn download(&self, url: &str, hash: &str, bucket: &mut Option<TokenBucket>) -> Result<Vec<u8>, InstallError> {
+        let Some(staged) = self.staged_path(hash) else {
+            return Ok(self.transport.get(url, bucket).await?);
+        };
+
+        let mut partial = staged.clone().into_os_string();
+        partial.push(".part");
+
+        if let Some(folder) = staged.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        let mut sink = PartialFile {
+            file: fs::OpenOptions::new().create(true).append(true).open(&partial).await?,
+        };
+        let offset = sink.file.metadata().await?.len();
+
+        self.transport.get_from(url, offset, &mut sink, bucket).await?;
+        sink.file.flush().await?;
+        drop(sink);
+
+        fs::rename(&partial, &staged).await?;
+        Ok(fs::read(&staged).await?)
+    }
+
+    /// Tries mirrors of the URL one by one, returns data and the URL that worked
+    async fn download_with_failover(&self, url: &str, hash: &str, bucket: &mut Option<TokenBucket>) -> Result<(Vec<u8>, String), InstallError> {
+        let mut last_error = None;
+
+        for candidate in mirrored_urls(&self.mirrors, url) {
+            match self.download(&candidate, hash, bucket).await {
+                Ok(data) => return Ok((data, candidate)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| InstallError::InvalidArtifact(url.to_string())))
+    }
+
+    /// Hashes everything in scan locations, progress gets called with files done so far and total amount of files
+    pub async fn rescan_mods(&mut self, config: Arc<Config>, mut progress: impl FnMut(usize, usize)) -> Result<(), InstallError> {
         let install_location = self.location.clone();
         let mod_hashtable = self.manifest_mods.mod_hash_table.load();
 
+        self.default_locations = config.install_profile.default_locations.clone();
+
         let mut installed = HashMap::new();
 
-        for scan_location in &config.scan_locations {
+        // Files that didn't change since last scan don't need to be hashed again
+        let known_files: HashMap<PathBuf, ModFileArtifact> = self.installed_mods.iter_versions()
+            .flat_map(|(_, _, x)| x.files.iter())
+            .map(|x| (x.file_path.clone(), x.clone()))
+            .collect();
+
+        // Files are listed first so it's known how many there are to go through
+        let mut files = vec![];
+
+        for scan_location in config.install_profile.enabled_locations() {
             let mut location = install_location.clone();
             append_relative_path(&mut location, scan_location)?;
 
             if location.exists() {
-                let files = get_all_files_of_extension(location, &["dll", "disabled"]).await?;
-
-                for file in files {
-                    let disabled = file.ends_with(".disabled");
-                    let hash = sha256_file(&file).await?;
-
-                    println!("file {} - hash: {}", file.to_string_lossy(), hash);
-
-                    let (mod_id, version) = if let Some((mod_id, version)) = mod_hashtable.get(&hash) {
-                        println!("recognized hash as {}", mod_id);
-                        (mod_id.clone(), version.clone())
-                    } else {
-                        println!("unrecognized");
-                        (
-                            file.file_name().map_or_else(|| "unknown.dll".to_string(), |x| x.to_string_lossy().to_string()),
-                            Version::zero()
-                        )
-                    };
+                files.extend(get_all_files_of_extension(location, &["dll", "disabled"]).await?);
+            }
+        }
 
-                    installed.entry(mod_id)
-                        .or_insert(HashMap::new())
-                        .entry(version)
-                        .or_insert(ModFile::default())
-                        .files.push(
-                        ModFileArtifact {
-                            file_path: file,
-                            file_hash: hash,
-                            disabled,
-                        }
-                    );
+        let total = files.len();
+
+        for (scanned, file) in files.into_iter().enumerate() {
+            progress(scanned, total);
+
+            let disabled = is_disabled_path(&file);
+
+            let metadata = fs::metadata(&file).await?;
+            let size = metadata.len();
+            let modified = metadata.modified().ok()
+                .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |x| x.as_secs());
+
+            let (hash, assembly_version) = match known_files.get(&file) {
+                Some(known) if modified != 0 && known.size == size && known.modified == modified => (known.file_hash.clone(), known.assembly_version.clone()),
+                _ => {
+                    let data = fs::read(&file).await?;
+                    (sha256_bytes(&data), detect_assembly_version(&data))
                 }
+            };
+
+            println!("file {} - hash: {}", file.to_string_lossy(), hash);
+
+            let (mod_id, version) = if let Some((mod_id, version)) = mod_hashtable.get(&hash) {
+                println!("recognized hash as {}", mod_id);
+                (mod_id.clone(), version.clone())
+            } else {
+                println!("unrecognized");
+                (
+                    file.file_name().map_or_else(|| "unknown.dll".to_string(), |x| x.to_string_lossy().to_string()),
+                    Version::zero()
+                )
+            };
+
+            installed.entry(mod_id)
+                .or_insert(HashMap::new())
+                .entry(version)
+                .or_insert(ModFile::default())
+                .files.push(
+                ModFileArtifact {
+                    file_path: file,
+                    file_hash: hash,
+                    disabled,
+                    size,
+                    modified,
+                    assembly_version,
+                }
+            );
+        }
+
+        progress(total, total);
+
+        self.installed_mods = ModMap(installed);
+
+        Ok(())
+    }
+
+    /// Looks through the whole game folder for files matching manifest hashes that scan locations don't cover
+    pub async fn find_stray_mods(&self, config: &Config) -> Result<Vec<StrayMod>, InstallError> {
+        let mod_hashtable = self.manifest_mods.mod_hash_table.load();
+        let mod_list = self.manifest_mods.mod_list.load();
+
+        let mut scanned = vec![];
+
+        for scan_location in config.install_profile.enabled_locations() {
+            let mut location = self.location.clone();
+            append_relative_path(&mut location, scan_location)?;
+            scanned.push(location);
+        }
+
+        let mut strays = vec![];
+
+        for file in get_all_files_of_extension(self.location.clone(), &["dll", "disabled"]).await? {
+            if scanned.iter().any(|x| file.starts_with(x)) {
+                continue;
+            }
+
+            let hash = sha256_bytes(&fs::read(&file).await?);
+
+            let Some((mod_id, version)) = mod_hashtable.get(&hash) else {
+                continue;
+            };
+
+            let Some(mod_info) = mod_list.get(mod_id) else {
+                continue;
+            };
+
+            let artifact = mod_info.versions.get(version)
+                .and_then(|x| x.artifacts.iter().find(|x| x.sha256.eq_ignore_ascii_case(&hash)));
+
+            if let Some(artifact) = artifact {
+                strays.push(StrayMod {
+                    mod_id: mod_id.clone(),
+                    version: version.clone(),
+                    file_path: file,
+                    expected_location: artifact_install_location(artifact, mod_info.install_category(), None, &config.install_profile.default_locations),
+                });
             }
         }
 
-        self.installed_mods = installed;
+        strays.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+        Ok(strays)
+    }
+
+    /// Manifest version that has an artifact with this hash
+    pub fn identify_artifact(&self, hash: &str) -> Option<IDVersion> {
+        self.manifest_mods.mod_hash_table.load().get(&hash.to_lowercase()).cloned()
+    }
+
+    /// Writes a file picked by user into a folder relative to Neos folder, rescan picks it up afterwards
+    pub async fn install_file(&self, file_name: &str, data: &[u8], location: &Path) -> Result<PathBuf, InstallError> {
+        if !is_safe_relative_path(location) || !is_safe_file_name(file_name) {
+            return Err(InstallError::UnsafePath(location.join(file_name)));
+        }
+
+        let mut path = self.location.clone();
+        append_relative_path(&mut path, location)?;
+        path.push(file_name);
+
+        if !is_within(&self.location, &path)? {
+            return Err(InstallError::UnsafePath(path));
+        }
+
+        if path.exists() || disabled_path(&path).exists() {
+            return Err(InstallError::FileAlreadyExists);
+        }
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        fs::write(&path, data).await?;
+
+        Ok(path)
+    }
+
+    /// Moves stray files into folders they belong in, keeping file names
+    pub async fn move_stray_mods(&self, strays: &[StrayMod]) -> Result<(), InstallError> {
+        for stray in strays {
+            if !is_safe_relative_path(&stray.expected_location) {
+                return Err(InstallError::UnsafePath(stray.expected_location.clone()));
+            }
+
+            let Some(file_name) = stray.file_path.file_name() else {
+                return Err(InstallError::FileNotFound);
+            };
+
+            let mut target = self.location.clone();
+            append_relative_path(&mut target, &stray.expected_location)?;
+            fs::create_dir_all(&target).await?;
+            target.push(file_name);
+
+            if target.exists() {
+                return Err(InstallError::FileAlreadyExists);
+            }
+
+            fs::rename(&stray.file_path, &target).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Loads mod map saved by the last session, returns false if there wasn't any
+    pub async fn load_cached_mods(&mut self) -> Result<bool, InstallError> {
+        let path = Config::mod_map_cache_path();
+
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let str = fs::read_to_string(path).await?;
+        self.installed_mods = serde_json::from_str(&str)?;
+
+        Ok(true)
+    }
+
+    pub async fn save_cached_mods(&self) -> Result<(), InstallError> {
+        let path = Config::mod_map_cache_path();
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string(&self.installed_mods)?).await?)
+    }
+
+    /// Downloads and verifies all artifacts first, so a bad artifact doesn't leave the mod half installed
+    async fn install_mod(&mut self, id: &IDVersion, location_override: Option<&Path>) -> Result<(), InstallError> {
+        let downloaded = self.download_artifacts(id, location_override).await?;
+        verify_artifacts(&downloaded)?;
+        self.write_artifacts(id, downloaded).await
+    }
+
+    /// Downloads all artifacts of the version into memory, nothing is written yet
+    pub async fn download_artifacts(&self, (mod_id, version): &IDVersion, location_override: Option<&Path>) -> Result<Vec<DownloadedArtifact>, InstallError> {
+        let mod_list = self.manifest_mods.mod_list.load_full();
+
+        let Some((mod_info, version_info)) = mod_list.get(mod_id).and_then(|x| Some((x, x.versions.get(version)?))) else {
+            return Err(InstallError::ModNotFound((mod_id.clone(), version.clone())))
+        };
+
+        let mut downloaded = vec![];
+        let mut bucket = self.bandwidth_limit.map(TokenBucket::new);
+
+        for artifact in &version_info.artifacts {
+            let Some(filename) = artifact.filename.clone().or_else(|| find_filename_from_url(&artifact.url, "")) else {
+                return Err(InstallError::InvalidArtifact(artifact.url.clone()))
+            };
+
+            let install_location = artifact_install_location(artifact, mod_info.install_category(), location_override, &self.default_locations);
+
+            if !is_safe_relative_path(&install_location) || !is_safe_file_name(&filename) {
+                return Err(InstallError::UnsafePath(install_location.join(filename)));
+            }
+
+            let mut path = self.location.clone();
+            append_relative_path(&mut path, install_location)?;
+            path.push(filename);
+
+            // Links inside of Neos folder could still lead outside of it
+            if !is_within(&self.location, &path)? {
+                return Err(InstallError::UnsafePath(path));
+            }
+
+            if path.exists() {
+                return Err(InstallError::FileAlreadyExists);
+            }
+
+            let (data, used_url) = match self.read_local(&artifact.sha256).await {
+                Some(data) => (data, artifact.url.clone()),
+                None => self.download_with_failover(&artifact.url, &artifact.sha256, &mut bucket).await?
+            };
+
+            downloaded.push(DownloadedArtifact {
+                path,
+                mirror: (used_url != artifact.url).then_some(used_url),
+                url: artifact.url.clone(),
+                expected_hash: artifact.sha256.clone(),
+                data,
+            });
+        }
+
+        Ok(downloaded)
+    }
+
+    /// Writes verified artifacts to disk and adds them to mod map, already written files are removed if any of them fails
+    pub async fn write_artifacts(&mut self, (mod_id, version): &IDVersion, downloaded: Vec<DownloadedArtifact>) -> Result<(), InstallError> {
+        let mut files: Vec<ModFileArtifact> = vec![];
+        let staged: Vec<PathBuf> = downloaded.iter()
+            .filter_map(|x| self.staged_path(&x.expected_hash))
+            .collect();
+
+        for artifact in downloaded {
+            let result: Result<(), io::Error> = async {
+                if let Some(folder) = artifact.path.parent() {
+                    fs::create_dir_all(folder).await?;
+                }
+
+                fs::write(&artifact.path, &artifact.data).await
+            }.await;
+
+            if let Err(e) = result {
+                for file in &files {
+                    fs::remove_file(&file.file_path).await.ok();
+                }
+
+                return Err(e.into());
+            }
+
+            // Cache checks hashes when reading, so a failed insert or a bad file only costs a download
+            if let Some(cache) = &self.cache {
+                cache.insert(&artifact.expected_hash, &artifact.data).await.ok();
+            }
+
+            let modified = fs::metadata(&artifact.path).await.ok()
+                .and_then(|x| x.modified().ok())
+                .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |x| x.as_secs());
+
+            files.push(ModFileArtifact {
+                file_path: artifact.path,
+                file_hash: artifact.expected_hash.to_lowercase(),
+                disabled: false,
+                size: artifact.data.len() as u64,
+                modified,
+                assembly_version: detect_assembly_version(&artifact.data),
+            });
+        }
+
+        self.installed_mods.insert(mod_id, version, ModFile { files });
+
+        for path in staged {
+            fs::remove_file(path).await.ok();
+        }
 
         Ok(())
     }
@@ -269,6 +1189,7 @@ impl ActualInstall {
         VirtualInstall {
             installed_mods: self.installed_mods.clone(),
             manifest_mods: self.manifest_mods.mod_list.load_full(),
+            default_locations: self.default_locations.clone(),
         }
     }
 }
@@ -279,14 +1200,34 @@ impl ModInstall for ActualInstall {
         &self.installed_mods
     }
 
+    fn default_locations(&self) -> &DefaultLocations {
+        &self.default_locations
+    }
+
     async fn perform_operations(&mut self, operations: &[ModInstallOperations]) -> Result<(), InstallError> {
         for op in operations {
             match op {
-                ModInstallOperations::InstallMod((id, version)) => {
-                    println!("Pretend am actually installing {}@{}", id, version)
+                ModInstallOperations::InstallMod(id) => {
+                    self.install_mod(id, None).await?;
+                }
+                ModInstallOperations::InstallModAt(id, location) => {
+                    self.install_mod(id, Some(location.as_path())).await?;
                 }
                 ModInstallOperations::UninstallMod((id, version)) => {
-                    println!("Pretend am actually uninstalling {}@{}", id, version)
+                    let Some(file) = self.installed_mods.file(id, version) else {
+                        return Err(InstallError::FileNotFound)
+                    };
+
+                    remove_artifacts(file).await?;
+
+                    self.installed_mods.remove(id, version);
+                }
+                ModInstallOperations::EnableMod((id, version)) | ModInstallOperations::DisableMod((id, version)) => {
+                    let Some(file) = self.installed_mods.file_mut(id, version) else {
+                        return Err(InstallError::FileNotFound)
+                    };
+
+                    set_artifacts_disabled(file, matches!(op, ModInstallOperations::DisableMod(_))).await?;
                 }
             }
         }
@@ -298,7 +1239,8 @@ impl ModInstall for ActualInstall {
 #[derive(Clone)]
 pub struct VirtualInstall {
     installed_mods: ModMap,
-    manifest_mods: Arc<ManifestMods>
+    manifest_mods: Arc<ManifestMods>,
+    default_locations: DefaultLocations
 }
 
 impl VirtualInstall {
@@ -306,6 +1248,7 @@ impl VirtualInstall {
         Self {
             installed_mods: mod_map,
             manifest_mods,
+            default_locations: Default::default(),
         }
     }
 }
@@ -316,26 +1259,40 @@ impl ModInstall for VirtualInstall {
         &self.installed_mods
     }
 
+    fn default_locations(&self) -> &DefaultLocations {
+        &self.default_locations
+    }
+
     async fn perform_operations(&mut self, operations: &[ModInstallOperations]) -> Result<(), InstallError> {
         for op in operations {
             match op {
                 ModInstallOperations::InstallMod ((mod_id, version))  => {
-                    let file = ModFile::new(mod_id, version, &self.manifest_mods);
+                    let file = ModFile::new(mod_id, version, &self.manifest_mods, &self.default_locations);
+
+                    self.installed_mods.insert(mod_id, version, file);
+                }
 
-                    let files = self.installed_mods.entry(mod_id.clone()).or_default();
+                ModInstallOperations::InstallModAt((mod_id, version), location) => {
+                    let file = ModFile::new_at(mod_id, version, &self.manifest_mods, Some(location), &self.default_locations);
 
-                    files.insert(version.clone(), file);
+                    self.installed_mods.insert(mod_id, version, file);
                 }
 
                 ModInstallOperations::UninstallMod((mod_id, version))  => {
-                    let Some(files) = self.installed_mods.get_mut(mod_id) else {
+                    if self.installed_mods.remove(mod_id, version).is_none() {
+                        return Err(InstallError::FileNotFound)
+                    }
+                }
+
+                ModInstallOperations::EnableMod((mod_id, version)) | ModInstallOperations::DisableMod((mod_id, version)) => {
+                    let Some(file) = self.installed_mods.file_mut(mod_id, version) else {
                         return Err(InstallError::FileNotFound)
                     };
 
-                    files.remove(version);
+                    let disabled = matches!(op, ModInstallOperations::DisableMod(_));
 
-                    if files.len() <= 0 {
-                        self.installed_mods.remove(mod_id);
+                    for artifact in &mut file.files {
+                        artifact.disabled = disabled;
                     }
                 }
             }
@@ -351,8 +1308,22 @@ pub enum InstallError {
     FileAlreadyExists,
     /// Happens when trying to uninstall a mod that already doesn't exist
     FileNotFound,
+    /// Happens when trying to install a mod or version that manifest doesn't have
+    ModNotFound(IDVersion),
+    /// Artifact URL doesn't have a file name and manifest doesn't provide one
+    InvalidArtifact(String),
+    /// Downloaded artifact doesn't match the hash in manifest
+    HashMismatch {
+        url: String,
+        expected: String,
+        found: String
+    },
+    DownloadError(TransportError),
     FileError(io::Error),
-    StripError(path::StripPrefixError)
+    StripError(path::StripPrefixError),
+    /// Install location or file name from manifest would put the file outside of Neos folder
+    UnsafePath(PathBuf),
+    JSONError(serde_json::Error)
 }
 
 impl Display for InstallError {
@@ -373,4 +1344,22 @@ impl From<path::StripPrefixError> for InstallError {
     fn from(value: StripPrefixError) -> Self {
         Self::StripError(value)
     }
+}
+
+impl From<reqwest::Error> for InstallError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::DownloadError(value.into())
+    }
+}
+
+impl From<TransportError> for InstallError {
+    fn from(value: TransportError) -> Self {
+        Self::DownloadError(value)
+    }
+}
+
+impl From<serde_json::Error> for InstallError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::JSONError(value)
+    }
 }
\ No newline at end of file
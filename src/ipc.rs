@@ -0,0 +1,185 @@
+use std::io;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+use std::process::Command;
+use std::time::Duration;
+use eframe::egui::Context;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc::Sender;
+use tokio::time::timeout;
+use crate::manager::{ManagerError, ManagerEvent};
+use crate::manifest::GUID;
+
+/// Scheme of links that mod websites can use, like neosmod://install/guid
+pub const URI_SCHEME: &str = "neosmod";
+
+/// Local port the running instance listens on for messages from instances started after it
+const INSTANCE_PORT: u16 = 48519;
+
+/// Longest message accepted from another instance
+const MAX_MESSAGE_SIZE: u64 = 4096;
+
+/// How long another instance gets to send its whole message
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Running instance greets with this first, so some other program on the port isn't taken for it
+const HANDSHAKE: &[u8] = b"neos-mod-organizer\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    Install(GUID),
+    /// Selects the mod in the installed mods list
+    Show(GUID)
+}
+
+/// Parses neosmod:// links, anything else or malformed gives None
+pub fn parse_deep_link(uri: &str) -> Option<DeepLink> {
+    let rest = uri.trim().strip_prefix(URI_SCHEME)?.strip_prefix("://")?;
+    let mut segments = rest.split('/').filter(|x| !x.is_empty());
+
+    match (segments.next()?, segments.next(), segments.next()) {
+        ("install", Some(guid), None) if is_valid_guid(guid) => Some(DeepLink::Install(guid.to_string())),
+        ("show", Some(guid), None) if is_valid_guid(guid) => Some(DeepLink::Show(guid.to_string())),
+        _ => None
+    }
+}
+
+/// Mod GUIDs are reverse domain names, like com.author.mod
+fn is_valid_guid(guid: &str) -> bool {
+    guid.len() <= 256 && guid.chars().all(|x| x.is_ascii_alphanumeric() || matches!(x, '.' | '-' | '_'))
+}
+
+/// Deep links in command line arguments, browsers pass the link as an argument when opening it
+pub fn deep_links_in(arguments: &[String]) -> Vec<DeepLink> {
+    arguments.iter()
+        .filter_map(|x| parse_deep_link(x))
+        .collect()
+}
+
+fn instance_address() -> SocketAddr {
+    SocketAddr::from((Ipv4Addr::LOCALHOST, INSTANCE_PORT))
+}
+
+/// Listener for messages from other instances, None if some other instance already has it
+pub fn claim_instance() -> Option<TcpListener> {
+    TcpListener::bind(instance_address()).ok()
+}
+
+/// Hands command line arguments to the instance that's already running, which focuses its window.
+/// Arguments go one per line, no arguments only asks for focus
+pub fn forward_to_running(arguments: &[String]) -> io::Result<()> {
+    forward_message(&instance_address(), arguments)
+}
+
+/// Sends arguments only after whoever is listening greets like an organizer
+pub fn forward_message(address: &SocketAddr, arguments: &[String]) -> io::Result<()> {
+    let mut stream = TcpStream::connect_timeout(address, Duration::from_secs(2))?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+    let mut greeting = [0; HANDSHAKE.len()];
+    stream.read_exact(&mut greeting)?;
+
+    if greeting != HANDSHAKE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Something other than the organizer is listening"));
+    }
+
+    stream.write_all(arguments.join("\n").as_bytes())?;
+    stream.flush()
+}
+
+/// Receives arguments forwarded by other instances and sends them to UI
+pub async fn listen_for_messages(listener: TcpListener, sender: Sender<ManagerEvent>, ctx: Context) {
+    let listener = match listener.set_nonblocking(true).and_then(|_| tokio::net::TcpListener::from_std(listener)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            sender.send(ManagerEvent::Error(ManagerError::Other(format!("Failed to listen for other instances: {}", e)))).await.ok();
+            ctx.request_repaint();
+            return;
+        }
+    };
+
+    while !sender.is_closed() {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+
+        // Connection that never finishes sending can't hold up the ones after it
+        tokio::spawn(receive_message(stream, sender.clone(), ctx.clone()));
+    }
+}
+
+async fn receive_message(mut stream: tokio::net::TcpStream, sender: Sender<ManagerEvent>, ctx: Context) {
+    let mut message = String::new();
+
+    let exchange = async {
+        stream.write_all(HANDSHAKE).await?;
+        (&mut stream).take(MAX_MESSAGE_SIZE).read_to_string(&mut message).await
+    };
+
+    match timeout(MESSAGE_TIMEOUT, exchange).await {
+        Ok(Ok(_)) => {}
+        _ => return
+    }
+
+    let arguments: Vec<String> = message.lines().map(|x| x.to_string()).collect();
+    let events = std::iter::once(ManagerEvent::FocusRequested)
+        .chain(deep_links_in(&arguments).into_iter().map(ManagerEvent::DeepLink));
+
+    for event in events {
+        if sender.send(event).await.is_err() {
+            return;
+        }
+    }
+
+    ctx.request_repaint();
+}
+
+/// Makes links with the scheme open the organizer
+#[cfg(target_os = "windows")]
+pub fn register_uri_scheme() -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let key = format!("HKCU\\Software\\Classes\\{}", URI_SCHEME);
+    let open_command = format!("\"{}\" \"%1\"", exe.to_string_lossy());
+
+    run_checked(Command::new("reg").args(["add", &key, "/ve", "/d", "URL:Neos Mod Organizer", "/f"]))?;
+    run_checked(Command::new("reg").args(["add", &key, "/v", "URL Protocol", "/d", "", "/f"]))?;
+    run_checked(Command::new("reg").args(["add", &format!("{}\\shell\\open\\command", key), "/ve", "/d", &open_command, "/f"]))
+}
+
+/// Makes links with the scheme open the organizer
+#[cfg(target_os = "linux")]
+pub fn register_uri_scheme() -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let folder = dirs::data_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data folder to put desktop entry in"))?
+        .join("applications");
+
+    let file_name = format!("neos-mod-organizer-{}.desktop", URI_SCHEME);
+
+    std::fs::create_dir_all(&folder)?;
+    std::fs::write(folder.join(&file_name), format!(
+        "[Desktop Entry]\nType=Application\nName=Neos Mod Organizer\nExec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        exe.to_string_lossy(),
+        URI_SCHEME
+    ))?;
+
+    run_checked(Command::new("xdg-mime").args(["default", &file_name, &format!("x-scheme-handler/{}", URI_SCHEME)]))
+}
+
+/// Makes links with the scheme open the organizer
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn register_uri_scheme() -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn run_checked(command: &mut Command) -> io::Result<()> {
+    let status = command.status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::Other, format!("{:?} failed with {}", command, status)))
+    }
+}
@@ -1,3 +1,4 @@
+use std::io;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -6,6 +7,13 @@ use mslnk::{MSLinkError, ShellLink};
 
 use serde::{Serialize, Deserialize};
 use strum_macros::{Display, EnumIter};
+use crate::config::Config;
+use crate::install::{IDVersion, ModConflict, ModMap};
+use crate::manifest::{GUID, ManifestMods};
+use crate::utils::detect_assembly_version;
+
+/// Resolutions outside of this range are most likely typos
+const SANE_RESOLUTION: std::ops::RangeInclusive<i32> = 320..=16384;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct LaunchOptions {
@@ -144,12 +152,12 @@ impl LaunchOptions {
         }
 
         if self.announce_home_on_lan {
-            args.push((format!("-ForceSRAnipal"), false));
+            args.push((format!("-AnnounceHomeOnLAN"), false));
         }
 
         if let Some(bootstrap) = &self.bootstrap {
             args.push((format!("-Bootstrap"), false));
-            args.push((bootstrap.to_string(), false));
+            args.push((bootstrap.to_string(), true));
         }
 
         if self.force_lan {
@@ -295,6 +303,159 @@ impl LaunchOptions {
         args
     }
 
+    /// Arguments joined into a single command line, as it would be in a shortcut
+    pub fn build_argument_string(&self) -> String {
+        self.build_arguments().into_iter()
+            .map(|(arg, quotes)| {
+                if quotes {
+                    format!("\"{}\"", arg)
+                } else {
+                    arg
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Reads options back from a command line, inverse of build_argument_string
+    pub fn parse_arguments(line: &str) -> Self {
+        Self::from_arguments(&split_arguments(line))
+    }
+
+    /// Reads options back from Neos arguments, anything that isn't recognized is skipped
+    pub fn from_arguments(args: &[String]) -> Self {
+        let mut options = Self {
+            use_mods: false,
+            ..Default::default()
+        };
+
+        let mut args = args.iter().peekable();
+
+        while let Some(arg) = args.next() {
+            let flag = arg.to_lowercase();
+
+            // Flags that take a value, value is skipped if it's missing. Negative numbers are still values
+            let mut value = || args.next_if(|x| !x.starts_with('-') || x.parse::<f64>().is_ok()).cloned();
+
+            match flag.as_str() {
+                "-steamvr" => options.device = Device::SteamVR,
+                "-legacysteamvrinput" => options.device = Device::LegacySteamVR,
+                "-rifttouch" => options.device = Device::Oculus,
+                "-screen" => options.device = Device::Desktop,
+                "-legacyscreen" => options.device = Device::LegacyDesktop,
+                "-screen360" => options.device = Device::Screen360,
+                "-staticcamera" => options.device = Device::CameraMode,
+                "-staticcamera360" => options.device = Device::Camera360Mode,
+                "-mixedrealitycamera" => options.device = Device::MixedReality,
+                "-forcesranipal" => options.force_sr_anipal = true,
+                "-enableowo" => options.enable_owo = value(),
+                "-loadassembly" => {
+                    if let Some(assembly) = value() {
+                        if assembly.to_lowercase().ends_with("neosmodloader.dll") {
+                            options.use_mods = true;
+                        } else {
+                            options.load_assembly.push(assembly);
+                        }
+                    }
+                }
+                "-join" => {
+                    options.auto_join = match value() {
+                        Some(address) if address.eq_ignore_ascii_case("auto") => JoinOptions::JoinAuto,
+                        Some(address) => JoinOptions::Join(address),
+                        None => JoinOptions::None
+                    };
+                }
+                "-open" => options.auto_join = value().map_or(JoinOptions::None, JoinOptions::Open),
+                "-announcehomeonlan" => options.announce_home_on_lan = true,
+                "-bootstrap" => options.bootstrap = value(),
+                "-forcelanonly" => options.force_lan = true,
+                "-forcerelay" => options.force_relay = true,
+                "-uselocalcloud" => options.use_local_cloud = true,
+                "-usestagingcloud" => options.use_staging_cloud = true,
+                "-camerabiggestgroup" => options.drone_camera = DroneCamera::CameraBiggestGroup,
+                "-cameratimelapse" => options.drone_camera = DroneCamera::CameraTimelapse,
+                "-camerastaybehind" => options.drone_camera = DroneCamera::CameraStayBehind,
+                "-camerastayinfront" => options.drone_camera = DroneCamera::CameraStayInFront,
+                "-useneoscamera" => options.use_neos_camera = true,
+                "-forcenovoice" => options.force_no_voice = true,
+                "-datapath" => options.data_path = value().map(PathBuf::from),
+                "-cachepath" => options.cache_path = value().map(PathBuf::from),
+                "-deleteunsyncedcloudrecords" => options.delete_unsynced_cloud_records = true,
+                "-forcesyncconflictingcloudrecords" => options.force_sync_conflicting_cloud_records = true,
+                "-repairdatabase" => options.repair_database = true,
+                "-ctaa" => {
+                    options.ctaa.get_or_insert_with(Default::default);
+                }
+                "-ctaatemporaledgepower" => {
+                    options.ctaa.get_or_insert_with(Default::default).temporal_edge_power = value().and_then(|x| x.parse().ok());
+                }
+                "-ctaaaptivesharpness" => {
+                    options.ctaa.get_or_insert_with(Default::default).aptive_sharpness = value().and_then(|x| x.parse().ok());
+                }
+                "-ctaasharpnessenabled" => {
+                    options.ctaa.get_or_insert_with(Default::default).sharpness_enabled = value().map_or(false, |x| x.eq_ignore_ascii_case("true"));
+                }
+                "-watchdog" => options.watchdog = value().map(PathBuf::from),
+                "-kiosk" => options.kiosk = true,
+                "-noui" => options.no_ui = true,
+                "-dontautoopencloudhome" => options.do_not_auto_load_cloud_home = true,
+                "-resetdash" => options.reset_dash = true,
+                "-skipintrotutorial" => options.skip_intro_tutorial = true,
+                "-forceintrotutorial" => options.force_intro_tutorial = true,
+                "-invisible" => options.invisible = true,
+                "-config" => options.config = value().map(PathBuf::from),
+                "-forcereticleabovehorizon" => options.force_reticle_above_horizon = true,
+                "-screen-fullscreen" => {
+                    options.display_mode = match value().as_deref() {
+                        Some("0") => WindowType::Windowed,
+                        Some("1") => WindowType::FullScreen,
+                        _ => WindowType::Auto
+                    };
+                }
+                "-screen-width" => options.resolution_width = value().and_then(|x| x.parse().ok()),
+                "-screen-height" => options.resolution_height = value().and_then(|x| x.parse().ok()),
+                _ => {}
+            }
+        }
+
+        options
+    }
+
+    /// Data folder the game is going to use, None if it isn't known
+    pub fn effective_data_path(&self) -> Option<PathBuf> {
+        self.data_path.clone().or_else(default_data_path)
+    }
+
+    /// Cache folder the game is going to use, None if it isn't known
+    pub fn effective_cache_path(&self) -> Option<PathBuf> {
+        self.cache_path.clone().or_else(default_cache_path)
+    }
+
+    /// Problems that would make the game launch wrong, mod conflicts are checked separately
+    pub fn check(&self, neos_folder: &Path) -> Vec<LaunchProblem> {
+        let mut problems = vec![];
+
+        if self.use_mods && !mod_loader_path(neos_folder).exists() {
+            problems.push(LaunchProblem::ModLoaderMissing);
+        }
+
+        if let Some(path) = self.data_path.as_ref().filter(|x| !x.exists()) {
+            problems.push(LaunchProblem::DataPathMissing(path.clone()));
+        }
+
+        if let Some(path) = self.cache_path.as_ref().filter(|x| !x.exists()) {
+            problems.push(LaunchProblem::CachePathMissing(path.clone()));
+        }
+
+        let resolution = [self.resolution_width, self.resolution_height];
+
+        if resolution.iter().flatten().any(|x| !SANE_RESOLUTION.contains(x)) {
+            problems.push(LaunchProblem::InvalidResolution(self.resolution_width, self.resolution_height));
+        }
+
+        problems
+    }
+
     pub fn build_command(&self, neos_path: impl AsRef<Path>) -> Command {
         let args = self.build_arguments().into_iter()
             .map(|(arg, _)| arg)
@@ -315,17 +476,7 @@ impl LaunchOptions {
         let neos_path = neos_path.as_ref();
         let shortcut_path = shortcut_path.as_ref();
 
-        let args = self.build_arguments().into_iter()
-            .map(|(arg, quotes)| {
-                if quotes {
-                    format!("\"{}\"", arg)
-                } else {
-                    arg
-                }
-            })
-            .collect::<Vec<String>>();
-
-        let arg_str = args.join(" ");
+        let arg_str = self.build_argument_string();
 
         let mut link = ShellLink::new(neos_path)?;
 
@@ -359,6 +510,120 @@ impl Default for Device {
     }
 }
 
+/// Where Neos keeps its data when -DataPath isn't set
+#[cfg(target_os="windows")]
+pub fn default_data_path() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join("AppData").join("LocalLow").join("Solirax").join("NeosVR"))
+}
+
+/// Game runs in Proton elsewhere, its folders are inside of the prefix
+#[cfg(not(target_os="windows"))]
+pub fn default_data_path() -> Option<PathBuf> {
+    None
+}
+
+/// Where Neos keeps its cache when -CachePath isn't set
+#[cfg(target_os="windows")]
+pub fn default_cache_path() -> Option<PathBuf> {
+    Some(std::env::temp_dir().join("Solirax").join("NeosVR"))
+}
+
+#[cfg(not(target_os="windows"))]
+pub fn default_cache_path() -> Option<PathBuf> {
+    None
+}
+
+/// Where NeosModLoader is loaded from, game is told to load it with -LoadAssembly
+pub fn mod_loader_path(neos_folder: &Path) -> PathBuf {
+    neos_folder.join("Libraries").join("NeosModLoader.dll")
+}
+
+/// Assembly version of installed NeosModLoader, None if it isn't installed
+pub fn mod_loader_version(neos_folder: &Path) -> Option<String> {
+    std::fs::read(mod_loader_path(neos_folder)).ok()
+        .and_then(|x| detect_assembly_version(&x))
+}
+
+/// Neos build version, FrooxEngine carries it as its assembly version
+pub fn neos_version(neos_folder: &Path) -> Option<String> {
+    std::fs::read(neos_folder.join("Neos_Data").join("Managed").join("FrooxEngine.dll")).ok()
+        .and_then(|x| detect_assembly_version(&x))
+}
+
+/// Something found wrong before launching the game
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchProblem {
+    /// Mods are enabled, but NeosModLoader isn't installed
+    ModLoaderMissing,
+    /// Several versions of the mod are enabled
+    VersionConflict(GUID),
+    /// Mods conflict directly, second one can be disabled for the launch
+    DirectConflict {
+        keep: IDVersion,
+        disable: IDVersion
+    },
+    /// Any other conflict that's serious enough to break the game
+    Conflict(ModConflict),
+    DataPathMissing(PathBuf),
+    CachePathMissing(PathBuf),
+    InvalidResolution(Option<i32>, Option<i32>)
+}
+
+/// Named launch options for running another instance next to the main one, like a camera account
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LaunchProfile {
+    pub name: String,
+    pub options: LaunchOptions
+}
+
+/// Quick launch modes, applied over saved launch options only for a single launch
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum LaunchPreset {
+    VR,
+    Desktop,
+    Streaming,
+    Headless
+}
+
+impl LaunchPreset {
+    /// Key used for localization
+    pub fn key(&self) -> &'static str {
+        match self {
+            LaunchPreset::VR => "vr",
+            LaunchPreset::Desktop => "desktop",
+            LaunchPreset::Streaming => "streaming",
+            LaunchPreset::Headless => "headless",
+        }
+    }
+
+    /// Options with the preset applied, everything the preset doesn't care about is kept
+    pub fn apply(&self, options: &LaunchOptions) -> LaunchOptions {
+        let mut options = options.clone();
+
+        match self {
+            LaunchPreset::VR => {
+                options.device = Device::SteamVR;
+                options.no_ui = false;
+            }
+            LaunchPreset::Desktop => {
+                options.device = Device::Desktop;
+                options.no_ui = false;
+            }
+            LaunchPreset::Streaming => {
+                options.device = Device::CameraMode;
+                options.use_neos_camera = true;
+            }
+            LaunchPreset::Headless => {
+                options.device = Device::Desktop;
+                options.no_ui = true;
+                options.invisible = true;
+            }
+        }
+
+        options
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum JoinOptions {
     None,
@@ -401,3 +666,68 @@ pub enum WindowType {
     Windowed,
     FullScreen
 }
+
+/// Splits command line into arguments, keeping quoted parts together
+pub fn split_arguments(line: &str) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut has_argument = false;
+
+    for character in line.chars() {
+        match character {
+            '"' => {
+                quoted = !quoted;
+                has_argument = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if has_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    has_argument = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_argument = true;
+            }
+        }
+    }
+
+    if has_argument {
+        arguments.push(current);
+    }
+
+    arguments
+}
+
+/// Asks process to close like closing its window would, so game gets a chance to save
+pub fn request_graceful_exit(pid: u32) -> io::Result<()> {
+    #[cfg(target_os="windows")]
+    let status = Command::new("taskkill").args(["/PID", &pid.to_string()]).status()?;
+    #[cfg(not(target_os="windows"))]
+    let status = Command::new("kill").args(["-TERM", &pid.to_string()]).status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::Other, format!("Asking process {} to exit failed with {}", pid, status)))
+    }
+}
+
+/// LoadAssembly values for enabled plugin mods, in load order and relative to Neos folder
+pub fn plugin_assemblies(mod_map: &ModMap, mods: &ManifestMods, config: &Config, neos_folder: &Path) -> Vec<String> {
+    let mut plugins: Vec<GUID> = mod_map.keys()
+        .filter(|x| mods.get(*x).map_or(false, |x| x.is_plugin()))
+        .cloned()
+        .collect();
+
+    config.sort_by_load_order(&mut plugins);
+
+    plugins.iter()
+        .flat_map(|guid| mod_map.installed_versions(guid).into_iter().filter_map(|version| mod_map.file(guid, version)))
+        .filter(|x| x.is_enabled())
+        .flat_map(|x| x.files.iter())
+        .filter(|x| x.file_path.extension().map_or(false, |x| x.eq_ignore_ascii_case("dll")))
+        .map(|x| x.file_path.strip_prefix(neos_folder).unwrap_or(&x.file_path).to_string_lossy().to_string())
+        .collect()
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use crate::config::Config;
+use crate::install::{InstallError, ModFile};
+use crate::manifest::Category;
+use crate::version::Version;
+
+/// What's known about a file that isn't in any manifest, recorded when it was installed or entered by user
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct LocalModInfo {
+    pub name: Option<String>,
+    pub version: Option<Version>,
+    /// Where the file came from, like a GitHub repository
+    pub source: Option<String>,
+    pub category: Option<Category>,
+    pub notes: Option<String>
+}
+
+/// Parts of LocalModInfo that user edits, the rest stays as it was recorded
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LocalModDetails {
+    pub name: Option<String>,
+    pub category: Option<Category>,
+    pub notes: Option<String>
+}
+
+impl LocalModInfo {
+    pub fn set_details(&mut self, details: LocalModDetails) {
+        self.name = details.name;
+        self.category = details.category;
+        self.notes = details.notes;
+    }
+}
+
+/// Info about unmanaged files by their hash, so it stays with the file if it's moved or renamed
+#[derive(Serialize, Deserialize, Default)]
+pub struct LocalMods {
+    pub mods: HashMap<String, LocalModInfo>
+}
+
+impl LocalMods {
+    pub async fn load() -> Result<LocalMods, InstallError> {
+        let path = Config::local_mods_path();
+
+        if !path.exists() {
+            return Ok(LocalMods::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    /// Info that ends up empty is dropped, so the file goes back to showing its file name
+    pub fn set_details(&mut self, hash: &str, details: LocalModDetails) {
+        let hash = hash.to_lowercase();
+        let info = self.mods.entry(hash.clone()).or_default();
+        info.set_details(details);
+
+        if *info == LocalModInfo::default() {
+            self.mods.remove(&hash);
+        }
+    }
+
+    pub async fn save(&self) -> Result<(), InstallError> {
+        let path = Config::local_mods_path();
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string(self)?).await?)
+    }
+}
+
+/// Hash info of the mod goes by, the first file that has any or just the first file
+pub fn local_key_of(local_mods: &HashMap<String, LocalModInfo>, file: &ModFile) -> Option<String> {
+    let hashes: Vec<String> = file.files.iter().map(|x| x.file_hash.to_lowercase()).collect();
+
+    hashes.iter().find(|x| local_mods.contains_key(*x)).or(hashes.first()).cloned()
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use once_cell::sync::Lazy;
+use crate::config::{Config, ConfigError};
+
+/// Translation key to translated string
+pub type LocaleBundle = HashMap<String, String>;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+static ENGLISH: Lazy<LocaleBundle> = Lazy::new(|| {
+    serde_json::from_str(include_str!("../locales/en.json")).expect("Built-in english locale is invalid")
+});
+
+static CURRENT: Lazy<ArcSwap<LocaleBundle>> = Lazy::new(|| ArcSwap::from_pointee(HashMap::new()));
+
+pub fn default_language() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
+
+/// Folder where additional language bundles are looked up, named as `<language code>.json`
+pub fn locales_path() -> PathBuf {
+    let mut path = Config::config_path();
+    path.pop();
+    path.push("locales");
+    path
+}
+
+/// Language codes of all bundles that can be loaded, english is always available
+pub fn available_languages() -> Vec<String> {
+    let mut languages = vec![default_language()];
+
+    if let Ok(directory) = std::fs::read_dir(locales_path()) {
+        for entry in directory.flatten() {
+            let path = entry.path();
+
+            if path.extension().map_or(false, |x| x == "json") {
+                if let Some(stem) = path.file_stem() {
+                    let code = stem.to_string_lossy().to_string();
+
+                    if !languages.contains(&code) {
+                        languages.push(code);
+                    }
+                }
+            }
+        }
+    }
+
+    languages.sort();
+    languages
+}
+
+pub fn load_bundle(language: &str) -> Result<LocaleBundle, ConfigError> {
+    let mut path = locales_path();
+    path.push(format!("{}.json", language));
+
+    let str = std::fs::read_to_string(path)?;
+
+    Ok(serde_json::from_str(&str)?)
+}
+
+/// Switches UI language, strings missing from the bundle fall back to english
+pub fn set_language(language: &str) -> Result<(), ConfigError> {
+    let bundle = if language == DEFAULT_LANGUAGE {
+        HashMap::new()
+    } else {
+        load_bundle(language)?
+    };
+
+    CURRENT.store(Arc::new(bundle));
+
+    Ok(())
+}
+
+/// Translates the key using current language
+pub fn tr(key: &str) -> String {
+    if let Some(value) = CURRENT.load().get(key) {
+        return value.clone();
+    }
+
+    ENGLISH.get(key).cloned().unwrap_or_else(|| key.to_string())
+}
+
+/// Translates the key and replaces `{name}` placeholders with provided values
+pub fn tr_args(key: &str, args: &[(&str, String)]) -> String {
+    let mut text = tr(key);
+
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+
+    text
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use crate::install::{IDVersion, InstallError, ModInstallOperations, ModMap};
+use crate::manifest::{GUID, ManifestMods};
+use crate::version::Version;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LockedMod {
+    pub version: Version,
+    /// Hashes of the mod's files, sorted
+    pub hashes: Vec<String>
+}
+
+/// Exact versions of installed mods from the manifest, so the same setup can be brought back or made on another machine.
+/// Mods the manifest doesn't know about can't be installed again, so they aren't in it
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Lockfile {
+    pub mods: BTreeMap<GUID, LockedMod>
+}
+
+/// What it takes for the install to match the lockfile
+#[derive(Clone, Debug, Default)]
+pub struct LockfileSync {
+    /// Uninstalls go first, so installed files don't run into the ones being replaced
+    pub operations: Vec<ModInstallOperations>,
+    /// Locked versions no manifest has anymore, whatever is installed of them is left alone
+    pub unavailable: Vec<IDVersion>
+}
+
+impl Lockfile {
+    /// Newest installed version of every mod the manifest knows about
+    pub fn from_mod_map(mod_map: &ModMap, mod_list: &ManifestMods) -> Self {
+        let mods = mod_map.keys()
+            .filter(|x| mod_list.contains_key(*x))
+            .filter_map(|mod_id| {
+                let version = mod_map.installed_version(mod_id)?;
+                let file = mod_map.file(mod_id, version)?;
+
+                let mut hashes: Vec<String> = file.files.iter()
+                    .map(|x| x.file_hash.clone())
+                    .collect();
+                hashes.sort();
+
+                Some((mod_id.clone(), LockedMod {
+                    version: version.clone(),
+                    hashes,
+                }))
+            })
+            .collect();
+
+        Self {
+            mods
+        }
+    }
+
+    pub async fn load(path: &Path) -> Result<Lockfile, InstallError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    /// Kept readable, so it can be shared and diffed
+    pub async fn save(&self, path: &Path) -> Result<(), InstallError> {
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string_pretty(self)?).await?)
+    }
+
+    /// Installs locked versions that aren't there, reinstalls ones with files that changed, and uninstalls manifest mods that aren't locked
+    pub fn sync_operations(&self, mod_map: &ModMap, mod_list: &ManifestMods) -> LockfileSync {
+        let mut sync = LockfileSync::default();
+        let mut installs = vec![];
+
+        let mut unlocked: Vec<&GUID> = mod_map.keys()
+            .filter(|x| mod_list.contains_key(*x) && !self.mods.contains_key(*x))
+            .collect();
+        unlocked.sort();
+
+        for mod_id in unlocked {
+            for version in mod_map.installed_versions(mod_id) {
+                sync.operations.push(ModInstallOperations::UninstallMod((mod_id.clone(), version.clone())));
+            }
+        }
+
+        for (mod_id, locked) in &self.mods {
+            let installed = mod_map.installed_versions(mod_id);
+
+            let matches = installed.len() == 1 && *installed[0] == locked.version && mod_map.file(mod_id, &locked.version).map_or(false, |file| {
+                let mut hashes: Vec<&String> = file.files.iter().map(|x| &x.file_hash).collect();
+                hashes.sort();
+                hashes.into_iter().eq(locked.hashes.iter())
+            });
+
+            if matches {
+                continue;
+            }
+
+            if !mod_list.get(mod_id).map_or(false, |x| x.versions.contains_key(&locked.version)) {
+                sync.unavailable.push((mod_id.clone(), locked.version.clone()));
+                continue;
+            }
+
+            for version in installed {
+                sync.operations.push(ModInstallOperations::UninstallMod((mod_id.clone(), version.clone())));
+            }
+
+            installs.push(ModInstallOperations::InstallMod((mod_id.clone(), locked.version.clone())));
+        }
+
+        sync.operations.extend(installs);
+
+        sync
+    }
+}
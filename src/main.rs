@@ -9,18 +9,42 @@ mod manifest;
 mod version;
 mod install;
 mod resolver;
+mod locale;
+mod history;
+mod modpack;
+mod trash;
+mod import;
+mod ipc;
+mod tray;
+mod notification;
+mod readme;
+mod feed;
+mod transport;
+mod planner;
+mod fixes;
+mod queue;
+mod cache;
+mod maintenance;
+mod github;
+mod local_mods;
+mod notes;
+mod lockfile;
+mod cloud_sync;
 
 #[cfg(test)]
 mod tests;
 
 
+use std::net::TcpListener;
 use std::path::{Component, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use std::thread;
+use std::time::Duration;
 use arc_swap::ArcSwap;
 use eframe::{App, CreationContext, Frame, NativeOptions, run_native};
-use eframe::egui::{Align2, CentralPanel, Color32, Context, Direction, FontId, Style, TextStyle, Vec2, Window};
+use eframe::egui::{Align2, Area, CentralPanel, Color32, Context, Direction, FontId, Frame as EguiFrame, Id, LayerId, Order, Pos2, Style, TextStyle, Vec2, Window};
 use eframe::egui::FontFamily;
 use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
 use tokio::runtime;
@@ -28,25 +52,54 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::time::Instant;
 use manager::{ManagerCommand, ManagerEvent};
-use crate::config::{Config, ConfigError};
+use crate::config::{clamp_ui_scale, Config, ConfigError, WindowState};
+use crate::ipc::{claim_instance, deep_links_in, DeepLink, forward_to_running, listen_for_messages, register_uri_scheme};
 use crate::manager::{Manager, validate_path};
+use crate::locale::{set_language, tr};
 use crate::manifest::GlobalModList;
 use crate::ui::first_time::{first_time_ui, FirstTimeState};
 use crate::ui::manager::{manager_ui, ManagerTabs, UIManagerState};
 use crate::ui::manager::mod_list::ModListState;
+use crate::ui::manager::tour::TourState;
+use crate::tray::{Tray, TrayAction};
+use crate::utils::handle_error;
 use crate::version::Version;
 
 
 fn main() {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let listener = claim_instance();
+
+    // Organizer is already open, two of them would fight over mods folder.
+    // If it can't be reached or doesn't greet back then something else is using the port and starting is fine
+    if listener.is_none() && forward_to_running(&arguments).is_ok() {
+        return;
+    }
+
+    // Keeps links pointing at wherever the organizer is now, links just don't open it if that fails
+    thread::spawn(|| register_uri_scheme().ok());
+
+    let deep_link = deep_links_in(&arguments).into_iter().next();
+
     let mut native_options = NativeOptions::default();
 
     native_options.min_window_size = Some(Vec2::new(900.0, 700.0));
 
+    if let Ok(config) = Config::load_config_sync() {
+        if let Some((width, height)) = config.window_state.size {
+            native_options.initial_window_size = Some(Vec2::new(width, height));
+        }
+
+        if let Some((x, y)) = config.window_state.position {
+            native_options.initial_window_pos = Some(Pos2::new(x, y));
+        }
+    }
+
     run_native(
         "Neos Mod Organizer",
         native_options,
         Box::new(|cc|
-            Box::new(UIApp::new(cc))
+            Box::new(UIApp::new(cc, listener, deep_link))
         )
     ).unwrap();
 }
@@ -58,10 +111,23 @@ pub struct UIApp {
     manager_commander: Option<Sender<ManagerCommand>>,
     manager_events: Option<Receiver<ManagerEvent>>,
     config: Option<Arc<ArcSwap<Config>>>,
+    window_state: WindowState,
+    shutdown_started: Option<Instant>,
+    shutdown_finished: bool,
+    /// Taken by the manager runtime once it starts
+    instance_listener: Option<TcpListener>,
+    /// Link the organizer was opened with, shown once manager UI is up
+    pending_deep_link: Option<DeepLink>,
+    tray: Option<Tray>,
+    /// Window was minimized to tray and is invisible
+    hidden_to_tray: bool,
 
     reset_timer: Instant
 }
 
+/// How long to wait for manager to finish up before closing anyway
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(15);
+
 pub enum UIState {
     FirstTime(FirstTimeState),
     Manager(UIManagerState),
@@ -69,25 +135,35 @@ pub enum UIState {
 }
 
 impl UIApp {
-    fn init_manager(&mut self, global_mods: GlobalModList) {
+    fn init_manager(&mut self, global_mods: GlobalModList, ctx: &Context) {
         let (command_s, command_r) = mpsc::channel::<ManagerCommand>(15);
         let (event_s, event_r) = mpsc::channel::<ManagerEvent>(15);
 
-        let mut manager = Manager::new(command_r, event_s, self.config.clone().unwrap(), global_mods);
+        let listener = self.instance_listener.take();
+        let instance_events = event_s.clone();
+        let ctx = ctx.clone();
+
+        let mut manager = Manager::new(command_r, event_s, ctx.clone(), self.config.clone().unwrap(), global_mods);
 
         thread::spawn(move || {
             runtime::Builder::new_multi_thread()
                 .enable_all()
                 .build()
                 .unwrap()
-                .block_on(manager.run_event_loop())
+                .block_on(async move {
+                    if let Some(listener) = listener {
+                        tokio::spawn(listen_for_messages(listener, instance_events, ctx));
+                    }
+
+                    manager.run_event_loop().await
+                })
         });
 
         self.manager_commander = Some(command_s);
         self.manager_events = Some(event_r);
     }
 
-    fn new(cc: &CreationContext<'_>) -> Self {
+    fn new(cc: &CreationContext<'_>, listener: Option<TcpListener>, deep_link: Option<DeepLink>) -> Self {
         // Styles
         let mut style = (*cc.egui_ctx.style()).clone();
 
@@ -116,27 +192,42 @@ impl UIApp {
 
         match Config::load_config_sync() {
             Ok(c) => {
+                if let Err(e) = set_language(&c.language) {
+                    toast.add(Toast {
+                        kind: ToastKind::Error,
+                        text: format!("Failed to load language \"{}\"\n{}", c.language, e).into(),
+                        options: ToastOptions::default()
+                            .duration_in_seconds(5.0)
+                            .show_progress(true),
+                    });
+                }
+
                 if validate_path(&c.neos_exe_location) {
                     let mods = GlobalModList::empty();
 
                     let mut instance = Self {
                         toast,
-                        state: UIState::Manager(UIManagerState {
-                            current_tab: ManagerTabs::Launcher,
-                            launcher_state: Default::default(),
-                            mod_list_state: ModListState::from_context(&cc.egui_ctx),
-                            test_state: Default::default(),
-                            manifest_mods: mods.clone(),
-                            mod_list: Default::default(),
+                        state: UIState::Manager({
+                            let mut manager_state = UIManagerState::from_context(&cc.egui_ctx, mods.clone());
+                            manager_state.current_tab = c.window_state.last_tab;
+                            manager_state.tour_state = TourState::new(!c.tour_completed);
+                            manager_state
                         }),
                         popup: None,
                         manager_commander: None,
                         manager_events: None,
                         config: Some(Arc::new(ArcSwap::new(Arc::new(c)))),
+                        window_state: Default::default(),
+                        shutdown_started: None,
+                        shutdown_finished: false,
+                        instance_listener: listener,
+                        pending_deep_link: deep_link,
+                        tray: Tray::new(),
+                        hidden_to_tray: false,
                         reset_timer: Instant::now(),
                     };
 
-                    instance.init_manager(mods);
+                    instance.init_manager(mods, &cc.egui_ctx);
 
                     instance
                 } else {
@@ -155,11 +246,20 @@ impl UIApp {
                             neos_path: "".to_string(),
                             picker_dialog: None,
                             config: Some(c),
+                            existing_setup: None,
+                            import_launch_options: None,
                         }),
                         popup: None,
                         manager_commander: None,
                         manager_events: None,
                         config: None,
+                        window_state: Default::default(),
+                        shutdown_started: None,
+                        shutdown_finished: false,
+                        instance_listener: listener,
+                        pending_deep_link: deep_link,
+                        tray: Tray::new(),
+                        hidden_to_tray: false,
                         reset_timer: Instant::now(),
                     }
                 }
@@ -174,7 +274,14 @@ impl UIApp {
                             manager_commander: None,
                             manager_events: None,
                             config: None,
-                            reset_timer: Instant::now(),
+                            window_state: Default::default(),
+                        shutdown_started: None,
+                        shutdown_finished: false,
+                        instance_listener: listener,
+                        pending_deep_link: deep_link,
+                        tray: Tray::new(),
+                        hidden_to_tray: false,
+                        reset_timer: Instant::now(),
                         }
                     }
                     _ => {
@@ -185,7 +292,14 @@ impl UIApp {
                             manager_commander: None,
                             manager_events: None,
                             config: None,
-                            reset_timer: Instant::now(),
+                            window_state: Default::default(),
+                        shutdown_started: None,
+                        shutdown_finished: false,
+                        instance_listener: listener,
+                        pending_deep_link: deep_link,
+                        tray: Tray::new(),
+                        hidden_to_tray: false,
+                        reset_timer: Instant::now(),
                         }
                     }
                 }
@@ -194,8 +308,89 @@ impl UIApp {
     }
 }
 
+impl UIApp {
+    fn show_window(&mut self, frame: &mut Frame) {
+        frame.set_visible(true);
+        frame.set_minimized(false);
+        frame.focus();
+
+        self.hidden_to_tray = false;
+    }
+
+    /// Scale from settings goes on top of whatever the display asks for, so it follows the window between monitors
+    fn apply_ui_scale(&self, ctx: &Context, frame: &Frame) {
+        let scale = self.config.as_ref().map_or(1.0, |x| clamp_ui_scale(x.load().ui_scale));
+        let pixels_per_point = frame.info().native_pixels_per_point.unwrap_or(1.0) * scale;
+
+        if (ctx.pixels_per_point() - pixels_per_point).abs() > f32::EPSILON {
+            ctx.set_pixels_per_point(pixels_per_point);
+        }
+    }
+
+    /// Reacts to the tray menu, and hides the window when it gets minimized if that's enabled
+    fn handle_tray(&mut self, ctx: &Context, frame: &mut Frame) {
+        let Some(tray) = &self.tray else {
+            return;
+        };
+
+        let actions: Vec<TrayAction> = std::iter::from_fn(|| tray.poll()).collect();
+
+        for action in actions {
+            let command = match action {
+                TrayAction::Launch => Some(ManagerCommand::LaunchNeos),
+                TrayAction::CheckUpdates => Some(ManagerCommand::RefreshManifests),
+                TrayAction::Open => {
+                    self.show_window(frame);
+                    None
+                }
+                TrayAction::Quit => {
+                    frame.close();
+                    None
+                }
+            };
+
+            if let (Some(command), Some(commander)) = (command, &self.manager_commander) {
+                handle_error(commander.blocking_send(command), &mut self.toast);
+            }
+        }
+
+        let minimize_to_tray = self.config.as_ref().map_or(false, |x| x.load().minimize_to_tray);
+
+        if minimize_to_tray && !self.hidden_to_tray && frame.info().window_info.minimized {
+            frame.set_visible(false);
+            self.hidden_to_tray = true;
+        }
+
+        // Hidden window doesn't get any input to wake it up, so the tray is checked on a timer
+        if self.hidden_to_tray {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+    }
+}
+
+fn shutdown_overlay(ctx: &Context) {
+    ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("shutdown_dim")))
+        .rect_filled(ctx.screen_rect(), 0.0, Color32::from_black_alpha(150));
+
+    Area::new("shutdown_overlay")
+        .order(Order::Foreground)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| {
+            EguiFrame::popup(ui.style())
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.heading(tr("shutdown.working"));
+                    });
+                });
+        });
+}
+
 impl App for UIApp {
     fn update(&mut self, ctx: &Context, frame: &mut Frame) {
+        self.handle_tray(ctx, frame);
+        self.apply_ui_scale(ctx, frame);
+
         if let UIState::FirstTime(state) = &mut self.state {
             if let Some(config) = first_time_ui(state, ctx, &mut self.toast) {
                 let config = Arc::new(ArcSwap::new(Arc::new(config)));
@@ -206,15 +401,11 @@ impl App for UIApp {
 
                 match config.load().save_config_sync() {
                     Ok(_) => {
-                        self.init_manager(mods.clone());
-                        self.state = UIState::Manager(UIManagerState {
-                            current_tab: ManagerTabs::Launcher,
-                            launcher_state: Default::default(),
-                            mod_list_state: ModListState::from_context(ctx),
-                            test_state: Default::default(),
-                            manifest_mods: mods,
-                            mod_list: Default::default(),
-                        });
+                        self.init_manager(mods.clone(), ctx);
+                        let mut manager_state = UIManagerState::from_context(ctx, mods);
+                        manager_state.tour_state = TourState::new(!config.load().tour_completed);
+
+                        self.state = UIState::Manager(manager_state);
                     }
                     Err(e) => {
                         self.toast.add(Toast {
@@ -230,9 +421,27 @@ impl App for UIApp {
         } else {
             match &mut self.state {
                 UIState::Manager(state) => {
+                    if let Some(link) = self.pending_deep_link.take() {
+                        state.deep_link_state.open(link);
+                    }
+
+                    state.window_hidden = self.hidden_to_tray || frame.info().window_info.minimized;
+
                     if self.manager_events.is_some() && self.manager_commander.is_some() {
                         manager_ui(state, self.config.as_ref().unwrap(), ctx, &mut self.toast, self.manager_commander.as_ref().unwrap(), self.manager_events.as_mut().unwrap());
                     }
+
+                    if state.notification_clicked.swap(false, Ordering::Relaxed) {
+                        state.focus_requested = true;
+                    }
+
+                    if state.focus_requested {
+                        state.focus_requested = false;
+                        frame.set_visible(true);
+                        frame.set_minimized(false);
+                        frame.focus();
+                        self.hidden_to_tray = false;
+                    }
                 }
                 UIState::CompleteError(str) => {
                     CentralPanel::default()
@@ -249,9 +458,60 @@ impl App for UIApp {
 
         self.toast.show(ctx);
 
+        if let Some(started) = self.shutdown_started {
+            let finished = matches!(&self.state, UIState::Manager(state) if state.shutdown_complete);
+
+            if finished || started.elapsed() > SHUTDOWN_TIMEOUT {
+                self.shutdown_finished = true;
+                frame.close();
+            } else {
+                shutdown_overlay(ctx);
+                ctx.request_repaint();
+            }
+        }
+
+        let window_info = &frame.info().window_info;
+
+        if !window_info.minimized && !window_info.maximized && !window_info.fullscreen {
+            self.window_state.size = Some((window_info.size.x, window_info.size.y));
+            self.window_state.position = window_info.position.map(|x| (x.x, x.y));
+        }
+
+        if let UIState::Manager(state) = &self.state {
+            self.window_state.last_tab = state.current_tab;
+        }
+
         /*Window::new("Style")
             .show(ctx, |ui| {
                 ctx.style_ui(ui);
             });*/
     }
+
+    fn on_close_event(&mut self) -> bool {
+        if self.shutdown_started.is_some() {
+            return self.shutdown_finished;
+        }
+
+        if let Some(config) = &self.config {
+            let mut config_str = config.load().as_ref().clone();
+            config_str.window_state = self.window_state.clone();
+            config.swap(Arc::new(config_str));
+        }
+
+        // Letting manager finish what it's doing and save the config before closing
+        if let Some(commander) = &self.manager_commander {
+            if commander.try_send(ManagerCommand::Shutdown).is_ok() {
+                self.shutdown_started = Some(Instant::now());
+                return false;
+            }
+        }
+
+        if let Some(config) = &self.config {
+            if let Err(e) = config.load().save_config_sync() {
+                eprintln!("Failed to save config: {}", e);
+            }
+        }
+
+        true
+    }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+use crate::config::Config;
+use crate::utils::folder_size;
+
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+/// Folders the organizer fills up by itself over time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaintenanceFolder {
+    Staging,
+    Trash,
+    Backups,
+    ArtifactCache
+}
+
+impl MaintenanceFolder {
+    pub const ALL: [MaintenanceFolder; 4] = [MaintenanceFolder::Staging, MaintenanceFolder::Trash, MaintenanceFolder::Backups, MaintenanceFolder::ArtifactCache];
+
+    pub fn path(&self) -> PathBuf {
+        match self {
+            MaintenanceFolder::Staging => Config::staging_path(),
+            MaintenanceFolder::Trash => Config::trash_path(),
+            MaintenanceFolder::Backups => Config::backups_path(),
+            MaintenanceFolder::ArtifactCache => Config::artifact_cache_path(),
+        }
+    }
+
+    /// Trash keeps its own retention, entries in it are removed through Trash so its file stays in sync
+    pub fn retention_days(&self, config: &Config) -> u32 {
+        match self {
+            MaintenanceFolder::Staging => config.maintenance.staging_retention_days,
+            MaintenanceFolder::Trash => config.trash.retention_days,
+            MaintenanceFolder::Backups => config.maintenance.backup_retention_days,
+            MaintenanceFolder::ArtifactCache => config.maintenance.cache_retention_days,
+        }
+    }
+}
+
+/// What's in a folder, items are its direct children
+#[derive(Clone, Debug, PartialEq)]
+pub struct FolderReport {
+    pub folder: MaintenanceFolder,
+    pub size: u64,
+    pub items: usize,
+    /// Seconds since unix epoch of the oldest item, None if there's nothing
+    pub oldest: Option<u64>
+}
+
+/// Item with its size and when it was last modified
+struct FolderItem {
+    path: PathBuf,
+    is_dir: bool,
+    size: u64,
+    modified: u64
+}
+
+async fn list_items(location: &Path) -> io::Result<Vec<FolderItem>> {
+    if !location.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut items = vec![];
+    let mut directory = fs::read_dir(location).await?;
+
+    while let Some(entry) = directory.next_entry().await? {
+        let metadata = entry.metadata().await?;
+        let size = if metadata.is_dir() {
+            folder_size(entry.path()).await?
+        } else {
+            metadata.len()
+        };
+
+        items.push(FolderItem {
+            path: entry.path(),
+            is_dir: metadata.is_dir(),
+            size,
+            modified: metadata.modified().ok()
+                .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |x| x.as_secs()),
+        });
+    }
+
+    Ok(items)
+}
+
+pub async fn inspect_folder(folder: MaintenanceFolder, location: &Path) -> io::Result<FolderReport> {
+    let items = list_items(location).await?;
+
+    Ok(FolderReport {
+        folder,
+        size: items.iter().map(|x| x.size).sum(),
+        items: items.len(),
+        oldest: items.iter().map(|x| x.modified).min(),
+    })
+}
+
+/// Deletes items of the folder that weren't modified in retention days, returns how many were deleted and how much was freed
+pub async fn collect_garbage(location: &Path, retention_days: u32, now: u64) -> io::Result<(usize, u64)> {
+    let cutoff = now.saturating_sub(retention_days as u64 * SECONDS_IN_DAY);
+    let mut removed = 0;
+    let mut freed = 0;
+
+    for item in list_items(location).await? {
+        if item.modified >= cutoff {
+            continue;
+        }
+
+        if item.is_dir {
+            fs::remove_dir_all(&item.path).await?;
+        } else {
+            fs::remove_file(&item.path).await?;
+        }
+
+        removed += 1;
+        freed += item.size;
+    }
+
+    Ok((removed, freed))
+}
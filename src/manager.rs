@@ -1,24 +1,61 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Child;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use arc_swap::ArcSwap;
-use eframe::egui::RichText;
+use eframe::egui::{Context, RichText};
 use egui_toast::ToastKind;
+use futures::future::join_all;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::mpsc::error::{SendError, TrySendError};
 use tokio::sync::RwLock;
-use tokio::time::{Instant, sleep};
-use crate::config::Config;
-use crate::install::{ActualInstall, ModFile, ModInstall, ModInstallOperations, ModMap};
-use crate::launch::LaunchOptions;
+use tokio::time::{Instant, timeout};
+use crate::cache::ArtifactCache;
+use crate::config::{Config, ConfigError, default_client, SyncTarget};
+use crate::install::{ActualInstall, ConflictSeverity, IDVersion, InstallError, ModConflict, ModFile, ModInstall, ModInstallOperations, ModMap, SideloadFile, StrayMod, verify_artifacts};
+use crate::maintenance::{collect_garbage, FolderReport, inspect_folder, MaintenanceFolder};
+use crate::github::{fetch_releases, GitHubRelease, parse_repo_url, version_from_tag};
+use crate::local_mods::{LocalModDetails, LocalModInfo, LocalMods};
+use crate::lockfile::Lockfile;
+use crate::cloud_sync::{merge_snapshots, pull_snapshot, push_snapshot, PendingSync, SyncConflict, SyncError, SyncSnapshot};
+use crate::notes::ModNotes;
+use crate::history::{History, HistoryEntry, HistoryFile, restore_backup};
+use crate::ipc::DeepLink;
+use crate::launch::{LaunchOptions, LaunchPreset, LaunchProblem, plugin_assemblies, request_graceful_exit};
+use crate::modpack::{export_mod_pack, import_operation, ModPackError, prepare_import, read_mod_pack};
 use crate::manager::ManagerEvent::ReadmeResponse;
-use crate::manifest::{aggregate_manifests, Artifact, Category, Dependency, download_manifest, download_readme, find_github_readme_link, GlobalModList, GUID, Mod, ModVersion};
-use crate::resolver::{find_latest_matching, resolve_install_mod, ResolveResult};
-use crate::utils::{get_all_files_of_extension, sha256_file};
-use crate::version::{Version, Comparator};
+use crate::manifest::{aggregate_manifests, fetch_artifact_size, Artifact, Category, Dependency, download_image, download_readme, find_github_readme_link, GlobalModList, GUID, Mod, ModVersion};
+use crate::trash::{Trash, TrashEntry};
+use crate::feed::{FEED_LENGTH, FeedEntry, KnownVersions};
+use crate::fixes::{ConflictFix, suggest_fixes};
+use crate::readme::{README_TIMEOUT, ReadmeCache, ReadmeError};
+use crate::planner::{PlanOutcome, PlanStep, simulate_plan};
+use crate::queue::SavedQueue;
+use crate::resolver::{available_updates, find_latest_matching, resolve_install_mod, ResolveResult};
+use crate::transport::Transport;
+use crate::utils::{clear_folder, find_filename_from_url, folder_size, format_size, get_all_files_of_extension, sha256_bytes, sha256_file, unix_timestamp};
+use crate::version::{Version, Comparator, VersionReq};
+
+/// How often config file is checked for changes made by hand
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long game gets to close by itself before it's killed
+const GRACEFUL_EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often UI hears about how far along the rescan is
+const SCAN_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often manifests are downloaded again in the background to look for updates
+const MANIFEST_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 pub fn validate_path(path: &PathBuf) -> bool {
     let Some(dir) = path.parent() else {
@@ -44,135 +81,2233 @@ pub fn validate_path(path: &PathBuf) -> bool {
     paths.into_iter().all(|path| path.exists())
 }
 
-pub async fn respond_to_readme_request(global_mods: &GlobalModList, guid: &str) -> Option<String> {
-    let mod_list = global_mods.mod_list.load();
-    let mod_info = mod_list.get(guid)?;
-    let source_location = mod_info.source_location.as_ref()?;
+/// Ok(None) if the mod doesn't have a README that can be found
+pub async fn respond_to_readme_request(client: &Client, global_mods: &GlobalModList, guid: &str) -> Result<Option<String>, ReadmeError> {
+    let Some(source_location) = global_mods.mod_list.load().get(guid).and_then(|x| x.source_location.clone()) else {
+        return Ok(None);
+    };
+
+    let request = async {
+        let Some(readme_link) = find_github_readme_link(client, &source_location).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(download_readme(client, &readme_link).await?))
+    };
+
+    timeout(README_TIMEOUT, request).await.map_err(|_| ReadmeError::TimedOut)?
+}
+
+/// Neos process started by the organizer
+struct GameInstance {
+    id: u64,
+    /// Launch profile it was started with, None for main launch options
+    profile: Option<String>,
+    data_path: Option<PathBuf>,
+    process: Child,
+    /// Set once it was asked to close, it's killed if still running past the deadline
+    stopping: Option<StopRequest>
+}
+
+struct StopRequest {
+    deadline: Instant,
+    restart: bool
+}
+
+impl GameInstance {
+    fn is_running(&mut self) -> bool {
+        matches!(self.process.try_wait(), Ok(None))
+    }
+}
 
-    let readme_link = find_github_readme_link(source_location).await.ok()??;
-    let readme = download_readme(&readme_link).await.ok()?;
+/// Sizes of game's data and cache folders, None if folder isn't known or couldn't be read
+#[derive(Debug, Clone, Default)]
+pub struct DataFolderSizes {
+    pub data: Option<(PathBuf, u64)>,
+    pub cache: Option<(PathBuf, u64)>
+}
 
-    Some(readme)
+#[derive(Debug, Clone)]
+pub struct GameInstanceInfo {
+    pub id: u64,
+    pub profile: Option<String>
 }
 
 pub struct Manager {
     command_receiver: Receiver<ManagerCommand>,
-    event_sender: Sender<ManagerEvent>,
+    event_sender: EventSender,
     config: Arc<ArcSwap<Config>>,
     global_mods: GlobalModList,
     install: ActualInstall,
-    readme_cache: HashMap<GUID, String>,
+    readme_cache: ReadmeCache,
+    known_versions: KnownVersions,
+    local_mods: LocalMods,
+    notes: ModNotes,
+    /// When config file was last written or loaded by the organizer
+    config_modified: Option<SystemTime>,
+    /// Manifest URL each mod came from
+    mod_sources: HashMap<GUID, String>,
+    instances: Vec<GameInstance>,
+    next_instance_id: u64,
+    /// Launch that's waiting for user to look over problems found before it
+    pending_launch: Option<(LaunchOptions, Option<String>)>,
+    /// Launch that's waiting for user to look over updates applied before it
+    pending_update_launch: Option<(LaunchOptions, Option<String>)>,
+    /// Mods that pending launch can disable to get rid of direct conflicts
+    pending_disable: Vec<IDVersion>,
+    /// Mods disabled for the running game, enabled back once it closes
+    session_disabled: Vec<IDVersion>,
+    queue: VecDeque<QueueItem>,
+    next_queue_id: u64,
+    next_batch: u64,
+    /// File that was inspected last, so installing it doesn't read or download it again
+    pending_sideload: Option<(SideloadFile, Vec<u8>)>,
+    /// Unfinished operations from the last run, waiting for user to resume or discard them
+    saved_queue: Vec<QueueItem>,
+    /// Commands that arrived while queue was being processed
+    pending_commands: VecDeque<ManagerCommand>,
+    /// Cloud sync waiting for its mod changes to be applied
+    pending_sync: Option<PendingSync>,
+    history: History,
+    /// Shared by everything that downloads, built from network settings
+    client: Client,
+    /// Manifests and mod files are downloaded through it, it's the client unless tests swap it out
+    transport: Arc<dyn Transport>,
+    trash: Trash,
+    /// Sizes of artifacts that manifest didn't provide, by URL
+    artifact_sizes: HashMap<String, u64>,
+    last_manifest_refresh: Instant,
+    /// Updates that were already announced, so they're only announced once
+    announced_updates: HashSet<(IDVersion, Version)>,
 }
 
 impl Manager {
-    pub fn new(receiver: Receiver<ManagerCommand>, sender: Sender<ManagerEvent>, config: Arc<ArcSwap<Config>>, global_mods: GlobalModList) -> Self {
+    pub fn new(receiver: Receiver<ManagerCommand>, sender: Sender<ManagerEvent>, ctx: Context, config: Arc<ArcSwap<Config>>, global_mods: GlobalModList) -> Self {
         let config_str = config.load_full();
 
         Self {
             command_receiver: receiver,
-            event_sender: sender,
+            event_sender: EventSender::new(sender, ctx),
             config,
             global_mods: global_mods.clone(),
             install: ActualInstall::new_empty(&config_str.neos_exe_location.parent().unwrap(), global_mods),
             readme_cache: Default::default(),
+            known_versions: Default::default(),
+            local_mods: Default::default(),
+            notes: Default::default(),
+            mod_sources: Default::default(),
+            config_modified: None,
+            instances: vec![],
+            next_instance_id: 0,
+            pending_launch: None,
+            pending_update_launch: None,
+            pending_disable: vec![],
+            session_disabled: vec![],
+            queue: Default::default(),
+            next_queue_id: 0,
+            next_batch: 0,
+            saved_queue: vec![],
+            pending_sideload: None,
+            pending_commands: Default::default(),
+            pending_sync: None,
+            history: Default::default(),
+            client: default_client(),
+            transport: Arc::new(default_client()),
+            trash: Default::default(),
+            artifact_sizes: Default::default(),
+            last_manifest_refresh: Instant::now(),
+            announced_updates: Default::default(),
         }
     }
 
-    pub async fn run_event_loop(&mut self) {
-        self.event_sender.send(ManagerEvent::LaunchOptionsState(self.config.load().launch_options.clone())).await.expect("Failed");
+    fn is_game_running(&mut self) -> bool {
+        self.instances.iter_mut().any(|x| x.is_running())
+    }
 
-        // Get the manifest
-        let time = Instant::now();
-        let config = self.config.load();
+    async fn send_instances(&self) {
+        let instances = self.instances.iter()
+            .map(|x| GameInstanceInfo {
+                id: x.id,
+                profile: x.profile.clone(),
+            })
+            .collect();
 
-        let (mods, errors) = aggregate_manifests(config.manifest_links.as_ref()).await;
+        self.event_sender.send(ManagerEvent::GameInstancesChanged(instances)).await.ok();
+    }
 
-        for (url, error) in errors {
-            self.event_sender.send(ManagerEvent::LongNotification(
-                ToastKind::Error,
-                format!("Reading manifest \"{}\" failed, error:\n{}", url, error)
-            )).await.ok();
+    /// Forgets instances that were closed, UI is told only if something changed
+    async fn prune_instances(&mut self) {
+        let count = self.instances.len();
+        // Stopping ones are left for check_stopping_instances, they might need to be restarted
+        self.instances.retain_mut(|x| x.stopping.is_some() || x.is_running());
+
+        if self.instances.len() != count {
+            self.send_instances().await;
         }
 
-        let len = mods.len();
-        self.global_mods.update_list(mods);
+        if self.instances.is_empty() {
+            self.restore_session_disabled().await;
+        }
+    }
 
-        self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Downloaded info about {} mods in {}ms", len, time.elapsed().as_millis()))).await.ok();
+    /// Folders can be huge, so they're measured in the background
+    fn measure_data_folders(&self) {
+        let options = self.config.load().launch_options.clone();
+        let sender = self.event_sender.clone();
 
-        // Rescan mods
-        let time = Instant::now();
+        tokio::spawn(async move {
+            let measure = |path: Option<PathBuf>| async move {
+                let path = path?;
+                let size = folder_size(path.clone()).await.ok()?;
 
-        if let Some(_) = handle_error(self.install.rescan_mods(self.config.load_full()).await, &self.event_sender).await {
-            self.event_sender.send(ManagerEvent::ModMapChanged(self.install.mod_map().clone())).await.ok();
-            self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Found {} mods in {}ms", self.install.mod_map().len(), time.elapsed().as_millis()))).await.ok();
+                Some((path, size))
+            };
+
+            let sizes = DataFolderSizes {
+                data: measure(options.effective_data_path()).await,
+                cache: measure(options.effective_cache_path()).await,
+            };
+
+            sender.send(ManagerEvent::DataFolderSizes(sizes)).await.ok();
+        });
+    }
+
+    async fn clear_cache(&mut self) {
+        if self.is_game_running() {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
         }
 
-        loop {
-            if let Some(command) = self.command_receiver.recv().await {
-                match command {
-                    ManagerCommand::Test => {println!("test")}
-                    ManagerCommand::LaunchNeos => {
-                        let mut command = self.config.load().launch_options.build_command(&self.config.load().neos_exe_location);
+        let Some(path) = self.config.load().launch_options.effective_cache_path() else {
+            return;
+        };
 
-                        handle_error(command.spawn(), &self.event_sender).await;
-                    }
+        let freed = folder_size(path.clone()).await.unwrap_or(0);
 
-                    ManagerCommand::CreateShortcut(path) => {
-                        #[cfg(target_os="windows")]
-                        handle_error(self.config.load().launch_options.make_shortcut(&self.config.load().neos_exe_location, path), &self.event_sender).await;
-                        #[cfg(not(target_os="windows"))]
-                        self.event_sender.send(ManagerEvent::Error(format!("Cannot create shortcut\nmslnk wasn't compiled due to compilation target"))).await.ok();
-                    }
+        if handle_error(clear_folder(&path).await, &self.event_sender).await.is_some() {
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Cleared cache, freed {}", format_size(freed)))).await.ok();
+        }
 
-                    ManagerCommand::SaveConfig => {
-                        handle_error(self.config.load().save_config().await, &self.event_sender).await;
-                    }
-                    ManagerCommand::RefreshModMap => {}
-                    ManagerCommand::RefreshManifests => {}
-                    ManagerCommand::FindReadmeFor(guid) => {
-                        if let Some(cached_readme) = self.readme_cache.get(&guid) {
-                            self.event_sender.send(ReadmeResponse(
-                                Some(cached_readme.clone())
-                            )).await.ok();
-                        } else {
-                            let response = respond_to_readme_request(&self.global_mods, &guid).await;
-
-                            if let Some(readme) = response.as_ref() {
-                                self.readme_cache.insert(guid, readme.clone());
-                            }
-
-                            self.event_sender.send(ReadmeResponse(
-                                response
-                            )).await.ok();
-                        }
+        self.measure_data_folders();
+    }
+
+    fn apply_cache_settings(&mut self) {
+        let cache = self.config.load().artifact_cache.enabled
+            .then(|| ArtifactCache::new(Config::artifact_cache_path()));
+
+        self.install.set_artifact_cache(cache);
+    }
+
+    async fn send_artifact_cache_size(&self) {
+        let cache = ArtifactCache::new(Config::artifact_cache_path());
+
+        if let Some(size) = handle_error(cache.size().await, &self.event_sender).await {
+            self.event_sender.send(ManagerEvent::ArtifactCacheSize(size)).await.ok();
+        }
+    }
+
+    /// Brings artifact cache down to its size limit, quietly unless asked for by user
+    async fn prune_artifact_cache(&self, notify: bool) {
+        let cache = ArtifactCache::new(Config::artifact_cache_path());
+        let max_size = self.config.load().artifact_cache.max_size_bytes();
+
+        if let Some(freed) = handle_error(cache.prune(max_size).await, &self.event_sender).await {
+            if notify {
+                self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Pruned artifact cache, freed {}", format_size(freed)))).await.ok();
+            }
+        }
+
+        self.send_artifact_cache_size().await;
+    }
+
+    async fn clear_artifact_cache(&self) {
+        let cache = ArtifactCache::new(Config::artifact_cache_path());
+
+        if let Some(freed) = handle_error(cache.clear().await, &self.event_sender).await {
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Cleared artifact cache, freed {}", format_size(freed)))).await.ok();
+        }
+
+        self.send_artifact_cache_size().await;
+    }
+
+    async fn send_maintenance_report(&self) {
+        let mut reports = vec![];
+
+        for folder in MaintenanceFolder::ALL {
+            if let Some(report) = handle_error(inspect_folder(folder, &folder.path()).await, &self.event_sender).await {
+                reports.push(report);
+            }
+        }
+
+        self.event_sender.send(ManagerEvent::MaintenanceReport(reports)).await.ok();
+    }
+
+    /// Deletes what's been sitting in organizer's own folders for longer than their retention
+    async fn clean_up_folders(&mut self) {
+        let config = self.config.load_full();
+        let mut removed = 0;
+        let mut freed = 0;
+
+        for folder in MaintenanceFolder::ALL {
+            let days = folder.retention_days(&config);
+
+            if folder == MaintenanceFolder::Trash {
+                let before = folder_size(folder.path()).await.unwrap_or(0);
+                removed += self.trash.purge_expired(days).await;
+                freed += before.saturating_sub(folder_size(folder.path()).await.unwrap_or(0));
+
+                handle_error(self.trash.save().await, &self.event_sender).await;
+                self.send_trash().await;
+                continue;
+            }
+
+            if let Some((count, size)) = handle_error(collect_garbage(&folder.path(), days, unix_timestamp()).await, &self.event_sender).await {
+                removed += count;
+                freed += size;
+            }
+        }
+
+        // Entries whose backups are gone can't be undone anymore
+        self.send_history().await;
+
+        self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Cleaned up {} items, freed {}", removed, format_size(freed)))).await.ok();
+        self.send_maintenance_report().await;
+        self.send_artifact_cache_size().await;
+    }
+
+    /// Disables mods that are in direct conflict, only until the game closes
+    async fn disable_for_session(&mut self, mods: Vec<IDVersion>) {
+        let operations: Vec<ModInstallOperations> = mods.iter()
+            .map(|x| ModInstallOperations::DisableMod(x.clone()))
+            .collect();
+
+        if handle_error(self.install.perform_operations(&operations).await, &self.event_sender).await.is_some() {
+            self.session_disabled.extend(mods);
+            self.send_mod_map().await;
+        }
+    }
+
+    async fn restore_session_disabled(&mut self) {
+        if self.session_disabled.is_empty() {
+            return;
+        }
+
+        let operations: Vec<ModInstallOperations> = self.session_disabled.drain(..)
+            .map(ModInstallOperations::EnableMod)
+            .collect();
+
+        handle_error(self.install.perform_operations(&operations).await, &self.event_sender).await;
+        self.send_mod_map().await;
+        handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+    }
+
+    /// Applies pending updates first if user wants that, launch continues once they looked over the results
+    async fn launch_neos(&mut self, options: LaunchOptions, profile: Option<String>) {
+        if self.config.load().update_before_launch && !self.is_game_running() {
+            let updates = self.pending_updates();
+
+            if !updates.is_empty() {
+                self.pending_update_launch = Some((options, profile));
+                self.update_before_launch(updates).await;
+                return;
+            }
+        }
+
+        self.check_launch(options, profile).await;
+    }
+
+    /// Installs newest versions of the mods, each update in its own batch so one failing doesn't hold back others.
+    /// There's no one to confirm anything mid launch, so updates that would bring new conflicts or mods from untrusted sources are skipped
+    async fn update_before_launch(&mut self, updates: Vec<(IDVersion, Version)>) {
+        self.event_sender.send(ManagerEvent::PreLaunchUpdatesStarted(updates.clone())).await.ok();
+
+        let mod_list = self.global_mods.mod_list.load_full();
+        let config = self.config.load_full();
+        let current_conflicts = self.install.check_for_conflicts(&mod_list);
+        let mut planned = self.install.virtualize();
+
+        for ((mod_id, _), newest) in &updates {
+            let operations = match resolve_install_mod(mod_id, &VersionReq::at_least(newest), self.install.mod_map(), &mod_list, |x| config.update_channel_for(x)) {
+                ResolveResult::Ok(operations) => operations,
+                failure => {
+                    send_error(ManagerError::Other(failure.to_string()), &self.event_sender).await;
+                    continue;
+                }
+            };
+
+            let untrusted = self.untrusted_mods(&operations);
+
+            if !untrusted.is_empty() {
+                let sources = untrusted.iter()
+                    .map(|(mod_id, source)| format!("{} from {}", mod_id, source))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                self.event_sender.send(ManagerEvent::LongNotification(ToastKind::Warning, format!("Didn't update {}, it needs mods from untrusted sources: {}", mod_id, sources))).await.ok();
+                continue;
+            }
+
+            // Checked on top of updates that are already going in, so they can't conflict with each other either
+            let mut with_update = planned.clone();
+
+            if handle_error(with_update.perform_operations(&operations).await, &self.event_sender).await.is_none() {
+                continue;
+            }
+
+            let brings_conflicts = with_update.check_for_conflicts(&mod_list).iter()
+                .any(|x| !current_conflicts.contains(x) && !config.is_conflict_suppressed(x));
+
+            if brings_conflicts {
+                self.event_sender.send(ManagerEvent::LongNotification(ToastKind::Warning, format!("Didn't update {}, the update would bring new conflicts", mod_id))).await.ok();
+                continue;
+            }
+
+            planned = with_update;
+            self.queue_operations(&operations);
+        }
+
+        self.send_queue().await;
+        self.process_queue().await;
+
+        let results = updates.into_iter()
+            .map(|(id, newest)| {
+                let updated = self.install.mod_map().installed_version(&id.0).map_or(false, |x| x >= &newest);
+                (id, newest, updated)
+            })
+            .collect();
+
+        self.event_sender.send(ManagerEvent::PreLaunchUpdatesFinished(results)).await.ok();
+    }
+
+    /// Checks if the game can launch fine first, UI gets to decide what to do if it can't
+    async fn check_launch(&mut self, options: LaunchOptions, profile: Option<String>) {
+        let config = self.config.load_full();
+        let mut problems = options.check(config.neos_exe_location.parent().unwrap_or(Path::new("")));
+
+        self.pending_disable.clear();
+
+        let conflicts = self.install.check_for_conflicts(&self.global_mods.mod_list.load()).into_iter()
+            .filter(|x| !config.is_conflict_suppressed(x));
+
+        for conflict in conflicts {
+            match conflict {
+                ModConflict::VersionConflict(guid) => problems.push(LaunchProblem::VersionConflict(guid)),
+                ModConflict::DirectConflict { this, conflict_with } => {
+                    let (keep, disable) = if config.conflict_loser(&this.0, &conflict_with.0) == &this.0 {
+                        (conflict_with, this)
+                    } else {
+                        (this, conflict_with)
+                    };
+
+                    // Both mods usually declare the conflict, one of them being disabled is enough
+                    if self.pending_disable.contains(&keep) || self.pending_disable.contains(&disable) {
+                        continue;
                     }
+
+                    self.pending_disable.push(disable.clone());
+                    problems.push(LaunchProblem::DirectConflict { keep, disable });
                 }
+                conflict if conflict.severity() == ConflictSeverity::Error => problems.push(LaunchProblem::Conflict(conflict)),
+                _ => {}
             }
         }
+
+        if !problems.is_empty() {
+            self.pending_launch = Some((options, profile));
+            self.event_sender.send(ManagerEvent::LaunchProblems(problems)).await.ok();
+            return;
+        }
+
+        self.start_instance(options, profile).await;
     }
-}
 
-#[inline]
-async fn handle_error<T, E: Error>(result: Result<T, E>, sender: &Sender<ManagerEvent>) -> Option<T> {
-    match result {
-        Ok(v) => Some(v),
-        Err(e) => {
-            sender.send(ManagerEvent::Error(e.to_string())).await.ok();
-            None
+    /// Instances can run side by side as long as they don't share a data folder
+    async fn start_instance(&mut self, mut options: LaunchOptions, profile: Option<String>) {
+        if self.instances.iter_mut().any(|x| x.data_path == options.data_path && x.is_running()) {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
+        }
+
+        if !self.is_game_running() {
+            self.check_for_external_changes().await;
+            handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+        }
+
+        // Plugins are loaded by the game itself, they need to be passed to it in load order
+        for assembly in plugin_assemblies(self.install.mod_map(), &self.global_mods.mod_list.load(), &self.config.load(), self.install.location()) {
+            if !options.load_assembly.contains(&assembly) {
+                options.load_assembly.push(assembly);
+            }
+        }
+
+        let mut command = options.build_command(&self.config.load().neos_exe_location);
+
+        if let Some(process) = handle_error(command.spawn(), &self.event_sender).await {
+            self.instances.push(GameInstance {
+                id: self.next_instance_id,
+                profile,
+                data_path: options.data_path,
+                process,
+                stopping: None,
+            });
+
+            self.next_instance_id += 1;
         }
+
+        self.prune_instances().await;
+        self.send_instances().await;
     }
-}
 
-/// For communication from UI to Manager
-#[derive(Debug)]
-pub enum ManagerCommand {
-    Test,
-    SaveConfig,
-    LaunchNeos,
-    CreateShortcut(PathBuf),
-    RefreshManifests,
-    RefreshModMap,
-    FindReadmeFor(GUID),
+    /// Asks instance to close, it's killed if it doesn't in time. Waiting happens in check_stopping_instances, so the manager isn't held up by it
+    async fn stop_instance(&mut self, id: u64, restart: bool) {
+        let Some(index) = self.instances.iter().position(|x| x.id == id) else {
+            return;
+        };
+
+        let instance = &mut self.instances[index];
+
+        if let Some(stopping) = &mut instance.stopping {
+            stopping.restart = restart;
+            return;
+        }
+
+        if instance.is_running() && request_graceful_exit(instance.process.id()).is_ok() {
+            instance.stopping = Some(StopRequest {
+                deadline: Instant::now() + GRACEFUL_EXIT_TIMEOUT,
+                restart,
+            });
+
+            return;
+        }
+
+        let instance = self.instances.remove(index);
+        self.end_instance(instance, restart).await;
+    }
+
+    /// Finishes stopping instances that closed or ran out of time
+    async fn check_stopping_instances(&mut self) {
+        let now = Instant::now();
+        let finished: Vec<u64> = self.instances.iter_mut()
+            .filter_map(|x| {
+                let deadline = x.stopping.as_ref()?.deadline;
+                (!x.is_running() || now >= deadline).then_some(x.id)
+            })
+            .collect();
+
+        for id in finished {
+            let Some(index) = self.instances.iter().position(|x| x.id == id) else {
+                continue;
+            };
+
+            let mut instance = self.instances.remove(index);
+            let restart = instance.stopping.take().map_or(false, |x| x.restart);
+
+            self.end_instance(instance, restart).await;
+        }
+    }
+
+    /// Kills the instance if it's still running. Relaunches it with current options if restarting
+    async fn end_instance(&mut self, mut instance: GameInstance, restart: bool) {
+        if instance.is_running() {
+            handle_error(instance.process.kill(), &self.event_sender).await;
+            instance.process.wait().ok();
+        }
+
+        self.send_instances().await;
+
+        if !restart {
+            return;
+        }
+
+        let options = match &instance.profile {
+            Some(name) => self.config.load().launch_profiles.iter()
+                .find(|x| &x.name == name)
+                .map(|x| x.options.clone()),
+            None => Some(self.config.load().launch_options.clone())
+        };
+
+        if let Some(options) = options {
+            self.launch_neos(options, instance.profile).await;
+        } else {
+            send_error(ManagerError::Other(format!("Launch profile \"{}\" doesn't exist anymore", instance.profile.unwrap_or_default())), &self.event_sender).await;
+        }
+    }
+
+    /// Rescans mods folder, letting UI know how far along it is. Progress is spaced out so big installs don't flood the channel
+    async fn rescan_mods(&mut self) -> Result<(), InstallError> {
+        let sender = self.event_sender.clone();
+        let mut last_sent = Instant::now();
+        let mut last_total = 0;
+
+        let result = self.install.rescan_mods(self.config.load_full(), |scanned, total| {
+            last_total = total;
+
+            if scanned < total && last_sent.elapsed() >= SCAN_PROGRESS_INTERVAL {
+                last_sent = Instant::now();
+                sender.try_send(ManagerEvent::ScanProgress { scanned, total }).ok();
+            }
+        }).await;
+
+        // Sent even if scan failed, so UI doesn't keep showing it
+        self.event_sender.send(ManagerEvent::ScanProgress { scanned: last_total, total: last_total }).await.ok();
+
+        result
+    }
+
+    /// Sends current mod map to UI along with conflicts found in it and fixes for the ones that aren't suppressed
+    async fn send_mod_map(&self) {
+        let mod_list = self.global_mods.mod_list.load_full();
+        let conflicts = self.install.check_for_conflicts(&mod_list);
+
+        self.event_sender.send(ManagerEvent::ModMapChanged(self.install.mod_map().clone())).await.ok();
+        self.event_sender.send(ManagerEvent::ConflictsChanged(conflicts)).await.ok();
+    }
+
+    /// Fixes are looked for in their own task, trying them out on virtual installs takes a while with a lot of versions around
+    fn suggest_conflict_fixes(&self) {
+        let mod_list = self.global_mods.mod_list.load_full();
+        let config = self.config.load_full();
+        let install = self.install.virtualize();
+        let sender = self.event_sender.clone();
+
+        tokio::spawn(async move {
+            let mut fixes = vec![];
+
+            for conflict in install.check_for_conflicts(&mod_list).into_iter().filter(|x| !config.is_conflict_suppressed(x)) {
+                let conflict_fixes = suggest_fixes(&install, &conflict, &mod_list, &config).await;
+
+                if !conflict_fixes.is_empty() {
+                    fixes.push((conflict, conflict_fixes));
+                }
+            }
+
+            sender.send(ManagerEvent::ConflictFixesFound(fixes)).await.ok();
+        });
+    }
+
+    /// Tries operations on a virtual copy of the install first, applies them only if they don't break anything
+    async fn preview_operations(&mut self, operations: Vec<ModInstallOperations>) {
+        let mod_list = self.global_mods.mod_list.load_full();
+        let current_conflicts = self.install.check_for_conflicts(&mod_list);
+
+        let mut virtual_install = self.install.virtualize();
+
+        if handle_error(virtual_install.perform_operations(&operations).await, &self.event_sender).await.is_none() {
+            return;
+        }
+
+        let config = self.config.load();
+        let new_conflicts = virtual_install.check_for_conflicts(&mod_list).into_iter()
+            .filter(|x| !current_conflicts.contains(x) && !config.is_conflict_suppressed(x))
+            .collect::<Vec<ModConflict>>();
+
+        let untrusted = self.untrusted_mods(&operations);
+
+        if new_conflicts.is_empty() && untrusted.is_empty() {
+            self.apply_operations(&operations).await;
+        } else {
+            self.event_sender.send(ManagerEvent::PlanConflicts(operations, new_conflicts, untrusted)).await.ok();
+        }
+    }
+
+    async fn simulate_plan(&mut self, steps: &[PlanStep]) {
+        let mod_list = self.global_mods.mod_list.load_full();
+        let config = self.config.load_full();
+
+        let mut outcome = simulate_plan(self.install.virtualize(), steps, &mod_list, |x| config.update_channel_for(x)).await;
+        outcome.untrusted = self.untrusted_mods(&outcome.operations);
+
+        self.event_sender.send(ManagerEvent::PlanSimulated(outcome)).await.ok();
+    }
+
+    /// Mods that operations would install from sources user didn't mark as trusted, along with the source
+    fn untrusted_mods(&self, operations: &[ModInstallOperations]) -> Vec<(GUID, String)> {
+        let config = self.config.load();
+        let mut untrusted: Vec<(GUID, String)> = vec![];
+
+        for operation in operations {
+            let (ModInstallOperations::InstallMod((mod_id, _)) | ModInstallOperations::InstallModAt((mod_id, _), _)) = operation else {
+                continue;
+            };
+
+            let Some(source) = self.mod_sources.get(mod_id) else {
+                continue;
+            };
+
+            if !config.is_source_trusted(source) && !untrusted.iter().any(|(x, _)| x == mod_id) {
+                untrusted.push((mod_id.clone(), source.clone()));
+            }
+        }
+
+        untrusted
+    }
+
+    /// Resolves the mod with its dependencies and goes through the same checks as any other operations
+    async fn install_mod(&mut self, guid: &str, version_req: &VersionReq) {
+        let mod_list = self.global_mods.mod_list.load_full();
+        let config = self.config.load_full();
+
+        match resolve_install_mod(guid, version_req, self.install.mod_map(), &mod_list, |x| config.update_channel_for(x)) {
+            ResolveResult::Ok(operations) => {
+                if operations.is_empty() {
+                    self.event_sender.send(ManagerEvent::Notification(ToastKind::Info, format!("{} is already installed", guid))).await.ok();
+                } else {
+                    self.preview_operations(operations).await;
+                }
+            }
+            failure => {
+                send_error(ManagerError::Other(failure.to_string()), &self.event_sender).await;
+            }
+        }
+    }
+
+    async fn apply_operations(&mut self, operations: &[ModInstallOperations]) {
+        if self.is_game_running() {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
+        }
+
+        self.queue_operations(operations);
+        self.send_queue().await;
+
+        self.process_queue().await;
+    }
+
+    /// Operations of a single request make a batch, if one of them fails the rest of the batch is skipped
+    fn queue_operations(&mut self, operations: &[ModInstallOperations]) {
+        self.next_batch += 1;
+
+        if let Some(pending) = &mut self.pending_sync {
+            pending.claim_batch(operations, self.next_batch);
+        }
+
+        for operation in operations {
+            self.next_queue_id += 1;
+
+            self.queue.push_back(QueueItem {
+                id: self.next_queue_id,
+                batch: self.next_batch,
+                operation: operation.clone(),
+                status: OperationStatus::Queued,
+            });
+        }
+    }
+
+    async fn send_queue(&self) {
+        self.event_sender.send(ManagerEvent::QueueChanged(self.queue.iter().cloned().collect())).await.ok();
+        self.save_queue().await;
+    }
+
+    /// Keeps unfinished operations on disk, so they can be resumed if the organizer closes before they're done
+    async fn save_queue(&self) {
+        let items = self.saved_queue.iter()
+            .chain(self.queue.iter().filter(|x| !x.status.is_finished()))
+            .map(|x| QueueItem {
+                status: OperationStatus::Queued,
+                ..x.clone()
+            })
+            .collect();
+
+        handle_error(SavedQueue { items }.save().await, &self.event_sender).await;
+    }
+
+    /// Offers operations left over from the last run, ones that went through before it closed are dropped
+    async fn load_saved_queue(&mut self) {
+        let Some(saved) = handle_error(SavedQueue::load().await, &self.event_sender).await else {
+            return;
+        };
+
+        self.saved_queue = saved.without_applied(self.install.mod_map()).items;
+        self.save_queue().await;
+
+        if !self.saved_queue.is_empty() {
+            self.event_sender.send(ManagerEvent::UnfinishedQueue(self.saved_queue.clone())).await.ok();
+        } else {
+            clear_folder(&Config::staging_path()).await.ok();
+        }
+    }
+
+    /// Queues operations from the last run again, keeping them in the batches they were in
+    async fn resume_saved_queue(&mut self) {
+        if self.is_game_running() {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
+        }
+
+        let saved = std::mem::take(&mut self.saved_queue);
+        let mut batches: Vec<(u64, Vec<ModInstallOperations>)> = vec![];
+
+        for item in saved {
+            match batches.last_mut() {
+                Some((batch, operations)) if *batch == item.batch => operations.push(item.operation),
+                _ => batches.push((item.batch, vec![item.operation]))
+            }
+        }
+
+        for (_, operations) in &batches {
+            self.queue_operations(operations);
+        }
+
+        self.send_queue().await;
+        self.process_queue().await;
+    }
+
+    async fn discard_saved_queue(&mut self) {
+        self.saved_queue.clear();
+        self.save_queue().await;
+
+        if self.queue.iter().all(|x| x.status.is_finished()) {
+            clear_folder(&Config::staging_path()).await.ok();
+        }
+    }
+
+    async fn set_status(&mut self, index: usize, status: OperationStatus) {
+        self.queue[index].status = status;
+        self.send_queue().await;
+    }
+
+    /// Apply requests that came in while queue was being processed go into the queue, everything else waits.
+    /// If Neos was started in the meantime they wait too, and get turned down like any other apply once the queue is done
+    fn collect_incoming_operations(&mut self) {
+        while let Ok(command) = self.command_receiver.try_recv() {
+            match command {
+                ManagerCommand::ApplyOperations(operations) if !self.is_game_running() => self.queue_operations(&operations),
+                command => self.pending_commands.push_back(command)
+            }
+        }
+    }
+
+    async fn run_install(&mut self, index: usize, id: &IDVersion, location: Option<&Path>) -> Result<(), InstallError> {
+        self.set_status(index, OperationStatus::Downloading).await;
+        let downloaded = self.install.download_artifacts(id, location).await?;
+
+        for artifact in &downloaded {
+            if let Some(mirror) = &artifact.mirror {
+                self.event_sender.send(ManagerEvent::Notification(ToastKind::Info, format!("Downloaded {} from mirror {}", artifact.url, mirror))).await.ok();
+            }
+        }
+
+        self.set_status(index, OperationStatus::Verifying).await;
+        verify_artifacts(&downloaded)?;
+
+        self.set_status(index, OperationStatus::Applying).await;
+        self.install.write_artifacts(id, downloaded).await
+    }
+
+    /// Uninstalls the mod, putting a copy of it into trash first if that's enabled
+    async fn run_uninstall(&mut self, id: &IDVersion) -> Result<(), InstallError> {
+        let trash_enabled = self.config.load().trash.enabled;
+        let file = self.install.mod_map().file(&id.0, &id.1).cloned();
+
+        let trashed = match file {
+            Some(file) if trash_enabled => {
+                self.trash.add(id.clone(), &file).await?;
+                self.trash.entries.last().map(|x| x.id)
+            }
+            _ => None
+        };
+
+        let result = self.install.perform_operations(&[ModInstallOperations::UninstallMod(id.clone())]).await;
+
+        if let Some(trash_id) = trashed {
+            if result.is_err() {
+                self.trash.delete(trash_id).await;
+            }
+
+            handle_error(self.trash.save().await, &self.event_sender).await;
+            self.send_trash().await;
+        }
+
+        result
+    }
+
+    async fn send_trash(&self) {
+        self.event_sender.send(ManagerEvent::TrashChanged(self.trash.entries.clone())).await.ok();
+    }
+
+    async fn restore_from_trash(&mut self, id: u64) {
+        if self.is_game_running() {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
+        }
+
+        if handle_error(self.trash.restore(id).await, &self.event_sender).await.is_some() {
+            handle_error(self.trash.save().await, &self.event_sender).await;
+
+            if let Some(_) = handle_error(self.rescan_mods().await, &self.event_sender).await {
+                self.send_mod_map().await;
+                handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+                self.write_lockfile().await;
+            }
+        }
+
+        self.send_trash().await;
+    }
+
+    async fn process_queue(&mut self) {
+        let mut applied = 0;
+        let mut failed = 0;
+        let mut installed_something = false;
+
+        while let Some(index) = self.queue.iter().position(|x| x.status == OperationStatus::Queued) {
+            let operation = self.queue[index].operation.clone();
+
+            let mut entry = self.history.new_entry(operation.clone());
+            self.backup_files(&mut entry).await;
+
+            let result = match &operation {
+                ModInstallOperations::InstallMod(id) => {
+                    installed_something = true;
+                    self.run_install(index, id, None).await
+                }
+                ModInstallOperations::InstallModAt(id, location) => {
+                    installed_something = true;
+                    self.run_install(index, id, Some(location.as_path())).await
+                }
+                ModInstallOperations::UninstallMod(id) => {
+                    self.set_status(index, OperationStatus::Applying).await;
+                    self.run_uninstall(id).await
+                }
+                _ => {
+                    self.set_status(index, OperationStatus::Applying).await;
+                    self.install.perform_operations(std::slice::from_ref(&operation)).await
+                }
+            };
+
+            match result {
+                Ok(_) => {
+                    applied += 1;
+                    self.set_status(index, OperationStatus::Done).await;
+
+                    self.record_history(entry).await;
+                }
+                Err(e) => {
+                    failed += 1;
+                    tokio::fs::remove_dir_all(entry.backup_folder()).await.ok();
+
+                    self.queue[index].status = OperationStatus::Failed(e.to_string());
+
+                    let batch = self.queue[index].batch;
+
+                    for item in self.queue.iter_mut().filter(|x| x.batch == batch && x.status == OperationStatus::Queued) {
+                        item.status = OperationStatus::Failed(format!("Skipped, previous change failed"));
+                    }
+
+                    self.send_queue().await;
+                    send_error(e.into(), &self.event_sender).await;
+                }
+            }
+
+            self.collect_incoming_operations();
+        }
+
+        // Installed files are picked up from disk, so map has exactly what's there
+        if installed_something {
+            handle_error(self.rescan_mods().await, &self.event_sender).await;
+        }
+
+        // Sending the map even if something failed, so UI doesn't keep showing what didn't happen
+        self.send_mod_map().await;
+        handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+        self.write_lockfile().await;
+        self.finish_pending_sync().await;
+
+        if installed_something {
+            self.prune_artifact_cache(false).await;
+        }
+
+        if failed == 0 {
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Applied {} changes", applied))).await.ok();
+        } else {
+            self.event_sender.send(ManagerEvent::LongNotification(ToastKind::Warning, format!("{} changes applied, {} failed", applied, failed))).await.ok();
+        }
+    }
+
+    /// Copies files of the version that is about to be uninstalled, so the uninstall can be undone
+    async fn backup_files(&self, entry: &mut HistoryEntry) {
+        let ModInstallOperations::UninstallMod((mod_id, version)) = &entry.operation else {
+            return;
+        };
+
+        let Some(file) = self.install.mod_map().file(mod_id, version) else {
+            return;
+        };
+
+        let folder = entry.backup_folder();
+        tokio::fs::create_dir_all(&folder).await.ok();
+
+        for (index, artifact) in file.files.iter().enumerate() {
+            let mut backup = folder.clone();
+            backup.push(format!("{}_{}", index, artifact.file_path.file_name().map_or_else(|| "unknown".to_string(), |x| x.to_string_lossy().to_string())));
+
+            // Uninstall still goes through without a backup, it just can't be undone
+            let backup = tokio::fs::copy(&artifact.file_path, &backup).await.ok().map(|_| backup);
+
+            entry.files.push(HistoryFile {
+                path: artifact.file_path.clone(),
+                backup,
+            });
+        }
+    }
+
+    async fn record_history(&mut self, mut entry: HistoryEntry) {
+        // Uninstalls already have their files from the backup
+        if entry.files.is_empty() {
+            let id = match &entry.operation {
+                ModInstallOperations::InstallMod(id) | ModInstallOperations::InstallModAt(id, _) => id,
+                ModInstallOperations::EnableMod(id) | ModInstallOperations::DisableMod(id) => id,
+                ModInstallOperations::UninstallMod(id) => id,
+            };
+
+            if let Some(file) = self.install.mod_map().file(&id.0, &id.1) {
+                entry.files = file.files.iter()
+                    .map(|x| HistoryFile {
+                        path: x.file_path.clone(),
+                        backup: None,
+                    })
+                    .collect();
+            }
+        }
+
+        self.history.push(entry).await;
+        self.send_history().await;
+
+        handle_error(self.history.save().await, &self.event_sender).await;
+    }
+
+    async fn send_history(&self) {
+        self.event_sender.send(ManagerEvent::HistoryChanged(self.history.entries.clone())).await.ok();
+    }
+
+    async fn undo_operation(&mut self, id: u64) {
+        if self.is_game_running() {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
+        }
+
+        let Some(entry) = self.history.entries.iter().find(|x| x.id == id).cloned() else {
+            return;
+        };
+
+        if !self.history.is_undoable(id) {
+            send_error(ManagerError::Other("This change can't be undone anymore, its files were changed or removed".to_string()), &self.event_sender).await;
+            return;
+        }
+
+        let result = match &entry.operation {
+            ModInstallOperations::InstallMod(id) | ModInstallOperations::InstallModAt(id, _) => {
+                self.install.perform_operations(&[ModInstallOperations::UninstallMod(id.clone())]).await
+            }
+            ModInstallOperations::UninstallMod(_) => {
+                match restore_backup(&entry).await {
+                    Ok(_) => self.rescan_mods().await,
+                    Err(e) => Err(e)
+                }
+            }
+            ModInstallOperations::EnableMod(id) => {
+                self.install.perform_operations(&[ModInstallOperations::DisableMod(id.clone())]).await
+            }
+            ModInstallOperations::DisableMod(id) => {
+                self.install.perform_operations(&[ModInstallOperations::EnableMod(id.clone())]).await
+            }
+        };
+
+        if handle_error(result, &self.event_sender).await.is_some() {
+            if let Some(entry) = self.history.get_mut(id) {
+                entry.undone = true;
+            }
+
+            handle_error(self.history.save().await, &self.event_sender).await;
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, "Change was undone".to_string())).await.ok();
+        }
+
+        self.send_history().await;
+        self.send_mod_map().await;
+        handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+        self.write_lockfile().await;
+    }
+
+    /// Rebuilds the client and applies speed limit after network settings change
+    /// Downloads go through the transport until network settings are reloaded
+    #[cfg(test)]
+    pub(crate) fn set_transport(&mut self, transport: Arc<dyn Transport>) {
+        self.install.set_transport(transport.clone());
+        self.transport = transport;
+    }
+
+    async fn reload_network_settings(&mut self) {
+        let network = self.config.load().network.clone();
+
+        if let Some(client) = handle_error(network.build_client(), &self.event_sender).await {
+            self.install.apply_network_settings(client.clone(), &network);
+            self.transport = Arc::new(client.clone());
+            self.client = client;
+        }
+    }
+
+    /// Lets UI know that mods folder was changed by something else, so it can offer a rescan
+    async fn check_for_external_changes(&self) {
+        let config = self.config.load_full();
+
+        if let Some(true) = handle_error(self.install.has_external_changes(&config).await, &self.event_sender).await {
+            self.event_sender.send(ManagerEvent::ExternalChangesDetected).await.ok();
+        }
+    }
+
+    async fn find_stray_mods(&self) {
+        let config = self.config.load_full();
+        let strays = handle_error(self.install.find_stray_mods(&config).await, &self.event_sender).await;
+
+        self.event_sender.send(ManagerEvent::StrayModsFound(strays.unwrap_or_default())).await.ok();
+    }
+
+    async fn move_stray_mods(&mut self, strays: Vec<StrayMod>) {
+        if self.is_game_running() {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
+        }
+
+        let moved = handle_error(self.install.move_stray_mods(&strays).await, &self.event_sender).await.is_some();
+
+        // Some of them could've been moved before one failed, so rescan either way
+        if handle_error(self.rescan_mods().await, &self.event_sender).await.is_some() {
+            self.send_mod_map().await;
+            handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+            self.write_lockfile().await;
+        }
+
+        if moved {
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Moved {} file(s) into their folders", strays.len()))).await.ok();
+        }
+    }
+
+    /// File name and contents of a DLL from a path or a direct download link
+    async fn read_sideload_source(&self, source: &str) -> Result<(String, Vec<u8>), InstallError> {
+        let (file_name, data) = if source.starts_with("http://") || source.starts_with("https://") {
+            let url = source.split(['?', '#']).next().unwrap_or(source);
+
+            (find_filename_from_url(url, ""), self.transport.get(source, &mut None).await?)
+        } else {
+            let path = PathBuf::from(source);
+
+            (path.file_name().map(|x| x.to_string_lossy().to_string()), tokio::fs::read(&path).await?)
+        };
+
+        match file_name {
+            Some(file_name) if file_name.to_lowercase().ends_with(".dll") => Ok((file_name, data)),
+            _ => Err(InstallError::InvalidArtifact(source.to_string()))
+        }
+    }
+
+    async fn inspect_sideload(&mut self, source: String) {
+        let Some((file_name, data)) = handle_error(self.read_sideload_source(&source).await, &self.event_sender).await else {
+            self.event_sender.send(ManagerEvent::SideloadInspected(None)).await.ok();
+            return;
+        };
+
+        let hash = sha256_bytes(&data);
+        let file = SideloadFile {
+            identified: self.install.identify_artifact(&hash),
+            source,
+            file_name,
+            size: data.len() as u64,
+            hash,
+        };
+
+        self.pending_sideload = Some((file.clone(), data));
+        self.event_sender.send(ManagerEvent::SideloadInspected(Some(file))).await.ok();
+    }
+
+    /// Info is recorded for the file by its hash, so it shows up with a proper name even though no manifest has it
+    async fn install_sideload(&mut self, source: String, location: PathBuf, info: Option<LocalModInfo>) {
+        if self.is_game_running() {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
+        }
+
+        let (file_name, data) = match self.pending_sideload.take() {
+            Some((file, data)) if file.source == source => (file.file_name, data),
+            _ => match handle_error(self.read_sideload_source(&source).await, &self.event_sender).await {
+                Some(read) => read,
+                None => return
+            }
+        };
+
+        if handle_error(self.install.install_file(&file_name, &data, &location).await, &self.event_sender).await.is_none() {
+            return;
+        }
+
+        if let Some(info) = info {
+            self.local_mods.mods.insert(sha256_bytes(&data), info);
+            handle_error(self.local_mods.save().await, &self.event_sender).await;
+            self.send_local_mods().await;
+        }
+
+        if handle_error(self.rescan_mods().await, &self.event_sender).await.is_some() {
+            self.send_mod_map().await;
+            handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+            self.write_lockfile().await;
+        }
+
+        self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Installed {}", file_name))).await.ok();
+    }
+
+    async fn send_local_mods(&self) {
+        self.event_sender.send(ManagerEvent::LocalModsChanged(self.local_mods.mods.clone())).await.ok();
+    }
+
+    async fn set_local_mod_details(&mut self, hash: String, details: LocalModDetails) {
+        self.local_mods.set_details(&hash, details);
+        handle_error(self.local_mods.save().await, &self.event_sender).await;
+        self.send_local_mods().await;
+    }
+
+    async fn send_notes(&self) {
+        self.event_sender.send(ManagerEvent::NotesChanged(self.notes.notes.clone())).await.ok();
+    }
+
+    async fn set_mod_notes(&mut self, mod_id: String, notes: String) {
+        self.notes.set(&mod_id, &notes);
+        handle_error(self.notes.save().await, &self.event_sender).await;
+        self.send_notes().await;
+    }
+
+    async fn fetch_github_releases(&self, repo_url: String) {
+        let result = match parse_repo_url(&repo_url) {
+            Some((owner, repo)) => fetch_releases(&self.client, &owner, &repo).await.map_err(|e| e.to_string()),
+            None => Err(format!("{} isn't a link to a GitHub repository", repo_url))
+        };
+
+        self.event_sender.send(ManagerEvent::GitHubReleases(repo_url, result)).await.ok();
+    }
+
+    async fn install_github_asset(&mut self, repo_url: String, tag: String, asset_url: String, location: PathBuf) {
+        let info = LocalModInfo {
+            name: parse_repo_url(&repo_url).map(|(_, repo)| repo),
+            version: version_from_tag(&tag),
+            source: Some(repo_url),
+            ..Default::default()
+        };
+
+        self.install_sideload(asset_url, location, Some(info)).await;
+    }
+
+    async fn fetch_artifact_sizes(&mut self, urls: Vec<String>) {
+        let missing: Vec<String> = urls.into_iter()
+            .filter(|x| !self.artifact_sizes.contains_key(x))
+            .collect();
+
+        let client = &self.client;
+        let fetched = join_all(missing.iter().map(|url| async move {
+            (url.clone(), fetch_artifact_size(client, url).await)
+        })).await;
+
+        for (url, result) in fetched {
+            // Servers that don't tell the size just won't have it shown
+            if let Ok(Some(size)) = result {
+                self.artifact_sizes.insert(url, size);
+            }
+        }
+
+        self.event_sender.send(ManagerEvent::ArtifactSizes(self.artifact_sizes.clone())).await.ok();
+    }
+
+    async fn export_mod_pack(&self, mods: &[IDVersion], path: PathBuf) {
+        let result = export_mod_pack(self.install.location(), self.install.mod_map(), mods, path).await;
+
+        if let Some(count) = handle_error(result, &self.event_sender).await {
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Exported {} mods", count))).await.ok();
+        }
+    }
+
+    async fn import_mod_pack(&mut self, path: PathBuf) {
+        if self.is_game_running() {
+            send_error(ManagerError::GameRunning, &self.event_sender).await;
+            return;
+        }
+
+        let Some(pack) = handle_error(read_mod_pack(path).await, &self.event_sender).await else {
+            return;
+        };
+
+        let hash_table = self.global_mods.mod_hash_table.load_full();
+
+        let Some(prepared) = handle_error(prepare_import(&pack, self.install.location(), &hash_table), &self.event_sender).await else {
+            return;
+        };
+
+        let mut operations = vec![];
+        let mut skipped = 0;
+
+        for (id, artifacts) in prepared {
+            let already_installed = self.install.mod_map().is_installed(&id.0, &id.1);
+
+            if already_installed || artifacts.iter().any(|x| x.path.exists()) {
+                skipped += 1;
+                continue;
+            }
+
+            if handle_error(verify_artifacts(&artifacts), &self.event_sender).await.is_none() {
+                continue;
+            }
+
+            // Installs pick up staged files instead of downloading, so the pack still works offline
+            let mut staged = true;
+
+            for artifact in &artifacts {
+                if handle_error(self.install.stage_artifact(&artifact.expected_hash, &artifact.data).await, &self.event_sender).await.is_none() {
+                    staged = false;
+                    break;
+                }
+            }
+
+            if staged {
+                operations.push(import_operation(id, &artifacts, self.install.location()));
+            }
+        }
+
+        if skipped > 0 {
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Info, format!("{} mods from the pack were already there", skipped))).await.ok();
+        }
+
+        // Mods from the pack go through the same conflict and untrusted source checks as any other install
+        if !operations.is_empty() {
+            self.preview_operations(operations).await;
+        }
+    }
+
+    /// Locks what's installed after the organizer changed it, nothing is written until manifests are there to tell which mods can be locked
+    async fn write_lockfile(&self) {
+        let mod_list = self.global_mods.mod_list.load();
+
+        if mod_list.is_empty() {
+            return;
+        }
+
+        let lockfile = Lockfile::from_mod_map(self.install.mod_map(), &mod_list);
+        handle_error(lockfile.save(&Config::lockfile_path()).await, &self.event_sender).await;
+    }
+
+    /// Installs and removes mods until the install matches the lockfile, the default one is used if no path is given
+    async fn sync_to_lockfile(&mut self, path: Option<PathBuf>) {
+        let path = path.unwrap_or_else(Config::lockfile_path);
+
+        if !path.exists() {
+            send_error(ManagerError::Other(format!("There's no lockfile at {}", path.display())), &self.event_sender).await;
+            return;
+        }
+
+        let Some(lockfile) = handle_error(Lockfile::load(&path).await, &self.event_sender).await else {
+            return;
+        };
+
+        let operations = self.lockfile_sync_operations(&lockfile).await;
+
+        if !operations.is_empty() {
+            self.preview_operations(operations).await;
+        }
+    }
+
+    /// Operations that make the install match the lockfile, tells the user if some can't be done or there's nothing to do
+    async fn lockfile_sync_operations(&self, lockfile: &Lockfile) -> Vec<ModInstallOperations> {
+        let sync = lockfile.sync_operations(self.install.mod_map(), &self.global_mods.mod_list.load());
+
+        if !sync.unavailable.is_empty() {
+            let unavailable = sync.unavailable.iter()
+                .map(|(mod_id, version)| format!("{} v{}", mod_id, version))
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Warning, format!("Manifests don't have these locked versions anymore: {}", unavailable))).await.ok();
+        }
+
+        if sync.operations.is_empty() {
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Info, "Installed mods already match the lockfile".to_string())).await.ok();
+        }
+
+        sync.operations
+    }
+
+    /// Launch profiles and the lockfile as they are on this PC
+    async fn local_snapshot(&self) -> SyncSnapshot {
+        let lockfile = match Lockfile::load(&Config::lockfile_path()).await {
+            Ok(lockfile) => lockfile,
+            Err(_) => Lockfile::from_mod_map(self.install.mod_map(), &self.global_mods.mod_list.load())
+        };
+
+        SyncSnapshot {
+            launch_profiles: self.config.load().launch_profiles.clone(),
+            lockfile,
+        }
+    }
+
+    /// Merges what's synced with local changes, pushing the result back if asked to. Conflicts are left for the user to pick a side
+    async fn cloud_sync(&mut self, push: bool) {
+        self.pending_sync = None;
+
+        let settings = self.config.load().cloud_sync.clone();
+
+        let Some(remote) = handle_error(pull_snapshot(&self.client, &settings).await, &self.event_sender).await else {
+            return;
+        };
+
+        let Some(base) = handle_error(SyncSnapshot::load_base().await, &self.event_sender).await else {
+            return;
+        };
+
+        // Nothing synced yet counts as nothing changed there
+        let remote = remote.unwrap_or_else(|| base.clone());
+        let local = self.local_snapshot().await;
+
+        let (merged, conflicts) = merge_snapshots(&base, &local, &remote);
+
+        if conflicts.is_empty() {
+            self.finish_cloud_sync(merged, remote, push).await;
+        } else {
+            self.event_sender.send(ManagerEvent::CloudSyncConflicts { merged, remote, conflicts, push }).await.ok();
+        }
+    }
+
+    /// Applies merged setup here and pushes it if asked to, install gets synced to the lockfile if it changed
+    async fn finish_cloud_sync(&mut self, merged: SyncSnapshot, remote: SyncSnapshot, push: bool) {
+        let local = self.local_snapshot().await;
+
+        if merged.launch_profiles != local.launch_profiles {
+            let mut config = self.config.load().as_ref().clone();
+            config.launch_profiles = merged.launch_profiles.clone();
+            self.config.swap(Arc::new(config));
+            self.save_config().await;
+        }
+
+        // Base is what both sides have, without pushing that's only what was pulled
+        let base = if push {
+            let settings = self.config.load().cloud_sync.clone();
+
+            let Some(new_gist) = handle_error(push_snapshot(&self.client, &settings, &merged).await, &self.event_sender).await else {
+                return;
+            };
+
+            if let Some(id) = new_gist {
+                let mut config = self.config.load().as_ref().clone();
+                config.cloud_sync.target = SyncTarget::Gist { id };
+                self.config.swap(Arc::new(config));
+                self.save_config().await;
+                self.event_sender.send(ManagerEvent::ConfigReloaded).await.ok();
+            }
+
+            merged.clone()
+        } else {
+            remote
+        };
+
+        let message = if push { "Setup was synced" } else { "Synced setup was pulled" };
+        self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, message.to_string())).await.ok();
+
+        let operations = if merged.lockfile != local.lockfile {
+            self.lockfile_sync_operations(&merged.lockfile).await
+        } else {
+            vec![]
+        };
+
+        if operations.is_empty() {
+            if merged.lockfile != local.lockfile && handle_error(merged.lockfile.save(&Config::lockfile_path()).await, &self.event_sender).await.is_none() {
+                return;
+            }
+
+            handle_error(base.save_base().await, &self.event_sender).await;
+            return;
+        }
+
+        // Lockfile and base stay as they are until the mods are actually synced, declining leaves it for the next sync
+        self.pending_sync = Some(PendingSync::new(base, operations.clone()));
+        self.preview_operations(operations).await;
+    }
+
+    /// Saves base of the cloud sync that was waiting on the queue, once all of its operations went through
+    async fn finish_pending_sync(&mut self) {
+        let Some(outcome) = self.pending_sync.as_ref().and_then(|x| x.outcome(&self.queue)) else {
+            return;
+        };
+
+        let Some(pending) = self.pending_sync.take() else {
+            return;
+        };
+
+        if outcome {
+            handle_error(pending.base.save_base().await, &self.event_sender).await;
+        } else {
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Warning, "Not all synced changes were applied, next sync will try again".to_string())).await.ok();
+        }
+    }
+
+    /// Saves config, unless it was edited by hand since last time, then the file wins and gets loaded instead
+    async fn save_config(&mut self) {
+        if self.check_config_file().await {
+            return;
+        }
+
+        handle_error(self.config.load().save_config().await, &self.event_sender).await;
+        self.config_modified = Config::config_modified_time().await;
+    }
+
+    /// Reloads config if the file was changed by something else, returns true if it was reloaded
+    async fn check_config_file(&mut self) -> bool {
+        let modified = Config::config_modified_time().await;
+
+        if modified.is_none() || modified == self.config_modified {
+            return false;
+        }
+
+        self.config_modified = modified;
+
+        let Some(config) = handle_error(Config::load_config().await, &self.event_sender).await else {
+            // File stays as user left it, it gets looked at again once they save it
+            return false;
+        };
+
+        let network_changed = config.network != self.config.load().network;
+
+        self.config.swap(Arc::new(config));
+        // Loading might have migrated the file
+        self.config_modified = Config::config_modified_time().await;
+
+        if network_changed {
+            self.reload_network_settings().await;
+        }
+
+        self.apply_cache_settings();
+
+        self.event_sender.send(ManagerEvent::ConfigReloaded).await.ok();
+        self.event_sender.send(ManagerEvent::LaunchOptionsState(self.config.load().launch_options.clone())).await.ok();
+        self.event_sender.send(ManagerEvent::Notification(ToastKind::Info, "Config file was changed outside of the organizer, reloaded it".to_string())).await.ok();
+
+        true
+    }
+
+    async fn refresh_manifests(&mut self) {
+        let time = Instant::now();
+        let config = self.config.load();
+        self.last_manifest_refresh = time;
+
+        let sources: Vec<(String, Option<String>)> = config.manifest_links.iter()
+            .map(|x| (x.clone(), config.source_trust(x).public_key))
+            .collect();
+
+        let (mods, mod_sources, errors) = aggregate_manifests(self.transport.as_ref(), &config.network.mirrors, &sources).await;
+        self.mod_sources = mod_sources;
+
+        for (url, error) in errors {
+            send_error(ManagerError::network(
+                format!("Reading manifest \"{}\" failed, error:\n{}", url, error),
+                Some(ManagerCommand::RefreshManifests)
+            ), &self.event_sender).await;
+        }
+
+        let len = mods.len();
+        let generation = self.global_mods.update_list(mods);
+
+        self.event_sender.send(ManagerEvent::ManifestUpdated {
+            count: len,
+            generation,
+        }).await.ok();
+
+        if self.known_versions.record(&self.global_mods.mod_list.load(), unix_timestamp()) {
+            handle_error(self.known_versions.save().await, &self.event_sender).await;
+        }
+
+        self.event_sender.send(ManagerEvent::ReleaseFeed(self.known_versions.recent_releases(FEED_LENGTH))).await.ok();
+
+        self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Downloaded info about {} mods in {}ms", len, time.elapsed().as_millis()))).await.ok();
+    }
+
+    /// Updates of installed mods on their channels, without the skipped ones
+    fn pending_updates(&self) -> Vec<(IDVersion, Version)> {
+        let config = self.config.load();
+        let mut updates = available_updates(self.install.mod_map(), &self.global_mods.mod_list.load(), |x| config.update_channel_for(x));
+        updates.retain(|((mod_id, _), newest)| !config.is_update_skipped(mod_id, newest));
+
+        updates
+    }
+
+    /// Tells UI about updates that weren't announced before, so it can notify about them while minimized
+    async fn announce_new_updates(&mut self) {
+        let new_updates: Vec<(IDVersion, Version)> = self.pending_updates().into_iter()
+            .filter(|x| self.announced_updates.insert(x.clone()))
+            .collect();
+
+        if !new_updates.is_empty() && self.config.load().update_notifications {
+            self.event_sender.send(ManagerEvent::UpdatesFound(new_updates)).await.ok();
+        }
+    }
+
+    pub async fn run_event_loop(&mut self) {
+        self.install.set_staging_folder(Config::staging_path());
+        self.apply_cache_settings();
+        self.event_sender.send(ManagerEvent::LaunchOptionsState(self.config.load().launch_options.clone())).await.expect("Failed");
+
+        // Showing last known mods right away, rescan below patches in the differences
+        if let Some(true) = handle_error(self.install.load_cached_mods().await, &self.event_sender).await {
+            self.send_mod_map().await;
+        }
+
+        if let Some(history) = handle_error(History::load().await, &self.event_sender).await {
+            self.history = history;
+            self.send_history().await;
+        }
+
+        if let Some(readme_cache) = handle_error(ReadmeCache::load().await, &self.event_sender).await {
+            self.readme_cache = readme_cache;
+        }
+
+        if let Some(known_versions) = handle_error(KnownVersions::load().await, &self.event_sender).await {
+            self.known_versions = known_versions;
+        }
+
+        if let Some(local_mods) = handle_error(LocalMods::load().await, &self.event_sender).await {
+            self.local_mods = local_mods;
+            self.send_local_mods().await;
+        }
+
+        if let Some(notes) = handle_error(ModNotes::load().await, &self.event_sender).await {
+            self.notes = notes;
+            self.send_notes().await;
+        }
+
+        if let Some(trash) = handle_error(Trash::load().await, &self.event_sender).await {
+            self.trash = trash;
+
+            if self.trash.purge_expired(self.config.load().trash.retention_days).await > 0 {
+                handle_error(self.trash.save().await, &self.event_sender).await;
+            }
+
+            self.send_trash().await;
+        }
+
+        self.reload_network_settings().await;
+
+        // Get the manifest
+        self.refresh_manifests().await;
+
+        // Rescan mods
+        let time = Instant::now();
+
+        if let Some(_) = handle_error(self.rescan_mods().await, &self.event_sender).await {
+            self.send_mod_map().await;
+            self.event_sender.send(ManagerEvent::Notification(ToastKind::Success, format!("Found {} mods in {}ms", self.install.mod_map().len(), time.elapsed().as_millis()))).await.ok();
+
+            handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+
+            // Later rescans leave it be, so it can bring back what manual changes broke
+            if !Config::lockfile_path().exists() {
+                self.write_lockfile().await;
+            }
+        }
+
+        self.load_saved_queue().await;
+        self.prune_artifact_cache(false).await;
+
+        self.config_modified = Config::config_modified_time().await;
+        self.measure_data_folders();
+        self.announce_new_updates().await;
+
+        loop {
+            self.check_stopping_instances().await;
+
+            let command = match self.pending_commands.pop_front() {
+                Some(command) => Some(command),
+                None => match timeout(CONFIG_CHECK_INTERVAL, self.command_receiver.recv()).await {
+                    Ok(command) => command,
+                    Err(_) => {
+                        self.check_config_file().await;
+                        self.prune_instances().await;
+
+                        if self.last_manifest_refresh.elapsed() > MANIFEST_REFRESH_INTERVAL {
+                            self.refresh_manifests().await;
+                            self.announce_new_updates().await;
+                        }
+
+                        continue;
+                    }
+                }
+            };
+
+            if let Some(command) = command {
+                if !self.handle_command(command).await {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Carries out a command from UI, false means the manager was shut down
+    pub(crate) async fn handle_command(&mut self, command: ManagerCommand) -> bool {
+        match command {
+            ManagerCommand::Test => {println!("test")}
+            ManagerCommand::LaunchNeos => {
+                let options = self.config.load().launch_options.clone();
+                self.launch_neos(options, None).await;
+            }
+
+            ManagerCommand::LaunchNeosPreset(preset) => {
+                let options = preset.apply(&self.config.load().launch_options);
+                self.launch_neos(options, None).await;
+            }
+
+            ManagerCommand::LaunchNeosProfile(name) => {
+                let profile = self.config.load().launch_profiles.iter()
+                    .find(|x| x.name == name)
+                    .cloned();
+
+                if let Some(profile) = profile {
+                    self.launch_neos(profile.options, Some(profile.name)).await;
+                } else {
+                    send_error(ManagerError::Other(format!("Launch profile \"{}\" doesn't exist", name)), &self.event_sender).await;
+                }
+            }
+
+            ManagerCommand::LaunchAnyway => {
+                if let Some((options, profile)) = self.pending_launch.take() {
+                    self.start_instance(options, profile).await;
+                }
+            }
+
+            ManagerCommand::ContinueLaunch => {
+                if let Some((options, profile)) = self.pending_update_launch.take() {
+                    self.check_launch(options, profile).await;
+                }
+            }
+
+            ManagerCommand::LaunchDisablingConflicts => {
+                if let Some((options, profile)) = self.pending_launch.take() {
+                    let mods = std::mem::take(&mut self.pending_disable);
+                    self.disable_for_session(mods).await;
+                    self.start_instance(options, profile).await;
+                }
+            }
+
+            ManagerCommand::MeasureDataFolders => {
+                self.measure_data_folders();
+            }
+
+            ManagerCommand::ClearCache => {
+                self.clear_cache().await;
+            }
+
+            ManagerCommand::StopInstance { id, restart } => {
+                self.stop_instance(id, restart).await;
+            }
+
+            ManagerCommand::CreateShortcut(path) => {
+                #[cfg(target_os="windows")]
+                handle_error(self.config.load().launch_options.make_shortcut(&self.config.load().neos_exe_location, path), &self.event_sender).await;
+                #[cfg(not(target_os="windows"))]
+                send_error(ManagerError::Other(format!("Cannot create shortcut\nmslnk wasn't compiled due to compilation target")), &self.event_sender).await;
+            }
+
+            ManagerCommand::SaveConfig => {
+                self.save_config().await;
+                self.apply_cache_settings();
+            }
+            ManagerCommand::PruneArtifactCache => {
+                self.prune_artifact_cache(true).await;
+            }
+            ManagerCommand::ClearArtifactCache => {
+                self.clear_artifact_cache().await;
+            }
+            ManagerCommand::InspectFolders => {
+                self.send_maintenance_report().await;
+            }
+            ManagerCommand::CleanUpFolders => {
+                self.clean_up_folders().await;
+            }
+            ManagerCommand::ResumeSavedQueue => {
+                self.resume_saved_queue().await;
+            }
+            ManagerCommand::DiscardSavedQueue => {
+                self.discard_saved_queue().await;
+            }
+            ManagerCommand::Shutdown => {
+                // Game has loaded its mods by now if it's still running
+                self.restore_session_disabled().await;
+                self.save_config().await;
+                self.event_sender.send(ManagerEvent::ShutdownComplete).await.ok();
+
+                return false;
+            }
+            ManagerCommand::RefreshModMap => {
+                if let Some(_) = handle_error(self.rescan_mods().await, &self.event_sender).await {
+                    self.send_mod_map().await;
+                    handle_error(self.install.save_cached_mods().await, &self.event_sender).await;
+                }
+            }
+            ManagerCommand::RefreshManifests => {
+                self.refresh_manifests().await;
+                self.announce_new_updates().await;
+            }
+            ManagerCommand::FetchArtifactSizes(urls) => {
+                self.fetch_artifact_sizes(urls).await;
+            }
+            ManagerCommand::CheckForExternalChanges => {
+                // Game can be touching the files while it's running, checking after it closes is enough
+                if !self.is_game_running() {
+                    self.check_for_external_changes().await;
+                }
+            }
+            ManagerCommand::InspectSideload(source) => {
+                self.inspect_sideload(source).await;
+            }
+            ManagerCommand::InstallSideload { source, location } => {
+                self.install_sideload(source, location, None).await;
+            }
+            ManagerCommand::FetchGitHubReleases(repo_url) => {
+                self.fetch_github_releases(repo_url).await;
+            }
+            ManagerCommand::InstallGitHubAsset { repo_url, tag, asset_url, location } => {
+                self.install_github_asset(repo_url, tag, asset_url, location).await;
+            }
+            ManagerCommand::SetLocalModDetails { hash, details } => {
+                self.set_local_mod_details(hash, details).await;
+            }
+            ManagerCommand::SetModNotes { mod_id, notes } => {
+                self.set_mod_notes(mod_id, notes).await;
+            }
+            ManagerCommand::FindStrayMods => {
+                self.find_stray_mods().await;
+            }
+            ManagerCommand::MoveStrayMods(strays) => {
+                self.move_stray_mods(strays).await;
+            }
+            ManagerCommand::ReloadNetworkSettings => {
+                self.reload_network_settings().await;
+                self.refresh_manifests().await;
+            }
+            ManagerCommand::InstallMod { guid, version_req } => {
+                self.install_mod(&guid, &version_req).await;
+            }
+            ManagerCommand::PreviewOperations(operations) => {
+                self.preview_operations(operations).await;
+            }
+            ManagerCommand::SuggestFixes => {
+                self.suggest_conflict_fixes();
+            }
+            ManagerCommand::SimulatePlan(steps) => {
+                self.simulate_plan(&steps).await;
+            }
+            ManagerCommand::ApplyOperations(operations) => {
+                self.apply_operations(&operations).await;
+            }
+            ManagerCommand::ExportModPack { mods, path } => {
+                self.export_mod_pack(&mods, path).await;
+            }
+            ManagerCommand::ImportModPack(path) => {
+                self.import_mod_pack(path).await;
+            }
+            ManagerCommand::SyncToLockfile(path) => {
+                self.sync_to_lockfile(path).await;
+            }
+            ManagerCommand::CloudSync { push } => {
+                self.cloud_sync(push).await;
+            }
+            ManagerCommand::FinishCloudSync { merged, remote, push } => {
+                self.finish_cloud_sync(merged, remote, push).await;
+            }
+            ManagerCommand::RestoreFromTrash(id) => {
+                self.restore_from_trash(id).await;
+            }
+            ManagerCommand::DeleteFromTrash(id) => {
+                self.trash.delete(id).await;
+                handle_error(self.trash.save().await, &self.event_sender).await;
+                self.send_trash().await;
+            }
+            ManagerCommand::EmptyTrash => {
+                self.trash.empty().await;
+                handle_error(self.trash.save().await, &self.event_sender).await;
+                self.send_trash().await;
+            }
+            ManagerCommand::UndoOperation(id) => {
+                self.undo_operation(id).await;
+            }
+            ManagerCommand::ClearFinishedOperations => {
+                self.queue.retain(|x| !x.status.is_finished());
+                self.send_queue().await;
+            }
+            ManagerCommand::FetchImage(url) => {
+                let image = download_image(&self.client, &url).await.ok();
+                self.event_sender.send(ManagerEvent::ImageLoaded(url, image)).await.ok();
+            }
+            ManagerCommand::FindReadmeFor(guid) => {
+                if let Some(cached_readme) = self.readme_cache.fresh(&guid, unix_timestamp()) {
+                    self.event_sender.send(ReadmeResponse(
+                        Ok(Some(cached_readme.to_string()))
+                    )).await.ok();
+                } else {
+                    let response = match respond_to_readme_request(&self.client, &self.global_mods, &guid).await {
+                        Ok(Some(readme)) => {
+                            self.readme_cache.insert(guid, readme.clone(), unix_timestamp());
+                            handle_error(self.readme_cache.save().await, &self.event_sender).await;
+                            Ok(Some(readme))
+                        }
+                        Ok(None) => Ok(None),
+                        // Outdated README is better than an error when offline
+                        Err(e) => match self.readme_cache.any(&guid) {
+                            Some(readme) => Ok(Some(readme.to_string())),
+                            None => Err(e.to_string())
+                        }
+                    };
+
+                    self.event_sender.send(ReadmeResponse(
+                        response
+                    )).await.ok();
+                }
+            }
+        }
+
+        true
+    }
+}
+
+#[inline]
+async fn handle_error<T, E: Into<ManagerError>>(result: Result<T, E>, sender: &EventSender) -> Option<T> {
+    match result {
+        Ok(v) => Some(v),
+        Err(e) => {
+            send_error(e.into(), sender).await;
+            None
+        }
+    }
+}
+
+/// Writes the error into the log file and sends it to UI
+async fn send_error(error: ManagerError, sender: &EventSender) {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs());
+    let line = format!("[{}] {}: {}\n", timestamp, error.category(), error);
+
+    let log_path = Config::log_path();
+
+    if let Some(folder) = log_path.parent() {
+        tokio::fs::create_dir_all(folder).await.ok();
+    }
+
+    if let Ok(mut file) = tokio::fs::OpenOptions::new().create(true).append(true).open(log_path).await {
+        file.write_all(line.as_bytes()).await.ok();
+    }
+
+    sender.send(ManagerEvent::Error(error)).await.ok();
+}
+
+#[derive(Debug, Clone)]
+pub enum ManagerError {
+    /// Something went wrong while downloading, can be retried with provided command
+    Network {
+        message: String,
+        retry: Option<ManagerCommand>
+    },
+    /// Errors while reading or writing files, details can be found in the log
+    Filesystem(String),
+    /// Received data couldn't be parsed
+    Parse(String),
+    /// Operation can't be done while the game is running
+    GameRunning,
+    Other(String)
+}
+
+impl ManagerError {
+    pub fn network(error: impl Display, retry: Option<ManagerCommand>) -> Self {
+        Self::Network {
+            message: error.to_string(),
+            retry,
+        }
+    }
+
+    pub fn category(&self) -> &'static str {
+        match self {
+            ManagerError::Network { .. } => "Network",
+            ManagerError::Filesystem(_) => "Filesystem",
+            ManagerError::Parse(_) => "Parse",
+            ManagerError::GameRunning => "GameRunning",
+            ManagerError::Other(_) => "Other",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            ManagerError::Network { .. } => "🌐",
+            ManagerError::Filesystem(_) => "🗁",
+            ManagerError::Parse(_) => "📄",
+            ManagerError::GameRunning => "🚀",
+            ManagerError::Other(_) => "⚠",
+        }
+    }
+
+    pub fn retry_command(&self) -> Option<&ManagerCommand> {
+        if let ManagerError::Network { retry, .. } = self {
+            retry.as_ref()
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for ManagerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManagerError::Network { message, .. } => write!(f, "{}", message),
+            ManagerError::Filesystem(message) => write!(f, "{}", message),
+            ManagerError::Parse(message) => write!(f, "{}", message),
+            ManagerError::GameRunning => write!(f, "Neos is already running"),
+            ManagerError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for ManagerError {}
+
+impl From<io::Error> for ManagerError {
+    fn from(value: io::Error) -> Self {
+        Self::Filesystem(value.to_string())
+    }
+}
+
+impl From<reqwest::Error> for ManagerError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::network(value, None)
+    }
+}
+
+impl From<serde_json::Error> for ManagerError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Parse(value.to_string())
+    }
+}
+
+impl From<ConfigError> for ManagerError {
+    fn from(value: ConfigError) -> Self {
+        match value {
+            ConfigError::IOError(e) => e.into(),
+            ConfigError::JSONError(e) => e.into(),
+            ConfigError::NetworkError(e) => e.into(),
+            e => Self::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<ModPackError> for ManagerError {
+    fn from(value: ModPackError) -> Self {
+        match value {
+            ModPackError::FileError(e) => e.into(),
+            ModPackError::JSONError(e) => e.into(),
+            e @ (ModPackError::ZipError(_) | ModPackError::UnsupportedVersion(_)) => Self::Parse(e.to_string()),
+            e => Self::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<SyncError> for ManagerError {
+    fn from(value: SyncError) -> Self {
+        match value {
+            SyncError::NetworkError(e) => e.into(),
+            SyncError::FileError(e) => e.into(),
+            SyncError::ParseError(e) => e.into(),
+            e => Self::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<InstallError> for ManagerError {
+    fn from(value: InstallError) -> Self {
+        match value {
+            InstallError::FileError(e) => e.into(),
+            InstallError::JSONError(e) => e.into(),
+            InstallError::DownloadError(e) => Self::network(e, None),
+            e @ (InstallError::HashMismatch { .. } | InstallError::ModNotFound(_) | InstallError::InvalidArtifact(_)) => Self::Other(e.to_string()),
+            e => Self::Filesystem(e.to_string()),
+        }
+    }
+}
+
+#[cfg(target_os="windows")]
+impl From<mslnk::MSLinkError> for ManagerError {
+    fn from(value: mslnk::MSLinkError) -> Self {
+        Self::Filesystem(value.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OperationStatus {
+    Queued,
+    Downloading,
+    Verifying,
+    Applying,
+    Done,
+    Failed(String)
+}
+
+impl OperationStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, OperationStatus::Done | OperationStatus::Failed(_))
+    }
+}
+
+/// Operation waiting in or going through the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueItem {
+    pub id: u64,
+    pub batch: u64,
+    pub operation: ModInstallOperations,
+    pub status: OperationStatus
+}
+
+/// For communication from UI to Manager
+#[derive(Debug, Clone)]
+pub enum ManagerCommand {
+    Test,
+    SaveConfig,
+    LaunchNeos,
+    /// Launches with a preset applied over saved launch options, preset doesn't get saved
+    LaunchNeosPreset(LaunchPreset),
+    /// Launches with a secondary launch profile by name
+    LaunchNeosProfile(String),
+    /// Goes through with the launch that was stopped by LaunchProblems
+    LaunchAnyway,
+    /// Same as LaunchAnyway, but mods in direct conflict get disabled until the game closes
+    LaunchDisablingConflicts,
+    /// Goes on with the launch that was held back to show results of PreLaunchUpdatesFinished
+    ContinueLaunch,
+    /// Answered with DataFolderSizes
+    MeasureDataFolders,
+    /// Deletes contents of the game's cache folder
+    ClearCache,
+    /// Removes least recently used artifacts until the artifact cache fits its size limit, answered with ArtifactCacheSize
+    PruneArtifactCache,
+    /// Answered with ArtifactCacheSize
+    ClearArtifactCache,
+    /// Measures staging, trash, backups and artifact cache, answered with MaintenanceReport
+    InspectFolders,
+    /// Deletes items older than their retention from folders in MaintenanceReport, answered with MaintenanceReport
+    CleanUpFolders,
+    /// Closes game instance, forcefully if it doesn't close in time
+    StopInstance {
+        id: u64,
+        restart: bool
+    },
+    CreateShortcut(PathBuf),
+    RefreshManifests,
+    /// Applies changed network settings, then downloads manifests again with them
+    ReloadNetworkSettings,
+    RefreshModMap,
+    /// Looks up sizes of artifacts with HEAD requests, answered with ArtifactSizes
+    FetchArtifactSizes(Vec<String>),
+    /// Compares files on disk with the mod map, answered with ExternalChangesDetected if they differ
+    CheckForExternalChanges,
+    /// Looks for known mods in the whole game folder, answered with StrayModsFound
+    FindStrayMods,
+    /// Moves files found by FindStrayMods into their folders, answered with ModMapChanged
+    MoveStrayMods(Vec<StrayMod>),
+    /// Reads a DLL from a path or URL and looks it up in manifests by hash, answered with SideloadInspected
+    InspectSideload(String),
+    /// Puts a DLL from a path or URL into a folder relative to Neos folder, answered with ModMapChanged
+    InstallSideload {
+        source: String,
+        location: PathBuf
+    },
+    /// Lists releases of a GitHub repository by its link, answered with GitHubReleases
+    FetchGitHubReleases(String),
+    /// Installs a release asset like InstallSideload, recording repository name and version from the tag for it
+    InstallGitHubAsset {
+        repo_url: String,
+        tag: String,
+        asset_url: String,
+        location: PathBuf
+    },
+    /// Changes name, category and notes shown for a file that isn't in any manifest, answered with LocalModsChanged
+    SetLocalModDetails {
+        hash: String,
+        details: LocalModDetails
+    },
+    /// Replaces notes of the mod by its ID in mod map, blank notes remove them. Answered with NotesChanged
+    SetModNotes {
+        mod_id: String,
+        notes: String
+    },
+    /// Resolves, checks, downloads and installs the mod along with its dependencies
+    InstallMod {
+        guid: GUID,
+        version_req: VersionReq
+    },
+    /// Checks operations for new conflicts and untrusted sources, applies them if there's none, otherwise answered with PlanConflicts
+    PreviewOperations(Vec<ModInstallOperations>),
+    /// Tries steps of a plan on a virtual install without applying anything, answered with PlanSimulated
+    SimulatePlan(Vec<PlanStep>),
+    /// Looks for fixes to current conflicts, answered with ConflictFixesFound
+    SuggestFixes,
+    /// Removes finished and failed operations from the queue
+    ClearFinishedOperations,
+    /// Queues operations from UnfinishedQueue again, downloads that were staged are reused after checking their hashes
+    ResumeSavedQueue,
+    /// Forgets operations from UnfinishedQueue along with their staged downloads
+    DiscardSavedQueue,
+    /// Reverts history entry with provided id, if its files are still around
+    UndoOperation(u64),
+    /// Puts trashed mod back into mods folder, answered with ModMapChanged
+    RestoreFromTrash(u64),
+    DeleteFromTrash(u64),
+    EmptyTrash,
+    /// Bundles files of provided mods into a zip for offline sharing
+    ExportModPack {
+        mods: Vec<IDVersion>,
+        path: PathBuf
+    },
+    /// Installs mods from a pack after checking them against the manifest, answered with ModMapChanged
+    ImportModPack(PathBuf),
+    /// Makes the install match the lockfile at the path, or the one the organizer keeps if there's none
+    SyncToLockfile(Option<PathBuf>),
+    /// Pulls the synced setup and merges it with this one, merged setup is pushed back if push is set
+    CloudSync {
+        push: bool
+    },
+    /// Sync continued after the user picked sides of conflicts in the merged setup
+    FinishCloudSync {
+        merged: SyncSnapshot,
+        remote: SyncSnapshot,
+        push: bool
+    },
+    /// Performs install operations on the actual install, answered with ModMapChanged
+    ApplyOperations(Vec<ModInstallOperations>),
+    FindReadmeFor(GUID),
+    /// Downloads an image for README gallery, answered with ImageLoaded
+    FetchImage(String),
+    /// Finishes up and stops the event loop, answered with ShutdownComplete
+    Shutdown,
+}
+
+/// Sends events to UI and wakes it up, otherwise events would wait for user input to be seen
+#[derive(Clone)]
+pub struct EventSender {
+    sender: Sender<ManagerEvent>,
+    ctx: Context
+}
+
+impl EventSender {
+    pub fn new(sender: Sender<ManagerEvent>, ctx: Context) -> Self {
+        Self {
+            sender,
+            ctx,
+        }
+    }
+
+    pub async fn send(&self, event: ManagerEvent) -> Result<(), SendError<ManagerEvent>> {
+        let result = self.sender.send(event).await;
+        self.ctx.request_repaint();
+
+        result
+    }
+
+    pub fn try_send(&self, event: ManagerEvent) -> Result<(), TrySendError<ManagerEvent>> {
+        let result = self.sender.try_send(event);
+        self.ctx.request_repaint();
+
+        result
+    }
 }
 
 /// For communication from Manager to UI
@@ -180,8 +2315,79 @@ pub enum ManagerCommand {
 pub enum ManagerEvent {
     LaunchOptionsState(LaunchOptions),
     ModMapChanged(ModMap),
-    ReadmeResponse(Option<String>),
+    /// Global mod list was replaced, anything derived from it should be rebuilt
+    ManifestUpdated {
+        count: usize,
+        generation: u64
+    },
+    /// Recently released versions across all mods, sent after manifests are refreshed
+    ReleaseFeed(Vec<FeedEntry>),
+    /// Conflicts found in the mod map, sent right after ModMapChanged
+    ConflictsChanged(Vec<ModConflict>),
+    /// Fixes for conflicts that weren't suppressed, answer to SuggestFixes
+    ConflictFixesFound(Vec<(ModConflict, Vec<ConflictFix>)>),
+    /// Operations that need confirmation, along with conflicts they would introduce and mods they'd install from untrusted sources
+    PlanConflicts(Vec<ModInstallOperations>, Vec<ModConflict>, Vec<(GUID, String)>),
+    PlanSimulated(PlanOutcome),
+    /// Contents of the operation queue, sent whenever anything in it changes
+    QueueChanged(Vec<QueueItem>),
+    /// Operations that were still queued when the organizer closed last time
+    UnfinishedQueue(Vec<QueueItem>),
+    HistoryChanged(Vec<HistoryEntry>),
+    TrashChanged(Vec<TrashEntry>),
+    /// All artifact sizes known so far, by URL
+    ArtifactSizes(HashMap<String, u64>),
+    /// Files in mods folder don't match the mod map anymore
+    ExternalChangesDetected,
+    /// How many files rescan went through so far, scan is done once all of them are
+    ScanProgress {
+        scanned: usize,
+        total: usize
+    },
+    /// Known mods outside of scan locations, empty if none were found or scan failed
+    StrayModsFound(Vec<StrayMod>),
+    /// None if the file couldn't be read or downloaded
+    SideloadInspected(Option<SideloadFile>),
+    /// Releases of the repository link, or why they couldn't be listed
+    GitHubReleases(String, Result<Vec<GitHubRelease>, String>),
+    /// Names and versions recorded for unmanaged files, by hash
+    LocalModsChanged(HashMap<String, LocalModInfo>),
+    /// User's notes about mods, by mod ID
+    NotesChanged(HashMap<String, String>),
+    /// Config was edited by hand and loaded again, anything cached from it is stale
+    ConfigReloaded,
+    /// Both this PC and the synced setup changed the same entries, merged setup has local sides of them for now
+    CloudSyncConflicts {
+        merged: SyncSnapshot,
+        remote: SyncSnapshot,
+        conflicts: Vec<SyncConflict>,
+        push: bool
+    },
+    /// Game instances started by the organizer that are still running
+    GameInstancesChanged(Vec<GameInstanceInfo>),
+    DataFolderSizes(DataFolderSizes),
+    /// How much space cached artifacts take up
+    ArtifactCacheSize(u64),
+    /// Sizes and ages of organizer's own folders
+    MaintenanceReport(Vec<FolderReport>),
+    /// Launch was held back, LaunchAnyway launches regardless
+    LaunchProblems(Vec<LaunchProblem>),
+    /// None if mod has no README, error message if it couldn't be fetched
+    ReadmeResponse(Result<Option<String>, String>),
+    /// Image data by URL, None if it couldn't be downloaded
+    ImageLoaded(String, Option<Vec<u8>>),
+    /// Link opened from a website, possibly forwarded by another instance
+    DeepLink(DeepLink),
+    /// Organizer was started again, existing window should come to front
+    FocusRequested,
+    /// Updates found since the last time, mod ID with installed version and the newer version
+    UpdatesFound(Vec<(IDVersion, Version)>),
+    /// Launch is applying these updates first
+    PreLaunchUpdatesStarted(Vec<(IDVersion, Version)>),
+    /// Updates applied before launch and whether they went through, ContinueLaunch goes on with the launch
+    PreLaunchUpdatesFinished(Vec<(IDVersion, Version, bool)>),
     Notification(ToastKind, String),
     LongNotification(ToastKind, String),
-    Error(String)
+    Error(ManagerError),
+    ShutdownComplete
 }
@@ -2,34 +2,128 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use arc_swap::ArcSwap;
 use futures::future::join_all;
 use regex::Regex;
+use reqwest::Client;
+use reqwest::header::CONTENT_LENGTH;
+use ring::signature::{ED25519, UnparsedPublicKey};
 use serde::{Serialize, Deserialize};
-use strum_macros::{Display};
+use serde_json::Value;
+use strum_macros::{Display, EnumIter};
+use crate::config::{mirrored_urls, MirrorRule};
+use crate::transport::{Transport, TransportError};
+use crate::utils::find_filename_from_url;
 use crate::version::{Version, Comparator, VersionReq};
 
-pub async fn download_manifest(url: &str) -> Result<ModManifest, reqwest::Error> {
-    Ok(reqwest::get(url)
-        .await?
-        .json()
-        .await?)
+/// Downloads the manifest, if public key is provided, detached signature at "<url>.sig" has to match it
+pub async fn download_manifest(transport: &dyn Transport, url: &str, public_key: Option<&str>) -> Result<ModManifest, ManifestError> {
+    let data = transport.get(url, &mut None).await?;
+
+    if let Some(public_key) = public_key {
+        let signature = transport.get(&format!("{}.sig", url), &mut None).await?;
+
+        verify_manifest_signature(&data, &String::from_utf8_lossy(&signature), public_key)?;
+    }
+
+    parse_manifest(&data)
+}
+
+/// Newest major version of manifest schema this organizer understands, newer minor versions only add fields
+pub const SUPPORTED_SCHEMA_MAJOR: u16 = 1;
+
+/// Checks schema version of the manifest before deserializing it, older schemas are migrated to the current one
+pub fn parse_manifest(data: &[u8]) -> Result<ModManifest, ManifestError> {
+    let mut value: Value = serde_json::from_slice(data)?;
+
+    let schema_version = match value.get("schemaVersion") {
+        None | Some(Value::Null) => Version::zero(),
+        Some(Value::String(version)) => Version::from_str(version)
+            .map_err(|_| ManifestError::InvalidSchemaVersion(version.clone()))?,
+        Some(other) => return Err(ManifestError::InvalidSchemaVersion(other.to_string()))
+    };
+
+    if schema_version.major() > SUPPORTED_SCHEMA_MAJOR {
+        return Err(ManifestError::UnsupportedSchema(schema_version));
+    }
+
+    migrate_manifest(&mut value, &schema_version);
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Brings manifests of older schemas up to the current one
+fn migrate_manifest(value: &mut Value, schema_version: &Version) {
+    if schema_version.major() < 1 {
+        // Category wasn't required before 1.0
+        if let Some(Value::Object(mods)) = value.get_mut("mods") {
+            for mod_info in mods.values_mut().filter_map(|x| x.as_object_mut()) {
+                mod_info.entry("category").or_insert_with(|| Value::String("Misc".to_string()));
+            }
+        }
+    }
+
+    if let Some(manifest) = value.as_object_mut() {
+        manifest.insert("schemaVersion".to_string(), Value::String(format!("{}.0.0", SUPPORTED_SCHEMA_MAJOR)));
+    }
+}
+
+/// Checks hex encoded ed25519 signature of the manifest against hex encoded public key
+pub fn verify_manifest_signature(data: &[u8], signature: &str, public_key: &str) -> Result<(), ManifestError> {
+    let signature = hex::decode(signature.trim()).map_err(|_| ManifestError::InvalidSignature)?;
+    let public_key = hex::decode(public_key.trim()).map_err(|_| ManifestError::InvalidKey)?;
+
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(data, &signature)
+        .map_err(|_| ManifestError::InvalidSignature)
+}
+
+/// Goes through mirrors of the manifest URL until one of them works
+pub async fn download_manifest_mirrored(transport: &dyn Transport, mirrors: &[MirrorRule], url: &str, public_key: Option<&str>) -> Result<ModManifest, ManifestError> {
+    let mut urls = mirrored_urls(mirrors, url).into_iter();
+    let mut result = download_manifest(transport, &urls.next().unwrap_or_else(|| url.to_string()), public_key).await;
+
+    for candidate in urls {
+        // Mirror serving a bad signature isn't a reason to stop trying others
+        if result.is_ok() {
+            break;
+        }
+
+        result = download_manifest(transport, &candidate, public_key).await;
+    }
+
+    result
 }
 
-pub async fn aggregate_manifests(urls: &[String]) -> (ManifestMods, Vec<(String, reqwest::Error)>) {
+/// Downloads all manifests and merges them, also returns which source each mod came from
+pub async fn aggregate_manifests(transport: &dyn Transport, mirrors: &[MirrorRule], sources: &[(String, Option<String>)]) -> (ManifestMods, HashMap<GUID, String>, Vec<(String, ManifestError)>) {
     let mut errors = vec![];
-    let mods = join_all(urls.iter().map(|x| async { (x.clone(), download_manifest(x).await) }))
-        .await
-        .into_iter()
-        .filter_map(|(url, x)| x.map_err(|e| errors.push((url, e))).ok())
-        .flat_map(|m| m.mods.into_iter())
-        .collect();
+    let mut mods = ManifestMods::new();
+    let mut mod_sources = HashMap::new();
+
+    let manifests = join_all(sources.iter().map(|(url, public_key)| async {
+        (url.clone(), download_manifest_mirrored(transport, mirrors, url, public_key.as_deref()).await)
+    })).await;
+
+    for (url, result) in manifests {
+        match result {
+            Ok(manifest) => {
+                for (guid, mod_info) in manifest.mods {
+                    mod_sources.insert(guid.clone(), url.clone());
+                    mods.insert(guid, mod_info);
+                }
+            }
+            Err(e) => errors.push((url, e))
+        }
+    }
 
-    (mods, errors)
+    (mods, mod_sources, errors)
 }
 
-pub async fn find_github_readme_link(repo_link: &str) -> Result<Option<String>, reqwest::Error> {
+pub async fn find_github_readme_link(client: &Client, repo_link: &str) -> Result<Option<String>, reqwest::Error> {
     let Some(stripped_repo_link) = repo_link.strip_prefix("https://github.com/") else { // Splitting off github site URL
         return Ok(None);
     };
@@ -42,8 +136,10 @@ pub async fn find_github_readme_link(repo_link: &str) -> Result<Option<String>,
 
     println!("author {}, repository {}", author, repository);
 
-    let body = reqwest::get(repo_link) // Getting HTML document of the repo
+    let body = client.get(repo_link) // Getting HTML document of the repo
+        .send()
         .await?
+        .error_for_status()?
         .text()
         .await?;
 
@@ -62,14 +158,72 @@ pub async fn find_github_readme_link(repo_link: &str) -> Result<Option<String>,
     Ok(Some(format!("https://raw.githubusercontent.com/{}/{}{}", author, repository, readme_link.as_str())))
 }
 
-pub async fn download_readme(readme_link: &str) -> Result<String, reqwest::Error> {
-    Ok(reqwest::get(readme_link)
+pub async fn download_readme(client: &Client, readme_link: &str) -> Result<String, reqwest::Error> {
+    Ok(client.get(readme_link)
+        .send()
         .await?
+        .error_for_status()?
         .text()
         .await?)
 }
 
 
+/// Asks the server how big the file is without downloading it
+pub async fn fetch_artifact_size(client: &Client, url: &str) -> Result<Option<u64>, reqwest::Error> {
+    let response = client.head(url)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(response.headers().get(CONTENT_LENGTH)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.parse().ok()))
+}
+
+/// Collects links of images referenced in a README, in order they appear, skipping badges
+pub fn find_image_links(markdown: &str) -> Vec<String> {
+    let matcher = Regex::new(r#"!\[[^\]]*\]\(\s*<?([^)\s>]+)>?[^)]*\)|(?i)<img[^>]+src\s*=\s*["']([^"']+)["']"#).unwrap();
+
+    let mut links: Vec<String> = vec![];
+
+    for captures in matcher.captures_iter(markdown) {
+        let Some(link) = captures.get(1).or_else(|| captures.get(2)) else {
+            continue;
+        };
+
+        let link = link.as_str();
+        let lowercase = link.to_lowercase();
+
+        if !(lowercase.starts_with("https://") || lowercase.starts_with("http://")) {
+            continue;
+        }
+
+        // Badges are usually SVGs and aren't screenshots anyway
+        if lowercase.split(['?', '#']).next().map_or(false, |x| x.ends_with(".svg"))
+            || lowercase.contains("shields.io")
+            || lowercase.contains("/badge") {
+            continue;
+        }
+
+        if !links.iter().any(|x| x == link) {
+            links.push(link.to_string());
+        }
+    }
+
+    links
+}
+
+pub async fn download_image(client: &Client, url: &str) -> Result<Vec<u8>, reqwest::Error> {
+    Ok(client.get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .to_vec())
+}
+
+
 pub type ManifestMods = HashMap<GUID, Mod>;
 /// Sha256 hash to mod_id and version
 pub type ModHashTable = HashMap<String, (String, Version)>;
@@ -81,6 +235,8 @@ pub struct GlobalModList {
     pub mod_list: Arc<ArcSwap<ManifestMods>>,
     pub mod_hash_table: Arc<ArcSwap<ModHashTable>>,
     pub reverse_hash_table: Arc<ArcSwap<ReverseHashTable>>,
+    /// Bumped every time the list gets replaced, sent to UI along with ManifestUpdated
+    generation: Arc<AtomicU64>,
 }
 
 impl GlobalModList {
@@ -89,6 +245,7 @@ impl GlobalModList {
             mod_list: Arc::new(Default::default()),
             mod_hash_table: Arc::new(Default::default()),
             reverse_hash_table: Arc::new(Default::default()),
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -100,12 +257,15 @@ impl GlobalModList {
             mod_list: Arc::new(ArcSwap::from(Arc::new(manifest_mods))),
             mod_hash_table: Arc::new(ArcSwap::from(Arc::new(hashtable))),
             reverse_hash_table: Arc::new(ArcSwap::from(Arc::new(reverse))),
+            generation: Arc::new(AtomicU64::new(1)),
         }
     }
 
-    pub fn update_list(&self, manifest_mods: ManifestMods) {
+    /// Replaces the mod list, returns new generation of the list
+    pub fn update_list(&self, manifest_mods: ManifestMods) -> u64 {
         self.mod_list.swap(Arc::new(manifest_mods));
         self.recreate_tables();
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
     }
 
     pub fn recreate_tables(&self) {
@@ -174,10 +334,29 @@ pub struct Mod {
     pub tags: Option<Vec<String>>,
     pub category: Category,
     pub flags: Option<Vec<String>>,
+    /// Not in the official manifest, but some mirrors provide it
+    #[serde(default)]
+    pub download_count: Option<u64>,
     #[serde(default)]
     pub versions: HashMap<Version, ModVersion>
 }
 
+impl Mod {
+    /// Plugins are either in Plugins category or flagged as plugin
+    pub fn is_plugin(&self) -> bool {
+        self.category == Category::Plugins || self.flags.as_ref().map_or(false, |x| x.iter().any(|x| x.eq_ignore_ascii_case("plugin")))
+    }
+
+    /// Category that decides where artifacts go by default
+    pub fn install_category(&self) -> Category {
+        if self.is_plugin() {
+            Category::Plugins
+        } else {
+            self.category
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ModVersion {
@@ -211,7 +390,93 @@ pub struct Artifact {
     pub filename: Option<String>,
     pub sha256: String,
     pub blake3: Option<String>,
-    pub install_location: Option<PathBuf>
+    pub install_location: Option<PathBuf>,
+    /// File size in bytes, fetched with a HEAD request when manifest doesn't have it
+    #[serde(default)]
+    pub size: Option<u64>
+}
+
+impl Artifact {
+    /// Name the file gets on disk
+    pub fn file_name(&self) -> Option<String> {
+        self.filename.clone().or_else(|| find_filename_from_url(&self.url, ".dll"))
+    }
+}
+
+/// How something differs between two versions
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change<T> {
+    Added(T),
+    Removed(T),
+    Changed(T, T)
+}
+
+/// Everything that differs between two versions of a mod, besides the changelog
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct VersionDiff {
+    pub dependencies: Vec<(GUID, Change<VersionReq>)>,
+    pub conflicts: Vec<(GUID, Change<VersionReq>)>,
+    /// Artifacts by file name, changed ones have different hashes
+    pub artifacts: Vec<(String, Change<String>)>,
+    pub neos_version_compatibility: Option<Change<VersionReq>>,
+    pub modloader_version_compatibility: Option<Change<VersionReq>>
+}
+
+impl VersionDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == VersionDiff::default()
+    }
+}
+
+fn diff_maps<V: PartialEq + Clone>(old: HashMap<String, V>, new: HashMap<String, V>) -> Vec<(String, Change<V>)> {
+    let mut changes: Vec<(String, Change<V>)> = old.iter()
+        .filter_map(|(key, old_value)| match new.get(key) {
+            None => Some((key.clone(), Change::Removed(old_value.clone()))),
+            Some(new_value) if new_value != old_value => Some((key.clone(), Change::Changed(old_value.clone(), new_value.clone()))),
+            Some(_) => None
+        })
+        .chain(new.iter()
+            .filter(|(key, _)| !old.contains_key(*key))
+            .map(|(key, new_value)| (key.clone(), Change::Added(new_value.clone()))))
+        .collect();
+
+    changes.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    changes
+}
+
+fn diff_options<V: PartialEq + Clone>(old: &Option<V>, new: &Option<V>) -> Option<Change<V>> {
+    match (old, new) {
+        (Some(old), Some(new)) if old != new => Some(Change::Changed(old.clone(), new.clone())),
+        (Some(old), None) => Some(Change::Removed(old.clone())),
+        (None, Some(new)) => Some(Change::Added(new.clone())),
+        _ => None
+    }
+}
+
+/// What changed going from old version to new one
+pub fn compare_versions(old: &ModVersion, new: &ModVersion) -> VersionDiff {
+    let dependencies = |version: &ModVersion| version.dependencies.iter()
+        .flatten()
+        .map(|(guid, dependency)| (guid.clone(), dependency.version.clone()))
+        .collect::<HashMap<GUID, VersionReq>>();
+
+    let conflicts = |version: &ModVersion| version.conflicts.iter()
+        .flatten()
+        .map(|(guid, conflict)| (guid.clone(), conflict.version.clone()))
+        .collect::<HashMap<GUID, VersionReq>>();
+
+    let artifacts = |version: &ModVersion| version.artifacts.iter()
+        .map(|x| (x.file_name().unwrap_or_else(|| x.url.clone()), x.sha256.to_lowercase()))
+        .collect::<HashMap<String, String>>();
+
+    VersionDiff {
+        dependencies: diff_maps(dependencies(old), dependencies(new)),
+        conflicts: diff_maps(conflicts(old), conflicts(new)),
+        artifacts: diff_maps(artifacts(old), artifacts(new)),
+        neos_version_compatibility: diff_options(&old.neos_version_compatibility, &new.neos_version_compatibility),
+        modloader_version_compatibility: diff_options(&old.modloader_version_compatibility, &new.modloader_version_compatibility),
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -221,7 +486,7 @@ pub struct Author {
     pub icon_url: Option<String>
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Ord, PartialOrd, Eq, Display, Hash)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Ord, PartialOrd, Eq, Display, Hash, EnumIter)]
 #[strum(serialize_all = "PascalCase")]
 pub enum Category {
     #[strum(serialize = "Asset Importing Tweaks")]
@@ -264,4 +529,48 @@ pub enum Category {
     #[strum(default)]
     #[serde(other)]
     Unknown
-}
\ No newline at end of file
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    NetworkError(TransportError),
+    JSONError(serde_json::Error),
+    /// Signature is missing, malformed or doesn't match the manifest
+    InvalidSignature,
+    /// Public key configured for the source isn't valid hex
+    InvalidKey,
+    InvalidSchemaVersion(String),
+    /// Manifest uses a schema that's newer than this organizer knows about
+    UnsupportedSchema(Version)
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestError::UnsupportedSchema(version) => {
+                write!(f, "Manifest uses schema version {}, which requires a newer version of the organizer", version)
+            }
+            _ => write!(f, "{:?}", self)
+        }
+    }
+}
+
+impl Error for ManifestError {}
+
+impl From<reqwest::Error> for ManifestError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::NetworkError(value.into())
+    }
+}
+
+impl From<TransportError> for ManifestError {
+    fn from(value: TransportError) -> Self {
+        Self::NetworkError(value)
+    }
+}
+
+impl From<serde_json::Error> for ManifestError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::JSONError(value)
+    }
+}
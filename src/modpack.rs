@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use tokio::task::{JoinError, spawn_blocking};
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::install::{DownloadedArtifact, enabled_path, IDVersion, ModInstallOperations, ModMap};
+use crate::manifest::{GUID, ModHashTable};
+use crate::utils::sha256_bytes;
+use crate::version::Version;
+
+/// Name of the metadata file inside of the pack
+const PACK_MANIFEST_NAME: &str = "modpack.json";
+
+/// Bumped when pack layout changes in a way older versions can't read
+const PACK_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModPackFile {
+    /// Where the file is inside of the zip
+    pub archive_path: String,
+    /// Where the file goes, relative to Neos folder
+    pub install_path: PathBuf,
+    pub sha256: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModPackMod {
+    pub mod_id: GUID,
+    pub version: Version,
+    pub files: Vec<ModPackFile>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModPackManifest {
+    pub format_version: u32,
+    pub mods: Vec<ModPackMod>
+}
+
+/// Mod pack read from disk, file contents are keyed by archive path
+pub struct ModPack {
+    pub manifest: ModPackManifest,
+    pub contents: HashMap<String, Vec<u8>>
+}
+
+/// Bundles files of provided mods along with a manifest into a zip file, returns how many mods were packed
+pub async fn export_mod_pack(install_location: &Path, mod_map: &ModMap, mods: &[IDVersion], target: PathBuf) -> Result<usize, ModPackError> {
+    let mut manifest = ModPackManifest {
+        format_version: PACK_FORMAT_VERSION,
+        mods: vec![],
+    };
+
+    let mut contents = vec![];
+
+    for (mod_id, version) in mods {
+        let Some(file) = mod_map.file(mod_id, version) else {
+            return Err(ModPackError::ModNotInstalled((mod_id.clone(), version.clone())));
+        };
+
+        let mut files = vec![];
+
+        for artifact in &file.files {
+            // Packs always install files enabled
+            let path = if artifact.disabled { enabled_path(&artifact.file_path) } else { artifact.file_path.clone() };
+            let install_path = path.strip_prefix(install_location)
+                .map_err(|_| ModPackError::InvalidPath(path.clone()))?
+                .to_path_buf();
+
+            let file_name = path.file_name().map_or_else(|| "unknown".to_string(), |x| x.to_string_lossy().to_string());
+            let archive_path = format!("files/{}/{}", contents.len(), file_name);
+
+            contents.push((archive_path.clone(), fs::read(&artifact.file_path).await?));
+
+            files.push(ModPackFile {
+                archive_path,
+                install_path,
+                sha256: artifact.file_hash.clone(),
+            });
+        }
+
+        manifest.mods.push(ModPackMod {
+            mod_id: mod_id.clone(),
+            version: version.clone(),
+            files,
+        });
+    }
+
+    let count = manifest.mods.len();
+    let manifest_json = serde_json::to_string_pretty(&manifest)?;
+
+    spawn_blocking(move || -> Result<(), ModPackError> {
+        let mut zip = ZipWriter::new(File::create(target)?);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        zip.start_file(PACK_MANIFEST_NAME, options)?;
+        zip.write_all(manifest_json.as_bytes())?;
+
+        for (archive_path, data) in contents {
+            zip.start_file(archive_path, options)?;
+            zip.write_all(&data)?;
+        }
+
+        zip.finish()?;
+
+        Ok(())
+    }).await??;
+
+    Ok(count)
+}
+
+pub async fn read_mod_pack(path: PathBuf) -> Result<ModPack, ModPackError> {
+    spawn_blocking(move || -> Result<ModPack, ModPackError> {
+        let mut zip = ZipArchive::new(File::open(path)?)?;
+
+        let manifest: ModPackManifest = {
+            let mut json = String::new();
+            zip.by_name(PACK_MANIFEST_NAME)?.read_to_string(&mut json)?;
+            serde_json::from_str(&json)?
+        };
+
+        if manifest.format_version > PACK_FORMAT_VERSION {
+            return Err(ModPackError::UnsupportedVersion(manifest.format_version));
+        }
+
+        let mut contents = HashMap::new();
+
+        for file in manifest.mods.iter().flat_map(|x| x.files.iter()) {
+            let mut data = vec![];
+            zip.by_name(&file.archive_path)?.read_to_end(&mut data)?;
+            contents.insert(file.archive_path.clone(), data);
+        }
+
+        Ok(ModPack {
+            manifest,
+            contents,
+        })
+    }).await?
+}
+
+/// Checks pack contents against hashes from the global manifest, and turns them into artifacts ready to be written
+pub fn prepare_import(pack: &ModPack, install_location: &Path, hash_table: &ModHashTable) -> Result<Vec<(IDVersion, Vec<DownloadedArtifact>)>, ModPackError> {
+    let mut prepared = vec![];
+
+    for pack_mod in &pack.manifest.mods {
+        let id = (pack_mod.mod_id.clone(), pack_mod.version.clone());
+        let mut artifacts = vec![];
+
+        for file in &pack_mod.files {
+            // Packs are shared between people, files shouldn't be able to escape Neos folder
+            if !file.install_path.components().all(|x| matches!(x, Component::Normal(_))) {
+                return Err(ModPackError::InvalidPath(file.install_path.clone()));
+            }
+
+            let Some(data) = pack.contents.get(&file.archive_path) else {
+                return Err(ModPackError::MissingFile(file.archive_path.clone()));
+            };
+
+            let hash = sha256_bytes(data);
+
+            if !hash.eq_ignore_ascii_case(&file.sha256) || hash_table.get(&hash) != Some(&id) {
+                return Err(ModPackError::UnknownFile {
+                    mod_id: id,
+                    file: file.install_path.clone(),
+                });
+            }
+
+            let mut path = install_location.to_path_buf();
+            path.push(&file.install_path);
+
+            artifacts.push(DownloadedArtifact {
+                path,
+                url: file.archive_path.clone(),
+                mirror: None,
+                expected_hash: hash,
+                data: data.clone(),
+            });
+        }
+
+        prepared.push((id, artifacts));
+    }
+
+    Ok(prepared)
+}
+
+/// Installs the mod into the folder the pack had it in, mods with files spread over several folders go where the manifest says
+pub fn import_operation(id: IDVersion, artifacts: &[DownloadedArtifact], install_location: &Path) -> ModInstallOperations {
+    let mut folders = artifacts.iter()
+        .filter_map(|x| x.path.strip_prefix(install_location).ok()?.parent());
+
+    match folders.next() {
+        Some(folder) if folders.all(|x| x == folder) => ModInstallOperations::InstallModAt(id, Path::new("/").join(folder)),
+        _ => ModInstallOperations::InstallMod(id)
+    }
+}
+
+#[derive(Debug)]
+pub enum ModPackError {
+    /// Mod selected for export isn't in the mod map anymore
+    ModNotInstalled(IDVersion),
+    /// File would end up outside of Neos folder
+    InvalidPath(PathBuf),
+    /// Pack manifest lists a file that isn't in the zip
+    MissingFile(String),
+    /// File doesn't match any hash the global manifest has for that mod
+    UnknownFile {
+        mod_id: IDVersion,
+        file: PathBuf
+    },
+    UnsupportedVersion(u32),
+    FileError(io::Error),
+    ZipError(ZipError),
+    JSONError(serde_json::Error),
+    JoinError(JoinError)
+}
+
+impl Display for ModPackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for ModPackError {}
+
+impl From<io::Error> for ModPackError {
+    fn from(value: io::Error) -> Self {
+        Self::FileError(value)
+    }
+}
+
+impl From<ZipError> for ModPackError {
+    fn from(value: ZipError) -> Self {
+        Self::ZipError(value)
+    }
+}
+
+impl From<serde_json::Error> for ModPackError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::JSONError(value)
+    }
+}
+
+impl From<JoinError> for ModPackError {
+    fn from(value: JoinError) -> Self {
+        Self::JoinError(value)
+    }
+}
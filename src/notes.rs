@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use crate::config::Config;
+use crate::install::InstallError;
+
+/// User's own notes about installed mods by mod ID, so they stay across updates
+#[derive(Serialize, Deserialize, Default)]
+pub struct ModNotes {
+    pub notes: HashMap<String, String>
+}
+
+impl ModNotes {
+    pub async fn load() -> Result<ModNotes, InstallError> {
+        let path = Config::notes_path();
+
+        if !path.exists() {
+            return Ok(ModNotes::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    /// Blank notes remove the entry
+    pub fn set(&mut self, mod_id: &str, notes: &str) {
+        if notes.trim().is_empty() {
+            self.notes.remove(mod_id);
+        } else {
+            self.notes.insert(mod_id.to_string(), notes.trim_end().to_string());
+        }
+    }
+
+    pub async fn save(&self) -> Result<(), InstallError> {
+        let path = Config::notes_path();
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string(self)?).await?)
+    }
+}
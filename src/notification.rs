@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::thread;
+use eframe::egui::Context;
+use notify_rust::Notification;
+
+/// Shows a system notification, clicked gets set when it's clicked on platforms that report that
+pub fn show_notification(summary: String, body: String, clicked: Arc<AtomicBool>, ctx: Context) {
+    // Waiting for the click blocks, so notification gets its own thread
+    thread::spawn(move || {
+        let mut notification = Notification::new();
+        notification.appname("Neos Mod Organizer")
+            .summary(&summary)
+            .body(&body);
+
+        #[cfg(all(unix, not(target_os = "macos")))]
+        {
+            use std::sync::atomic::Ordering;
+            use crate::locale::tr;
+
+            notification.action("default", &tr("notification.open"));
+
+            if let Ok(handle) = notification.show() {
+                handle.wait_for_action(|action| {
+                    if action == "default" {
+                        clicked.store(true, Ordering::Relaxed);
+                        ctx.request_repaint();
+                    }
+                });
+            }
+        }
+
+        // Clicks aren't reported here, UI switches to updates once the window is opened again instead
+        #[cfg(not(all(unix, not(target_os = "macos"))))]
+        {
+            drop((clicked, ctx));
+
+            notification.show().ok();
+        }
+    });
+}
@@ -0,0 +1,75 @@
+use crate::config::UpdateChannel;
+use crate::install::{IDVersion, ModConflict, ModInstall, ModInstallOperations, ModMap, VirtualInstall};
+use crate::manifest::{GUID, ManifestMods};
+use crate::resolver::{resolve_install_mod, ResolveResult};
+use crate::version::VersionReq;
+
+/// Hypothetical change in a plan, nothing happens to the actual install until the plan is applied
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlanStep {
+    /// Mod along with its dependencies, resolved against what earlier steps left installed
+    Install(GUID, VersionReq),
+    Uninstall(IDVersion)
+}
+
+/// What the whole plan would do, steps are tried one after another on a virtual copy of the install
+#[derive(Clone, Debug, Default)]
+pub struct PlanOutcome {
+    /// Steps the outcome was made for, UI ignores outcomes of steps it doesn't have anymore
+    pub steps: Vec<PlanStep>,
+    /// Operations of all steps, in order
+    pub operations: Vec<ModInstallOperations>,
+    /// Conflicts the plan would add on top of what's already there
+    pub conflicts: Vec<ModConflict>,
+    /// Index of steps that couldn't be carried out, with the reason
+    pub failed_steps: Vec<(usize, String)>,
+    /// Mods the plan installs from sources that aren't trusted, with URL of the source
+    pub untrusted: Vec<(GUID, String)>,
+    /// Installed mods once the plan is done
+    pub mod_map: ModMap
+}
+
+/// Carries out steps on the virtual install, steps that fail are skipped and the rest still go on
+pub async fn simulate_plan(mut install: VirtualInstall, steps: &[PlanStep], mod_list: &ManifestMods, channel_for: impl Fn(&str) -> UpdateChannel) -> PlanOutcome {
+    let current_conflicts = install.check_for_conflicts(mod_list);
+    let mut outcome = PlanOutcome {
+        steps: steps.to_vec(),
+        ..Default::default()
+    };
+
+    for (index, step) in steps.iter().enumerate() {
+        let operations = match step {
+            PlanStep::Install(mod_id, requirement) => {
+                match resolve_install_mod(mod_id, requirement, install.mod_map(), mod_list, &channel_for) {
+                    ResolveResult::Ok(operations) => operations,
+                    failure => {
+                        outcome.failed_steps.push((index, failure.to_string()));
+                        continue;
+                    }
+                }
+            }
+            PlanStep::Uninstall((mod_id, version)) => {
+                if !install.mod_map().is_installed(mod_id, version) {
+                    outcome.failed_steps.push((index, format!("{} v{} isn't installed", mod_id, version)));
+                    continue;
+                }
+
+                vec![ModInstallOperations::UninstallMod((mod_id.clone(), version.clone()))]
+            }
+        };
+
+        if let Err(e) = install.perform_operations(&operations).await {
+            outcome.failed_steps.push((index, e.to_string()));
+            continue;
+        }
+
+        outcome.operations.extend(operations);
+    }
+
+    outcome.conflicts = install.check_for_conflicts(mod_list).into_iter()
+        .filter(|x| !current_conflicts.contains(x))
+        .collect();
+    outcome.mod_map = install.mod_map().clone();
+
+    outcome
+}
@@ -0,0 +1,56 @@
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use crate::config::Config;
+use crate::install::{InstallError, ModInstallOperations, ModMap};
+use crate::manager::QueueItem;
+
+/// Operations that weren't finished when the organizer closed, offered to be resumed on next start
+#[derive(Serialize, Deserialize, Default)]
+pub struct SavedQueue {
+    pub items: Vec<QueueItem>
+}
+
+impl SavedQueue {
+    pub async fn load() -> Result<SavedQueue, InstallError> {
+        let path = Config::queue_path();
+
+        if !path.exists() {
+            return Ok(SavedQueue::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    /// Empty queue removes the file, so nothing is offered on next start
+    pub async fn save(&self) -> Result<(), InstallError> {
+        let path = Config::queue_path();
+
+        if self.items.is_empty() {
+            if path.exists() {
+                fs::remove_file(path).await?;
+            }
+
+            return Ok(());
+        }
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string(self)?).await?)
+    }
+
+    /// Drops operations that the install already reflects, they went through before the organizer closed
+    pub fn without_applied(mut self, mod_map: &ModMap) -> SavedQueue {
+        self.items.retain(|x| !is_applied(&x.operation, mod_map));
+        self
+    }
+}
+
+fn is_applied(operation: &ModInstallOperations, mod_map: &ModMap) -> bool {
+    match operation {
+        ModInstallOperations::InstallMod((mod_id, version)) | ModInstallOperations::InstallModAt((mod_id, version), _) => mod_map.is_installed(mod_id, version),
+        ModInstallOperations::UninstallMod((mod_id, version)) => !mod_map.is_installed(mod_id, version),
+        _ => false
+    }
+}
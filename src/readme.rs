@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use crate::config::Config;
+use crate::install::InstallError;
+use crate::manifest::GUID;
+
+/// How long a fetched README is shown before it gets fetched again
+pub const README_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+/// Finding and downloading README gives up after this long
+pub const README_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug)]
+pub enum ReadmeError {
+    NetworkError(reqwest::Error),
+    TimedOut
+}
+
+impl Display for ReadmeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadmeError::NetworkError(e) => write!(f, "{}", e),
+            ReadmeError::TimedOut => write!(f, "Request timed out after {} seconds", README_TIMEOUT.as_secs())
+        }
+    }
+}
+
+impl Error for ReadmeError {}
+
+impl From<reqwest::Error> for ReadmeError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::NetworkError(value)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CachedReadme {
+    /// Seconds since unix epoch
+    pub timestamp: u64,
+    pub readme: String
+}
+
+/// READMEs fetched for mods, kept on disk so More Info opens instantly and works offline
+#[derive(Serialize, Deserialize, Default)]
+pub struct ReadmeCache {
+    pub readmes: HashMap<GUID, CachedReadme>
+}
+
+impl ReadmeCache {
+    pub async fn load() -> Result<ReadmeCache, InstallError> {
+        let path = Config::readme_cache_path();
+
+        if !path.exists() {
+            return Ok(ReadmeCache::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    pub async fn save(&self) -> Result<(), InstallError> {
+        let path = Config::readme_cache_path();
+
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+
+        Ok(fs::write(path, serde_json::to_string(self)?).await?)
+    }
+
+    /// README that's not older than TTL
+    pub fn fresh(&self, guid: &str, now: u64) -> Option<&str> {
+        self.readmes.get(guid)
+            .filter(|x| now.saturating_sub(x.timestamp) < README_TTL_SECONDS)
+            .map(|x| x.readme.as_str())
+    }
+
+    /// README no matter how old, for when it couldn't be fetched again
+    pub fn any(&self, guid: &str) -> Option<&str> {
+        self.readmes.get(guid).map(|x| x.readme.as_str())
+    }
+
+    pub fn insert(&mut self, guid: GUID, readme: String, now: u64) {
+        self.readmes.insert(guid, CachedReadme {
+            timestamp: now,
+            readme,
+        });
+    }
+}
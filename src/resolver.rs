@@ -1,10 +1,34 @@
-use std::collections::{HashMap, VecDeque};
-use crate::install::{ModInstallOperations, ModMap};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::config::UpdateChannel;
+use crate::install::{IDVersion, ModInstallOperations, ModMap};
 use crate::manifest::{GUID, Mod, ModVersion};
-use crate::version::{Version, VersionReq};
+use crate::version::{SimplifiedReq, Version, VersionReq};
+
+/// Newest version of the mod on the channel, stable channel only offers prereleases to those who already run one
+pub fn latest_update_for<'a>(installed: &Version, mod_info: &'a Mod, channel: UpdateChannel) -> Option<&'a Version> {
+    mod_info.versions.keys()
+        .filter(|x| channel == UpdateChannel::Prerelease || installed.is_prerelease() || !x.is_prerelease())
+        .max()
+}
+
+/// Installed mods that have a newer version in the manifest on their channel, along with that version
+pub fn available_updates(mod_map: &ModMap, mod_list: &HashMap<GUID, Mod>, channel_for: impl Fn(&str) -> UpdateChannel) -> Vec<(IDVersion, Version)> {
+    let mut updates: Vec<(IDVersion, Version)> = mod_map.keys()
+        .filter_map(|mod_id| {
+            let installed = mod_map.installed_version(mod_id)?;
+            let newest = latest_update_for(installed, mod_list.get(mod_id)?, channel_for(mod_id))?;
+
+            (newest > installed).then(|| ((mod_id.clone(), installed.clone()), newest.clone()))
+        })
+        .collect();
+
+    updates.sort_by(|a, b| a.0.cmp(&b.0));
+    updates
+}
 
 #[inline]
-pub fn find_latest_matching<'a>(mod_id: &str, requirement: &VersionReq, mod_list: &'a HashMap<GUID, Mod>) -> Option<(&'a Mod, &'a Version, &'a ModVersion)> {
+pub fn find_latest_matching<'a>(mod_id: &str, requirement: &VersionReq, mod_list: &'a HashMap<GUID, Mod>, channel: UpdateChannel) -> Option<(&'a Mod, &'a Version, &'a ModVersion)> {
     let Some(mod_info) = mod_list.get(mod_id) else {
         return None;
     };
@@ -17,6 +41,11 @@ pub fn find_latest_matching<'a>(mod_id: &str, requirement: &VersionReq, mod_list
         return None;
     }
 
+    // Prereleases are only picked when nothing else fits, unless the mod follows prereleases
+    if channel == UpdateChannel::Stable && fitting_versions.iter().any(|(version, _)| !version.is_prerelease()) {
+        fitting_versions.retain(|(version, _)| !version.is_prerelease());
+    }
+
     fitting_versions.sort_by(|(a, _), (b, _)| {
         b.cmp(a)
     });
@@ -26,44 +55,212 @@ pub fn find_latest_matching<'a>(mod_id: &str, requirement: &VersionReq, mod_list
     Some((mod_info, latest_version, latest_info))
 }
 
-pub fn resolve_install_mod(mod_id: &str, requirement: &VersionReq, current_install: &ModMap, mod_list: &HashMap<GUID, Mod>) -> ResolveResult {
-    let mut ops = Vec::new();
-    let mut queue = VecDeque::from([(mod_id, requirement)]);
+/// Passes over the picked mods before giving up on settling their versions, picks can keep flipping in odd manifests
+const MAX_RESOLVE_PASSES: usize = 32;
+
+/// What was decided for a mod during resolution
+enum Resolved<'a> {
+    /// Installed version already fits, it stays as it is
+    Kept,
+    Picked {
+        version: &'a Version,
+        info: &'a ModVersion
+    }
+}
+
+/// Everything that requires something of the mod, None stands for whoever asked for the mod to be installed.
+/// Installed mods count too, unless they're being replaced or the current install already doesn't meet their requirement,
+/// those are left to conflict checking
+fn requirements_for<'a>(mod_id: &str, root: (&str, &VersionReq), resolved: &HashMap<GUID, Resolved<'a>>, current_install: &ModMap, mod_list: &HashMap<GUID, Mod>) -> Vec<(Option<GUID>, VersionReq)> {
+    let mut requirements = vec![];
 
-    while let Some((mod_id, requirement)) = queue.pop_back() {
-        let mut piece = vec![];
+    if mod_id == root.0 {
+        requirements.push((None, root.1.clone()));
+    }
 
-        let Some((_, version, version_info)) = find_latest_matching(mod_id, requirement, mod_list) else {
-            return ResolveResult::UnableToFind {
-                mod_id: mod_id.to_string(),
-                requirement: requirement.clone()
+    for (dependent, resolution) in resolved {
+        if let Resolved::Picked { info, .. } = resolution {
+            if let Some(dependency) = info.dependencies.as_ref().and_then(|x| x.get(mod_id)) {
+                requirements.push((Some(dependent.clone()), dependency.version.clone()));
             }
-        };
+        }
+    }
 
-        if let Some(installed_versions) = current_install.get(mod_id) {
-            if installed_versions.iter().any(|(v, _)| requirement.matches(v) && v >= version) {
+    // Mod user asked for goes in regardless of what installed mods think of it
+    if mod_id != root.0 {
+        for ((dependent, _), requirement) in find_dependents(mod_id, current_install, mod_list) {
+            if matches!(resolved.get(&dependent), Some(Resolved::Picked { .. })) || dependent == root.0 {
                 continue;
-            } else {
-                for (version, _) in installed_versions {
-                    piece.push(ModInstallOperations::UninstallMod((mod_id.to_string(), version.clone())));
-                }
+            }
+
+            if let DependencyStatus::Satisfied(_) = dependency_status(mod_id, &requirement, current_install) {
+                requirements.push((Some(dependent), requirement));
             }
         }
+    }
 
-        piece.push(ModInstallOperations::InstallMod((mod_id.to_string(), version.clone())));
+    requirements.sort_by(|(a, _), (b, _)| a.cmp(b));
 
-        ops.push(piece);
+    requirements
+}
 
-        if let Some(dependencies) = &version_info.dependencies {
-            for (depedency_id, dependency_info) in dependencies {
-                queue.push_back((depedency_id.as_str(), &dependency_info.version));
+/// Merges requirements into one, or tells why no version of the mod fits them
+fn merge_requirements(mod_id: &str, requirements: &[(Option<GUID>, VersionReq)], mod_list: &HashMap<GUID, Mod>, channel: UpdateChannel) -> Result<VersionReq, ResolveResult> {
+    let merged = requirements.iter()
+        .map(|(_, x)| x.clone())
+        .reduce(|a, b| a.intersect(&b))
+        .unwrap_or_else(|| VersionReq::at_least(&Version::zero()));
+
+    // No need to look through versions if the requirements can't overlap at all
+    let merged = match merged.simplify() {
+        SimplifiedReq::Satisfiable(simplified) => {
+            if find_latest_matching(mod_id, &simplified, mod_list, channel).is_some() {
+                return Ok(simplified);
             }
+
+            simplified
+        }
+        SimplifiedReq::Unsatisfiable => merged
+    };
+
+    // One of them can't be met by itself, that's what's worth reporting
+    let unmet = requirements.iter().find(|(_, x)| find_latest_matching(mod_id, x, mod_list, channel).is_none());
+
+    Err(match unmet {
+        Some((_, requirement)) => ResolveResult::UnableToFind {
+            mod_id: mod_id.to_string(),
+            requirement: requirement.clone(),
+        },
+        None if requirements.len() < 2 => ResolveResult::UnableToFind {
+            mod_id: mod_id.to_string(),
+            requirement: merged,
+        },
+        None => ResolveResult::Contradiction {
+            mod_id: mod_id.to_string(),
+            requirements: requirements.to_vec(),
+        }
+    })
+}
+
+/// Picks versions of the mod and everything it needs, requirements different mods have on the same dependency are merged,
+/// so a version that satisfies all of them gets picked
+pub fn resolve_install_mod(mod_id: &str, requirement: &VersionReq, current_install: &ModMap, mod_list: &HashMap<GUID, Mod>, channel_for: impl Fn(&str) -> UpdateChannel) -> ResolveResult {
+    let mut resolved: HashMap<GUID, Resolved> = HashMap::new();
+    let mut order: Vec<GUID> = vec![mod_id.to_string()];
+
+    // Dependencies picked later can narrow down requirements of the ones picked earlier, so it goes until nothing changes
+    for _ in 0..MAX_RESOLVE_PASSES {
+        let mut changed = false;
+        let mut index = 0;
+
+        while index < order.len() {
+            let current = order[index].clone();
+            index += 1;
+
+            let channel = channel_for(&current);
+            let requirements = requirements_for(&current, (mod_id, requirement), &resolved, current_install, mod_list);
+
+            let merged = match merge_requirements(&current, &requirements, mod_list, channel) {
+                Ok(merged) => merged,
+                Err(failure) => return failure
+            };
+
+            let Some((_, version, version_info)) = find_latest_matching(&current, &merged, mod_list, channel) else {
+                continue;
+            };
+
+            let resolution = if current_install.installed_versions(&current).iter().any(|v| merged.matches(v) && *v >= version) {
+                Resolved::Kept
+            } else {
+                Resolved::Picked {
+                    version,
+                    info: version_info,
+                }
+            };
+
+            let same = match (resolved.get(&current), &resolution) {
+                (Some(Resolved::Kept), Resolved::Kept) => true,
+                (Some(Resolved::Picked { version: a, .. }), Resolved::Picked { version: b, .. }) => a == b,
+                _ => false
+            };
+
+            if same {
+                continue;
+            }
+
+            if let Resolved::Picked { info, .. } = &resolution {
+                for dependency_id in info.dependencies.iter().flat_map(|x| x.keys()) {
+                    if !order.contains(dependency_id) {
+                        order.push(dependency_id.clone());
+                    }
+                }
+            }
+
+            resolved.insert(current, resolution);
+            changed = true;
+        }
+
+        if !changed {
+            break;
         }
     }
 
-    ops.reverse();
+    // Dependencies were found after mods that need them, so they go first
+    let ops = order.iter().rev()
+        .filter_map(|mod_id| match resolved.get(mod_id)? {
+            Resolved::Kept => None,
+            Resolved::Picked { version, .. } => Some((mod_id, *version))
+        })
+        .flat_map(|(mod_id, version)| {
+            current_install.installed_versions(mod_id).into_iter()
+                .map(|x| ModInstallOperations::UninstallMod((mod_id.clone(), x.clone())))
+                .chain([ModInstallOperations::InstallMod((mod_id.clone(), version.clone()))])
+                .collect::<Vec<ModInstallOperations>>()
+        })
+        .collect();
+
+    ResolveResult::Ok(ops)
+}
+
+/// Installed mods that depend on the mod, along with the versions they need
+pub fn find_dependents(mod_id: &str, current_install: &ModMap, mod_list: &HashMap<GUID, Mod>) -> Vec<(IDVersion, VersionReq)> {
+    let mut dependents: Vec<(IDVersion, VersionReq)> = current_install.iter_versions()
+        .filter_map(|(installed_id, version, _)| {
+            let dependency = mod_list.get(installed_id)?
+                .versions.get(version)?
+                .dependencies.as_ref()?
+                .get(mod_id)?;
 
-    ResolveResult::Ok(ops.into_iter().flatten().collect())
+            Some(((installed_id.clone(), version.clone()), dependency.version.clone()))
+        })
+        .collect();
+
+    dependents.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    dependents
+}
+
+/// How a dependency stands against what's installed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DependencyStatus {
+    /// Installed version that matches the requirement
+    Satisfied(Version),
+    /// Installed, but none of installed versions match, newest one is given
+    WrongVersion(Version),
+    Missing
+}
+
+pub fn dependency_status(mod_id: &str, requirement: &VersionReq, current_install: &ModMap) -> DependencyStatus {
+    let versions = current_install.installed_versions(mod_id);
+
+    let Some(newest) = versions.last() else {
+        return DependencyStatus::Missing;
+    };
+
+    match versions.iter().rev().find(|x| requirement.matches(x)) {
+        Some(version) => DependencyStatus::Satisfied((*version).clone()),
+        None => DependencyStatus::WrongVersion((*newest).clone())
+    }
 }
 
 pub enum ResolveResult {
@@ -74,5 +271,30 @@ pub enum ResolveResult {
     UnableToFind {
         mod_id: GUID,
         requirement: VersionReq
+    },
+
+    /// Versions of the mod exist for each requirement, but none of them fit all at once.
+    /// Requirements come with the mod that has them, None is the one being installed
+    Contradiction {
+        mod_id: GUID,
+        requirements: Vec<(Option<GUID>, VersionReq)>
+    }
+}
+
+impl Display for ResolveResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolveResult::Ok(operations) => write!(f, "Resolved into {} operations", operations.len()),
+            ResolveResult::UnableToFind { mod_id, requirement } => write!(f, "Couldn't find {} matching {}", mod_id, requirement),
+            ResolveResult::Contradiction { mod_id, requirements } => {
+                write!(f, "No version of {} fits all of its requirements: {}", mod_id, requirements.iter()
+                    .map(|(by, requirement)| match by {
+                        Some(by) => format!("{} by {}", requirement, by),
+                        None => format!("{} requested", requirement)
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", "))
+            }
+        }
     }
 }
\ No newline at end of file
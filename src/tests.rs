@@ -1,9 +1,39 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
-use crate::install::{ModFile, ModInstall, ModMap, VirtualInstall};
-use crate::manifest::{Artifact, Category, Conflict, Dependency, ManifestMods, Mod, ModVersion};
-use crate::version::{Version, Comparator};
+use std::time::Duration;
+use arc_swap::ArcSwap;
+use eframe::egui::{Context, Pos2, pos2, Rect};
+use strum::IntoEnumIterator;
+use tokio::time::Instant;
+use crate::config::{clamp_ui_scale, Config, CONFIG_VERSION, DefaultLocations, InstallType, migrate_config_json, mirrored_urls, MirrorRule, SourceTrust, SuppressedConflict, UpdateChannel};
+use crate::install::{ActualInstall, ConflictSeverity, disabled_path, enabled_path, InstallError, is_disabled_path, ModConflict, ModFile, ModFileArtifact, ModInstall, ModInstallOperations, ModMap, ModMapDiff, VirtualInstall};
+use crate::import::{extract_launch_options, parse_shortcut};
+use crate::ipc::{deep_links_in, DeepLink, forward_message, listen_for_messages, parse_deep_link};
+use crate::manager::{Manager, ManagerCommand, ManagerEvent, OperationStatus, QueueItem};
+use crate::launch::{CinematicTemporalAntiAliasing, Device, DroneCamera, JoinOptions, LaunchOptions, LaunchPreset, LaunchProblem, LaunchProfile, split_arguments, WindowType};
+use crate::resolver::{available_updates, dependency_status, DependencyStatus, find_dependents, resolve_install_mod, ResolveResult};
+use crate::manifest::{aggregate_manifests, Artifact, Category, Change, compare_versions, Conflict, Dependency, find_image_links, GlobalModList, ManifestError, ManifestMods, Mod, ModManifest, ModVersion, parse_manifest, verify_manifest_signature};
+use crate::feed::KnownVersions;
+use crate::github::{fetch_releases, parse_repo_url, version_from_tag};
+use crate::local_mods::{local_key_of, LocalModDetails, LocalModInfo, LocalMods};
+use crate::notes::ModNotes;
+use crate::maintenance::{collect_garbage, inspect_folder, MaintenanceFolder};
+use crate::planner::{PlanStep, simulate_plan};
+use crate::fixes::{FixKind, suggest_fixes};
+use crate::lockfile::{LockedMod, Lockfile};
+use crate::cloud_sync::{merge_snapshots, PendingSync, SyncConflict, SyncSnapshot};
+use crate::readme::{README_TTL_SECONDS, ReadmeCache};
+use crate::cache::ArtifactCache;
+use crate::transport::{BodySink, Transport, TransportError};
+use crate::modpack::{import_operation, ModPack, ModPackError, ModPackFile, ModPackManifest, ModPackMod, prepare_import};
+use crate::ui::manager::duplicates::{build_resolution_operations, DuplicateResolution};
+use crate::ui::manager::mod_entry::{ModEntryGeometry, ModEntryStyle};
+use crate::ui::manager::report::{build_mod_report, system_info};
+use crate::utils::{clear_folder, detect_assembly_version, folder_size, format_size, is_safe_file_name, is_safe_relative_path, is_within, sha256_bytes, TokenBucket, unix_timestamp};
+use crate::version::{SimplifiedReq, Version, VersionReq};
 
 #[test]
 fn mod_install_missing_dependency() {
@@ -18,6 +48,7 @@ fn mod_install_missing_dependency() {
             tags: None,
             category: Category::AssetImportingTweaks,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(1), ModVersion {
                     changelog: None,
@@ -28,7 +59,7 @@ fn mod_install_missing_dependency() {
                     conflicts: None,
                     dependencies: Some(HashMap::from([
                         (format!("test.mod.dep"), Dependency {
-                            version: Comparator::from_str("1").unwrap(),
+                            version: VersionReq::from_str("1").unwrap(),
                         })
                     ])),
                     artifacts: vec![
@@ -38,6 +69,7 @@ fn mod_install_missing_dependency() {
                             sha256: "135153".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -45,11 +77,11 @@ fn mod_install_missing_dependency() {
         })
     ]));
 
-    let mod_map: ModMap = HashMap::from([
-        (format!("test.mod.1"), vec![
-            ModFile::new("test.mod.1", Version::from_major(1), &manifest_mods)
-        ])
-    ]);
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.1"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.1", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ]))
+    ]));
 
     let virt = VirtualInstall::new(mod_map, manifest_mods.clone());
 
@@ -69,6 +101,7 @@ fn mod_install_valid_dependency() {
             tags: None,
             category: Category::AssetImportingTweaks,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(1), ModVersion {
                     changelog: None,
@@ -79,7 +112,7 @@ fn mod_install_valid_dependency() {
                     conflicts: None,
                     dependencies: Some(HashMap::from([
                         (format!("test.mod.dep"), Dependency {
-                            version: Comparator::from_str("1").unwrap(),
+                            version: VersionReq::from_str("1").unwrap(),
                         })
                     ])),
                     artifacts: vec![
@@ -89,6 +122,7 @@ fn mod_install_valid_dependency() {
                             sha256: "135153".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -104,6 +138,7 @@ fn mod_install_valid_dependency() {
             tags: None,
             category: Category::Libraries,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(1), ModVersion {
                     changelog: None,
@@ -120,6 +155,7 @@ fn mod_install_valid_dependency() {
                             sha256: "356357".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -127,14 +163,14 @@ fn mod_install_valid_dependency() {
         })
     ]));
 
-    let mod_map: ModMap = HashMap::from([
-        (format!("test.mod.1"), vec![
-            ModFile::new("test.mod.1", Version::from_major(1), &manifest_mods)
-        ]),
-        (format!("test.mod.dep"), vec![
-            ModFile::new("test.mod.dep", Version::from_major(1), &manifest_mods)
-        ])
-    ]);
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.1"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.1", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ])),
+        (format!("test.mod.dep"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.dep", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ]))
+    ]));
 
     let virt = VirtualInstall::new(mod_map, manifest_mods.clone());
 
@@ -154,6 +190,7 @@ fn mod_install_invalid_dependency() {
             tags: None,
             category: Category::AssetImportingTweaks,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(1), ModVersion {
                     changelog: None,
@@ -164,7 +201,7 @@ fn mod_install_invalid_dependency() {
                     conflicts: None,
                     dependencies: Some(HashMap::from([
                         (format!("test.mod.dep"), Dependency {
-                            version: Comparator::from_str("1").unwrap(),
+                            version: VersionReq::from_str("1").unwrap(),
                         })
                     ])),
                     artifacts: vec![
@@ -174,6 +211,7 @@ fn mod_install_invalid_dependency() {
                             sha256: "135153".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -189,6 +227,7 @@ fn mod_install_invalid_dependency() {
             tags: None,
             category: Category::Libraries,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(2), ModVersion {
                     changelog: None,
@@ -205,6 +244,7 @@ fn mod_install_invalid_dependency() {
                             sha256: "356357".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -212,14 +252,14 @@ fn mod_install_invalid_dependency() {
         })
     ]));
 
-    let mod_map: ModMap = HashMap::from([
-        (format!("test.mod.1"), vec![
-            ModFile::new("test.mod.1", Version::from_major(1), &manifest_mods)
-        ]),
-        (format!("test.mod.dep"), vec![
-            ModFile::new("test.mod.dep", Version::from_major(2), &manifest_mods)
-        ])
-    ]);
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.1"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.1", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ])),
+        (format!("test.mod.dep"), HashMap::from([
+            (Version::from_major(2), ModFile::new("test.mod.dep", &Version::from_major(2), &manifest_mods, &Default::default()))
+        ]))
+    ]));
 
     let virt = VirtualInstall::new(mod_map, manifest_mods.clone());
 
@@ -239,6 +279,7 @@ fn mod_install_multiple_versions() {
             tags: None,
             category: Category::AssetImportingTweaks,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(1), ModVersion {
                     changelog: None,
@@ -249,7 +290,7 @@ fn mod_install_multiple_versions() {
                     conflicts: None,
                     dependencies: Some(HashMap::from([
                         (format!("test.mod.dep"), Dependency {
-                            version: Comparator::from_str("1").unwrap(),
+                            version: VersionReq::from_str("1").unwrap(),
                         })
                     ])),
                     artifacts: vec![
@@ -259,6 +300,7 @@ fn mod_install_multiple_versions() {
                             sha256: "135153".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -274,6 +316,7 @@ fn mod_install_multiple_versions() {
             tags: None,
             category: Category::Libraries,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(2), ModVersion {
                     changelog: None,
@@ -290,6 +333,7 @@ fn mod_install_multiple_versions() {
                             sha256: "356357".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 }),
@@ -308,6 +352,7 @@ fn mod_install_multiple_versions() {
                             sha256: "356357".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -315,15 +360,15 @@ fn mod_install_multiple_versions() {
         })
     ]));
 
-    let mod_map: ModMap = HashMap::from([
-        (format!("test.mod.1"), vec![
-            ModFile::new("test.mod.1", Version::from_major(1), &manifest_mods)
-        ]),
-        (format!("test.mod.dep"), vec![
-            ModFile::new("test.mod.dep", Version::from_major(2), &manifest_mods),
-            ModFile::new("test.mod.dep", Version::from_major(3), &manifest_mods)
-        ])
-    ]);
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.1"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.1", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ])),
+        (format!("test.mod.dep"), HashMap::from([
+            (Version::from_major(2), ModFile::new("test.mod.dep", &Version::from_major(2), &manifest_mods, &Default::default())),
+            (Version::from_major(3), ModFile::new("test.mod.dep", &Version::from_major(3), &manifest_mods, &Default::default()))
+        ]))
+    ]));
 
     let virt = VirtualInstall::new(mod_map, manifest_mods.clone());
 
@@ -343,6 +388,7 @@ fn mod_install_direct_conflict() {
             tags: None,
             category: Category::AssetImportingTweaks,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(1), ModVersion {
                     changelog: None,
@@ -352,7 +398,7 @@ fn mod_install_direct_conflict() {
                     flags: None,
                     conflicts: Some(HashMap::from([
                         (format!("test.mod.dep"), Conflict {
-                            version: Comparator::from_str("*").unwrap(),
+                            version: VersionReq::from_str("*").unwrap(),
                         })
                     ])),
                     dependencies: None,
@@ -363,6 +409,7 @@ fn mod_install_direct_conflict() {
                             sha256: "135153".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -378,6 +425,7 @@ fn mod_install_direct_conflict() {
             tags: None,
             category: Category::Libraries,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(2), ModVersion {
                     changelog: None,
@@ -394,6 +442,7 @@ fn mod_install_direct_conflict() {
                             sha256: "356357".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -401,14 +450,14 @@ fn mod_install_direct_conflict() {
         })
     ]));
 
-    let mod_map: ModMap = HashMap::from([
-        (format!("test.mod.1"), vec![
-            ModFile::new("test.mod.1", Version::from_major(1), &manifest_mods)
-        ]),
-        (format!("test.mod.dep"), vec![
-            ModFile::new("test.mod.dep", Version::from_major(1), &manifest_mods)
-        ])
-    ]);
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.1"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.1", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ])),
+        (format!("test.mod.dep"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.dep", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ]))
+    ]));
 
     let virt = VirtualInstall::new(mod_map, manifest_mods.clone());
 
@@ -428,6 +477,7 @@ fn mod_install_direct_conflict_unaffected() {
             tags: None,
             category: Category::AssetImportingTweaks,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(1), ModVersion {
                     changelog: None,
@@ -437,7 +487,7 @@ fn mod_install_direct_conflict_unaffected() {
                     flags: None,
                     conflicts: Some(HashMap::from([
                         (format!("test.mod.dep"), Conflict {
-                            version: Comparator::from_str("^0.1").unwrap(),
+                            version: VersionReq::from_str("^0.1").unwrap(),
                         })
                     ])),
                     dependencies: None,
@@ -448,6 +498,7 @@ fn mod_install_direct_conflict_unaffected() {
                             sha256: "135153".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -463,6 +514,7 @@ fn mod_install_direct_conflict_unaffected() {
             tags: None,
             category: Category::Libraries,
             flags: None,
+            download_count: None,
             versions: HashMap::from([
                 (Version::from_major(2), ModVersion {
                     changelog: None,
@@ -479,6 +531,7 @@ fn mod_install_direct_conflict_unaffected() {
                             sha256: "356357".to_string(),
                             blake3: None,
                             install_location: None,
+                            size: None,
                         }
                     ],
                 })
@@ -486,16 +539,2537 @@ fn mod_install_direct_conflict_unaffected() {
         })
     ]));
 
-    let mod_map: ModMap = HashMap::from([
-        (format!("test.mod.1"), vec![
-            ModFile::new("test.mod.1", Version::from_major(1), &manifest_mods)
-        ]),
-        (format!("test.mod.dep"), vec![
-            ModFile::new("test.mod.dep", Version::from_major(1), &manifest_mods)
-        ])
-    ]);
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.1"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.1", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ])),
+        (format!("test.mod.dep"), HashMap::from([
+            (Version::from_major(1), ModFile::new("test.mod.dep", &Version::from_major(1), &manifest_mods, &Default::default()))
+        ]))
+    ]));
 
     let virt = VirtualInstall::new(mod_map, manifest_mods.clone());
 
     assert_eq!(virt.check_for_conflicts(&manifest_mods).len(), 0)
-}
\ No newline at end of file
+}
+
+#[test]
+fn version_prerelease_ordering() {
+    let version = |s: &str| Version::from_str(s).unwrap();
+
+    assert!(version("1.0.0-beta2") < version("1.0.0-beta10"));
+    assert!(version("1.0.0-beta") < version("1.0.0"));
+    assert!(version("1.0.0-alpha") < version("1.0.0-beta"));
+    assert!(version("1.0.0-beta.1") < version("1.0.0-beta.1.1"));
+    assert!(version("1.0.0-rc1") < version("1.0.1-alpha"));
+    assert_eq!(version("1.0.0-rc.1"), version("1.0.0-rc1"));
+
+    // Equal versions have to be the same key in maps
+    let versions: HashMap<Version, ()> = HashMap::from([(version("1.0.0-rc.1"), ())]);
+    assert!(versions.contains_key(&version("1.0.0-rc1")));
+    assert!(versions.contains_key(&version("1.0-rc-1")));
+    assert!(!versions.contains_key(&version("1.0.0-rc2")));
+}
+
+/// Requirement, version and whether the version should match, following semantics documented on VersionOp
+const VERSION_REQ_CORPUS: &[(&str, &str, bool)] = &[
+    // Exact
+    ("=1.2.3.4", "1.2.3.4", true),
+    ("=1.2.3.4", "1.2.3.5", false),
+    ("=1.2.3", "1.2.3", true),
+    ("=1.2.3", "1.2.3.9", true),
+    ("=1.2.3", "1.2.4", false),
+    ("=1.2", "1.2.9.9", true),
+    ("=1.2", "1.3", false),
+    ("=1", "1.9", true),
+    ("=1", "2.0", false),
+    ("1.2", "1.2.5", true),
+    ("1.2", "1.1.9", false),
+
+    // Greater
+    (">1.2.3.4", "1.2.3.5", true),
+    (">1.2.3.4", "1.2.3.4", false),
+    (">1.2.3", "1.2.3.9", false),
+    (">1.2.3", "1.2.4", true),
+    (">1.2", "1.2.9", false),
+    (">1.2", "1.3", true),
+    (">1", "1.9", false),
+    (">1", "2", true),
+
+    // GreaterEq
+    (">=1.2", "1.2", true),
+    (">=1.2", "1.2.0.1", true),
+    (">=1.2", "1.1.9", false),
+
+    // Less
+    ("<1.2", "1.1.9", true),
+    ("<1.2", "1.2", false),
+    ("<1.2.3.4", "1.2.3.3", true),
+    ("<1.2.3.4", "1.2.3.4", false),
+
+    // LessEq
+    ("<=1.2.3.4", "1.2.3.4", true),
+    ("<=1.2.3.4", "1.2.3.5", false),
+    ("<=1.2.3", "1.2.3.9", true),
+    ("<=1.2", "1.2.9", true),
+    ("<=1.2", "1.3", false),
+    ("<=1", "1.9", true),
+    ("<=1", "2", false),
+
+    // Tilde
+    ("~1.2.3.4", "1.2.3.4", true),
+    ("~1.2.3.4", "1.2.9", true),
+    ("~1.2.3.4", "1.2.3.3", false),
+    ("~1.2.3", "1.2.9", true),
+    ("~1.2.3", "1.2.2", false),
+    ("~1.2.3", "1.3", false),
+    ("~1.2", "1.2.5", true),
+    ("~1.2", "1.3", false),
+    ("~1", "1.5", true),
+    ("~1", "2", false),
+
+    // Caret
+    ("^1.2.3", "1.9", true),
+    ("^1.2.3", "1.2.2", false),
+    ("^1.2.3", "2.0", false),
+    ("^0.2.3", "0.2.9", true),
+    ("^0.2.3", "0.3", false),
+    ("^0.0.3", "0.0.3.5", true),
+    ("^0.0.3", "0.0.4", false),
+    ("^0.0.0.3", "0.0.0.3", true),
+    ("^0.0.0.3", "0.0.0.4", false),
+    ("^0.0", "0.0.5", true),
+    ("^0.0", "0.1", false),
+    ("^0", "0.9", true),
+    ("^0", "1", false),
+    ("^1", "1.9", true),
+    ("^1", "2", false),
+
+    // Wildcards
+    ("1.2.*", "1.2.7", true),
+    ("1.2.*", "1.3", false),
+    ("1.2.*.*", "1.2.7.1", true),
+    ("1.*", "1.9.9", true),
+    ("1.*", "2.0", false),
+    ("1.*.*", "1.5", true),
+    ("1.*.*.*", "0.9", false),
+    ("*", "0.0.1", true),
+    ("*", "99", true),
+    ("*.*", "5.4", true),
+
+    // Whitespace around operators
+    (">= 1.2", "1.2", true),
+    ("  < 2  ", "1.9", true),
+    (">= 1.0, < 2.0", "1.5", true),
+    (">= 1.0, < 2.0", "2.0", false),
+    (">=1.0 <2.0", "1.5", true),
+    (">=1.0 <2.0", "2.1", false),
+
+    // Wildcards inside of comparator sets
+    ("1.*, >=1.2", "1.1", false),
+    ("1.*, >=1.2", "1.3", true),
+    ("1.*, >=1.2", "2.0", false),
+    ("<2, 1.2.*", "1.2.3", true),
+
+    // OR groups
+    ("1.* || 3.*", "1.4", true),
+    ("1.* || 3.*", "2.0", false),
+    ("1.* || 3.*", "3.1", true),
+    ("<1.0 || >=2.0, <3.0", "0.5", true),
+    ("<1.0 || >=2.0, <3.0", "1.5", false),
+    ("<1.0 || >=2.0, <3.0", "2.5", true),
+    ("<1.0 || >=2.0, <3.0", "3.0", false),
+    ("=1.0||=2.0", "2.0", true),
+];
+
+const VERSION_SAMPLES: &[&str] = &[
+    "0", "0.0.0.1", "0.0.3", "0.1", "0.2.5", "1", "1.0.1", "1.1", "1.2", "1.2.3", "1.2.3.4", "1.2.3.5", "1.3", "2", "2.5.1", "3.0", "10"
+];
+
+#[test]
+fn version_req_corpus() {
+    for (requirement, version, expected) in VERSION_REQ_CORPUS {
+        let req = VersionReq::from_str(requirement).unwrap();
+        let version = Version::from_str(version).unwrap();
+
+        assert_eq!(req.matches(&version), *expected, "{} against {}", requirement, version);
+    }
+}
+
+#[test]
+fn version_req_at_least() {
+    let version = |x: &str| Version::from_str(x).unwrap();
+    let req = VersionReq::at_least(&version("1.0.0-rc"));
+
+    assert!(req.matches(&version("1.0.0-rc")));
+    assert!(req.matches(&version("1.0.0")));
+    assert!(req.matches(&version("2.1")));
+    assert!(!req.matches(&version("1.0.0-beta")));
+    assert!(!req.matches(&version("0.9")));
+}
+
+#[test]
+fn version_req_exact() {
+    let version = |x: &str| Version::from_str(x).unwrap();
+    let req = VersionReq::exact(&version("1.2"));
+
+    assert!(req.matches(&version("1.2")));
+    assert!(req.matches(&version("1.2.0.0")));
+    assert!(!req.matches(&version("1.2.1")));
+    assert!(!req.matches(&version("1.2.0.1")));
+    assert!(!req.matches(&version("1.2-rc")));
+
+    let prerelease = VersionReq::exact(&version("2.0.0-beta"));
+    assert!(prerelease.matches(&version("2.0.0-beta")));
+    assert!(!prerelease.matches(&version("2.0.0")));
+}
+
+#[test]
+fn version_req_rejects_invalid() {
+    for requirement in ["1.2.*.4", "1.*.2", "*.1", "", "1.0 ||", "|| 1.0", ">=", "1.0, , 2.0 ||"] {
+        assert!(VersionReq::from_str(requirement).is_err(), "{} should be rejected", requirement);
+    }
+}
+
+#[test]
+fn version_req_display_round_trip() {
+    for (requirement, _, _) in VERSION_REQ_CORPUS {
+        let req = VersionReq::from_str(requirement).unwrap();
+        let reparsed = VersionReq::from_str(&req.to_string()).unwrap();
+
+        for version in VERSION_SAMPLES {
+            let version = Version::from_str(version).unwrap();
+            assert_eq!(req.matches(&version), reparsed.matches(&version), "{} reparsed as {}", requirement, req);
+        }
+    }
+}
+
+#[test]
+fn version_req_groups_combine() {
+    let single_group = VERSION_REQ_CORPUS.iter()
+        .map(|(x, _, _)| *x)
+        .filter(|x| !x.contains("||"))
+        .collect::<Vec<&str>>();
+
+    for a in &single_group {
+        for b in &single_group {
+            let req_a = VersionReq::from_str(a).unwrap();
+            let req_b = VersionReq::from_str(b).unwrap();
+            let any = VersionReq::from_str(&format!("{} || {}", a, b)).unwrap();
+            let all = VersionReq::from_str(&format!("{}, {}", a, b)).unwrap();
+
+            for version in VERSION_SAMPLES {
+                let version = Version::from_str(version).unwrap();
+
+                assert_eq!(any.matches(&version), req_a.matches(&version) || req_b.matches(&version), "{} || {} against {}", a, b, version);
+                assert_eq!(all.matches(&version), req_a.matches(&version) && req_b.matches(&version), "{}, {} against {}", a, b, version);
+            }
+        }
+    }
+}
+
+fn test_artifact(filename: &str, sha256: &str) -> Artifact {
+    Artifact {
+        url: format!("test.com/{}", filename),
+        filename: None,
+        sha256: sha256.to_string(),
+        blake3: None,
+        install_location: None,
+        size: None,
+    }
+}
+
+fn test_version(dependencies: &[(&str, &str)], artifacts: Vec<Artifact>) -> ModVersion {
+    ModVersion {
+        changelog: None,
+        release_url: None,
+        neos_version_compatibility: None,
+        modloader_version_compatibility: None,
+        flags: None,
+        conflicts: None,
+        dependencies: if dependencies.is_empty() {
+            None
+        } else {
+            Some(dependencies.iter()
+                .map(|(id, req)| (id.to_string(), Dependency { version: VersionReq::from_str(req).unwrap() }))
+                .collect())
+        },
+        artifacts,
+    }
+}
+
+fn test_mod(name: &str, category: Category, versions: Vec<(Version, ModVersion)>) -> Mod {
+    Mod {
+        name: name.to_string(),
+        color: None,
+        description: "".to_string(),
+        authors: Default::default(),
+        source_location: None,
+        website: None,
+        tags: None,
+        category,
+        flags: None,
+        download_count: None,
+        versions: versions.into_iter().collect(),
+    }
+}
+
+/// Mod depending on a library and a library with two versions
+fn resolver_manifest() -> Arc<ManifestMods> {
+    Arc::new(HashMap::from([
+        (format!("test.mod.1"), test_mod("Test Mod 1", Category::AssetImportingTweaks, vec![
+            (Version::from_major(1), test_version(&[("test.mod.lib", "^1.1")], vec![test_artifact("test.dll", "135153")]))
+        ])),
+        (format!("test.mod.2"), test_mod("Test Mod 2", Category::Audio, vec![
+            (Version::from_major(1), test_version(&[("test.mod.lib", "1"), ("test.mod.1", "*")], vec![test_artifact("test2.dll", "246264")]))
+        ])),
+        (format!("test.mod.lib"), test_mod("Test Lib", Category::Libraries, vec![
+            (Version::from_minor(1, 0), test_version(&[], vec![test_artifact("testlib.dll", "356357")])),
+            (Version::from_minor(1, 2), test_version(&[], vec![test_artifact("testlib.dll", "467468")])),
+        ])),
+        (format!("test.mod.broken"), test_mod("Broken Mod", Category::Audio, vec![
+            (Version::from_major(1), test_version(&[("test.mod.missing", "*")], vec![test_artifact("broken.dll", "578579")]))
+        ])),
+    ]))
+}
+
+async fn resolve_and_install(mod_id: &str, mod_map: ModMap, manifest_mods: &Arc<ManifestMods>) -> VirtualInstall {
+    let ResolveResult::Ok(operations) = resolve_install_mod(mod_id, &VersionReq::from_str("*").unwrap(), &mod_map, manifest_mods, |_| UpdateChannel::Stable) else {
+        panic!("Failed to resolve {}", mod_id);
+    };
+
+    let mut virt = VirtualInstall::new(mod_map, manifest_mods.clone());
+    virt.perform_operations(&operations).await.unwrap();
+
+    virt
+}
+
+#[tokio::test]
+async fn resolve_into_empty_install() {
+    let manifest_mods = resolver_manifest();
+    let virt = resolve_and_install("test.mod.1", ModMap::default(), &manifest_mods).await;
+
+    assert!(virt.mod_map()["test.mod.lib"].contains_key(&Version::from_minor(1, 2)));
+    assert!(virt.mod_map()["test.mod.1"].contains_key(&Version::from_major(1)));
+    assert_eq!(virt.check_for_conflicts(&manifest_mods).len(), 0);
+}
+
+#[tokio::test]
+async fn resolve_replaces_outdated_dependency() {
+    let manifest_mods = resolver_manifest();
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.lib"), HashMap::from([
+            (Version::from_minor(1, 0), ModFile::new("test.mod.lib", &Version::from_minor(1, 0), &manifest_mods, &Default::default()))
+        ]))
+    ]));
+
+    let virt = resolve_and_install("test.mod.1", mod_map, &manifest_mods).await;
+
+    assert_eq!(virt.mod_map()["test.mod.lib"].len(), 1);
+    assert!(virt.mod_map()["test.mod.lib"].contains_key(&Version::from_minor(1, 2)));
+    assert_eq!(virt.check_for_conflicts(&manifest_mods).len(), 0);
+}
+
+#[tokio::test]
+async fn resolve_shared_dependency_once() {
+    let manifest_mods = resolver_manifest();
+
+    let ResolveResult::Ok(operations) = resolve_install_mod("test.mod.2", &VersionReq::from_str("*").unwrap(), &ModMap::default(), &manifest_mods, |_| UpdateChannel::Stable) else {
+        panic!("Failed to resolve test.mod.2");
+    };
+
+    let lib_installs = operations.iter()
+        .filter(|x| matches!(x, ModInstallOperations::InstallMod((id, _)) if id == "test.mod.lib"))
+        .count();
+
+    assert_eq!(lib_installs, 1);
+
+    let virt = resolve_and_install("test.mod.2", ModMap::default(), &manifest_mods).await;
+    assert_eq!(virt.check_for_conflicts(&manifest_mods).len(), 0);
+}
+
+#[test]
+fn resolve_exact_downgrade() {
+    let manifest_mods = resolver_manifest();
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.lib"), HashMap::from([
+            (Version::from_minor(1, 2), ModFile::new("test.mod.lib", &Version::from_minor(1, 2), &manifest_mods, &Default::default()))
+        ]))
+    ]));
+
+    let ResolveResult::Ok(operations) = resolve_install_mod("test.mod.lib", &VersionReq::exact(&Version::from_minor(1, 0)), &mod_map, &manifest_mods, |_| UpdateChannel::Stable) else {
+        panic!("Failed to resolve test.mod.lib 1.0");
+    };
+
+    assert_eq!(operations.len(), 2);
+    assert!(matches!(&operations[0], ModInstallOperations::UninstallMod((id, version)) if id == "test.mod.lib" && *version == Version::from_minor(1, 2)));
+    assert!(matches!(&operations[1], ModInstallOperations::InstallMod((id, version)) if id == "test.mod.lib" && *version == Version::from_minor(1, 0)));
+}
+
+#[test]
+fn resolve_missing_dependency() {
+    let manifest_mods = resolver_manifest();
+
+    let result = resolve_install_mod("test.mod.broken", &VersionReq::from_str("*").unwrap(), &ModMap::default(), &manifest_mods, |_| UpdateChannel::Stable);
+
+    assert!(matches!(result, ResolveResult::UnableToFind { mod_id, .. } if mod_id == "test.mod.missing"));
+}
+
+#[test]
+fn token_bucket_test() {
+    let mut bucket = TokenBucket::new(1000);
+    let start = Instant::now();
+
+    // Starts with a full second worth of tokens
+    assert_eq!(bucket.take_at(1000, start), Duration::ZERO);
+    assert_eq!(bucket.take_at(500, start), Duration::from_millis(500));
+
+    // Debt gets paid off first, then tokens build up again
+    assert_eq!(bucket.take_at(0, start + Duration::from_millis(500)), Duration::ZERO);
+    assert_eq!(bucket.take_at(250, start + Duration::from_millis(750)), Duration::ZERO);
+
+    // Idle time can't store more than a second worth
+    assert_eq!(bucket.take_at(2000, start + Duration::from_secs(60)), Duration::from_secs(1));
+}
+
+#[test]
+fn mirrored_urls_test() {
+    let mut disabled = MirrorRule::new("https://raw.githubusercontent.com/".to_string(), "https://disabled.example.com/".to_string());
+    disabled.enabled = false;
+
+    let mirrors = vec![
+        MirrorRule::new("https://raw.githubusercontent.com/".to_string(), "https://mirror.example.com/raw/".to_string()),
+        disabled,
+        MirrorRule::new("https://github.com/".to_string(), "https://mirror.example.com/gh/".to_string()),
+    ];
+
+    assert_eq!(mirrored_urls(&mirrors, "https://raw.githubusercontent.com/a/b/manifest.json"), vec![
+        "https://mirror.example.com/raw/a/b/manifest.json".to_string(),
+        "https://raw.githubusercontent.com/a/b/manifest.json".to_string(),
+    ]);
+
+    assert_eq!(mirrored_urls(&mirrors, "https://example.com/mod.dll"), vec!["https://example.com/mod.dll".to_string()]);
+}
+
+fn test_pack(install_path: &str, data: &[u8]) -> ModPack {
+    let hash = sha256_bytes(data);
+
+    ModPack {
+        manifest: ModPackManifest {
+            format_version: 1,
+            mods: vec![ModPackMod {
+                mod_id: "test.mod".to_string(),
+                version: Version::from_str("1.0.0").unwrap(),
+                files: vec![ModPackFile {
+                    archive_path: "files/0/TestMod.dll".to_string(),
+                    install_path: PathBuf::from(install_path),
+                    sha256: hash,
+                }],
+            }],
+        },
+        contents: HashMap::from([("files/0/TestMod.dll".to_string(), data.to_vec())]),
+    }
+}
+
+#[test]
+fn mod_pack_import_checks() {
+    let data = b"test mod";
+    let hash_table = HashMap::from([(sha256_bytes(data), ("test.mod".to_string(), Version::from_str("1.0.0").unwrap()))]);
+    let install_location = PathBuf::from("/neos");
+
+    let prepared = prepare_import(&test_pack("nml_mods/TestMod.dll", data), &install_location, &hash_table).unwrap();
+    assert_eq!(prepared.len(), 1);
+    assert_eq!(prepared[0].1[0].path, PathBuf::from("/neos/nml_mods/TestMod.dll"));
+
+    // Installed through the usual checks, into the folder the pack had it in
+    assert!(matches!(
+        import_operation(prepared[0].0.clone(), &prepared[0].1, &install_location),
+        ModInstallOperations::InstallModAt((mod_id, _), location) if mod_id == "test.mod" && location == PathBuf::from("/nml_mods")
+    ));
+
+    // Files can't be placed outside of Neos folder
+    assert!(matches!(
+        prepare_import(&test_pack("../TestMod.dll", data), &install_location, &hash_table),
+        Err(ModPackError::InvalidPath(_))
+    ));
+
+    // Files have to be known to the manifest
+    assert!(matches!(
+        prepare_import(&test_pack("nml_mods/TestMod.dll", b"something else"), &install_location, &hash_table),
+        Err(ModPackError::UnknownFile { .. })
+    ));
+}
+
+#[tokio::test]
+async fn find_dependents_test() {
+    let manifest_mods = resolver_manifest();
+    let virt = resolve_and_install("test.mod.2", ModMap::default(), &manifest_mods).await;
+
+    let dependents = find_dependents("test.mod.lib", virt.mod_map(), &manifest_mods);
+
+    assert_eq!(dependents, vec![
+        (("test.mod.1".to_string(), Version::from_major(1)), VersionReq::from_str("^1.1").unwrap()),
+        (("test.mod.2".to_string(), Version::from_major(1)), VersionReq::from_str("1").unwrap()),
+    ]);
+
+    assert!(find_dependents("test.mod.2", virt.mod_map(), &manifest_mods).is_empty());
+}
+
+#[test]
+fn format_size_test() {
+    assert_eq!(format_size(0), "0 B");
+    assert_eq!(format_size(1023), "1023 B");
+    assert_eq!(format_size(1536), "1.5 KB");
+    assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+}
+
+#[test]
+fn find_image_links_test() {
+    let readme = r#"# Cool mod
+![Build](https://img.shields.io/badge/build-passing-green)
+![Screenshot](https://example.com/shot1.png)
+Some text ![relative](images/local.png)
+<img width="300" src="https://example.com/shot2.jpg">
+![again](https://example.com/shot1.png "title")
+![vector](https://example.com/logo.svg?raw=true)
+"#;
+
+    assert_eq!(find_image_links(readme), vec![
+        "https://example.com/shot1.png".to_string(),
+        "https://example.com/shot2.jpg".to_string(),
+    ]);
+}
+
+#[test]
+fn unsafe_install_paths() {
+    assert!(is_safe_relative_path(Path::new("/nml_mods")));
+    assert!(is_safe_relative_path(Path::new("nml_mods/sub/./folder")));
+    assert!(!is_safe_relative_path(Path::new("/nml_mods/../../..")));
+    assert!(!is_safe_relative_path(Path::new("..\\..\\Windows")));
+    assert!(!is_safe_relative_path(Path::new("C:\\Windows")));
+
+    assert!(is_safe_file_name("TestMod.dll"));
+    assert!(!is_safe_file_name(".."));
+    assert!(!is_safe_file_name("..\\..\\evil.dll"));
+    assert!(!is_safe_file_name("sub/evil.dll"));
+    assert!(!is_safe_file_name(""));
+
+    let artifact = |filename: &str, install_location: &str| Artifact {
+        url: "https://example.com/TestMod.dll".to_string(),
+        filename: Some(filename.to_string()),
+        sha256: "00".to_string(),
+        blake3: None,
+        install_location: Some(PathBuf::from(install_location)),
+        size: None,
+    };
+
+    let manifest_mods: ManifestMods = HashMap::from([
+        ("test.mod".to_string(), Mod {
+            name: "Test Mod".to_string(),
+            color: None,
+            description: "".to_string(),
+            authors: Default::default(),
+            source_location: None,
+            website: None,
+            tags: None,
+            category: Category::Misc,
+            flags: None,
+            download_count: None,
+            versions: HashMap::from([
+                (Version::from_major(1), ModVersion {
+                    changelog: None,
+                    release_url: None,
+                    neos_version_compatibility: None,
+                    modloader_version_compatibility: None,
+                    flags: None,
+                    conflicts: None,
+                    dependencies: None,
+                    artifacts: vec![
+                        artifact("TestMod.dll", "/nml_mods"),
+                        artifact("evil.dll", "/nml_mods/../../.."),
+                        artifact("..\\..\\evil.dll", "/nml_mods"),
+                    ],
+                })
+            ]),
+        })
+    ]);
+
+    let file = ModFile::new("test.mod", &Version::from_major(1), &manifest_mods, &Default::default());
+
+    assert_eq!(file.files.len(), 1);
+    assert_eq!(file.files[0].file_path, PathBuf::from("/nml_mods/TestMod.dll"));
+
+    let root = std::env::temp_dir().join("nmo_path_test");
+    std::fs::create_dir_all(&root).unwrap();
+
+    assert!(is_within(&root, &root.join("nml_mods/TestMod.dll")).unwrap());
+    assert!(!is_within(&root, &root.join("missing/../../evil.dll")).unwrap());
+    assert!(!is_within(&root, &std::env::temp_dir().join("evil.dll")).unwrap());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn manifest_signature_test() {
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let public_key = hex::encode(key_pair.public_key().as_ref());
+
+    let manifest = br#"{"schemaVersion":"1.0.0","mods":{}}"#;
+    let signature = hex::encode(key_pair.sign(manifest).as_ref());
+
+    assert!(verify_manifest_signature(manifest, &signature, &public_key).is_ok());
+    assert!(verify_manifest_signature(manifest, &format!("{}\n", signature), &public_key).is_ok());
+
+    assert!(matches!(
+        verify_manifest_signature(br#"{"schemaVersion":"1.0.0","mods":{"evil":{}}}"#, &signature, &public_key),
+        Err(ManifestError::InvalidSignature)
+    ));
+    assert!(matches!(verify_manifest_signature(manifest, "not hex", &public_key), Err(ManifestError::InvalidSignature)));
+    assert!(matches!(verify_manifest_signature(manifest, &signature, "zz"), Err(ManifestError::InvalidKey)));
+}
+
+#[test]
+fn manifest_schema_versions() {
+    let current = parse_manifest(br#"{"schemaVersion":"1.0.0","mods":{}}"#).unwrap();
+    assert_eq!(current.schema_version, Some(Version::from_patch(1, 0, 0)));
+
+    // Newer minor versions only add things, unknown fields get ignored
+    assert!(parse_manifest(br#"{"schemaVersion":"1.3.0","newField":true,"mods":{}}"#).is_ok());
+
+    let legacy = parse_manifest(br#"{"mods":{"test.mod":{"name":"Test","description":"","authors":{},"versions":{}}}}"#).unwrap();
+    assert_eq!(legacy.mods["test.mod"].category, Category::Misc);
+    assert_eq!(legacy.schema_version, Some(Version::from_patch(1, 0, 0)));
+
+    assert!(matches!(
+        parse_manifest(br#"{"schemaVersion":"2.0.0","mods":[]}"#),
+        Err(ManifestError::UnsupportedSchema(_))
+    ));
+    assert!(matches!(
+        parse_manifest(br#"{"schemaVersion":"banana","mods":{}}"#),
+        Err(ManifestError::InvalidSchemaVersion(_))
+    ));
+}
+
+#[test]
+fn config_migration_v0() {
+    let mut value = serde_json::json!({
+        "neos_exe_location": "/neos/Neos.exe",
+        "scan_locations": ["/nml_mods", "/custom"]
+    });
+
+    assert_eq!(migrate_config_json(&mut value), Some(0));
+    assert_eq!(value["config_version"], CONFIG_VERSION);
+
+    let config: Config = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(config.install_profile.install_type, InstallType::Unknown);
+    assert_eq!(config.install_profile.enabled_locations().collect::<Vec<_>>(), vec![&PathBuf::from("/nml_mods"), &PathBuf::from("/custom")]);
+
+    // Running it again doesn't do anything
+    assert_eq!(migrate_config_json(&mut value), None);
+}
+
+#[test]
+fn config_migration_newer_version() {
+    let mut value = serde_json::json!({
+        "config_version": CONFIG_VERSION + 1,
+        "neos_exe_location": "/neos/Neos.exe",
+        "something_new": true
+    });
+
+    assert_eq!(migrate_config_json(&mut value), None);
+    assert_eq!(value["config_version"], CONFIG_VERSION + 1);
+    assert!(serde_json::from_value::<Config>(value).is_ok());
+}
+
+#[test]
+fn launch_options_from_arguments() {
+    let arguments = split_arguments(r#"%command% -Screen -LoadAssembly "Libraries\NeosModLoader.dll" -Join Auto -DataPath "D:\Neos Data" -screen-fullscreen 0 -SomethingUnknown"#);
+
+    assert_eq!(arguments[3], "Libraries\\NeosModLoader.dll");
+    assert_eq!(arguments[7], "D:\\Neos Data");
+
+    let options = LaunchOptions::from_arguments(&arguments);
+
+    assert_eq!(options.device, Device::Desktop);
+    assert!(options.use_mods);
+    assert!(options.load_assembly.is_empty());
+    assert_eq!(options.auto_join, JoinOptions::JoinAuto);
+    assert_eq!(options.data_path, Some(PathBuf::from("D:\\Neos Data")));
+    assert_eq!(options.display_mode, WindowType::Windowed);
+
+    // What gets built should read back the same
+    let built: Vec<String> = options.build_arguments().into_iter().map(|(x, _)| x).collect();
+    assert_eq!(LaunchOptions::from_arguments(&built), options);
+}
+
+#[test]
+fn steam_launch_options_extraction() {
+    let vdf = r#""UserLocalConfigStore"
+{
+    "Software"
+    {
+        "Valve"
+        {
+            "Steam"
+            {
+                "apps"
+                {
+                    "440"
+                    {
+                        "LaunchOptions"		"-novid"
+                    }
+                    "740250"
+                    {
+                        "LastPlayed"		"1690000000"
+                        "cloud"
+                        {
+                            "quota"		"0"
+                        }
+                        "LaunchOptions"		"-Screen -Bootstrap \"My Bootstrap\""
+                    }
+                    "740251"
+                    {
+                        "LaunchOptions"		"-SteamVR"
+                    }
+                }
+            }
+        }
+    }
+}"#;
+
+    assert_eq!(extract_launch_options(vdf, "740250"), Some("-Screen -Bootstrap \"My Bootstrap\"".to_string()));
+    assert_eq!(extract_launch_options(vdf, "12345"), None);
+}
+
+#[test]
+fn shortcut_parsing() {
+    fn unicode_string(data: &mut Vec<u8>, string: &str) {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        data.extend((units.len() as u16).to_le_bytes());
+        data.extend(units.iter().flat_map(|x| x.to_le_bytes()));
+    }
+
+    let target = "C:\\Neos\\Neos.exe";
+
+    let mut data = vec![0u8; 0x4C];
+    data[0] = 0x4C;
+    // Has link info, working dir, arguments and is unicode
+    data[0x14] = 0x2 | 0x10 | 0x20 | 0x80;
+
+    // Link info with only local base path set
+    let mut info = vec![];
+    info.extend(((0x1C + target.len() + 1) as u32).to_le_bytes());
+    info.extend(0x1Cu32.to_le_bytes());
+    info.extend(1u32.to_le_bytes());
+    info.extend(0u32.to_le_bytes());
+    info.extend(0x1Cu32.to_le_bytes());
+    info.extend(0u32.to_le_bytes());
+    info.extend(0u32.to_le_bytes());
+    info.extend(target.as_bytes());
+    info.push(0);
+    data.extend(info);
+
+    unicode_string(&mut data, "C:\\Neos");
+    unicode_string(&mut data, "-Screen -DataPath \"D:\\Neos Data\"");
+
+    let shortcut = parse_shortcut(&data).unwrap();
+    assert_eq!(shortcut.target.as_deref(), Some(target));
+    assert_eq!(shortcut.working_dir.as_deref(), Some("C:\\Neos"));
+
+    let options = LaunchOptions::parse_arguments(&shortcut.arguments.unwrap());
+    assert_eq!(options.device, Device::Desktop);
+    assert_eq!(options.data_path, Some(PathBuf::from("D:\\Neos Data")));
+
+    // Truncated data isn't a link
+    assert!(parse_shortcut(&data[..0x30]).is_none());
+    assert!(parse_shortcut(b"not a shortcut").is_none());
+}
+
+#[test]
+fn launch_options_round_trip() {
+    let options = LaunchOptions {
+        device: Device::SteamVR,
+        force_sr_anipal: true,
+        enable_owo: Some("192.168.1.20".to_string()),
+        use_mods: true,
+        display_mode: WindowType::FullScreen,
+        resolution_width: Some(1920),
+        resolution_height: Some(1080),
+        auto_join: JoinOptions::Join("neos-session:///S-123".to_string()),
+        announce_home_on_lan: true,
+        bootstrap: Some("My Bootstrap".to_string()),
+        force_lan: true,
+        force_relay: true,
+        use_local_cloud: true,
+        use_staging_cloud: true,
+        drone_camera: DroneCamera::CameraTimelapse,
+        use_neos_camera: true,
+        force_no_voice: true,
+        data_path: Some(PathBuf::from("D:\\Neos Data")),
+        cache_path: Some(PathBuf::from("D:\\Neos Cache")),
+        delete_unsynced_cloud_records: true,
+        force_sync_conflicting_cloud_records: true,
+        repair_database: true,
+        ctaa: Some(CinematicTemporalAntiAliasing {
+            temporal_edge_power: Some(-0.5),
+            sharpness_enabled: true,
+            aptive_sharpness: Some(0.25),
+        }),
+        watchdog: Some(PathBuf::from("C:\\watchdog.txt")),
+        load_assembly: vec!["Libraries\\Other.dll".to_string()],
+        kiosk: true,
+        no_ui: true,
+        do_not_auto_load_cloud_home: true,
+        reset_dash: true,
+        skip_intro_tutorial: true,
+        force_intro_tutorial: true,
+        invisible: true,
+        config: Some(PathBuf::from("C:\\Neos Config.json")),
+        force_reticle_above_horizon: true,
+    };
+
+    assert_eq!(LaunchOptions::parse_arguments(&options.build_argument_string()), options);
+    assert_eq!(LaunchOptions::parse_arguments(&LaunchOptions::default().build_argument_string()), LaunchOptions::default());
+
+    // Every variant of enum options should survive too
+    for device in Device::iter() {
+        let options = LaunchOptions { device, ..Default::default() };
+        assert_eq!(LaunchOptions::parse_arguments(&options.build_argument_string()), options);
+    }
+
+    for drone_camera in DroneCamera::iter() {
+        let options = LaunchOptions { drone_camera, ..Default::default() };
+        assert_eq!(LaunchOptions::parse_arguments(&options.build_argument_string()), options);
+    }
+
+    for display_mode in WindowType::iter() {
+        let options = LaunchOptions { display_mode, ..Default::default() };
+        assert_eq!(LaunchOptions::parse_arguments(&options.build_argument_string()), options);
+    }
+
+    for auto_join in [JoinOptions::None, JoinOptions::JoinAuto, JoinOptions::Open("neos-session:///S-123".to_string())] {
+        let options = LaunchOptions { auto_join, ..Default::default() };
+        assert_eq!(LaunchOptions::parse_arguments(&options.build_argument_string()), options);
+    }
+}
+
+#[test]
+fn launch_presets_keep_other_options() {
+    let options = LaunchOptions {
+        data_path: Some(PathBuf::from("D:\\Neos Data")),
+        auto_join: JoinOptions::JoinAuto,
+        ..Default::default()
+    };
+
+    let headless = LaunchPreset::Headless.apply(&options);
+    assert!(headless.no_ui && headless.invisible);
+    assert_eq!(headless.device, Device::Desktop);
+    assert_eq!(headless.data_path, options.data_path);
+    assert_eq!(headless.auto_join, options.auto_join);
+
+    assert_eq!(LaunchPreset::VR.apply(&headless).no_ui, false);
+    assert_eq!(LaunchPreset::Streaming.apply(&options).device, Device::CameraMode);
+}
+
+#[test]
+fn launch_options_check() {
+    let missing = PathBuf::from("/definitely/not/a/neos/folder");
+
+    let options = LaunchOptions {
+        use_mods: false,
+        ..Default::default()
+    };
+    assert!(options.check(&missing).is_empty());
+
+    let options = LaunchOptions {
+        use_mods: true,
+        data_path: Some(missing.join("Data")),
+        resolution_width: Some(1920),
+        resolution_height: Some(0),
+        ..Default::default()
+    };
+
+    assert_eq!(options.check(&missing), vec![
+        LaunchProblem::ModLoaderMissing,
+        LaunchProblem::DataPathMissing(missing.join("Data")),
+        LaunchProblem::InvalidResolution(Some(1920), Some(0))
+    ]);
+}
+
+#[test]
+fn conflict_loser_preference() {
+    let mut config = Config::new(PathBuf::from("/neos/Neos.exe"));
+    let (first, second) = (format!("mod.a"), format!("mod.b"));
+
+    assert_eq!(config.conflict_loser(&first, &second), &second);
+
+    config.conflict_preference = vec![second.clone()];
+    assert_eq!(config.conflict_loser(&first, &second), &first);
+
+    config.conflict_preference = vec![first.clone(), second.clone()];
+    assert_eq!(config.conflict_loser(&first, &second), &second);
+    assert_eq!(config.conflict_loser(&second, &first), &second);
+}
+
+#[test]
+fn skipped_updates() {
+    let mut config = Config::new(PathBuf::from("/neos/Neos.exe"));
+    config.skipped_updates.insert(format!("mod.a"), Version::from_patch(1, 4, 0));
+
+    assert!(config.is_update_skipped("mod.a", &Version::from_patch(1, 4, 0)));
+    assert!(config.is_update_skipped("mod.a", &Version::from_patch(1, 3, 2)));
+    assert!(!config.is_update_skipped("mod.a", &Version::from_patch(1, 4, 1)));
+    assert!(!config.is_update_skipped("mod.b", &Version::from_patch(1, 4, 0)));
+}
+
+#[test]
+fn load_order_sorting() {
+    let mut config = Config::new(PathBuf::from("/neos/Neos.exe"));
+    config.load_order = vec![format!("mod.c"), format!("mod.a")];
+
+    let mut mods = vec![format!("mod.a"), format!("mod.d"), format!("mod.b"), format!("mod.c")];
+    config.sort_by_load_order(&mut mods);
+
+    assert_eq!(mods, vec![format!("mod.c"), format!("mod.a"), format!("mod.b"), format!("mod.d")]);
+}
+
+#[test]
+fn ui_scale_clamping() {
+    assert_eq!(clamp_ui_scale(1.5), 1.5);
+    assert_eq!(clamp_ui_scale(0.1), 0.75);
+    assert_eq!(clamp_ui_scale(40.0), 3.0);
+    assert_eq!(clamp_ui_scale(f32::NAN), 1.0);
+}
+
+#[test]
+fn pinning_mods_toggles() {
+    let mut config = Config::new(PathBuf::from("/neos/Neos.exe"));
+    assert!(!config.is_pinned("mod.a"));
+
+    config.toggle_pinned("mod.a");
+    config.toggle_pinned("mod.b");
+    assert!(config.is_pinned("mod.a") && config.is_pinned("mod.b"));
+
+    config.toggle_pinned("mod.a");
+    assert!(!config.is_pinned("mod.a"));
+    assert_eq!(config.pinned_mods, vec![format!("mod.b")]);
+}
+
+#[test]
+fn plugin_mods_install_location() {
+    let plugin = |category: Category, flags: Option<Vec<String>>| Mod {
+        name: "Plugin".to_string(),
+        color: None,
+        description: "".to_string(),
+        authors: Default::default(),
+        source_location: None,
+        website: None,
+        tags: None,
+        category,
+        flags,
+        download_count: None,
+        versions: Default::default(),
+    };
+
+    let defaults = DefaultLocations::default();
+
+    assert!(plugin(Category::Plugins, None).is_plugin());
+    assert!(plugin(Category::Misc, Some(vec!["Plugin".to_string()])).is_plugin());
+    assert!(!plugin(Category::Misc, Some(vec!["deprecated".to_string()])).is_plugin());
+
+    let flagged = plugin(Category::Misc, Some(vec!["plugin".to_string()]));
+    assert_eq!(defaults.for_category(flagged.install_category()), PathBuf::from("/Libraries"));
+    assert_eq!(defaults.for_category(Category::Misc), PathBuf::from("/nml_mods"));
+}
+
+#[tokio::test]
+async fn folder_size_and_clearing() {
+    let root = std::env::temp_dir().join("nmo_cache_test");
+    std::fs::create_dir_all(root.join("sub")).unwrap();
+    std::fs::write(root.join("a.bin"), [0u8; 100]).unwrap();
+    std::fs::write(root.join("sub").join("b.bin"), [0u8; 50]).unwrap();
+
+    assert_eq!(folder_size(root.clone()).await.unwrap(), 150);
+
+    clear_folder(&root).await.unwrap();
+
+    assert!(root.exists());
+    assert_eq!(folder_size(root.clone()).await.unwrap(), 0);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn assembly_version_detection() {
+    let utf16 = |text: &str| text.encode_utf16().chain([0]).flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>();
+
+    let mut data = b"MZ\0\0".to_vec();
+    data.extend(utf16("Assembly Version"));
+    data.extend([0, 0]);
+    data.extend(utf16("1.2.3.0"));
+
+    assert_eq!(detect_assembly_version(&data), Some("1.2.3.0".to_string()));
+
+    // Falls back to file version from the fixed file info
+    let mut data = b"MZ\0\0".to_vec();
+    data.extend(0xFEEF04BDu32.to_le_bytes());
+    data.extend(0x10000u32.to_le_bytes());
+    data.extend(((2u32 << 16) | 4).to_le_bytes());
+    data.extend((1u32 << 16).to_le_bytes());
+
+    assert_eq!(detect_assembly_version(&data), Some("2.4.1.0".to_string()));
+
+    assert_eq!(detect_assembly_version(b"not a dll"), None);
+}
+
+#[test]
+fn deep_link_parsing() {
+    assert_eq!(parse_deep_link("neosmod://install/com.author.mod"), Some(DeepLink::Install("com.author.mod".to_string())));
+    assert_eq!(parse_deep_link("neosmod://install/com.author.mod/\n"), Some(DeepLink::Install("com.author.mod".to_string())));
+
+    assert_eq!(parse_deep_link("neosmod://install/"), None);
+    assert_eq!(parse_deep_link("neosmod://install/com.author.mod/extra"), None);
+    assert_eq!(parse_deep_link("neosmod://uninstall/com.author.mod"), None);
+    assert_eq!(parse_deep_link("neosmod://show/com.author.mod"), Some(DeepLink::Show("com.author.mod".to_string())));
+    assert_eq!(parse_deep_link("neosmod://show/../evil"), None);
+    assert_eq!(parse_deep_link("neosmod://install/../../evil"), None);
+    assert_eq!(parse_deep_link("https://install/com.author.mod"), None);
+
+    let arguments = vec!["--verbose".to_string(), "neosmod://install/com.author.mod".to_string()];
+    assert_eq!(deep_links_in(&arguments), vec![DeepLink::Install("com.author.mod".to_string())]);
+}
+
+#[test]
+fn available_updates_test() {
+    let version = |x: &str| Version::from_str(x).unwrap();
+
+    let mods: ManifestMods = HashMap::from([
+        (format!("mod.outdated"), test_mod("Outdated", Category::Audio, vec![
+            (version("1.0.0"), test_version(&[], vec![])),
+            (version("1.1.0"), test_version(&[], vec![])),
+            (version("2.0.0-beta"), test_version(&[], vec![])),
+        ])),
+        (format!("mod.latest"), test_mod("Latest", Category::Audio, vec![
+            (version("1.0.0"), test_version(&[], vec![])),
+        ])),
+        (format!("mod.beta"), test_mod("Beta", Category::Audio, vec![
+            (version("1.0.0-beta"), test_version(&[], vec![])),
+            (version("1.0.0-rc"), test_version(&[], vec![])),
+        ])),
+    ]);
+
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("mod.outdated"), HashMap::from([(version("1.0.0"), ModFile::default())])),
+        (format!("mod.latest"), HashMap::from([(version("1.0.0"), ModFile::default())])),
+        (format!("mod.beta"), HashMap::from([(version("1.0.0-beta"), ModFile::default())])),
+        (format!("unknown.dll"), HashMap::from([(Version::zero(), ModFile::default())])),
+    ]));
+
+    // Prereleases are only offered to mods that are on a prerelease already
+    assert_eq!(available_updates(&mod_map, &mods, |_| UpdateChannel::Stable), vec![
+        ((format!("mod.beta"), version("1.0.0-beta")), version("1.0.0-rc")),
+        ((format!("mod.outdated"), version("1.0.0")), version("1.1.0")),
+    ]);
+
+    // Mods following prereleases get offered them too
+    let channel_for = |x: &str| if x == "mod.outdated" { UpdateChannel::Prerelease } else { UpdateChannel::Stable };
+
+    assert_eq!(available_updates(&mod_map, &mods, channel_for), vec![
+        ((format!("mod.beta"), version("1.0.0-beta")), version("1.0.0-rc")),
+        ((format!("mod.outdated"), version("1.0.0")), version("2.0.0-beta")),
+    ]);
+}
+
+#[tokio::test]
+async fn stray_mods_outside_scan_locations() {
+    let root = std::env::temp_dir().join("nmo_stray_test");
+    std::fs::remove_dir_all(&root).ok();
+    std::fs::create_dir_all(root.join("nml_mods")).unwrap();
+    std::fs::write(root.join("nml_mods").join("Scanned.dll"), b"scanned").unwrap();
+    std::fs::write(root.join("Stray.dll"), b"stray").unwrap();
+    std::fs::write(root.join("Unknown.dll"), b"unknown").unwrap();
+
+    let global_mods = GlobalModList::from_list(HashMap::from([
+        (format!("test.scanned"), test_mod("Scanned", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![test_artifact("Scanned.dll", &sha256_bytes(b"scanned"))]))
+        ])),
+        (format!("test.stray"), test_mod("Stray", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![test_artifact("Stray.dll", &sha256_bytes(b"stray"))]))
+        ])),
+    ]));
+
+    let config = Config::new(root.join("Neos.exe"));
+    let install = ActualInstall::new_empty(&root, global_mods);
+
+    let strays = install.find_stray_mods(&config).await.unwrap();
+
+    assert_eq!(strays.len(), 1);
+    assert_eq!(strays[0].mod_id, "test.stray");
+    assert_eq!(strays[0].file_path, root.join("Stray.dll"));
+    assert_eq!(strays[0].expected_location, PathBuf::from("/nml_mods"));
+
+    install.move_stray_mods(&strays).await.unwrap();
+
+    assert!(root.join("nml_mods").join("Stray.dll").exists());
+    assert!(install.find_stray_mods(&config).await.unwrap().is_empty());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn disabled_path_round_trip() {
+    let path = PathBuf::from("/nml_mods/Mod.dll");
+
+    assert!(!is_disabled_path(&path));
+    assert_eq!(disabled_path(&path), PathBuf::from("/nml_mods/Mod.dll.disabled"));
+    assert!(is_disabled_path(&disabled_path(&path)));
+    assert_eq!(enabled_path(&disabled_path(&path)), path);
+
+    assert!(is_disabled_path(Path::new("/nml_mods/Mod.DLL.Disabled")));
+    assert_eq!(enabled_path(Path::new("/nml_mods/Mod.DLL.Disabled")), PathBuf::from("/nml_mods/Mod.DLL"));
+    assert_eq!(enabled_path(Path::new("/nml_mods/Mod.disabled")), PathBuf::from("/nml_mods/Mod"));
+    assert_eq!(enabled_path(Path::new("/nml_mods/Mod.dll")), PathBuf::from("/nml_mods/Mod.dll"));
+
+    // Artifacts that aren't DLLs keep their own extension
+    let config = PathBuf::from("/nml_config/Config.json");
+    assert_eq!(enabled_path(&disabled_path(&config)), config);
+    assert!(!is_disabled_path(Path::new("/nml_mods/disabled.dll")));
+}
+
+#[tokio::test]
+async fn disabled_mods_rescan_and_enable() {
+    let root = std::env::temp_dir().join("nmo_disabled_test");
+    std::fs::remove_dir_all(&root).ok();
+    std::fs::create_dir_all(root.join("nml_mods")).unwrap();
+    std::fs::write(root.join("nml_mods").join("Enabled.dll"), b"enabled").unwrap();
+    std::fs::write(root.join("nml_mods").join("Disabled.dll.disabled"), b"disabled").unwrap();
+
+    let global_mods = GlobalModList::from_list(HashMap::from([
+        (format!("test.enabled"), test_mod("Enabled", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![test_artifact("Enabled.dll", &sha256_bytes(b"enabled"))]))
+        ])),
+        (format!("test.disabled"), test_mod("Disabled", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![test_artifact("Disabled.dll", &sha256_bytes(b"disabled"))]))
+        ])),
+    ]));
+
+    let config = Config::new(root.join("Neos.exe"));
+    let mut install = ActualInstall::new_empty(&root, global_mods);
+
+    let mut progress = vec![];
+    install.rescan_mods(Arc::new(config.clone()), |scanned, total| progress.push((scanned, total))).await.unwrap();
+    assert_eq!(progress, vec![(0, 2), (1, 2), (2, 2)]);
+
+    // Disabled files are still recognized by their hash
+    let disabled = &install.mod_map()["test.disabled"][&Version::from_major(1)];
+    assert!(disabled.files[0].disabled);
+    assert_eq!(disabled.files[0].file_hash, sha256_bytes(b"disabled"));
+    assert!(install.mod_map()["test.enabled"][&Version::from_major(1)].is_enabled());
+
+    install.perform_operations(&[
+        ModInstallOperations::EnableMod((format!("test.disabled"), Version::from_major(1))),
+        ModInstallOperations::DisableMod((format!("test.enabled"), Version::from_major(1)))
+    ]).await.unwrap();
+
+    assert!(root.join("nml_mods").join("Disabled.dll").exists());
+    assert!(root.join("nml_mods").join("Enabled.dll.disabled").exists());
+
+    // State on disk matches what the install thinks after rescanning
+    install.rescan_mods(Arc::new(config), |_, _| {}).await.unwrap();
+    assert!(install.mod_map()["test.disabled"][&Version::from_major(1)].is_enabled());
+    assert!(!install.mod_map()["test.enabled"][&Version::from_major(1)].is_enabled());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn dependency_status_test() {
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.lib"), HashMap::from([
+            (Version::from_minor(1, 0), ModFile::default()),
+            (Version::from_minor(1, 2), ModFile::default())
+        ]))
+    ]));
+
+    let req = |x: &str| VersionReq::from_str(x).unwrap();
+
+    assert_eq!(dependency_status("test.mod.lib", &req("^1.1"), &mod_map), DependencyStatus::Satisfied(Version::from_minor(1, 2)));
+    assert_eq!(dependency_status("test.mod.lib", &req("1.0"), &mod_map), DependencyStatus::Satisfied(Version::from_minor(1, 0)));
+    assert_eq!(dependency_status("test.mod.lib", &req("^2"), &mod_map), DependencyStatus::WrongVersion(Version::from_minor(1, 2)));
+    assert_eq!(dependency_status("test.mod.missing", &req("*"), &mod_map), DependencyStatus::Missing);
+}
+
+#[test]
+fn compare_versions_test() {
+    let mut old = test_version(&[("a", "1.0.0"), ("b", "1.0.0")], vec![test_artifact("mod.dll", "AA"), test_artifact("old.dll", "BB")]);
+    old.neos_version_compatibility = Some(VersionReq::from_str(">=2022.1.28").unwrap());
+
+    let new = test_version(&[("a", "2.0.0"), ("c", "1.0.0")], vec![test_artifact("mod.dll", "aa"), test_artifact("new.dll", "CC")]);
+
+    let diff = compare_versions(&old, &new);
+
+    assert_eq!(diff.dependencies, vec![
+        ("a".to_string(), Change::Changed(VersionReq::from_str("1.0.0").unwrap(), VersionReq::from_str("2.0.0").unwrap())),
+        ("b".to_string(), Change::Removed(VersionReq::from_str("1.0.0").unwrap())),
+        ("c".to_string(), Change::Added(VersionReq::from_str("1.0.0").unwrap())),
+    ]);
+
+    // Same hash in different case is the same file
+    assert_eq!(diff.artifacts, vec![
+        ("new.dll".to_string(), Change::Added("cc".to_string())),
+        ("old.dll".to_string(), Change::Removed("bb".to_string())),
+    ]);
+
+    assert_eq!(diff.neos_version_compatibility, Some(Change::Removed(VersionReq::from_str(">=2022.1.28").unwrap())));
+    assert!(diff.conflicts.is_empty());
+    assert!(compare_versions(&new, &new).is_empty());
+}
+
+#[test]
+fn readme_cache_expiry() {
+    let mut cache = ReadmeCache::default();
+    cache.insert("a".to_string(), "# Readme".to_string(), 1000);
+
+    assert_eq!(cache.fresh("a", 1000 + README_TTL_SECONDS - 1), Some("# Readme"));
+    assert_eq!(cache.fresh("a", 1000 + README_TTL_SECONDS), None);
+    assert_eq!(cache.any("a"), Some("# Readme"));
+    assert_eq!(cache.fresh("b", 1000), None);
+}
+
+#[test]
+fn known_versions_feed() {
+    let v = |x: &str| Version::from_str(x).unwrap();
+    let mod_with = |versions: &[&str]| test_mod("Mod", Category::Misc, versions.iter().map(|x| (v(x), test_version(&[], vec![]))).collect());
+
+    let mut known = KnownVersions::default();
+
+    // Everything is from before tracking started on the first run
+    assert!(known.record(&HashMap::from([("a".to_string(), mod_with(&["1.0.0"]))]), 100));
+    assert!(known.recent_releases(10).is_empty());
+    assert!(!known.record(&HashMap::from([("a".to_string(), mod_with(&["1.0.0"]))]), 200));
+
+    let mod_list = HashMap::from([
+        ("a".to_string(), mod_with(&["1.0.0", "1.1.0"])),
+        ("b".to_string(), mod_with(&["0.1.0", "0.2.0"]))
+    ]);
+
+    assert!(known.record(&mod_list, 300));
+
+    let feed: Vec<(String, Version)> = known.recent_releases(10).into_iter()
+        .map(|x| (x.mod_id, x.version))
+        .collect();
+
+    // Only the newest version of a mod that's new to the manifest
+    assert_eq!(feed, vec![("a".to_string(), v("1.1.0")), ("b".to_string(), v("0.2.0"))]);
+    assert_eq!(known.recent_releases(1).len(), 1);
+}
+
+#[test]
+fn mod_entry_geometry() {
+    let style = ModEntryStyle::from_text_size(15.0);
+    let rect = |min: (f32, f32), max: (f32, f32)| Rect::from_min_max(pos2(min.0, min.1), pos2(max.0, max.1));
+
+    let collapsed = ModEntryGeometry::compute(&style, Pos2::ZERO, 500.0, 0.0, false);
+
+    assert_eq!(collapsed.element, rect((0.0, 0.0), (500.0, 60.0)));
+    assert_eq!(collapsed.checkbox, rect((425.0, 15.0), (455.0, 45.0)));
+    assert_eq!(collapsed.arrow, pos2(475.0, 30.0));
+    assert_eq!(collapsed.text_container, rect((0.0, 0.0), (410.0, 60.0)));
+
+    let expanded_height = style.expanded_height(None);
+    assert_eq!(expanded_height, 40.0);
+
+    let expanded = ModEntryGeometry::compute(&style, pos2(10.0, 100.0), 500.0, expanded_height, true);
+
+    assert_eq!(expanded.element, rect((10.0, 100.0), (510.0, 160.0)));
+    assert_eq!(expanded.background, rect((10.0, 100.0), (510.0, 190.0)));
+    assert_eq!(expanded.arrow, pos2(485.0, 131.0));
+    assert_eq!(expanded.more_info_button, rect((407.5, 165.0), (505.0, 185.0)));
+    assert_eq!(expanded.uninstall_button, rect((305.0, 165.0), (402.5, 185.0)));
+    assert_eq!(expanded.update_button, rect((202.5, 165.0), (300.0, 185.0)));
+    assert_eq!(expanded.description_pos(&style, 30.0), pos2(20.0, 127.0));
+
+    // Everything grows along with text
+    let large = ModEntryGeometry::compute(&ModEntryStyle::from_text_size(30.0), Pos2::ZERO, 500.0, 0.0, false);
+    assert_eq!(large.element.height(), 120.0);
+    assert_eq!(large.checkbox.size(), collapsed.checkbox.size() * 2.0);
+}
+
+#[test]
+fn mod_map_queries() {
+    let version = |x: &str| Version::from_str(x).unwrap();
+    let artifact = |path: &str, disabled: bool| ModFileArtifact {
+        file_path: PathBuf::from(path),
+        file_hash: "hash".to_string(),
+        disabled,
+        size: 0,
+        modified: 0,
+        assembly_version: None,
+    };
+
+    let mut mod_map = ModMap::from(HashMap::from([
+        (format!("mod.a"), HashMap::from([
+            (version("1.0.0"), ModFile { files: vec![artifact("a1.dll", false)] }),
+            (version("2.0.0"), ModFile { files: vec![artifact("a2.dll", false), artifact("a2.txt", true)] }),
+        ])),
+        (format!("mod.b"), HashMap::from([
+            (version("0.1.0"), ModFile { files: vec![artifact("b.dll", true)] }),
+        ])),
+    ]));
+
+    assert_eq!(mod_map.installed_version("mod.a"), Some(&version("2.0.0")));
+    assert_eq!(mod_map.installed_versions("mod.a"), vec![&version("1.0.0"), &version("2.0.0")]);
+    assert_eq!(mod_map.installed_version("mod.missing"), None);
+    assert!(mod_map.is_enabled("mod.a", &version("1.0.0")));
+    assert!(!mod_map.is_enabled("mod.a", &version("2.0.0")));
+    assert!(!mod_map.is_enabled("mod.missing", &version("1.0.0")));
+    assert_eq!(mod_map.total_files(), 4);
+    assert_eq!(mod_map.version_count(), 3);
+    assert_eq!(mod_map.id_versions(), vec![
+        (format!("mod.a"), version("1.0.0")),
+        (format!("mod.a"), version("2.0.0")),
+        (format!("mod.b"), version("0.1.0")),
+    ]);
+
+    let old = mod_map.clone();
+
+    // Removing the last version takes the mod out too
+    assert!(mod_map.remove("mod.b", &version("0.1.0")).is_some());
+    assert!(!mod_map.contains_key("mod.b"));
+    assert!(mod_map.remove("mod.b", &version("0.1.0")).is_none());
+
+    mod_map.insert("mod.c", &version("1.0.0"), ModFile::default());
+    mod_map.file_mut("mod.a", &version("1.0.0")).unwrap().files[0].disabled = true;
+
+    assert_eq!(old.diff(&mod_map), ModMapDiff {
+        added: vec![(format!("mod.c"), version("1.0.0"))],
+        removed: vec![(format!("mod.b"), version("0.1.0"))],
+        changed: vec![(format!("mod.a"), version("1.0.0"))],
+    });
+    assert!(mod_map.diff(&mod_map).is_empty());
+}
+
+/// Answers with canned bodies by URL, anything else gets 404
+#[derive(Default)]
+struct MockTransport {
+    responses: HashMap<String, Vec<u8>>,
+    requests: std::sync::Mutex<Vec<String>>,
+    /// Offsets that ranged requests started at
+    offsets: std::sync::Mutex<Vec<u64>>
+}
+
+impl MockTransport {
+    fn with(mut self, url: &str, body: &[u8]) -> Self {
+        self.responses.insert(url.to_string(), body.to_vec());
+        self
+    }
+
+    fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn offsets(&self) -> Vec<u64> {
+        self.offsets.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for MockTransport {
+    async fn get(&self, url: &str, _: &mut Option<TokenBucket>) -> Result<Vec<u8>, TransportError> {
+        self.requests.lock().unwrap().push(url.to_string());
+
+        self.responses.get(url).cloned().ok_or_else(|| TransportError::Status {
+            url: url.to_string(),
+            status: 404,
+        })
+    }
+
+    async fn get_from(&self, url: &str, offset: u64, sink: &mut dyn BodySink, bucket: &mut Option<TokenBucket>) -> Result<(), TransportError> {
+        self.offsets.lock().unwrap().push(offset);
+        let data = self.get(url, bucket).await?;
+
+        match data.get(offset as usize..) {
+            Some(rest) => sink.write(rest).await?,
+            None => {
+                sink.restart().await?;
+                sink.write(&data).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Empty Neos folder with an install that downloads through the mock
+fn mock_install(name: &str, mods: ManifestMods, transport: &Arc<MockTransport>) -> (PathBuf, ActualInstall) {
+    let root = std::env::temp_dir().join(name);
+    std::fs::remove_dir_all(&root).ok();
+    std::fs::create_dir_all(&root).unwrap();
+
+    let mut install = ActualInstall::new_empty(&root, GlobalModList::from_list(mods));
+    install.set_transport(transport.clone());
+
+    (root, install)
+}
+
+#[tokio::test]
+async fn install_through_transport() {
+    let mods = HashMap::from([
+        (format!("test.mod"), test_mod("Mod", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![
+                test_artifact("Mod.dll", &sha256_bytes(b"mod")),
+                test_artifact("Extra.dll", &sha256_bytes(b"extra")),
+            ]))
+        ])),
+    ]);
+
+    let transport = Arc::new(MockTransport::default()
+        .with("test.com/Mod.dll", b"mod")
+        .with("test.com/Extra.dll", b"extra"));
+
+    let (root, mut install) = mock_install("nmo_transport_install_test", mods, &transport);
+    let id = (format!("test.mod"), Version::from_major(1));
+
+    install.perform_operations(&[ModInstallOperations::InstallMod(id.clone())]).await.unwrap();
+
+    assert_eq!(transport.requests(), vec!["test.com/Mod.dll", "test.com/Extra.dll"]);
+    assert_eq!(std::fs::read(root.join("nml_mods").join("Mod.dll")).unwrap(), b"mod");
+    assert_eq!(std::fs::read(root.join("nml_mods").join("Extra.dll")).unwrap(), b"extra");
+    assert_eq!(install.mod_map().file(&id.0, &id.1).unwrap().files.len(), 2);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn install_hash_mismatch_writes_nothing() {
+    let mods = HashMap::from([
+        (format!("test.mod"), test_mod("Mod", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![
+                test_artifact("Mod.dll", &sha256_bytes(b"mod")),
+                test_artifact("Extra.dll", &sha256_bytes(b"extra")),
+            ]))
+        ])),
+    ]);
+
+    let transport = Arc::new(MockTransport::default()
+        .with("test.com/Mod.dll", b"mod")
+        .with("test.com/Extra.dll", b"tampered"));
+
+    let (root, mut install) = mock_install("nmo_transport_mismatch_test", mods, &transport);
+
+    let result = install.perform_operations(&[ModInstallOperations::InstallMod((format!("test.mod"), Version::from_major(1)))]).await;
+
+    assert!(matches!(result, Err(InstallError::HashMismatch { url, .. }) if url == "test.com/Extra.dll"));
+    assert!(!root.join("nml_mods").join("Mod.dll").exists());
+    assert!(install.mod_map().is_empty());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn install_partial_failures_roll_back() {
+    let mods = HashMap::from([
+        (format!("test.missing"), test_mod("Missing", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![
+                test_artifact("Present.dll", &sha256_bytes(b"present")),
+                test_artifact("Missing.dll", &sha256_bytes(b"missing")),
+            ]))
+        ])),
+        (format!("test.blocked"), test_mod("Blocked", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![
+                test_artifact("First.dll", &sha256_bytes(b"first")),
+                Artifact {
+                    install_location: Some(PathBuf::from("/blocked")),
+                    ..test_artifact("Second.dll", &sha256_bytes(b"second"))
+                },
+            ]))
+        ])),
+    ]);
+
+    let transport = Arc::new(MockTransport::default()
+        .with("test.com/Present.dll", b"present")
+        .with("test.com/First.dll", b"first")
+        .with("test.com/Second.dll", b"second"));
+
+    let (root, mut install) = mock_install("nmo_transport_partial_test", mods, &transport);
+
+    // One artifact failing to download stops the install before anything is written
+    let result = install.perform_operations(&[ModInstallOperations::InstallMod((format!("test.missing"), Version::from_major(1)))]).await;
+
+    assert!(matches!(result, Err(InstallError::DownloadError(TransportError::Status { status: 404, .. }))));
+    assert!(!root.join("nml_mods").join("Present.dll").exists());
+
+    // File in place of a folder makes the second write fail, first file is removed again
+    std::fs::write(root.join("blocked"), b"").unwrap();
+
+    let result = install.perform_operations(&[ModInstallOperations::InstallMod((format!("test.blocked"), Version::from_major(1)))]).await;
+
+    assert!(matches!(result, Err(InstallError::FileError(_))));
+    assert!(!root.join("nml_mods").join("First.dll").exists());
+    assert!(install.mod_map().is_empty());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn aggregate_manifests_through_transport() {
+    let transport = MockTransport::default()
+        .with("https://one.test/manifest.json", br#"{"schemaVersion":"1.0.0","mods":{"test.one":{"name":"One","description":"","authors":{},"category":"Misc","versions":{}}}}"#)
+        .with("https://two.test/manifest.json", b"not json");
+
+    let sources = vec![
+        (format!("https://one.test/manifest.json"), None),
+        (format!("https://two.test/manifest.json"), None),
+        (format!("https://three.test/manifest.json"), None),
+    ];
+
+    let (mods, mod_sources, errors) = aggregate_manifests(&transport, &[], &sources).await;
+
+    assert_eq!(mods.len(), 1);
+    assert_eq!(mod_sources.get("test.one").map(|x| x.as_str()), Some("https://one.test/manifest.json"));
+
+    let mut errors: Vec<(String, bool)> = errors.into_iter()
+        .map(|(url, e)| (url, matches!(e, ManifestError::NetworkError(TransportError::Status { status: 404, .. }))))
+        .collect();
+    errors.sort();
+
+    assert_eq!(errors, vec![
+        (format!("https://three.test/manifest.json"), true),
+        (format!("https://two.test/manifest.json"), false),
+    ]);
+}
+
+/// Small seeded generator for property tests, same cases every run so failures can be reproduced
+struct CaseGenerator(u64);
+
+impl CaseGenerator {
+    fn next(&mut self) -> u64 {
+        // xorshift64
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    /// Version string with 1 to 4 parts and sometimes a prerelease suffix
+    fn version_string(&mut self) -> String {
+        let parts = 1 + self.below(4);
+        let mut version = (0..parts)
+            .map(|_| self.below(12).to_string())
+            .collect::<Vec<String>>()
+            .join(".");
+
+        if self.below(4) == 0 {
+            let suffix = *self.pick(&["-alpha", "-beta2", "-beta10", "-rc.1", "-rc1", ".beta"]);
+            version.push_str(suffix);
+        }
+
+        version
+    }
+}
+
+const PROPERTY_CASES: usize = 2000;
+
+#[test]
+fn version_parse_display_round_trip() {
+    let mut generator = CaseGenerator(0x5EED_1234);
+
+    for _ in 0..PROPERTY_CASES {
+        let text = generator.version_string();
+        let version = Version::from_str(&text).unwrap();
+
+        assert_eq!(version.to_string(), text);
+
+        let reparsed = Version::from_str(&version.to_string()).unwrap();
+        assert_eq!(reparsed, version, "{}", text);
+        assert_eq!(reparsed.to_string(), version.to_string());
+    }
+}
+
+#[test]
+fn version_ordering_is_total() {
+    let mut generator = CaseGenerator(0xC0FFEE);
+
+    for _ in 0..PROPERTY_CASES {
+        let [a, b, c] = [(); 3].map(|_| Version::from_str(&generator.version_string()).unwrap());
+
+        assert_eq!(a.cmp(&b), b.cmp(&a).reverse(), "{} and {}", a, b);
+        assert_eq!(a == b, a.cmp(&b) == Ordering::Equal, "{} and {}", a, b);
+
+        if a <= b && b <= c {
+            assert!(a <= c, "{} <= {} <= {}", a, b, c);
+        }
+
+        // Missing parts are zeros
+        if !a.is_prerelease() && !a.has_revision() {
+            assert_eq!(Version::from_str(&format!("{}.0", a)).unwrap(), a);
+        }
+    }
+}
+
+#[test]
+fn version_req_invariants() {
+    let mut generator = CaseGenerator(0xBADC0DE);
+
+    for _ in 0..PROPERTY_CASES {
+        let base = Version::from_str(&generator.version_string()).unwrap();
+        let other = Version::from_str(&generator.version_string()).unwrap();
+
+        assert!(VersionReq::exact(&base).matches(&base), "{}", base);
+        assert_eq!(VersionReq::at_least(&base).matches(&other), other >= base, "{} against {}", other, base);
+
+        let op = *generator.pick(&["=", ">", ">=", "<", "<=", "~", "^", ""]);
+        let requirement = VersionReq::from_str(&format!("{}{}", op, base)).unwrap();
+        let reparsed = VersionReq::from_str(&requirement.to_string()).unwrap();
+
+        assert_eq!(requirement.matches(&other), reparsed.matches(&other), "{} reparsed as {} against {}", requirement, reparsed, other);
+
+        // With all four parts given, bounds are plain comparisons
+        if base.has_revision() && !base.is_prerelease() && !other.is_prerelease() {
+            let matches = |op: &str| VersionReq::from_str(&format!("{}{}", op, base)).unwrap().matches(&other);
+
+            assert_eq!(matches(">="), other >= base, ">={} against {}", base, other);
+            assert_eq!(matches("<"), other < base, "<{} against {}", base, other);
+            assert_eq!(matches(">"), other > base, ">{} against {}", base, other);
+            assert_eq!(matches("<="), other <= base, "<={} against {}", base, other);
+        }
+
+        // Wildcard after the major part matches the whole major version
+        if !other.is_prerelease() {
+            let wildcard = VersionReq::from_str(&format!("{}.*", base.major())).unwrap();
+            assert_eq!(wildcard.matches(&other), other.major() == base.major(), "{}.* against {}", base.major(), other);
+        }
+    }
+}
+
+#[test]
+fn version_rejects_malformed() {
+    for version in ["", ".1", "1..2", "1.", "1.2.", "1.2.3.4.5", "1.99999", "1.*.2", "x"] {
+        assert!(Version::from_str(version).is_err(), "{} should be rejected", version);
+    }
+
+    let wildcard = Version::from_str("1.*.*").unwrap();
+    assert!(!wildcard.has_minor() && !wildcard.has_patch());
+
+    let dotted = Version::from_str("1.0.0.beta").unwrap();
+    assert_eq!(dotted, Version::from_str("1.0.0-beta").unwrap());
+    assert_eq!(dotted.to_string(), "1.0.0.beta");
+}
+
+#[tokio::test]
+async fn plan_simulation_is_cumulative() {
+    let manifest_mods: Arc<ManifestMods> = Arc::new(HashMap::from([
+        (format!("test.app"), test_mod("App", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[("test.lib", "*")], vec![test_artifact("App.dll", "app")]))
+        ])),
+        (format!("test.lib"), test_mod("Lib", Category::Libraries, vec![
+            (Version::from_major(1), test_version(&[], vec![test_artifact("Lib.dll", "lib")]))
+        ])),
+    ]));
+
+    let steps = vec![
+        PlanStep::Install(format!("test.app"), VersionReq::from_str("*").unwrap()),
+        PlanStep::Uninstall((format!("test.lib"), Version::from_major(1))),
+        PlanStep::Install(format!("test.missing"), VersionReq::from_str("*").unwrap()),
+        PlanStep::Uninstall((format!("test.app"), Version::from_major(2))),
+    ];
+
+    let install = VirtualInstall::new(ModMap::default(), manifest_mods.clone());
+    let outcome = simulate_plan(install, &steps, &manifest_mods, |_| UpdateChannel::Stable).await;
+
+    // Uninstall only works because the first step installed the dependency
+    assert_eq!(outcome.operations.len(), 3);
+    assert_eq!(outcome.failed_steps.iter().map(|(index, _)| *index).collect::<Vec<usize>>(), vec![2, 3]);
+    assert_eq!(outcome.mod_map.id_versions(), vec![(format!("test.app"), Version::from_major(1))]);
+    assert!(matches!(outcome.conflicts.as_slice(), [ModConflict::DependencyMissing { this, .. }] if this.0 == "test.app"));
+    assert_eq!(outcome.steps, steps);
+}
+
+#[tokio::test]
+async fn install_reuses_verified_staged_downloads() {
+    let mods = HashMap::from([
+        (format!("test.mod"), test_mod("Mod", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![
+                test_artifact("Mod.dll", &sha256_bytes(b"mod")),
+                test_artifact("Extra.dll", &sha256_bytes(b"extra")),
+            ]))
+        ])),
+    ]);
+
+    let transport = Arc::new(MockTransport::default()
+        .with("test.com/Extra.dll", b"extra"));
+
+    let (root, mut install) = mock_install("nmo_staged_install_test", mods, &transport);
+    let staging = root.join("staging");
+    install.set_staging_folder(staging.clone());
+
+    // One download survived the last run, other one was cut off halfway
+    std::fs::create_dir_all(&staging).unwrap();
+    std::fs::write(staging.join(sha256_bytes(b"mod")), b"mod").unwrap();
+    std::fs::write(staging.join(sha256_bytes(b"extra")), b"ext").unwrap();
+
+    let id = (format!("test.mod"), Version::from_major(1));
+    install.perform_operations(&[ModInstallOperations::InstallMod(id.clone())]).await.unwrap();
+
+    assert_eq!(transport.requests(), vec!["test.com/Extra.dll"]);
+    assert_eq!(std::fs::read(root.join("nml_mods").join("Mod.dll")).unwrap(), b"mod");
+    assert_eq!(std::fs::read(root.join("nml_mods").join("Extra.dll")).unwrap(), b"extra");
+    assert_eq!(std::fs::read_dir(&staging).unwrap().count(), 0);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn install_resumes_partial_downloads() {
+    let body = b"large artifact body".to_vec();
+    let mods = HashMap::from([
+        (format!("test.mod"), test_mod("Mod", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![
+                test_artifact("Mod.dll", &sha256_bytes(&body)),
+            ]))
+        ])),
+    ]);
+
+    let transport = Arc::new(MockTransport::default()
+        .with("test.com/Mod.dll", &body));
+
+    let (root, mut install) = mock_install("nmo_partial_install_test", mods, &transport);
+    let staging = root.join("staging");
+    install.set_staging_folder(staging.clone());
+
+    // Download was cut off after the first few bytes
+    std::fs::create_dir_all(&staging).unwrap();
+    std::fs::write(staging.join(format!("{}.part", sha256_bytes(&body))), &body[..5]).unwrap();
+
+    let id = (format!("test.mod"), Version::from_major(1));
+    install.perform_operations(&[ModInstallOperations::InstallMod(id.clone())]).await.unwrap();
+
+    assert_eq!(transport.offsets(), vec![5]);
+    assert_eq!(std::fs::read(root.join("nml_mods").join("Mod.dll")).unwrap(), body);
+    assert_eq!(std::fs::read_dir(&staging).unwrap().count(), 0);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn artifact_cache_serves_reinstalls_and_prunes_oldest() {
+    let mods = HashMap::from([
+        (format!("test.mod"), test_mod("Mod", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![
+                test_artifact("Mod.dll", &sha256_bytes(b"mod")),
+            ]))
+        ])),
+    ]);
+
+    let transport = Arc::new(MockTransport::default()
+        .with("test.com/Mod.dll", b"mod"));
+
+    let (root, mut install) = mock_install("nmo_artifact_cache_test", mods, &transport);
+    let cache = ArtifactCache::new(root.join("cache"));
+    install.set_artifact_cache(Some(cache.clone()));
+
+    let id = (format!("test.mod"), Version::from_major(1));
+    install.perform_operations(&[ModInstallOperations::InstallMod(id.clone())]).await.unwrap();
+    install.perform_operations(&[ModInstallOperations::UninstallMod(id.clone())]).await.unwrap();
+    install.perform_operations(&[ModInstallOperations::InstallMod(id.clone())]).await.unwrap();
+
+    assert_eq!(transport.requests(), vec!["test.com/Mod.dll"]);
+    assert_eq!(std::fs::read(root.join("nml_mods").join("Mod.dll")).unwrap(), b"mod");
+
+    // Corrupted entries are dropped instead of being handed out
+    cache.insert(&sha256_bytes(b"other"), b"corrupted").await.unwrap();
+    assert_eq!(cache.get(&sha256_bytes(b"other")).await, None);
+
+    let older = sha256_bytes(b"older");
+    cache.insert(&older, b"older").await.unwrap();
+    std::fs::File::options().write(true).open(root.join("cache").join(&older)).unwrap()
+        .set_modified(std::time::SystemTime::UNIX_EPOCH).unwrap();
+
+    assert_eq!(cache.size().await.unwrap(), 8);
+    assert_eq!(cache.prune(3).await.unwrap(), 5);
+    assert_eq!(cache.get(&older).await, None);
+    assert_eq!(cache.get(&sha256_bytes(b"mod")).await, Some(b"mod".to_vec()));
+    assert_eq!(cache.clear().await.unwrap(), 3);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn garbage_collection_removes_only_expired_items() {
+    let root = std::env::temp_dir().join("nmo_garbage_collection_test");
+    std::fs::remove_dir_all(&root).ok();
+    std::fs::create_dir_all(root.join("old_folder")).unwrap();
+
+    std::fs::write(root.join("old_folder").join("file"), b"12345").unwrap();
+    std::fs::write(root.join("old_file"), b"123").unwrap();
+    std::fs::write(root.join("new_file"), b"12").unwrap();
+
+    let now = unix_timestamp();
+    let old = std::time::SystemTime::now() - std::time::Duration::from_secs(10 * 24 * 60 * 60);
+
+    for path in [root.join("old_folder"), root.join("old_file")] {
+        std::fs::File::open(&path).unwrap().set_modified(old).unwrap();
+    }
+
+    let report = inspect_folder(MaintenanceFolder::Staging, &root).await.unwrap();
+    assert_eq!(report.items, 3);
+    assert_eq!(report.size, 10);
+    assert!(report.oldest.unwrap() <= now - 9 * 24 * 60 * 60);
+
+    assert_eq!(collect_garbage(&root, 30, now).await.unwrap(), (0, 0));
+    assert_eq!(collect_garbage(&root, 7, now).await.unwrap(), (2, 8));
+    assert!(root.join("new_file").exists());
+
+    let report = inspect_folder(MaintenanceFolder::Staging, &root).await.unwrap();
+    assert_eq!((report.items, report.size), (1, 2));
+
+    // Missing folders have nothing to clean up
+    assert_eq!(collect_garbage(&root.join("missing"), 7, now).await.unwrap(), (0, 0));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn sideloaded_files_are_identified_and_written_safely() {
+    let mods = HashMap::from([
+        (format!("test.mod"), test_mod("Mod", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![
+                test_artifact("Mod.dll", &sha256_bytes(b"mod")),
+            ]))
+        ])),
+    ]);
+
+    let transport = Arc::new(MockTransport::default());
+    let (root, install) = mock_install("nmo_sideload_test", mods, &transport);
+
+    assert_eq!(install.identify_artifact(&sha256_bytes(b"mod")), Some((format!("test.mod"), Version::from_major(1))));
+    assert_eq!(install.identify_artifact(&sha256_bytes(b"unknown")), None);
+
+    let path = install.install_file("Local.dll", b"unknown", Path::new("nml_mods")).await.unwrap();
+    assert_eq!(path, root.join("nml_mods").join("Local.dll"));
+    assert_eq!(std::fs::read(&path).unwrap(), b"unknown");
+
+    assert!(matches!(install.install_file("Local.dll", b"again", Path::new("nml_mods")).await, Err(InstallError::FileAlreadyExists)));
+    assert!(matches!(install.install_file("Local.dll", b"unknown", Path::new("../outside")).await, Err(InstallError::UnsafePath(_))));
+    assert!(matches!(install.install_file("../Local.dll", b"unknown", Path::new("nml_mods")).await, Err(InstallError::UnsafePath(_))));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn github_releases_are_listed_with_dll_assets() {
+    assert_eq!(parse_repo_url("https://github.com/owner/Some-Mod"), Some((format!("owner"), format!("Some-Mod"))));
+    assert_eq!(parse_repo_url("github.com/owner/repo.git/"), Some((format!("owner"), format!("repo"))));
+    assert_eq!(parse_repo_url("https://github.com/owner/repo/releases/tag/v1.0.0"), Some((format!("owner"), format!("repo"))));
+    assert_eq!(parse_repo_url("https://github.com/owner"), None);
+    assert_eq!(parse_repo_url("https://github.com/owner/.."), None);
+    assert_eq!(parse_repo_url("https://gitlab.com/owner/repo"), None);
+
+    assert_eq!(version_from_tag("v1.2.3"), Some(Version::from_str("1.2.3").unwrap()));
+    assert_eq!(version_from_tag("2.0"), Some(Version::from_str("2.0").unwrap()));
+    assert_eq!(version_from_tag("release"), None);
+
+    let body = br#"[
+        {"tag_name": "v1.1.0", "name": "Fixes", "prerelease": true, "assets": [
+            {"name": "Mod.dll", "browser_download_url": "https://github.com/owner/repo/releases/download/v1.1.0/Mod.dll", "size": 2048},
+            {"name": "Source.zip", "browser_download_url": "https://github.com/owner/repo/releases/download/v1.1.0/Source.zip", "size": 4096}
+        ]},
+        {"tag_name": "v1.0.0", "name": null, "assets": []}
+    ]"#;
+
+    let transport = MockTransport::default()
+        .with("https://api.github.com/repos/owner/repo/releases", body);
+
+    let releases = fetch_releases(&transport, "owner", "repo").await.unwrap();
+
+    assert_eq!(releases.len(), 2);
+    assert!(releases[0].prerelease);
+    assert_eq!(releases[0].dll_assets().map(|x| x.name.as_str()).collect::<Vec<_>>(), vec!["Mod.dll"]);
+    assert_eq!(releases[1].dll_assets().count(), 0);
+
+    assert!(fetch_releases(&transport, "owner", "missing").await.is_err());
+}
+
+#[test]
+fn local_mod_details_keep_recorded_info_and_drop_when_empty() {
+    let artifact = |hash: &str| ModFileArtifact {
+        file_path: PathBuf::from(format!("{}.dll", hash)),
+        file_hash: hash.to_string(),
+        disabled: false,
+        size: 0,
+        modified: 0,
+        assembly_version: None,
+    };
+    let file = ModFile { files: vec![artifact("AA"), artifact("BB")] };
+
+    let mut local_mods = LocalMods::default();
+    assert_eq!(local_key_of(&local_mods.mods, &file).as_deref(), Some("aa"));
+
+    local_mods.mods.insert("bb".to_string(), LocalModInfo {
+        source: Some("https://github.com/owner/repo".to_string()),
+        ..Default::default()
+    });
+    assert_eq!(local_key_of(&local_mods.mods, &file).as_deref(), Some("bb"));
+
+    local_mods.set_details("BB", LocalModDetails {
+        name: Some("Repo".to_string()),
+        category: Some(Category::Misc),
+        notes: Some("Fixes things".to_string()),
+    });
+
+    let info = &local_mods.mods["bb"];
+    assert_eq!(info.name.as_deref(), Some("Repo"));
+    assert_eq!(info.category, Some(Category::Misc));
+    assert_eq!(info.source.as_deref(), Some("https://github.com/owner/repo"));
+
+    // Files recorded before details existed still load
+    let old: LocalModInfo = serde_json::from_str(r#"{"name":"Old","version":null,"source":null}"#).unwrap();
+    assert_eq!(old.category, None);
+    assert_eq!(old.notes, None);
+
+    local_mods.set_details("aa", LocalModDetails { name: Some("Other".to_string()), ..Default::default() });
+    local_mods.set_details("aa", LocalModDetails::default());
+    assert!(!local_mods.mods.contains_key("aa"));
+    assert!(local_mods.mods.contains_key("bb"));
+}
+
+#[test]
+fn mod_notes_are_trimmed_and_removed_when_blank() {
+    let mut notes = ModNotes::default();
+
+    notes.set("mod.a", "Needs restart after changing settings\n\n");
+    notes.set("mod.b", "Breaks with mod.a");
+    assert_eq!(notes.notes["mod.a"], "Needs restart after changing settings");
+
+    notes.set("mod.b", "  \n ");
+    assert!(!notes.notes.contains_key("mod.b"));
+
+    let loaded: ModNotes = serde_json::from_str(&serde_json::to_string(&notes).unwrap()).unwrap();
+    assert_eq!(loaded.notes, notes.notes);
+}
+
+#[test]
+fn conflicts_have_severity_and_can_be_suppressed() {
+    let version = |x: &str| Version::from_str(x).unwrap();
+    let direct = ModConflict::DirectConflict {
+        this: ("mod.a".to_string(), version("1.0.0")),
+        conflict_with: ("mod.b".to_string(), version("2.0.0")),
+    };
+    let mismatch = ModConflict::DependencyMismatch {
+        this: ("mod.a".to_string(), version("1.0.0")),
+        needs: ("mod.c".to_string(), VersionReq::from_str(">=2.0.0").unwrap()),
+        found_versions: vec![version("1.5.0")],
+    };
+
+    assert_eq!(direct.severity(), ConflictSeverity::Error);
+    assert_eq!(mismatch.severity(), ConflictSeverity::Warning);
+    assert_eq!(ModConflict::FileConflict { this: ("mod.a".to_string(), version("1.0.0")), already_exists: PathBuf::from("a.dll") }.severity(), ConflictSeverity::Error);
+
+    assert!(direct.involves("mod.a") && direct.involves("mod.b"));
+    assert!(!mismatch.involves("mod.c"));
+
+    let mut config = Config::new(PathBuf::from("/neos/Neos.exe"));
+    config.suppressed_conflicts.push(SuppressedConflict {
+        conflict: mismatch.clone(),
+        reason: "Works with older version".to_string(),
+    });
+
+    let config: Config = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+    assert_eq!(config.suppression_of(&mismatch).map(|x| x.reason.as_str()), Some("Works with older version"));
+    assert!(!config.is_conflict_suppressed(&direct));
+
+    // Suppression is for that exact conflict, different versions bring it back
+    let newer_mismatch = ModConflict::DependencyMismatch {
+        this: ("mod.a".to_string(), version("1.0.0")),
+        needs: ("mod.c".to_string(), VersionReq::from_str(">=2.0.0").unwrap()),
+        found_versions: vec![version("1.6.0")],
+    };
+    assert!(!config.is_conflict_suppressed(&newer_mismatch));
+}
+
+/// Library with a few versions and mods that want different ranges of it
+fn merging_manifest() -> Arc<ManifestMods> {
+    Arc::new(HashMap::from([
+        (format!("test.mod.a"), test_mod("Mod A", Category::Audio, vec![
+            (Version::from_major(1), test_version(&[("test.mod.lib", "^1.2")], vec![test_artifact("a.dll", "a1")]))
+        ])),
+        (format!("test.mod.b"), test_mod("Mod B", Category::Audio, vec![
+            (Version::from_major(1), test_version(&[("test.mod.lib", ">=1.4")], vec![test_artifact("b.dll", "b1")]))
+        ])),
+        (format!("test.mod.c"), test_mod("Mod C", Category::Audio, vec![
+            (Version::from_major(1), test_version(&[("test.mod.lib", "^2.0")], vec![test_artifact("c.dll", "c1")]))
+        ])),
+        (format!("test.mod.lib"), test_mod("Test Lib", Category::Libraries, vec![
+            (Version::from_minor(1, 2), test_version(&[], vec![test_artifact("lib.dll", "12")])),
+            (Version::from_minor(1, 4), test_version(&[], vec![test_artifact("lib.dll", "14")])),
+            (Version::from_minor(2, 0), test_version(&[], vec![test_artifact("lib.dll", "20")])),
+        ])),
+    ]))
+}
+
+#[tokio::test]
+async fn resolve_merges_requirements_of_dependents() {
+    let any = VersionReq::from_str("*").unwrap();
+    assert!(VersionReq::from_str("^1.2").unwrap().intersect(&VersionReq::from_str(">=1.4").unwrap()).matches(&Version::from_minor(1, 4)));
+    assert!(!VersionReq::from_str("^1.2").unwrap().intersect(&VersionReq::from_str(">=1.4").unwrap()).matches(&Version::from_minor(2, 0)));
+    assert!(VersionReq::from_str("1 || 3").unwrap().intersect(&any).matches(&Version::from_major(3)));
+
+    let manifest_mods = merging_manifest();
+    let file = |id: &str, version: Version| (version.clone(), ModFile::new(id, &version, &manifest_mods, &Default::default()));
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.a"), HashMap::from([file("test.mod.a", Version::from_major(1))])),
+        (format!("test.mod.lib"), HashMap::from([file("test.mod.lib", Version::from_minor(1, 2))])),
+    ]));
+
+    // B alone would take lib 2.0, A being installed keeps it on 1.x
+    let ResolveResult::Ok(operations) = resolve_install_mod("test.mod.b", &any, &mod_map, &manifest_mods, |_| UpdateChannel::Stable) else {
+        panic!("Failed to resolve test.mod.b");
+    };
+
+    assert!(operations.iter().any(|x| matches!(x, ModInstallOperations::InstallMod((id, version)) if id == "test.mod.lib" && *version == Version::from_minor(1, 4))));
+
+    let mut virt = VirtualInstall::new(mod_map.clone(), manifest_mods.clone());
+    virt.perform_operations(&operations).await.unwrap();
+    assert_eq!(virt.check_for_conflicts(&manifest_mods).len(), 0);
+
+    // Nothing satisfies both A and C
+    let result = resolve_install_mod("test.mod.c", &any, &mod_map, &manifest_mods, |_| UpdateChannel::Stable);
+    let ResolveResult::Contradiction { mod_id, requirements } = result else {
+        panic!("Expected a contradiction");
+    };
+
+    assert_eq!(mod_id, "test.mod.lib");
+    assert_eq!(requirements.len(), 2);
+    assert!(requirements.iter().any(|(by, _)| by.as_deref() == Some("test.mod.a")));
+
+    // Same goes for conflict checking when both are installed anyway
+    let mut both = mod_map.clone();
+    let (version, mod_file) = file("test.mod.c", Version::from_major(1));
+    both.insert("test.mod.c", &version, mod_file);
+
+    let conflicts = VirtualInstall::new(both, manifest_mods.clone()).check_for_conflicts(&manifest_mods);
+    assert!(conflicts.iter().any(|x| matches!(x, ModConflict::IncompatibleRequirements { this, requirements } if this == "test.mod.lib" && requirements.len() == 2)));
+}
+
+#[test]
+fn version_req_simplify() {
+    let req = |x: &str| VersionReq::from_str(x).unwrap();
+    let simplified = |x: VersionReq| match x.simplify() {
+        SimplifiedReq::Satisfiable(x) => Some(x.to_string()),
+        SimplifiedReq::Unsatisfiable => None
+    };
+
+    assert_eq!(simplified(req("^1.2")).as_deref(), Some("^1.2"));
+    assert_eq!(simplified(req("^1.2").intersect(&req(">=1.4"))).as_deref(), Some(">=1.4, <2"));
+    assert_eq!(simplified(req("^1.2").intersect(&req("^2.0"))), None);
+    assert_eq!(simplified(req("1.2 || 1.3")).as_deref(), Some(">=1.2, <1.4"));
+    assert_eq!(simplified(req("* || 1")).as_deref(), Some("*"));
+    assert_eq!(simplified(req("<1.0 || >=2.0, <3.0")).as_deref(), Some("<1.0 || >=2.0, <3.0"));
+
+    // Simplified requirement has to match exactly what the original did
+    let samples = VERSION_SAMPLES.iter()
+        .chain(VERSION_REQ_CORPUS.iter().map(|(_, x, _)| x))
+        .map(|x| Version::from_str(x).unwrap())
+        .collect::<Vec<Version>>();
+
+    let requirements = VERSION_REQ_CORPUS.iter()
+        .map(|(x, _, _)| req(x))
+        .collect::<Vec<VersionReq>>();
+
+    for a in &requirements {
+        for b in &requirements {
+            let combined = a.intersect(b);
+
+            match combined.simplify() {
+                SimplifiedReq::Satisfiable(simple) => {
+                    for version in &samples {
+                        assert_eq!(simple.matches(version), combined.matches(version), "{} simplified to {} against {}", combined, simple, version);
+                    }
+                }
+                SimplifiedReq::Unsatisfiable => {
+                    assert!(!samples.iter().any(|x| combined.matches(x)), "{} is said to be unsatisfiable", combined);
+                }
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn conflict_fixes_clear_the_conflict() {
+    let manifest_mods = merging_manifest();
+    let config = Config::new(PathBuf::from("/neos/Neos.exe"));
+    let file = |id: &str, version: Version| (version.clone(), ModFile::new(id, &version, &manifest_mods, &Default::default()));
+
+    // C needs lib 2.0, but 1.2 is installed
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.c"), HashMap::from([file("test.mod.c", Version::from_major(1))])),
+        (format!("test.mod.lib"), HashMap::from([file("test.mod.lib", Version::from_minor(1, 2))])),
+    ]));
+
+    let install = VirtualInstall::new(mod_map.clone(), manifest_mods.clone());
+    let conflicts = install.check_for_conflicts(&manifest_mods);
+    let mismatch = conflicts.iter().find(|x| matches!(x, ModConflict::DependencyMismatch { .. })).unwrap();
+
+    let fixes = suggest_fixes(&install, mismatch, &manifest_mods, &config).await;
+    let kinds = fixes.iter().map(|x| x.kind.clone()).collect::<Vec<FixKind>>();
+
+    assert_eq!(kinds, vec![
+        FixKind::Update(format!("test.mod.lib"), Version::from_minor(2, 0)),
+        FixKind::Uninstall((format!("test.mod.c"), Version::from_major(1))),
+    ]);
+
+    for fix in &fixes {
+        let mut virt = install.clone();
+        virt.perform_operations(&fix.operations).await.unwrap();
+        assert!(virt.check_for_conflicts(&manifest_mods).is_empty(), "{:?} didn't clear the conflict", fix.kind);
+    }
+
+    // With A installed too, updating lib would just break A instead
+    let mut with_a = mod_map.clone();
+    let (version, mod_file) = file("test.mod.a", Version::from_major(1));
+    with_a.insert("test.mod.a", &version, mod_file);
+
+    let install = VirtualInstall::new(with_a, manifest_mods.clone());
+    let fixes = suggest_fixes(&install, mismatch, &manifest_mods, &config).await;
+
+    assert!(fixes.iter().all(|x| !matches!(x.kind, FixKind::Update(..))));
+    assert!(fixes.iter().any(|x| x.kind == FixKind::Uninstall((format!("test.mod.c"), Version::from_major(1)))));
+}
+
+#[tokio::test]
+async fn lockfile_sync_brings_back_locked_install() {
+    let manifest_mods = merging_manifest();
+    let file = |id: &str, version: Version| (version.clone(), ModFile::new(id, &version, &manifest_mods, &Default::default()));
+
+    let locked_map = ModMap::from(HashMap::from([
+        (format!("test.mod.a"), HashMap::from([file("test.mod.a", Version::from_major(1))])),
+        (format!("test.mod.lib"), HashMap::from([file("test.mod.lib", Version::from_minor(1, 2))])),
+        (format!("unknown.dll"), HashMap::from([file("unknown.dll", Version::from_major(1))])),
+    ]));
+
+    let lockfile = Lockfile::from_mod_map(&locked_map, &manifest_mods);
+    assert_eq!(lockfile.mods.keys().collect::<Vec<&String>>(), vec!["test.mod.a", "test.mod.lib"]);
+    assert_eq!(lockfile.mods["test.mod.lib"].hashes, vec!["12".to_string()]);
+
+    let path = std::env::temp_dir().join("nmo_lockfile_test/mods.lock.json");
+    lockfile.save(&path).await.unwrap();
+    assert_eq!(Lockfile::load(&path).await.unwrap(), lockfile);
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+
+    // Nothing to do when it already matches
+    assert!(lockfile.sync_operations(&locked_map, &manifest_mods).operations.is_empty());
+
+    // A got removed, lib updated, C installed and the unknown file removed by hand
+    let changed_map = ModMap::from(HashMap::from([
+        (format!("test.mod.c"), HashMap::from([file("test.mod.c", Version::from_major(1))])),
+        (format!("test.mod.lib"), HashMap::from([file("test.mod.lib", Version::from_minor(2, 0))])),
+    ]));
+
+    let sync = lockfile.sync_operations(&changed_map, &manifest_mods);
+    assert!(sync.unavailable.is_empty());
+    assert!(matches!(sync.operations.first(), Some(ModInstallOperations::UninstallMod((id, _))) if id == "test.mod.c"));
+
+    let mut virt = VirtualInstall::new(changed_map, manifest_mods.clone());
+    virt.perform_operations(&sync.operations).await.unwrap();
+    assert_eq!(Lockfile::from_mod_map(virt.mod_map(), &manifest_mods), lockfile);
+
+    // Changed file gets reinstalled, versions manifest doesn't have are reported
+    let mut edited_map = locked_map.clone();
+    edited_map.file_mut("test.mod.lib", &Version::from_minor(1, 2)).unwrap().files[0].file_hash = "edited".to_string();
+
+    let mut with_missing = lockfile.clone();
+    with_missing.mods.insert(format!("test.mod.b"), LockedMod {
+        version: Version::from_major(9),
+        hashes: vec![],
+    });
+
+    let sync = with_missing.sync_operations(&edited_map, &manifest_mods);
+    assert_eq!(sync.unavailable, vec![(format!("test.mod.b"), Version::from_major(9))]);
+    assert_eq!(sync.operations.len(), 2);
+    assert!(matches!(&sync.operations[1], ModInstallOperations::InstallMod((id, version)) if id == "test.mod.lib" && *version == Version::from_minor(1, 2)));
+}
+
+#[test]
+fn cloud_sync_merges_changes_from_both_sides() {
+    let locked = |version: &str| LockedMod {
+        version: Version::from_str(version).unwrap(),
+        hashes: vec![version.to_string()],
+    };
+    let profile = |name: &str, use_mods: bool| LaunchProfile {
+        name: name.to_string(),
+        options: LaunchOptions {
+            use_mods,
+            ..Default::default()
+        },
+    };
+    let snapshot = |mods: Vec<(&str, &str)>, launch_profiles: Vec<LaunchProfile>| SyncSnapshot {
+        launch_profiles,
+        lockfile: Lockfile {
+            mods: mods.into_iter().map(|(id, version)| (id.to_string(), locked(version))).collect(),
+        },
+    };
+
+    let base = snapshot(vec![("test.mod.a", "1.0"), ("test.mod.b", "1.0")], vec![profile("Camera", true)]);
+
+    // Mod a updated here, mod c installed on the other PC along with a new profile
+    let local = snapshot(vec![("test.mod.a", "1.1"), ("test.mod.b", "1.0")], vec![profile("Camera", true)]);
+    let remote = snapshot(vec![("test.mod.a", "1.0"), ("test.mod.b", "1.0"), ("test.mod.c", "2.0")], vec![profile("Camera", true), profile("Vanilla", false)]);
+
+    let (merged, conflicts) = merge_snapshots(&base, &local, &remote);
+    assert!(conflicts.is_empty());
+    assert_eq!(merged, snapshot(
+        vec![("test.mod.a", "1.1"), ("test.mod.b", "1.0"), ("test.mod.c", "2.0")],
+        vec![profile("Camera", true), profile("Vanilla", false)]
+    ));
+
+    // Both sides changed mod b and the camera profile
+    let local = snapshot(vec![("test.mod.a", "1.0"), ("test.mod.b", "1.1")], vec![profile("Camera", false)]);
+    let remote = snapshot(vec![("test.mod.a", "1.0"), ("test.mod.b", "1.2")], vec![]);
+
+    let (mut merged, conflicts) = merge_snapshots(&base, &local, &remote);
+    assert_eq!(conflicts.len(), 2);
+    assert!(conflicts.contains(&SyncConflict::Mod {
+        mod_id: "test.mod.b".to_string(),
+        local: Some(locked("1.1")),
+        remote: Some(locked("1.2")),
+    }));
+    assert!(conflicts.contains(&SyncConflict::Profile {
+        name: "Camera".to_string(),
+        local: Some(profile("Camera", false)),
+        remote: None,
+    }));
+
+    // Local side is kept until picked otherwise
+    assert_eq!(merged.lockfile.mods["test.mod.b"], locked("1.1"));
+
+    for conflict in &conflicts {
+        conflict.resolve(&mut merged, false);
+    }
+
+    assert_eq!(merged, snapshot(vec![("test.mod.a", "1.0"), ("test.mod.b", "1.2")], vec![]));
+}
+
+#[tokio::test]
+async fn stalled_instance_message_does_not_block_others() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(8);
+
+    tokio::spawn(listen_for_messages(listener, sender, Default::default()));
+
+    // Connects and never sends anything
+    let _stalled = tokio::net::TcpStream::connect(address).await.unwrap();
+
+    let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+    tokio::io::AsyncWriteExt::write_all(&mut stream, b"neosmod://show/test.mod.a").await.unwrap();
+    drop(stream);
+
+    let event = tokio::time::timeout(Duration::from_secs(2), receiver.recv()).await.unwrap();
+    assert!(matches!(event, Some(ManagerEvent::FocusRequested)));
+
+    let event = tokio::time::timeout(Duration::from_secs(2), receiver.recv()).await.unwrap();
+    assert!(matches!(event, Some(ManagerEvent::DeepLink(DeepLink::Show(guid))) if guid == "test.mod.a"));
+}
+
+#[tokio::test]
+async fn forwarding_needs_organizer_handshake() {
+    // Some other program on the port, accepting but never answering
+    let other = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let other_address = other.local_addr().unwrap();
+    let holder = std::thread::spawn(move || other.accept());
+
+    let result = tokio::task::spawn_blocking(move || forward_message(&other_address, &[])).await.unwrap();
+    assert!(result.is_err());
+    holder.join().unwrap().unwrap();
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(8);
+
+    tokio::spawn(listen_for_messages(listener, sender, Default::default()));
+
+    let arguments = vec!["neosmod://install/test.mod.a".to_string()];
+    let result = tokio::task::spawn_blocking(move || forward_message(&address, &arguments)).await.unwrap();
+    assert!(result.is_ok());
+
+    let event = tokio::time::timeout(Duration::from_secs(2), receiver.recv()).await.unwrap();
+    assert!(matches!(event, Some(ManagerEvent::FocusRequested)));
+
+    let event = tokio::time::timeout(Duration::from_secs(2), receiver.recv()).await.unwrap();
+    assert!(matches!(event, Some(ManagerEvent::DeepLink(DeepLink::Install(guid))) if guid == "test.mod.a"));
+}
+
+#[tokio::test]
+async fn disabling_duplicate_versions_clears_version_conflict() {
+    let manifest_mods = merging_manifest();
+    let file = |version: Version| (version.clone(), ModFile::new("test.mod.lib", &version, &manifest_mods, &Default::default()));
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.lib"), HashMap::from([file(Version::from_minor(1, 2)), file(Version::from_minor(1, 4))])),
+    ]));
+
+    let mut virt = VirtualInstall::new(mod_map, manifest_mods.clone());
+    assert_eq!(virt.check_for_conflicts(&manifest_mods), vec![ModConflict::VersionConflict(format!("test.mod.lib"))]);
+
+    let versions = [Version::from_minor(1, 4), Version::from_minor(1, 2)];
+    let operations = build_resolution_operations("test.mod.lib", &versions, &versions[0], DuplicateResolution::Disable);
+    virt.perform_operations(&operations).await.unwrap();
+
+    // Disabled version stays installed, but isn't loaded next to the kept one
+    assert!(virt.mod_map().is_installed("test.mod.lib", &versions[1]));
+    assert!(virt.check_for_conflicts(&manifest_mods).is_empty());
+}
+
+#[tokio::test]
+async fn resolve_skips_dependents_the_install_already_fails() {
+    let any = VersionReq::from_str("*").unwrap();
+
+    let mut mods = merging_manifest().as_ref().clone();
+    mods.insert(format!("test.mod.d"), test_mod("Mod D", Category::Audio, vec![
+        (Version::from_major(1), test_version(&[("test.mod.lib", "^3.0")], vec![test_artifact("d.dll", "d1")]))
+    ]));
+    let manifest_mods = Arc::new(mods);
+
+    // A and D already don't get the lib they want
+    let file = |id: &str, version: Version| (version.clone(), ModFile::new(id, &version, &manifest_mods, &Default::default()));
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.a"), HashMap::from([file("test.mod.a", Version::from_major(1))])),
+        (format!("test.mod.d"), HashMap::from([file("test.mod.d", Version::from_major(1))])),
+        (format!("test.mod.lib"), HashMap::from([file("test.mod.lib", Version::from_minor(2, 0))])),
+    ]));
+
+    let ResolveResult::Ok(operations) = resolve_install_mod("test.mod.c", &any, &mod_map, &manifest_mods, |_| UpdateChannel::Stable) else {
+        panic!("Failed to resolve test.mod.c");
+    };
+
+    assert_eq!(operations.len(), 1);
+    assert!(matches!(&operations[0], ModInstallOperations::InstallMod((id, _)) if id == "test.mod.c"));
+
+    let mut virt = VirtualInstall::new(mod_map.clone(), manifest_mods.clone());
+    virt.perform_operations(&operations).await.unwrap();
+
+    let conflicts = virt.check_for_conflicts(&manifest_mods);
+    assert!(conflicts.iter().any(|x| matches!(x, ModConflict::DependencyMismatch { this, .. } if this.0 == "test.mod.d")));
+
+    // Without the impossible requirement it's a plain disagreement between A and C
+    let mut without_d = virt.mod_map().clone();
+    without_d.remove("test.mod.d", &Version::from_major(1));
+
+    let conflicts = VirtualInstall::new(without_d, manifest_mods.clone()).check_for_conflicts(&manifest_mods);
+    assert!(conflicts.iter().any(|x| matches!(x, ModConflict::IncompatibleRequirements { this, requirements } if this == "test.mod.lib" && requirements.len() == 2)));
+}
+
+#[test]
+fn pending_sync_waits_for_its_operations() {
+    let operations = vec![
+        ModInstallOperations::InstallMod((format!("test.mod.a"), Version::from_major(1))),
+        ModInstallOperations::UninstallMod((format!("test.mod.b"), Version::from_major(2))),
+    ];
+    let item = |id: u64, batch: u64, operation: &ModInstallOperations, status: OperationStatus| QueueItem {
+        id,
+        batch,
+        operation: operation.clone(),
+        status,
+    };
+
+    let mut pending = PendingSync::new(SyncSnapshot::default(), operations.clone());
+
+    // Declined preview never queues anything, so the sync stays unfinished
+    assert_eq!(pending.outcome(&[item(1, 1, &operations[0], OperationStatus::Done)]), None);
+
+    // Other changes don't count as the sync
+    assert!(!pending.claim_batch(&operations[..1], 1));
+    assert!(pending.claim_batch(&operations, 2));
+    assert!(!pending.claim_batch(&operations, 3));
+
+    let mut queue = vec![
+        item(1, 1, &operations[0], OperationStatus::Failed(format!("unrelated"))),
+        item(2, 2, &operations[0], OperationStatus::Done),
+        item(3, 2, &operations[1], OperationStatus::Queued),
+    ];
+    assert_eq!(pending.outcome(&queue), None);
+
+    queue[2].status = OperationStatus::Done;
+    assert_eq!(pending.outcome(&queue), Some(true));
+
+    queue[2].status = OperationStatus::Failed(format!("broken"));
+    assert_eq!(pending.outcome(&queue), Some(false));
+}
+
+#[test]
+fn mod_report_lists_mods_and_system() {
+    let utf16 = |text: &str| text.encode_utf16().chain([0]).flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>();
+    let fake_dll = |version: &str| {
+        let mut data = b"MZ\0\0".to_vec();
+        data.extend(utf16("Assembly Version"));
+        data.extend([0, 0]);
+        data.extend(utf16(version));
+        data
+    };
+
+    let root = std::env::temp_dir().join("nmo_mod_report_test");
+    std::fs::remove_dir_all(&root).ok();
+    std::fs::create_dir_all(root.join("Neos_Data/Managed")).unwrap();
+    std::fs::create_dir_all(root.join("Libraries")).unwrap();
+
+    assert!(system_info(&root).contains("- Neos version: unknown\n"));
+    assert!(system_info(&root).contains("- NeosModLoader version: not installed\n"));
+
+    std::fs::write(root.join("Neos_Data/Managed/FrooxEngine.dll"), fake_dll("2022.1.28.1310")).unwrap();
+    std::fs::write(root.join("Libraries/NeosModLoader.dll"), fake_dll("1.12.6.0")).unwrap();
+
+    let info = system_info(&root);
+    assert!(info.contains("- Neos version: 2022.1.28.1310\n"));
+    assert!(info.contains("- NeosModLoader version: 1.12.6.0\n"));
+    assert!(info.contains(&format!("- Organizer version: {}\n", env!("CARGO_PKG_VERSION"))));
+
+    let manifest_mods = Arc::new(HashMap::from([
+        (format!("test.mod.a"), test_mod("Mod | A", Category::Audio, vec![
+            (Version::from_major(1), test_version(&[], vec![test_artifact("a.dll", "aaaaaaaaaaaaaaaaaaaa")]))
+        ])),
+        (format!("test.mod.b"), test_mod("Mod B", Category::Audio, vec![
+            (Version::from_minor(2, 1), test_version(&[], vec![test_artifact("b.dll", "bbbb")]))
+        ])),
+    ]));
+
+    let file = |id: &str, version: Version| (version.clone(), ModFile::new(id, &version, &manifest_mods, &Default::default()));
+    let (b_version, mut b_file) = file("test.mod.b", Version::from_minor(2, 1));
+    b_file.files.iter_mut().for_each(|x| x.disabled = true);
+
+    let mod_map = ModMap::from(HashMap::from([
+        (format!("test.mod.a"), HashMap::from([file("test.mod.a", Version::from_major(1))])),
+        (format!("test.mod.b"), HashMap::from([(b_version, b_file)])),
+    ]));
+
+    let global_mods = GlobalModList::from_list(manifest_mods.as_ref().clone());
+    let report = build_mod_report(&mod_map, &global_mods, Some(info.clone()));
+
+    assert!(report.starts_with(&info));
+    assert!(report.contains("**Installed mods (2)**"));
+    assert!(report.contains("| Mod \\| A | test.mod.a | 1 | Yes | aaaaaaaaaaaa |\n"));
+    assert!(report.contains("| Mod B | test.mod.b | 2.1 | No | bbbb |\n"));
+
+    // Without system info it's only the table
+    assert!(build_mod_report(&mod_map, &global_mods, None).starts_with("**Installed mods (2)**"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+const MOCK_MANIFEST: &str = "https://manifest.test/manifest.json";
+
+/// Manifest with the mods, served by a mock along with the files
+fn mock_manifest_transport(mods: ManifestMods, files: &[(&str, &[u8])]) -> Arc<MockTransport> {
+    let manifest = ModManifest {
+        schema_version: Some(Version::from_major(1)),
+        mods,
+    };
+
+    let transport = files.iter().fold(MockTransport::default(), |transport, (url, body)| transport.with(url, body));
+
+    Arc::new(transport.with(MOCK_MANIFEST, &serde_json::to_vec(&manifest).unwrap()))
+}
+
+#[tokio::test]
+async fn manager_installs_and_updates_through_transport() {
+    let root = std::env::temp_dir().join("nmo_manager_install_test");
+    std::fs::remove_dir_all(&root).ok();
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::remove_file(Config::lockfile_path()).ok();
+
+    let mut config = Config::new(root.join("Neos.exe"));
+    config.manifest_links = vec![MOCK_MANIFEST.to_string()];
+    config.manifest_trust.insert(MOCK_MANIFEST.to_string(), SourceTrust { trusted: true, public_key: None });
+
+    let (_command_sender, command_receiver) = tokio::sync::mpsc::channel(8);
+    // Roomy enough that the manager never waits on it, events are read after each command
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+    let mut events = vec![];
+
+    let mut latest_map = |events: &mut Vec<ManagerEvent>| {
+        while let Ok(event) = event_receiver.try_recv() {
+            events.push(event);
+        }
+
+        events.iter().rev()
+            .find_map(|x| match x {
+                ManagerEvent::ModMapChanged(map) => Some(map.clone()),
+                _ => None
+            })
+            .unwrap()
+    };
+
+    let mut manager = Manager::new(command_receiver, event_sender, Context::default(), Arc::new(ArcSwap::from_pointee(config)), GlobalModList::from_list(Default::default()));
+
+    let lib = || (format!("test.lib"), test_mod("Lib", Category::Misc, vec![
+        (Version::from_major(1), test_version(&[], vec![test_artifact("Lib.dll", &sha256_bytes(b"lib"))]))
+    ]));
+    let v1 = || (Version::from_major(1), test_version(&[("test.lib", "^1")], vec![test_artifact("Mod.dll", &sha256_bytes(b"mod 1"))]));
+    let v2 = || (Version::from_major(2), test_version(&[("test.lib", "^1")], vec![test_artifact("Mod2.dll", &sha256_bytes(b"mod 2"))]));
+
+    // Installing pulls in the dependency too
+    manager.set_transport(mock_manifest_transport(HashMap::from([
+        lib(),
+        (format!("test.mod"), test_mod("Mod", Category::Misc, vec![v1()])),
+    ]), &[("test.com/Lib.dll", b"lib"), ("test.com/Mod.dll", b"mod 1")]));
+
+    assert!(manager.handle_command(ManagerCommand::RefreshManifests).await);
+    assert!(manager.handle_command(ManagerCommand::InstallMod {
+        guid: format!("test.mod"),
+        version_req: VersionReq::from_str("*").unwrap(),
+    }).await);
+
+    assert_eq!(std::fs::read(root.join("nml_mods/Mod.dll")).unwrap(), b"mod 1");
+    assert_eq!(std::fs::read(root.join("nml_mods/Lib.dll")).unwrap(), b"lib");
+    assert_eq!(latest_map(&mut events).installed_version("test.mod"), Some(&Version::from_major(1)));
+    assert_eq!(latest_map(&mut events).installed_version("test.lib"), Some(&Version::from_major(1)));
+
+    let lockfile = Lockfile::load(&Config::lockfile_path()).await.unwrap();
+    assert_eq!(lockfile.mods.get("test.mod").map(|x| x.version.clone()), Some(Version::from_major(1)));
+
+    // Broken download doesn't get written or picked up
+    let updated = || HashMap::from([
+        lib(),
+        (format!("test.mod"), test_mod("Mod", Category::Misc, vec![v1(), v2()])),
+        (format!("test.other"), test_mod("Other", Category::Misc, vec![
+            (Version::from_major(1), test_version(&[], vec![test_artifact("Other.dll", &sha256_bytes(b"other"))]))
+        ])),
+    ]);
+    manager.set_transport(mock_manifest_transport(updated(), &[("test.com/Other.dll", b"tampered"), ("test.com/Mod2.dll", b"mod 2")]));
+
+    assert!(manager.handle_command(ManagerCommand::RefreshManifests).await);
+    assert!(manager.handle_command(ManagerCommand::InstallMod {
+        guid: format!("test.other"),
+        version_req: VersionReq::from_str("*").unwrap(),
+    }).await);
+
+    assert!(!root.join("nml_mods/Other.dll").exists());
+    assert!(!latest_map(&mut events).contains_key("test.other"));
+    assert!(events.iter().any(|x| matches!(x, ManagerEvent::Error(_))));
+    assert!(events.iter().any(|x| matches!(x, ManagerEvent::UpdatesFound(updates) if updates.len() == 1)));
+
+    // Update replaces the old version
+    assert!(manager.handle_command(ManagerCommand::InstallMod {
+        guid: format!("test.mod"),
+        version_req: VersionReq::at_least(&Version::from_major(2)),
+    }).await);
+
+    assert_eq!(std::fs::read(root.join("nml_mods/Mod2.dll")).unwrap(), b"mod 2");
+    assert!(!root.join("nml_mods/Mod.dll").exists());
+    assert_eq!(latest_map(&mut events).installed_versions("test.mod"), vec![&Version::from_major(2)]);
+
+    let lockfile = Lockfile::load(&Config::lockfile_path()).await.unwrap();
+    assert_eq!(lockfile.mods.get("test.mod").map(|x| x.version.clone()), Some(Version::from_major(2)));
+
+    std::fs::remove_dir_all(&root).ok();
+}
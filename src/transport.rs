@@ -0,0 +1,133 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use reqwest::header::RANGE;
+use crate::utils::TokenBucket;
+
+/// How installs and manifest fetching get data from URLs, tests put canned responses in place of the network
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Whole body of the URL, throttled by the bucket if there's one
+    async fn get(&self, url: &str, bucket: &mut Option<TokenBucket>) -> Result<Vec<u8>, TransportError>;
+
+    /// Streams the body into the sink starting at offset, transports without range support send it whole
+    async fn get_from(&self, url: &str, offset: u64, sink: &mut dyn BodySink, bucket: &mut Option<TokenBucket>) -> Result<(), TransportError> {
+        let data = self.get(url, bucket).await?;
+
+        if offset > 0 {
+            sink.restart().await?;
+        }
+
+        Ok(sink.write(&data).await?)
+    }
+}
+
+/// Where streamed body goes, so it's kept even if the download gets cut off
+#[async_trait]
+pub trait BodySink: Send {
+    /// Server sent the body from the start, anything written before has to go
+    async fn restart(&mut self) -> io::Result<()>;
+    async fn write(&mut self, chunk: &[u8]) -> io::Result<()>;
+}
+
+#[async_trait]
+impl Transport for Client {
+    async fn get(&self, url: &str, bucket: &mut Option<TokenBucket>) -> Result<Vec<u8>, TransportError> {
+        let mut response = Client::get(self, url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(TransportError::Status {
+                url: url.to_string(),
+                status: response.status().as_u16(),
+            });
+        }
+
+        let mut data = vec![];
+
+        while let Some(chunk) = response.chunk().await? {
+            if let Some(bucket) = bucket {
+                bucket.throttle(chunk.len()).await;
+            }
+
+            data.extend_from_slice(&chunk);
+        }
+
+        Ok(data)
+    }
+
+    async fn get_from(&self, url: &str, offset: u64, sink: &mut dyn BodySink, bucket: &mut Option<TokenBucket>) -> Result<(), TransportError> {
+        let mut request = Client::get(self, url);
+
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+
+        let mut response = request.send().await?;
+
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT if offset > 0 => {}
+            // Partial file is already as long as the whole body or longer, so it can't be a part of it
+            StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => {
+                sink.restart().await?;
+                return self.get_from(url, 0, sink, bucket).await;
+            }
+            status if status.is_success() => {
+                if offset > 0 {
+                    sink.restart().await?;
+                }
+            }
+            status => return Err(TransportError::Status {
+                url: url.to_string(),
+                status: status.as_u16(),
+            })
+        }
+
+        while let Some(chunk) = response.chunk().await? {
+            if let Some(bucket) = bucket {
+                bucket.throttle(chunk.len()).await;
+            }
+
+            sink.write(&chunk).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum TransportError {
+    NetworkError(reqwest::Error),
+    /// Server answered, but not with success
+    Status {
+        url: String,
+        status: u16
+    },
+    /// Body couldn't be written where it was going
+    WriteError(io::Error)
+}
+
+impl Display for TransportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransportError::NetworkError(e) => write!(f, "{}", e),
+            TransportError::Status { url, status } => write!(f, "{} answered with status {}", url, status),
+            TransportError::WriteError(e) => write!(f, "Couldn't write downloaded data: {}", e)
+        }
+    }
+}
+
+impl Error for TransportError {}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::NetworkError(value)
+    }
+}
+
+impl From<io::Error> for TransportError {
+    fn from(value: io::Error) -> Self {
+        Self::WriteError(value)
+    }
+}
@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Serialize, Deserialize};
+use tokio::fs;
+use crate::config::Config;
+use crate::history::move_file;
+use crate::install::{IDVersion, InstallError, ModFile};
+
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrashFile {
+    pub original: PathBuf,
+    pub trashed: PathBuf
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrashEntry {
+    pub id: u64,
+    pub mod_id: IDVersion,
+    /// Seconds since unix epoch
+    pub timestamp: u64,
+    pub files: Vec<TrashFile>
+}
+
+impl TrashEntry {
+    pub fn folder(&self) -> PathBuf {
+        let mut path = Config::trash_path();
+        path.push(self.id.to_string());
+        path
+    }
+}
+
+/// Copies of uninstalled mods, kept around for a while in case user wants them back
+#[derive(Serialize, Deserialize, Default)]
+pub struct Trash {
+    pub entries: Vec<TrashEntry>
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs())
+}
+
+impl Trash {
+    fn index_path() -> PathBuf {
+        let mut path = Config::trash_path();
+        path.push("trash.json");
+        path
+    }
+
+    pub async fn load() -> Result<Trash, InstallError> {
+        let path = Self::index_path();
+
+        if !path.exists() {
+            return Ok(Trash::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path).await?)?)
+    }
+
+    pub async fn save(&self) -> Result<(), InstallError> {
+        fs::create_dir_all(Config::trash_path()).await?;
+
+        Ok(fs::write(Self::index_path(), serde_json::to_string(self)?).await?)
+    }
+
+    /// Copies files of the mod into trash, has to happen before the mod gets uninstalled
+    pub async fn add(&mut self, mod_id: IDVersion, file: &ModFile) -> Result<(), InstallError> {
+        let mut entry = TrashEntry {
+            id: self.entries.last().map_or(1, |x| x.id + 1),
+            mod_id,
+            timestamp: now(),
+            files: vec![],
+        };
+
+        let folder = entry.folder();
+        fs::create_dir_all(&folder).await?;
+
+        for (index, artifact) in file.files.iter().enumerate() {
+            let mut trashed = folder.clone();
+            trashed.push(format!("{}_{}", index, artifact.file_path.file_name().map_or_else(|| "unknown".to_string(), |x| x.to_string_lossy().to_string())));
+
+            if let Err(e) = fs::copy(&artifact.file_path, &trashed).await {
+                fs::remove_dir_all(&folder).await.ok();
+                return Err(e.into());
+            }
+
+            entry.files.push(TrashFile {
+                original: artifact.file_path.clone(),
+                trashed,
+            });
+        }
+
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    /// Puts files back where they were, fails without touching anything if any of them is in the way
+    pub async fn restore(&mut self, id: u64) -> Result<(), InstallError> {
+        let Some(index) = self.entries.iter().position(|x| x.id == id) else {
+            return Err(InstallError::FileNotFound);
+        };
+
+        if self.entries[index].files.iter().any(|x| x.original.exists()) {
+            return Err(InstallError::FileAlreadyExists);
+        }
+
+        let entry = self.entries.remove(index);
+
+        for file in &entry.files {
+            move_file(&file.trashed, &file.original).await?;
+        }
+
+        fs::remove_dir_all(entry.folder()).await.ok();
+
+        Ok(())
+    }
+
+    pub async fn delete(&mut self, id: u64) {
+        if let Some(index) = self.entries.iter().position(|x| x.id == id) {
+            let entry = self.entries.remove(index);
+            fs::remove_dir_all(entry.folder()).await.ok();
+        }
+    }
+
+    pub async fn empty(&mut self) {
+        for entry in self.entries.drain(..) {
+            fs::remove_dir_all(entry.folder()).await.ok();
+        }
+    }
+
+    /// Deletes entries older than retention period, returns how many were removed
+    pub async fn purge_expired(&mut self, retention_days: u32) -> usize {
+        let cutoff = now().saturating_sub(retention_days as u64 * SECONDS_IN_DAY);
+        let expired: Vec<u64> = self.entries.iter()
+            .filter(|x| x.timestamp < cutoff)
+            .map(|x| x.id)
+            .collect();
+
+        for id in &expired {
+            self.delete(*id).await;
+        }
+
+        expired.len()
+    }
+}
@@ -0,0 +1,98 @@
+/// Size of the generated tray icon in pixels
+#[cfg(target_os = "windows")]
+const ICON_SIZE: u32 = 32;
+
+/// Things picked from the tray icon menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub enum TrayAction {
+    Launch,
+    CheckUpdates,
+    Open,
+    Quit
+}
+
+/// Tray icon with its menu, only supported on Windows
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub struct Tray {
+    #[cfg(target_os = "windows")]
+    _icon: tray_icon::TrayIcon,
+    #[cfg(target_os = "windows")]
+    items: Vec<(tray_icon::menu::MenuItem, TrayAction)>
+}
+
+/// Icon drawn in code so there's no image to ship, a filled circle in the organizer's accent color
+#[cfg(target_os = "windows")]
+fn icon_rgba() -> Vec<u8> {
+    let center = ICON_SIZE as f32 / 2.0;
+
+    (0..ICON_SIZE * ICON_SIZE)
+        .flat_map(|index| {
+            let x = (index % ICON_SIZE) as f32 + 0.5 - center;
+            let y = (index / ICON_SIZE) as f32 + 0.5 - center;
+
+            if (x * x + y * y).sqrt() <= center - 1.0 {
+                [90, 140, 230, 255]
+            } else {
+                [0, 0, 0, 0]
+            }
+        })
+        .collect()
+}
+
+impl Tray {
+    /// Adds the icon to the tray, None if the platform doesn't have one or it failed
+    #[cfg(target_os = "windows")]
+    pub fn new() -> Option<Self> {
+        use tray_icon::{Icon, TrayIconBuilder};
+        use tray_icon::menu::{Menu, MenuItem, PredefinedMenuItem};
+        use crate::locale::tr;
+
+        let items = vec![
+            (MenuItem::new(tr("tray_icon.launch"), true, None), TrayAction::Launch),
+            (MenuItem::new(tr("tray_icon.check_updates"), true, None), TrayAction::CheckUpdates),
+            (MenuItem::new(tr("tray_icon.open"), true, None), TrayAction::Open),
+            (MenuItem::new(tr("tray_icon.quit"), true, None), TrayAction::Quit),
+        ];
+
+        let menu = Menu::new();
+        let separator = PredefinedMenuItem::separator();
+
+        menu.append_items(&[&items[0].0, &items[1].0, &items[2].0, &separator, &items[3].0]).ok()?;
+
+        let icon = Icon::from_rgba(icon_rgba(), ICON_SIZE, ICON_SIZE).ok()?;
+
+        let tray = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip("Neos Mod Organizer")
+            .with_icon(icon)
+            .build().ok()?;
+
+        Some(Self {
+            _icon: tray,
+            items,
+        })
+    }
+
+    /// Adds the icon to the tray, None if the platform doesn't have one or it failed
+    #[cfg(not(target_os = "windows"))]
+    pub fn new() -> Option<Self> {
+        None
+    }
+
+    /// Next menu item that was clicked since last poll
+    #[cfg(target_os = "windows")]
+    pub fn poll(&self) -> Option<TrayAction> {
+        let event = tray_icon::menu::MenuEvent::receiver().try_recv().ok()?;
+
+        self.items.iter()
+            .find(|(item, _)| event.id == item.id())
+            .map(|(_, action)| *action)
+    }
+
+    /// Next menu item that was clicked since last poll
+    #[cfg(not(target_os = "windows"))]
+    pub fn poll(&self) -> Option<TrayAction> {
+        None
+    }
+}
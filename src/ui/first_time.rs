@@ -2,7 +2,9 @@ use std::path::PathBuf;
 use eframe::egui::{Align, Align2, Button, CentralPanel, Context, Label, Layout, RichText, TopBottomPanel, Vec2, Widget};
 use egui_file::{FileDialog};
 use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
-use crate::config::{Config, default_manifest_links, default_scan_locations};
+use crate::config::Config;
+use crate::import::{detect_existing_setup, ExistingSetup};
+use crate::locale::{tr, tr_args};
 use crate::manager::validate_path;
 use crate::utils::place_in_middle;
 
@@ -11,10 +13,28 @@ pub struct FirstTimeState {
     pub neos_path_picker: Option<PathBuf>,
     pub neos_path: String,
     pub picker_dialog: Option<FileDialog>,
-    pub config: Option<Config>
+    pub config: Option<Config>,
+    /// Validated path along with what was found there, import page is shown while this is set
+    pub existing_setup: Option<(PathBuf, ExistingSetup)>,
+    /// Which of the found argument sources launch options get imported from
+    pub import_launch_options: Option<usize>
+}
+
+fn make_config(state: &FirstTimeState, path: PathBuf) -> Config {
+    if let Some(mut config) = state.config.clone() {
+        config.neos_exe_location = path;
+
+        config
+    } else {
+        Config::new(path)
+    }
 }
 
 pub fn first_time_ui(state: &mut FirstTimeState, ctx: &Context, toasts: &mut Toasts) -> Option<Config> {
+    if state.existing_setup.is_some() {
+        return import_ui(state, ctx);
+    }
+
     TopBottomPanel::top("top")
         .show_separator_line(false)
         .show(ctx, |ui| {
@@ -54,20 +74,18 @@ pub fn first_time_ui(state: &mut FirstTimeState, ctx: &Context, toasts: &mut Toa
     if path.inner.inner.is_some() {
         let path = path.inner.inner.unwrap();
 
-        let config = if let Some(mut config) = state.config.clone() {
-            config.neos_exe_location = path;
+        // Configs being fixed up already have everything set up
+        if state.config.is_none() {
+            let setup = detect_existing_setup(&path);
 
-            config
-        } else {
-            Config {
-                neos_exe_location: path,
-                launch_options: Default::default(),
-                scan_locations: default_scan_locations(),
-                manifest_links: default_manifest_links(),
+            if !setup.is_empty() {
+                state.import_launch_options = if setup.argument_sources.is_empty() { None } else { Some(0) };
+                state.existing_setup = Some((path, setup));
+                return None;
             }
-        };
+        }
 
-        return Some(config);
+        return Some(make_config(state, path));
     }
 
     CentralPanel::default()
@@ -111,4 +129,78 @@ pub fn first_time_ui(state: &mut FirstTimeState, ctx: &Context, toasts: &mut Toa
         });
 
     None
-}
\ No newline at end of file
+}
+
+fn import_ui(state: &mut FirstTimeState, ctx: &Context) -> Option<Config> {
+    TopBottomPanel::top("top")
+        .show_separator_line(false)
+        .show(ctx, |ui| {
+            ui.centered_and_justified(|ui| {
+                Label::new(RichText::from(tr("first_time.import_title")).heading().size(30.0)).ui(ui)
+            })
+        });
+
+    let finished = TopBottomPanel::bottom("bottom")
+        .show_separator_line(false)
+        .min_height(40.0)
+        .show(ctx, |ui| {
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                let finished = Button::new(RichText::from(format!("    {}    ", tr("first_time.finish"))).size(14.0))
+                    .ui(ui).clicked();
+
+                if Button::new(RichText::from(format!("    {}    ", tr("first_time.back"))).size(14.0))
+                    .ui(ui).clicked() {
+                    state.existing_setup = None;
+                }
+
+                finished
+            }).inner
+        }).inner;
+
+    if finished {
+        let (path, setup) = state.existing_setup.take()?;
+        let mut config = make_config(state, path);
+
+        if let Some(options) = state.import_launch_options.and_then(|x| setup.launch_options(x)) {
+            config.launch_options = options;
+        }
+
+        return Some(config);
+    }
+
+    let Some((_, setup)) = &state.existing_setup else {
+        return None;
+    };
+
+    CentralPanel::default()
+        .show(ctx, |ui| {
+            place_in_middle(ui, Vec2::new(450.0, 200.0), |ui| {
+                ui.vertical(|ui| {
+                    if setup.enabled_mods > 0 || setup.disabled_mods > 0 {
+                        ui.label(tr_args("first_time.found_mods", &[
+                            ("enabled", setup.enabled_mods.to_string()),
+                            ("disabled", setup.disabled_mods.to_string())
+                        ]));
+                    }
+
+                    if setup.mod_loader_installed {
+                        ui.label(tr("first_time.found_mod_loader"));
+                    }
+
+                    if !setup.argument_sources.is_empty() {
+                        ui.add_space(5.0);
+                        ui.label(tr("first_time.import_launch_options"));
+
+                        for (index, source) in setup.argument_sources.iter().enumerate() {
+                            ui.radio_value(&mut state.import_launch_options, Some(index), &source.name);
+                            ui.label(RichText::new(&source.arguments).monospace().weak());
+                        }
+
+                        ui.radio_value(&mut state.import_launch_options, None, tr("first_time.dont_import"));
+                    }
+                });
+            });
+        });
+
+    None
+}
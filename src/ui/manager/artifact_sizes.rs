@@ -0,0 +1,48 @@
+use std::collections::{HashMap, HashSet};
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::manager::ManagerCommand;
+use crate::manifest::Artifact;
+use crate::utils::handle_error;
+
+/// Artifact sizes from manifest or fetched by manager, missing ones get requested once
+#[derive(Default)]
+pub struct ArtifactSizes {
+    known: HashMap<String, u64>,
+    requested: HashSet<String>,
+    pending: Vec<String>
+}
+
+impl ArtifactSizes {
+    pub fn update(&mut self, known: HashMap<String, u64>) {
+        self.known = known;
+    }
+
+    pub fn get(&mut self, artifact: &Artifact) -> Option<u64> {
+        if let Some(size) = artifact.size.or_else(|| self.known.get(&artifact.url).copied()) {
+            return Some(size);
+        }
+
+        if self.requested.insert(artifact.url.clone()) {
+            self.pending.push(artifact.url.clone());
+        }
+
+        None
+    }
+
+    /// Total size of artifacts, None if any of them isn't known yet
+    pub fn total<'a>(&mut self, artifacts: impl IntoIterator<Item = &'a Artifact>) -> Option<u64> {
+        artifacts.into_iter()
+            .map(|x| self.get(x))
+            .fold(Some(0), |total, size| Some(total? + size?))
+    }
+
+    /// Sends sizes that were asked for this frame to manager
+    pub fn request_pending(&mut self, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        handle_error(command.blocking_send(ManagerCommand::FetchArtifactSizes(std::mem::take(&mut self.pending))), toasts);
+    }
+}
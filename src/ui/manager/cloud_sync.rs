@@ -0,0 +1,125 @@
+use eframe::egui::{Context, ScrollArea};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::cloud_sync::{SyncConflict, SyncSnapshot};
+use crate::locale::{tr, tr_args};
+use crate::lockfile::LockedMod;
+use crate::manager::ManagerCommand;
+use crate::manifest::GlobalModList;
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+
+pub struct CloudSyncState {
+    modal: Modal,
+    merged: SyncSnapshot,
+    remote: SyncSnapshot,
+    /// Conflicts with the side picked for each, true keeps the local one
+    conflicts: Vec<(SyncConflict, bool)>,
+    push: bool
+}
+
+impl CloudSyncState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "cloud_sync_modal"),
+            merged: Default::default(),
+            remote: Default::default(),
+            conflicts: vec![],
+            push: false,
+        }
+    }
+
+    pub fn open(&mut self, merged: SyncSnapshot, remote: SyncSnapshot, conflicts: Vec<SyncConflict>, push: bool) {
+        self.merged = merged;
+        self.remote = remote;
+        self.conflicts = conflicts.into_iter().map(|x| (x, true)).collect();
+        self.push = push;
+        self.modal.open();
+    }
+}
+
+fn describe_sync_conflict(conflict: &SyncConflict, global_mods: &GlobalModList) -> String {
+    match conflict {
+        SyncConflict::Mod { mod_id, local, remote } => {
+            let side = |x: &Option<LockedMod>| x.as_ref().map_or_else(|| tr("cloud_sync.removed"), |x| format!("v{}", x.version));
+
+            tr_args("cloud_sync.conflict_mod", &[
+                ("name", mod_name(global_mods, mod_id)),
+                ("local", side(local)),
+                ("remote", side(remote))
+            ])
+        }
+        SyncConflict::Profile { name, local, remote } => {
+            let side = |x: bool| if x { tr("cloud_sync.changed") } else { tr("cloud_sync.removed") };
+
+            tr_args("cloud_sync.conflict_profile", &[
+                ("name", name.clone()),
+                ("local", side(local.is_some())),
+                ("remote", side(remote.is_some()))
+            ])
+        }
+    }
+}
+
+/// Entries changed both here and in the synced setup, sync goes on once a side is picked for each
+pub fn cloud_sync_modal(state: &mut UIManagerState, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let sync_state = &mut state.cloud_sync_state;
+    let global_mods = &state.manifest_mods;
+    let mut proceed = false;
+
+    if !sync_state.modal.is_open() {
+        return;
+    }
+
+    sync_state.modal.show(|ui| {
+        sync_state.modal.title(ui, tr("cloud_sync.title"));
+
+        sync_state.modal.frame(ui, |ui| {
+            ui.label(tr("cloud_sync.description"));
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("cloud_sync_scroll")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for (conflict, keep_local) in &mut sync_state.conflicts {
+                        ui.label(describe_sync_conflict(conflict, global_mods));
+
+                        ui.horizontal(|ui| {
+                            ui.radio_value(keep_local, true, tr("cloud_sync.keep_local"));
+                            ui.radio_value(keep_local, false, tr("cloud_sync.take_remote"));
+                        });
+
+                        ui.add_space(3.0);
+                    }
+                });
+        });
+
+        sync_state.modal.buttons(ui, |ui| {
+            if sync_state.modal.suggested_button(ui, tr("cloud_sync.continue")).clicked() {
+                proceed = true;
+            }
+
+            sync_state.modal.button(ui, tr("cloud_sync.cancel"));
+        });
+    });
+
+    if !proceed {
+        return;
+    }
+
+    let mut merged = sync_state.merged.clone();
+
+    for (conflict, keep_local) in &sync_state.conflicts {
+        conflict.resolve(&mut merged, *keep_local);
+    }
+
+    handle_error(command.blocking_send(ManagerCommand::FinishCloudSync {
+        merged,
+        remote: sync_state.remote.clone(),
+        push: sync_state.push,
+    }), toasts);
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use eframe::egui::{Color32, Context, Grid, RichText, ScrollArea, TextEdit, Ui, Widget};
+use egui_modal::Modal;
+use crate::config::Config;
+use crate::install::{IDVersion, ModMap};
+use crate::launch::mod_loader_version;
+use crate::locale::{tr, tr_args};
+use crate::manifest::{GUID, Mod};
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::UIManagerState;
+use crate::version::{Version, VersionReq};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    Compatible,
+    Breaks,
+    /// Manifest doesn't say or there's no version to check against
+    Unknown
+}
+
+/// Whether the version fits into the range declared in the manifest
+pub fn check_compatibility(range: Option<&VersionReq>, version: Option<&Version>) -> Compatibility {
+    match (range, version) {
+        (Some(range), Some(version)) if range.matches(version) => Compatibility::Compatible,
+        (Some(_), Some(_)) => Compatibility::Breaks,
+        _ => Compatibility::Unknown
+    }
+}
+
+/// Neos and mod loader versions every installed mod version declares it works with
+pub fn compatibility_ranges(mod_map: &ModMap, mod_list: &HashMap<GUID, Mod>) -> Vec<(IDVersion, Option<VersionReq>, Option<VersionReq>)> {
+    let mut ranges: Vec<(IDVersion, Option<VersionReq>, Option<VersionReq>)> = mod_map.iter_versions()
+        .map(|(mod_id, version, _)| {
+            let info = mod_list.get(mod_id).and_then(|x| x.versions.get(version));
+
+            (
+                (mod_id.clone(), version.clone()),
+                info.and_then(|x| x.neos_version_compatibility.clone()),
+                info.and_then(|x| x.modloader_version_compatibility.clone())
+            )
+        })
+        .collect();
+
+    ranges.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+    ranges
+}
+
+pub struct CompatibilityState {
+    modal: Modal,
+    /// Neos build user is about to update to
+    neos_version: String,
+    mod_loader_version: String
+}
+
+impl CompatibilityState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "compatibility_modal"),
+            neos_version: String::new(),
+            mod_loader_version: String::new(),
+        }
+    }
+}
+
+pub fn compatibility_button(compatibility_state: &mut CompatibilityState, config: &Config, ui: &mut Ui) {
+    if ui.button(tr("compatibility.open")).clicked() {
+        // Installed mod loader is what mods will run with unless user says otherwise
+        if compatibility_state.mod_loader_version.is_empty() {
+            compatibility_state.mod_loader_version = config.neos_exe_location.parent()
+                .and_then(mod_loader_version)
+                .unwrap_or_default();
+        }
+
+        compatibility_state.modal.open();
+    }
+}
+
+/// Version typed by user, along with a warning next to the field if it's not a version
+fn version_field(ui: &mut Ui, label: String, text: &mut String) -> Option<Version> {
+    ui.label(label);
+
+    TextEdit::singleline(text)
+        .desired_width(120.0)
+        .ui(ui);
+
+    let version = Version::from_str(text.trim()).ok();
+
+    if version.is_none() && !text.trim().is_empty() {
+        ui.small(RichText::new(tr("compatibility.invalid_version")).color(Color32::LIGHT_RED));
+    }
+
+    ui.end_row();
+
+    version
+}
+
+fn compatibility_cell(ui: &mut Ui, range: &Option<VersionReq>, compatibility: Compatibility) {
+    let text = range.as_ref().map_or_else(|| "-".to_string(), |x| x.to_string());
+
+    match compatibility {
+        Compatibility::Compatible => ui.label(RichText::new(format!("✔ {}", text)).color(Color32::LIGHT_GREEN)),
+        Compatibility::Breaks => ui.label(RichText::new(format!("✖ {}", text)).color(Color32::LIGHT_RED)),
+        Compatibility::Unknown => ui.label(RichText::new(text).weak())
+    };
+}
+
+/// Installed mods against versions of Neos and the mod loader they declare support for
+pub fn compatibility_modal(state: &mut UIManagerState) {
+    let compatibility_state = &mut state.compatibility_state;
+    let global_mods = &state.manifest_mods;
+
+    if !compatibility_state.modal.is_open() {
+        return;
+    }
+
+    let ranges = compatibility_ranges(&state.mod_list, &global_mods.mod_list.load());
+
+    compatibility_state.modal.show(|ui| {
+        compatibility_state.modal.title(ui, tr("compatibility.title"));
+
+        compatibility_state.modal.frame(ui, |ui| {
+            ui.label(tr("compatibility.description"));
+
+            ui.add_space(5.0);
+
+            let (neos_version, mod_loader_version) = Grid::new("compatibility_versions")
+                .num_columns(3)
+                .show(ui, |ui| {
+                    (
+                        version_field(ui, tr("compatibility.neos_version"), &mut compatibility_state.neos_version),
+                        version_field(ui, tr("compatibility.mod_loader_version"), &mut compatibility_state.mod_loader_version)
+                    )
+                })
+                .inner;
+
+            let rows: Vec<(String, Version, &Option<VersionReq>, Compatibility, &Option<VersionReq>, Compatibility)> = ranges.iter()
+                .map(|((mod_id, version), neos, mod_loader)| (
+                    mod_name(global_mods, mod_id),
+                    version.clone(),
+                    neos,
+                    check_compatibility(neos.as_ref(), neos_version.as_ref()),
+                    mod_loader,
+                    check_compatibility(mod_loader.as_ref(), mod_loader_version.as_ref())
+                ))
+                .collect();
+
+            let breaking = rows.iter()
+                .filter(|x| x.3 == Compatibility::Breaks || x.5 == Compatibility::Breaks)
+                .count();
+
+            ui.add_space(5.0);
+
+            if breaking > 0 {
+                ui.label(RichText::new(tr_args("compatibility.breaking", &[("count", breaking.to_string())])).color(Color32::LIGHT_RED));
+            } else if neos_version.is_some() || mod_loader_version.is_some() {
+                ui.label(tr("compatibility.nothing_breaks"));
+            }
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("compatibility_scroll")
+                .max_height(350.0)
+                .show(ui, |ui| {
+                    Grid::new("compatibility_grid")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("compatibility.mod")).strong());
+                            ui.label(RichText::new(tr("compatibility.neos")).strong());
+                            ui.label(RichText::new(tr("compatibility.mod_loader")).strong());
+                            ui.end_row();
+
+                            for (name, version, neos, neos_compatibility, mod_loader, mod_loader_compatibility) in &rows {
+                                ui.label(format!("{} v{}", name, version));
+                                compatibility_cell(ui, neos, *neos_compatibility);
+                                compatibility_cell(ui, mod_loader, *mod_loader_compatibility);
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+
+        compatibility_state.modal.buttons(ui, |ui| {
+            compatibility_state.modal.button(ui, tr("compatibility.close"));
+        });
+    });
+}
@@ -0,0 +1,255 @@
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use eframe::egui::{Button, Context, RichText, ScrollArea, TextEdit, Ui};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::config::{Config, SuppressedConflict};
+use crate::fixes::{ConflictFix, FixKind};
+use crate::install::{ConflictSeverity, ModConflict};
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::manifest::GlobalModList;
+use crate::ui::manager::plan_preview::{describe_conflict, describe_operation, mod_name, severity_color};
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+
+pub struct ConflictsState {
+    modal: Modal,
+    /// Conflict the reason is being typed for
+    suppressing: Option<ModConflict>,
+    reason: String,
+    /// Fixes found for each conflict, conflicts without any aren't here
+    fixes: Vec<(ModConflict, Vec<ConflictFix>)>,
+    /// Fixes are asked for once the panel is open, looking for them takes a while
+    fixes_outdated: bool,
+    finding_fixes: bool
+}
+
+impl ConflictsState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "conflicts_modal"),
+            suppressing: None,
+            reason: String::new(),
+            fixes: vec![],
+            fixes_outdated: true,
+            finding_fixes: false,
+        }
+    }
+
+    pub fn set_fixes(&mut self, fixes: Vec<(ModConflict, Vec<ConflictFix>)>) {
+        self.fixes = fixes;
+        self.finding_fixes = false;
+    }
+
+    /// Fixes found so far were for conflicts that might not be there anymore
+    pub fn conflicts_changed(&mut self) {
+        self.fixes.clear();
+        self.fixes_outdated = true;
+    }
+}
+
+fn fixes_for<'a>(fixes: &'a [(ModConflict, Vec<ConflictFix>)], conflict: &ModConflict) -> &'a [ConflictFix] {
+    fixes.iter()
+        .find(|(x, _)| x == conflict)
+        .map_or(&[], |(_, x)| x.as_slice())
+}
+
+/// Conflicts that weren't suppressed, worst first
+pub fn active_conflicts<'a>(conflicts: &'a [ModConflict], config: &Config) -> Vec<&'a ModConflict> {
+    let mut active: Vec<&ModConflict> = conflicts.iter()
+        .filter(|x| !config.is_conflict_suppressed(x))
+        .collect();
+
+    active.sort_by_key(|x| std::cmp::Reverse(x.severity()));
+
+    active
+}
+
+/// Shows how many conflicts there are, colored by the worst one. Nothing is shown if there's none
+pub fn conflicts_button(conflicts_state: &mut ConflictsState, conflicts: &[ModConflict], config: &Config, ui: &mut Ui) {
+    if conflicts.is_empty() && config.suppressed_conflicts.is_empty() {
+        return;
+    }
+
+    let active = active_conflicts(conflicts, config);
+    let text = RichText::new(format!("⚠ {}", active.len()));
+
+    let text = match active.first() {
+        Some(worst) => text.color(severity_color(worst.severity())),
+        None => text.weak()
+    };
+
+    if ui.button(text).on_hover_text(tr("conflicts.hover")).clicked() {
+        conflicts_state.suppressing = None;
+        conflicts_state.fixes_outdated = true;
+        conflicts_state.modal.open();
+    }
+}
+
+fn describe_fix(kind: &FixKind, global_mods: &GlobalModList) -> String {
+    let (key, mod_id, version) = match kind {
+        FixKind::Update(mod_id, version) => ("conflicts.fix_update", mod_id, version),
+        FixKind::Downgrade(mod_id, version) => ("conflicts.fix_downgrade", mod_id, version),
+        FixKind::Install(mod_id, version) => ("conflicts.fix_install", mod_id, version),
+        FixKind::Uninstall((mod_id, version)) => ("conflicts.fix_uninstall", mod_id, version),
+        FixKind::Reinstall((mod_id, version)) => ("conflicts.fix_reinstall", mod_id, version),
+        FixKind::KeepOnly((mod_id, version)) => ("conflicts.fix_keep_only", mod_id, version)
+    };
+
+    tr_args(key, &[
+        ("name", mod_name(global_mods, mod_id)),
+        ("version", version.to_string())
+    ])
+}
+
+fn severity_label(severity: ConflictSeverity) -> String {
+    match severity {
+        ConflictSeverity::Warning => tr("conflicts.warning"),
+        ConflictSeverity::Error => tr("conflicts.error")
+    }
+}
+
+/// Lists conflicts with their severity and fixes found for them, any of them can be suppressed with a reason so they stop being reported
+pub fn conflicts_modal(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let conflicts_state = &mut state.conflicts_state;
+    let global_mods = &state.manifest_mods;
+    let current_config = config.load();
+    let active = active_conflicts(&state.conflicts, &current_config);
+    let mut suppress = None;
+    let mut unsuppress = None;
+    let mut apply_fix = None;
+
+    if !conflicts_state.modal.is_open() {
+        return;
+    }
+
+    if conflicts_state.fixes_outdated {
+        conflicts_state.fixes_outdated = false;
+        conflicts_state.finding_fixes = true;
+        handle_error(command.blocking_send(ManagerCommand::SuggestFixes), toasts);
+    }
+
+    conflicts_state.modal.show(|ui| {
+        conflicts_state.modal.title(ui, tr("conflicts.title"));
+
+        conflicts_state.modal.frame(ui, |ui| {
+            ScrollArea::vertical()
+                .id_source("conflicts_scroll")
+                .max_height(350.0)
+                .show(ui, |ui| {
+                    if active.is_empty() {
+                        ui.label(RichText::new(tr("conflicts.none")).weak());
+                    } else if conflicts_state.finding_fixes {
+                        ui.small(RichText::new(tr("conflicts.finding_fixes")).weak());
+                    }
+
+                    for conflict in &active {
+                        let severity = conflict.severity();
+
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(severity_label(severity)).strong().color(severity_color(severity)));
+                            ui.label(describe_conflict(conflict, global_mods));
+
+                            if conflicts_state.suppressing.as_ref() != Some(*conflict) && ui.small_button(tr("conflicts.suppress")).clicked() {
+                                conflicts_state.suppressing = Some((*conflict).clone());
+                                conflicts_state.reason.clear();
+                            }
+                        });
+
+                        let fixes = fixes_for(&conflicts_state.fixes, conflict);
+
+                        if !fixes.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(RichText::new(tr("conflicts.fixes")).weak());
+
+                                for fix in fixes {
+                                    let hover = fix.operations.iter()
+                                        .map(|x| describe_operation(x, global_mods))
+                                        .collect::<Vec<String>>()
+                                        .join("\n");
+
+                                    if ui.small_button(describe_fix(&fix.kind, global_mods)).on_hover_text(hover).clicked() {
+                                        apply_fix = Some(fix.operations.clone());
+                                    }
+                                }
+                            });
+                        }
+
+                        if conflicts_state.suppressing.as_ref() == Some(*conflict) {
+                            ui.horizontal(|ui| {
+                                ui.add(TextEdit::singleline(&mut conflicts_state.reason)
+                                    .hint_text(tr("conflicts.reason"))
+                                    .desired_width(300.0));
+
+                                let reason = conflicts_state.reason.trim();
+
+                                if ui.add_enabled(!reason.is_empty(), Button::new(tr("conflicts.confirm"))).clicked() {
+                                    suppress = Some(SuppressedConflict {
+                                        conflict: (*conflict).clone(),
+                                        reason: reason.to_string(),
+                                    });
+                                }
+                            });
+                        }
+                    }
+
+                    if !current_config.suppressed_conflicts.is_empty() {
+                        ui.add_space(5.0);
+                        ui.separator();
+                        ui.strong(tr("conflicts.suppressed"));
+
+                        for suppressed in &current_config.suppressed_conflicts {
+                            let present = state.conflicts.contains(&suppressed.conflict);
+
+                            ui.horizontal(|ui| {
+                                let description = describe_conflict(&suppressed.conflict, global_mods);
+                                ui.label(if present { RichText::new(description) } else { RichText::new(description).weak() });
+
+                                if ui.small_button(tr("conflicts.unsuppress")).clicked() {
+                                    unsuppress = Some(suppressed.conflict.clone());
+                                }
+                            });
+
+                            ui.small(RichText::new(tr_args("conflicts.suppressed_reason", &[("reason", suppressed.reason.clone())])).weak());
+                        }
+                    }
+                });
+        });
+
+        conflicts_state.modal.buttons(ui, |ui| {
+            conflicts_state.modal.button(ui, tr("conflicts.close"));
+        });
+    });
+
+    // Goes through the usual preview, so anything the fix would break still gets shown first
+    if let Some(operations) = apply_fix {
+        conflicts_state.modal.close();
+        handle_error(command.blocking_send(ManagerCommand::PreviewOperations(operations)), toasts);
+    }
+
+    if suppress.is_none() && unsuppress.is_none() {
+        return;
+    }
+
+    let mut new_config = current_config.as_ref().clone();
+
+    if let Some(suppressed) = suppress {
+        new_config.suppressed_conflicts.push(suppressed);
+        conflicts_state.suppressing = None;
+    }
+
+    if let Some(conflict) = unsuppress {
+        new_config.suppressed_conflicts.retain(|x| x.conflict != conflict);
+    }
+
+    // Suppressed conflicts don't get fixes
+    conflicts_state.fixes_outdated = true;
+
+    config.swap(Arc::new(new_config));
+    handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+
+    // Badges in the list depend on what's suppressed
+    state.mod_list_state.invalidate();
+}
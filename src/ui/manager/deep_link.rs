@@ -0,0 +1,101 @@
+use std::str::FromStr;
+use eframe::egui::{Button, Context, RichText};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::ipc::DeepLink;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::manifest::GUID;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+use crate::version::VersionReq;
+
+pub struct DeepLinkState {
+    modal: Modal,
+    /// Mod the opened link wants installed
+    install: Option<GUID>
+}
+
+impl DeepLinkState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "deep_link_modal"),
+            install: None,
+        }
+    }
+
+    pub fn open(&mut self, link: DeepLink) {
+        match link {
+            DeepLink::Install(guid) => self.install = Some(guid),
+            // Doesn't need confirming, mod list just jumps to the mod
+            DeepLink::Show(_) => return
+        }
+
+        self.modal.open();
+    }
+}
+
+/// Confirmation for installs requested by links, manifest might still be loading when link comes in
+pub fn deep_link_modal(state: &mut UIManagerState, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let deep_link_state = &mut state.deep_link_state;
+
+    let Some(guid) = deep_link_state.install.clone() else {
+        return;
+    };
+
+    let mod_list = state.manifest_mods.mod_list.load();
+    let mod_info = mod_list.get(&guid);
+    let installed = state.mod_list.contains_key(&guid);
+
+    deep_link_state.modal.show(|ui| {
+        deep_link_state.modal.title(ui, tr("deep_link.title"));
+
+        deep_link_state.modal.frame(ui, |ui| {
+            match mod_info {
+                Some(mod_info) => {
+                    ui.label(tr_args("deep_link.install", &[("name", mod_info.name.clone())]));
+
+                    ui.add_space(5.0);
+
+                    ui.label(RichText::new(&mod_info.description).weak());
+
+                    if !mod_info.authors.is_empty() {
+                        let mut authors: Vec<&String> = mod_info.authors.keys().collect();
+                        authors.sort();
+
+                        ui.small(tr_args("deep_link.authors", &[("authors", authors.into_iter().cloned().collect::<Vec<_>>().join(", "))]));
+                    }
+
+                    ui.small(RichText::new(&guid).weak());
+
+                    if installed {
+                        ui.add_space(5.0);
+                        ui.label(tr("deep_link.already_installed"));
+                    }
+                }
+                None => {
+                    ui.label(tr_args("deep_link.unknown_mod", &[("guid", guid.clone())]));
+                }
+            }
+        });
+
+        deep_link_state.modal.buttons(ui, |ui| {
+            if deep_link_state.modal.button(ui, tr("deep_link.cancel")).clicked() {
+                deep_link_state.install = None;
+            }
+
+            let can_install = mod_info.is_some() && !installed;
+
+            if ui.add_enabled(can_install, Button::new(tr("deep_link.confirm"))).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::InstallMod {
+                    guid: guid.clone(),
+                    version_req: VersionReq::from_str("*").unwrap(),
+                }), toasts);
+
+                deep_link_state.install = None;
+                deep_link_state.modal.close();
+            }
+        });
+    });
+}
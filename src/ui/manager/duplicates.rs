@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+use eframe::egui::{Context, RichText, ScrollArea};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::install::{ModConflict, ModInstallOperations, ModMap};
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::manifest::GUID;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+use crate::version::Version;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DuplicateResolution {
+    /// Other versions get deleted
+    Uninstall,
+    /// Other versions stay on disk, but get disabled
+    Disable
+}
+
+pub struct DuplicatesState {
+    modal: Modal,
+    /// Mods that have more than one version installed
+    duplicates: Vec<GUID>,
+    /// Mods user decided not to deal with for this session
+    skipped: HashSet<GUID>,
+    current: Option<GUID>,
+    keep: Option<Version>,
+    resolution: DuplicateResolution
+}
+
+impl DuplicatesState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "duplicates_modal"),
+            duplicates: vec![],
+            skipped: Default::default(),
+            current: None,
+            keep: None,
+            resolution: DuplicateResolution::Uninstall,
+        }
+    }
+
+    pub fn set_conflicts(&mut self, conflicts: &[ModConflict]) {
+        self.duplicates = conflicts.iter()
+            .filter_map(|x| match x {
+                ModConflict::VersionConflict(guid) => Some(guid.clone()),
+                _ => None
+            })
+            .collect();
+
+        self.duplicates.sort();
+    }
+
+    fn next_pending(&self) -> Option<&GUID> {
+        self.duplicates.iter().find(|x| !self.skipped.contains(*x))
+    }
+}
+
+/// Operations that leave only the kept version enabled
+pub fn build_resolution_operations(mod_id: &str, versions: &[Version], keep: &Version, resolution: DuplicateResolution) -> Vec<ModInstallOperations> {
+    let mut operations: Vec<ModInstallOperations> = versions.iter()
+        .filter(|x| *x != keep)
+        .map(|x| {
+            let id_version = (mod_id.to_string(), x.clone());
+
+            match resolution {
+                DuplicateResolution::Uninstall => ModInstallOperations::UninstallMod(id_version),
+                DuplicateResolution::Disable => ModInstallOperations::DisableMod(id_version),
+            }
+        })
+        .collect();
+
+    operations.push(ModInstallOperations::EnableMod((mod_id.to_string(), keep.clone())));
+
+    operations
+}
+
+fn sorted_versions(mod_map: &ModMap, guid: &str) -> Vec<Version> {
+    mod_map.installed_versions(guid).into_iter()
+        .rev()
+        .cloned()
+        .collect()
+}
+
+pub fn duplicates_modal(state: &mut UIManagerState, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let duplicates_state = &mut state.duplicates_state;
+
+    // Picking up next mod when nothing is being shown
+    if !duplicates_state.modal.is_open() {
+        let Some(guid) = duplicates_state.next_pending().cloned() else {
+            return;
+        };
+
+        if state.mod_list.installed_versions(&guid).len() < 2 {
+            return;
+        }
+
+        duplicates_state.keep = sorted_versions(&state.mod_list, &guid).into_iter().next();
+        duplicates_state.resolution = DuplicateResolution::Uninstall;
+        duplicates_state.current = Some(guid);
+        duplicates_state.modal.open();
+    }
+
+    let Some(guid) = duplicates_state.current.clone() else {
+        return;
+    };
+
+    let versions = sorted_versions(&state.mod_list, &guid);
+    let name = state.manifest_mods.mod_list.load().get(&guid)
+        .map_or_else(|| guid.clone(), |x| x.name.clone());
+
+    duplicates_state.modal.show(|ui| {
+        duplicates_state.modal.title(ui, tr("duplicates.title"));
+
+        duplicates_state.modal.frame(ui, |ui| {
+            ui.label(tr_args("duplicates.description", &[("name", name.clone())]));
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("duplicates_versions_scroll")
+                .max_height(250.0)
+                .show(ui, |ui| {
+                    for (index, version) in versions.iter().enumerate() {
+                        let label = if index == 0 {
+                            format!("v{} ({})", version, tr("duplicates.newest"))
+                        } else {
+                            format!("v{}", version)
+                        };
+
+                        ui.radio_value(&mut duplicates_state.keep, Some(version.clone()), RichText::new(label).strong());
+
+                        if let Some(file) = state.mod_list.file(&guid, version) {
+                            ui.indent(version.to_string(), |ui| {
+                                for artifact in &file.files {
+                                    ui.small(artifact.file_path.to_string_lossy().to_string());
+                                    ui.small(RichText::new(&artifact.file_hash).weak());
+                                }
+                            });
+                        }
+                    }
+                });
+
+            ui.add_space(5.0);
+
+            ui.radio_value(&mut duplicates_state.resolution, DuplicateResolution::Uninstall, tr("duplicates.uninstall_others"));
+            ui.radio_value(&mut duplicates_state.resolution, DuplicateResolution::Disable, tr("duplicates.disable_others"));
+        });
+
+        duplicates_state.modal.buttons(ui, |ui| {
+            if duplicates_state.modal.suggested_button(ui, tr("duplicates.apply")).clicked() {
+                if let Some(keep) = &duplicates_state.keep {
+                    let operations = build_resolution_operations(&guid, &versions, keep, duplicates_state.resolution);
+                    handle_error(command.blocking_send(ManagerCommand::PreviewOperations(operations)), toasts);
+                }
+
+                // Mod comes back with the next conflicts event if something went wrong
+                duplicates_state.duplicates.retain(|x| x != &guid);
+                duplicates_state.current = None;
+            }
+
+            if duplicates_state.modal.button(ui, tr("duplicates.skip")).clicked() {
+                duplicates_state.skipped.insert(guid.clone());
+                duplicates_state.current = None;
+            }
+        });
+    });
+}
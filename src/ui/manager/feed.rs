@@ -0,0 +1,88 @@
+use eframe::egui::{Align, Button, Layout, RichText, Ui};
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::feed::FeedEntry;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::ui::manager::UIManagerState;
+use crate::utils::{handle_error, time_ago};
+use crate::version::VersionReq;
+
+/// Lines of changelog shown under each release
+const CHANGELOG_LINES: usize = 3;
+
+#[derive(Default)]
+pub struct FeedState {
+    pub entries: Vec<FeedEntry>
+}
+
+/// Beginning of the changelog, with an ellipsis if there's more of it
+fn changelog_snippet(changelog: &str) -> String {
+    let lines: Vec<&str> = changelog.trim().lines()
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .collect();
+
+    let mut snippet = lines.iter().take(CHANGELOG_LINES).copied().collect::<Vec<_>>().join("\n");
+
+    if lines.len() > CHANGELOG_LINES {
+        snippet.push_str("\n…");
+    }
+
+    snippet
+}
+
+/// Versions that showed up in manifests lately, across all mods
+pub fn feed_ui(state: &mut UIManagerState, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    ui.heading(tr("tabs.whats_new"));
+
+    ui.add_space(5.0);
+
+    let mod_list = state.manifest_mods.mod_list.load();
+
+    let entries: Vec<_> = state.feed_state.entries.iter()
+        .filter_map(|entry| {
+            let mod_info = mod_list.get(&entry.mod_id)?;
+            Some((entry, mod_info, mod_info.versions.get(&entry.version)?))
+        })
+        .collect();
+
+    if entries.is_empty() {
+        ui.label(RichText::new(tr("feed.empty")).weak());
+        return;
+    }
+
+    for (entry, mod_info, version_info) in entries {
+        let installed = state.mod_list.installed_version(&entry.mod_id);
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(&mod_info.name).strong());
+            ui.small(format!("v{}", entry.version));
+            ui.small(RichText::new(time_ago(entry.first_seen)).weak());
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                let text = match installed {
+                    Some(installed) if *installed >= entry.version => {
+                        ui.add_enabled(false, Button::new(tr("feed.installed")));
+                        return;
+                    }
+                    Some(installed) => tr_args("feed.update", &[("version", installed.to_string())]),
+                    None => tr("feed.install")
+                };
+
+                if ui.button(text).clicked() {
+                    handle_error(command.blocking_send(ManagerCommand::InstallMod {
+                        guid: entry.mod_id.clone(),
+                        version_req: VersionReq::at_least(&entry.version),
+                    }), toasts);
+                }
+            });
+        });
+
+        if let Some(changelog) = version_info.changelog.as_deref().filter(|x| !x.trim().is_empty()) {
+            ui.label(RichText::new(changelog_snippet(changelog)).weak().small());
+        }
+
+        ui.separator();
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use eframe::egui::{Align, Button, ColorImage, Context, ImageButton, Layout, RichText, ScrollArea, TextureHandle, TextureOptions, Ui, vec2};
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::utils::handle_error;
+
+/// Height of the thumbnails under the main image
+const THUMBNAIL_HEIGHT: f32 = 60.0;
+
+pub enum GalleryImage {
+    Loading,
+    /// Downloaded, but not turned into a texture yet
+    Downloaded(Vec<u8>),
+    Loaded(TextureHandle),
+    Failed
+}
+
+/// Images found in README, textures stay cached between modal opens
+#[derive(Default)]
+pub struct GalleryState {
+    pub links: Vec<String>,
+    pub current: usize,
+    images: HashMap<String, GalleryImage>
+}
+
+impl GalleryState {
+    pub fn set_links(&mut self, links: Vec<String>) {
+        self.links = links;
+        self.current = 0;
+    }
+
+    pub fn image_loaded(&mut self, url: String, data: Option<Vec<u8>>) {
+        self.images.insert(url, data.map_or(GalleryImage::Failed, GalleryImage::Downloaded));
+    }
+
+    /// Returns texture of the image if it's ready, asks manager to download it otherwise
+    fn texture(&mut self, ctx: &Context, url: &str, toasts: &mut Toasts, command: &Sender<ManagerCommand>) -> Option<TextureHandle> {
+        let entry = self.images.entry(url.to_string()).or_insert_with(|| {
+            handle_error(command.blocking_send(ManagerCommand::FetchImage(url.to_string())), toasts);
+            GalleryImage::Loading
+        });
+
+        if let GalleryImage::Downloaded(data) = entry {
+            *entry = match image::load_from_memory(data) {
+                Ok(decoded) => {
+                    let decoded = decoded.to_rgba8();
+                    let size = [decoded.width() as usize, decoded.height() as usize];
+                    let color_image = ColorImage::from_rgba_unmultiplied(size, decoded.as_raw());
+
+                    GalleryImage::Loaded(ctx.load_texture(url, color_image, TextureOptions::LINEAR))
+                }
+                Err(_) => GalleryImage::Failed
+            };
+        }
+
+        match entry {
+            GalleryImage::Loaded(texture) => Some(texture.clone()),
+            _ => None
+        }
+    }
+
+    fn is_failed(&self, url: &str) -> bool {
+        matches!(self.images.get(url), Some(GalleryImage::Failed))
+    }
+}
+
+pub fn gallery_ui(gallery: &mut GalleryState, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    if gallery.links.is_empty() {
+        ui.centered_and_justified(|ui| {
+            ui.heading(tr("gallery.no_images"));
+        });
+        return;
+    }
+
+    let ctx = ui.ctx().clone();
+    let url = gallery.links[gallery.current].clone();
+    let available = ui.available_size() - vec2(0.0, THUMBNAIL_HEIGHT + 40.0);
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(gallery.current > 0, Button::new("◀")).clicked() {
+            gallery.current -= 1;
+        }
+
+        ui.label(tr_args("gallery.position", &[
+            ("current", (gallery.current + 1).to_string()),
+            ("total", gallery.links.len().to_string())
+        ]));
+
+        if ui.add_enabled(gallery.current + 1 < gallery.links.len(), Button::new("▶")).clicked() {
+            gallery.current += 1;
+        }
+
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            if ui.button(tr("gallery.open_in_browser")).clicked() {
+                handle_error(open::that(&url), toasts);
+            }
+        });
+    });
+
+    ui.allocate_ui(available, |ui| {
+        ui.centered_and_justified(|ui| {
+            match gallery.texture(&ctx, &url, toasts, command) {
+                Some(texture) => {
+                    let size = texture.size_vec2();
+                    let scale = (available.x / size.x).min(available.y / size.y).min(1.0);
+                    ui.image(&texture, size * scale);
+                }
+                None if gallery.is_failed(&url) => {
+                    ui.label(RichText::new(tr("gallery.failed")).weak());
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+        });
+    });
+
+    ScrollArea::horizontal()
+        .id_source("gallery_thumbnails")
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                for index in 0..gallery.links.len() {
+                    let link = gallery.links[index].clone();
+
+                    match gallery.texture(&ctx, &link, toasts, command) {
+                        Some(texture) => {
+                            let size = texture.size_vec2();
+                            let thumbnail = size * (THUMBNAIL_HEIGHT / size.y);
+
+                            if ui.add(ImageButton::new(&texture, thumbnail).selected(index == gallery.current)).clicked() {
+                                gallery.current = index;
+                            }
+                        }
+                        None => {
+                            if ui.selectable_label(index == gallery.current, (index + 1).to_string()).clicked() {
+                                gallery.current = index;
+                            }
+                        }
+                    }
+                }
+            });
+        });
+}
@@ -0,0 +1,164 @@
+use std::str::FromStr;
+use eframe::egui::{Align, Button, Label, Layout, RichText, ScrollArea, TextEdit, Ui, vec2, Widget};
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::manifest::{Artifact, GlobalModList, GUID, Mod, ModVersion};
+use crate::ui::manager::UIManagerState;
+use crate::utils::{format_size, handle_error};
+use crate::version::{Version, VersionReq};
+
+/// Height of a single catalog row, rows have to be the same height for virtualized scrolling
+const ROW_HEIGHT: f32 = 48.0;
+
+/// Catalog row data, built once per manifest update instead of every frame
+struct CatalogEntry {
+    guid: GUID,
+    name: String,
+    description: String,
+    category: String,
+    download_count: Option<u64>,
+    latest: Option<(Version, Vec<Artifact>)>,
+    /// Lowercase name, guid and description for filtering
+    search_text: String
+}
+
+impl CatalogEntry {
+    fn new(guid: &GUID, mod_info: &Mod) -> Self {
+        Self {
+            guid: guid.clone(),
+            name: mod_info.name.clone(),
+            description: mod_info.description.lines().next().unwrap_or_default().to_string(),
+            category: mod_info.category.to_string(),
+            download_count: mod_info.download_count,
+            latest: latest_version(mod_info).map(|(version, info)| (version.clone(), info.artifacts.clone())),
+            search_text: format!("{}\n{}\n{}", mod_info.name, guid, mod_info.description).to_lowercase(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct GetModsState {
+    filter: String,
+    entries: Vec<CatalogEntry>,
+    /// Generation of the global mod list that entries were built from
+    generation: Option<u64>,
+    /// Indices of entries matching the filter
+    visible: Vec<usize>,
+    visible_filter: Option<String>
+}
+
+impl GetModsState {
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+    }
+
+    fn refresh(&mut self, global_mods: &GlobalModList, generation: u64) {
+        if self.generation != Some(generation) {
+            let mod_list = global_mods.mod_list.load();
+
+            self.entries = mod_list.iter()
+                .map(|(guid, mod_info)| CatalogEntry::new(guid, mod_info))
+                .collect();
+            self.entries.sort_by_cached_key(|x| x.name.to_lowercase());
+
+            self.generation = Some(generation);
+            self.visible_filter = None;
+        }
+
+        if self.visible_filter.as_ref() != Some(&self.filter) {
+            let filter = self.filter.to_lowercase();
+
+            self.visible = self.entries.iter()
+                .enumerate()
+                .filter(|(_, x)| x.search_text.contains(&filter))
+                .map(|(index, _)| index)
+                .collect();
+
+            self.visible_filter = Some(self.filter.clone());
+        }
+    }
+}
+
+/// Newest stable version, or newest prerelease if mod doesn't have stable ones
+fn latest_version(mod_info: &Mod) -> Option<(&Version, &ModVersion)> {
+    mod_info.versions.iter()
+        .filter(|(x, _)| !x.is_prerelease())
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .or_else(|| mod_info.versions.iter().max_by(|(a, _), (b, _)| a.cmp(b)))
+}
+
+pub fn get_mods_ui(state: &mut UIManagerState, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    ui.horizontal(|ui| {
+        TextEdit::singleline(&mut state.get_mods_state.filter)
+            .hint_text(tr("get_mods.search"))
+            .desired_width(250.0)
+            .ui(ui);
+
+        ui.small(RichText::new(tr_args("get_mods.available", &[("count", state.manifest_mod_count.to_string())])).weak());
+    });
+
+    ui.separator();
+
+    state.get_mods_state.refresh(&state.manifest_mods, state.manifest_generation);
+
+    let get_mods_state = &state.get_mods_state;
+
+    if get_mods_state.visible.is_empty() {
+        ui.label(RichText::new(tr("get_mods.nothing_found")).weak());
+        return;
+    }
+
+    // Only rows that are on screen get built, sizes are asked for only those as well
+    ScrollArea::vertical()
+        .id_source("get_mods_scroll")
+        .auto_shrink([false; 2])
+        .show_rows(ui, ROW_HEIGHT, get_mods_state.visible.len(), |ui, range| {
+            for index in range {
+                let entry = &get_mods_state.entries[get_mods_state.visible[index]];
+                let installed = state.mod_list.contains_key(&entry.guid);
+
+                ui.allocate_ui_with_layout(vec2(ui.available_width(), ROW_HEIGHT), Layout::top_down(Align::Min), |ui| {
+                    ui.set_height(ROW_HEIGHT);
+
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(&entry.name).strong());
+
+                        if let Some((version, _)) = &entry.latest {
+                            ui.small(format!("v{}", version));
+                        }
+
+                        ui.small(RichText::new(&entry.category).weak());
+
+                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                            if installed {
+                                ui.add_enabled(false, Button::new(tr("get_mods.installed")));
+                            } else if ui.add_enabled(entry.latest.is_some(), Button::new(tr("get_mods.install"))).clicked() {
+                                handle_error(command.blocking_send(ManagerCommand::InstallMod {
+                                    guid: entry.guid.clone(),
+                                    version_req: VersionReq::from_str("*").unwrap(),
+                                }), toasts);
+                            }
+
+                            if let Some(downloads) = entry.download_count {
+                                ui.small(tr_args("get_mods.downloads", &[("count", downloads.to_string())]));
+                            }
+
+                            if let Some((_, artifacts)) = &entry.latest {
+                                if let Some(size) = state.artifact_sizes.total(artifacts) {
+                                    ui.small(format_size(size));
+                                }
+                            }
+                        });
+                    });
+
+                    Label::new(RichText::new(&entry.description).weak().small())
+                        .wrap(false)
+                        .ui(ui);
+
+                    ui.separator();
+                });
+            }
+        });
+}
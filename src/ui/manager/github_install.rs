@@ -0,0 +1,190 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use eframe::egui::{Button, Color32, ComboBox, Context, RichText, ScrollArea, Spinner, TextEdit, Ui};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::config::Config;
+use crate::github::GitHubRelease;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::ui::manager::UIManagerState;
+use crate::utils::{format_size, handle_error};
+
+pub struct GitHubInstallState {
+    modal: Modal,
+    repo_url: String,
+    /// Scan location the asset goes into
+    location: Option<PathBuf>,
+    fetching: bool,
+    /// Repository link the releases are for, with releases or why they couldn't be listed
+    releases: Option<(String, Result<Vec<GitHubRelease>, String>)>
+}
+
+impl GitHubInstallState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "github_install_modal"),
+            repo_url: String::new(),
+            location: None,
+            fetching: false,
+            releases: None,
+        }
+    }
+
+    pub fn set_releases(&mut self, repo_url: String, releases: Result<Vec<GitHubRelease>, String>) {
+        self.fetching = false;
+        self.releases = Some((repo_url, releases));
+    }
+}
+
+pub fn github_install_button(github_state: &mut GitHubInstallState, ui: &mut Ui) {
+    if ui.button(tr("github_install.open")).on_hover_text(tr("github_install.hover")).clicked() {
+        github_state.modal.open();
+    }
+}
+
+/// Asset picked for installing, with the tag of its release
+fn releases_ui(releases: &[GitHubRelease], ui: &mut Ui) -> Option<(String, String)> {
+    let mut picked = None;
+
+    if releases.is_empty() {
+        ui.label(RichText::new(tr("github_install.no_releases")).weak());
+    }
+
+    for release in releases {
+        ui.horizontal(|ui| {
+            ui.strong(&release.tag_name);
+
+            if let Some(name) = release.name.as_ref().filter(|x| **x != release.tag_name) {
+                ui.label(name);
+            }
+
+            if release.prerelease {
+                ui.label(RichText::new(tr("github_install.prerelease")).color(Color32::GOLD));
+            }
+        });
+
+        let mut has_dll = false;
+
+        for asset in release.dll_assets() {
+            has_dll = true;
+
+            ui.horizontal(|ui| {
+                ui.add_space(10.0);
+
+                if ui.small_button(tr("github_install.install")).clicked() {
+                    picked = Some((release.tag_name.clone(), asset.browser_download_url.clone()));
+                }
+
+                ui.label(&asset.name);
+                ui.small(RichText::new(format_size(asset.size)).weak());
+            });
+        }
+
+        if !has_dll {
+            ui.horizontal(|ui| {
+                ui.add_space(10.0);
+                ui.small(RichText::new(tr("github_install.no_dll")).weak());
+            });
+        }
+
+        ui.add_space(3.0);
+    }
+
+    picked
+}
+
+/// Lists releases of a repository that isn't in any manifest and installs one of their DLLs as an unmanaged mod
+pub fn github_install_modal(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let locations: Vec<PathBuf> = config.load().install_profile.enabled_locations().cloned().collect();
+    let github_state = &mut state.github_install_state;
+    let mut fetch = false;
+    let mut picked = None;
+
+    if !github_state.modal.is_open() {
+        return;
+    }
+
+    if github_state.location.as_ref().map_or(true, |x| !locations.contains(x)) {
+        github_state.location = locations.first().cloned();
+    }
+
+    github_state.modal.show(|ui| {
+        github_state.modal.title(ui, tr("github_install.title"));
+
+        github_state.modal.frame(ui, |ui| {
+            ui.label(tr("github_install.description"));
+
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                ui.add(TextEdit::singleline(&mut github_state.repo_url)
+                    .hint_text("https://github.com/owner/repository")
+                    .desired_width(300.0));
+
+                let repo_url = github_state.repo_url.trim();
+
+                if ui.add_enabled(!repo_url.is_empty() && !github_state.fetching, Button::new(tr("github_install.fetch"))).clicked() {
+                    fetch = true;
+                }
+            });
+
+            ComboBox::from_label(tr("sideload.location"))
+                .selected_text(github_state.location.as_ref().map_or(String::new(), |x| x.to_string_lossy().to_string()))
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for location in &locations {
+                        ui.selectable_value(&mut github_state.location, Some(location.clone()), location.to_string_lossy());
+                    }
+                });
+
+            ui.add_space(5.0);
+
+            if github_state.fetching {
+                ui.horizontal(|ui| {
+                    ui.add(Spinner::new());
+                    ui.label(tr("github_install.fetching"));
+                });
+                return;
+            }
+
+            match &github_state.releases {
+                Some((repo_url, Ok(releases))) => {
+                    ScrollArea::vertical()
+                        .id_source("github_releases_scroll")
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            picked = releases_ui(releases, ui).map(|(tag, asset_url)| (repo_url.clone(), tag, asset_url));
+                        });
+                }
+                Some((_, Err(e))) => {
+                    ui.label(RichText::new(tr_args("github_install.failed", &[("error", e.clone())])).color(Color32::LIGHT_RED));
+                }
+                None => {}
+            }
+        });
+
+        github_state.modal.buttons(ui, |ui| {
+            github_state.modal.button(ui, tr("github_install.close"));
+        });
+    });
+
+    if fetch {
+        github_state.fetching = true;
+        github_state.releases = None;
+        handle_error(command.blocking_send(ManagerCommand::FetchGitHubReleases(github_state.repo_url.trim().to_string())), toasts);
+    }
+
+    if let (Some((repo_url, tag, asset_url)), Some(location)) = (picked, github_state.location.clone()) {
+        handle_error(command.blocking_send(ManagerCommand::InstallGitHubAsset {
+            repo_url,
+            tag,
+            asset_url,
+            location,
+        }), toasts);
+
+        github_state.modal.close();
+    }
+}
@@ -0,0 +1,179 @@
+use eframe::egui::{Context, Key, RichText, TextEdit, TopBottomPanel, Ui, Widget};
+use strum::IntoEnumIterator;
+use crate::install::ModMap;
+use crate::locale::{tr, tr_args};
+use crate::manifest::{GlobalModList, GUID};
+use crate::ui::manager::settings::SettingsSection;
+use crate::ui::manager::{ManagerTabs, UIManagerState};
+
+/// How many results are shown per group
+const MAX_RESULTS: usize = 5;
+
+#[derive(Default)]
+pub struct GlobalSearchState {
+    query: String,
+    focus: bool
+}
+
+impl GlobalSearchState {
+    pub fn focus(&mut self) {
+        self.focus = true;
+    }
+}
+
+/// Matches from every tab, mods are GUID and name
+#[derive(Default)]
+pub struct SearchResults {
+    pub installed: Vec<(GUID, String)>,
+    pub catalog: Vec<(GUID, String)>,
+    pub settings: Vec<(SettingsSection, String)>
+}
+
+impl SearchResults {
+    pub fn is_empty(&self) -> bool {
+        self.installed.is_empty() && self.catalog.is_empty() && self.settings.is_empty()
+    }
+}
+
+/// Where clicking a result takes the user
+enum SearchJump {
+    Installed(GUID),
+    Catalog(GUID),
+    Settings(SettingsSection)
+}
+
+/// Searches installed mods, the catalog and settings section names at once
+pub fn search_everything(query: &str, mod_map: &ModMap, global_mods: &GlobalModList) -> SearchResults {
+    let query = query.trim().to_lowercase();
+
+    if query.is_empty() {
+        return SearchResults::default();
+    }
+
+    let mod_list = global_mods.mod_list.load();
+    let matches = |text: &str| text.to_lowercase().contains(&query);
+
+    let mut installed: Vec<(GUID, String)> = mod_map.keys()
+        .map(|guid| (guid.clone(), mod_list.get(guid).map_or_else(|| guid.clone(), |x| x.name.clone())))
+        .filter(|(guid, name)| matches(name) || matches(guid))
+        .collect();
+    installed.sort_by_cached_key(|(_, name)| name.to_lowercase());
+
+    let mut catalog: Vec<(GUID, String)> = mod_list.iter()
+        .filter(|(guid, mod_info)| matches(&mod_info.name) || matches(guid))
+        .map(|(guid, mod_info)| (guid.clone(), mod_info.name.clone()))
+        .collect();
+    catalog.sort_by_cached_key(|(_, name)| name.to_lowercase());
+
+    let settings = SettingsSection::iter()
+        .filter_map(|section| {
+            let title = tr(section.title_key());
+
+            if matches(&title) {
+                return Some((section, title));
+            }
+
+            section.setting_keys().iter()
+                .map(|key| tr(key))
+                .find(|label| matches(label))
+                .map(|label| (section, format!("{} › {}", title, label)))
+        })
+        .collect();
+
+    SearchResults {
+        installed,
+        catalog,
+        settings,
+    }
+}
+
+/// Heading and first few results of a group, returns the clicked one
+fn result_group<T: Clone>(ui: &mut Ui, title: String, results: &[(T, String)]) -> Option<T> {
+    if results.is_empty() {
+        return None;
+    }
+
+    let mut clicked = None;
+
+    ui.label(RichText::new(title).strong());
+
+    for (value, label) in results.iter().take(MAX_RESULTS) {
+        if ui.link(label).clicked() {
+            clicked = Some(value.clone());
+        }
+    }
+
+    if results.len() > MAX_RESULTS {
+        ui.small(RichText::new(tr_args("global_search.more", &[("count", (results.len() - MAX_RESULTS).to_string())])).weak());
+    }
+
+    clicked
+}
+
+/// Search field above everything else, results are grouped by tab they're in
+pub fn global_search_ui(state: &mut UIManagerState, ctx: &Context) {
+    let mut jump = None;
+
+    TopBottomPanel::top("global_search")
+        .show(ctx, |ui| {
+            let search_state = &mut state.global_search_state;
+
+            let response = TextEdit::singleline(&mut search_state.query)
+                .hint_text(tr("global_search.hint"))
+                .desired_width(f32::INFINITY)
+                .ui(ui);
+
+            if search_state.focus {
+                response.request_focus();
+                search_state.focus = false;
+            }
+
+            if response.has_focus() && ui.input(|i| i.key_pressed(Key::Escape)) {
+                search_state.query.clear();
+            }
+
+            if search_state.query.trim().is_empty() {
+                return;
+            }
+
+            let results = search_everything(&search_state.query, &state.mod_list, &state.manifest_mods);
+
+            if results.is_empty() {
+                ui.label(RichText::new(tr("global_search.nothing_found")).weak());
+                return;
+            }
+
+            ui.columns(3, |columns| {
+                if let Some(guid) = result_group(&mut columns[0], tr("tabs.installed_mods"), &results.installed) {
+                    jump = Some(SearchJump::Installed(guid));
+                }
+
+                if let Some(guid) = result_group(&mut columns[1], tr("tabs.get_mods"), &results.catalog) {
+                    jump = Some(SearchJump::Catalog(guid));
+                }
+
+                if let Some(section) = result_group(&mut columns[2], tr("tabs.settings"), &results.settings) {
+                    jump = Some(SearchJump::Settings(section));
+                }
+            });
+        });
+
+    if let Some(jump) = jump {
+        match jump {
+            SearchJump::Installed(guid) => {
+                state.current_tab = ManagerTabs::InstalledMods;
+                state.mod_list_state.select_mod(&guid);
+            }
+            SearchJump::Catalog(guid) => {
+                state.current_tab = ManagerTabs::GetMods;
+                state.get_mods_state.set_filter(&guid);
+            }
+            SearchJump::Settings(section) => {
+                state.current_tab = ManagerTabs::Settings;
+                state.settings_state.jump_to(section);
+            }
+        }
+
+        state.global_search_state.query.clear();
+    }
+}
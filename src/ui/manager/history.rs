@@ -0,0 +1,47 @@
+use eframe::egui::{Align, Layout, RichText, Ui};
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::history::{HistoryEntry, UNDOABLE_ENTRIES};
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::ui::manager::plan_preview::describe_operation;
+use crate::ui::manager::UIManagerState;
+use crate::utils::{handle_error, time_ago};
+
+fn entry_ui(entry: &HistoryEntry, undoable: bool, state: &UIManagerState, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    ui.horizontal(|ui| {
+        ui.small(time_ago(entry.timestamp));
+
+        let description = describe_operation(&entry.operation, &state.manifest_mods);
+
+        if entry.undone {
+            ui.label(RichText::new(description).strikethrough().weak());
+            ui.small(tr("history.undone"));
+        } else {
+            ui.label(description);
+        }
+
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            if undoable && ui.button(tr("history.undo")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::UndoOperation(entry.id)), toasts);
+            }
+        });
+    });
+}
+
+pub fn history_ui(state: &mut UIManagerState, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    ui.heading(tr("tabs.history"));
+    ui.label(tr_args("history.description", &[("count", UNDOABLE_ENTRIES.to_string())]));
+
+    ui.add_space(5.0);
+
+    if state.history.is_empty() {
+        ui.label(RichText::new(tr("history.empty")).weak());
+        return;
+    }
+
+    for (index, entry) in state.history.iter().rev().enumerate() {
+        let undoable = index < UNDOABLE_ENTRIES && entry.is_recoverable();
+        entry_ui(entry, undoable, state, ui, toasts, command);
+    }
+}
@@ -0,0 +1,113 @@
+use eframe::egui::{Color32, Context, RichText, ScrollArea};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::install::ModConflict;
+use crate::launch::LaunchProblem;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::manifest::{GlobalModList, GUID};
+use crate::ui::manager::plan_preview::{describe_conflict, mod_name};
+use crate::ui::manager::{ManagerTabs, UIManagerState};
+use crate::utils::handle_error;
+
+pub struct LaunchCheckState {
+    modal: Modal,
+    problems: Vec<LaunchProblem>
+}
+
+impl LaunchCheckState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "launch_check_modal"),
+            problems: vec![],
+        }
+    }
+
+    pub fn open(&mut self, problems: Vec<LaunchProblem>) {
+        self.problems = problems;
+        self.modal.open();
+    }
+}
+
+fn describe_problem(problem: &LaunchProblem, global_mods: &GlobalModList) -> String {
+    let optional = |x: &Option<i32>| x.map_or_else(|| "-".to_string(), |x| x.to_string());
+
+    match problem {
+        LaunchProblem::ModLoaderMissing => tr("launch_check.mod_loader_missing"),
+        LaunchProblem::VersionConflict(guid) => describe_conflict(&ModConflict::VersionConflict(guid.clone()), global_mods),
+        LaunchProblem::DirectConflict { keep, disable } => tr_args("launch_check.direct_conflict", &[
+            ("name", format!("{} v{}", mod_name(global_mods, &disable.0), disable.1)),
+            ("other", format!("{} v{}", mod_name(global_mods, &keep.0), keep.1))
+        ]),
+        LaunchProblem::Conflict(conflict) => describe_conflict(conflict, global_mods),
+        LaunchProblem::DataPathMissing(path) => tr_args("launch_check.data_path_missing", &[("path", path.to_string_lossy().to_string())]),
+        LaunchProblem::CachePathMissing(path) => tr_args("launch_check.cache_path_missing", &[("path", path.to_string_lossy().to_string())]),
+        LaunchProblem::InvalidResolution(width, height) => tr_args("launch_check.invalid_resolution", &[
+            ("width", optional(width)),
+            ("height", optional(height))
+        ]),
+    }
+}
+
+/// Mod the problem is about, if there's one to show in the mod list
+fn problem_mod(problem: &LaunchProblem) -> Option<&GUID> {
+    match problem {
+        LaunchProblem::VersionConflict(guid) => Some(guid),
+        LaunchProblem::DirectConflict { disable, .. } => Some(&disable.0),
+        LaunchProblem::Conflict(conflict) => Some(conflict.mod_id()),
+        _ => None
+    }
+}
+
+pub fn launch_check_modal(state: &mut UIManagerState, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let check_state = &mut state.launch_check_state;
+    let global_mods = &state.manifest_mods;
+    let has_direct_conflicts = check_state.problems.iter().any(|x| matches!(x, LaunchProblem::DirectConflict { .. }));
+    let mut show_mod = None;
+
+    check_state.modal.show(|ui| {
+        check_state.modal.title(ui, tr("launch_check.title"));
+
+        check_state.modal.frame(ui, |ui| {
+            ui.label(tr("launch_check.description"));
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("launch_check_scroll")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for problem in &check_state.problems {
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(format!("⚠ {}", describe_problem(problem, global_mods))).color(Color32::LIGHT_RED));
+
+                            if let Some(guid) = problem_mod(problem) {
+                                if ui.small_button(tr("launch_check.show")).clicked() {
+                                    show_mod = Some(guid.clone());
+                                }
+                            }
+                        });
+                    }
+                });
+        });
+
+        check_state.modal.buttons(ui, |ui| {
+            if has_direct_conflicts && check_state.modal.button(ui, tr("launch_check.disable_conflicts")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::LaunchDisablingConflicts), toasts);
+            }
+
+            if check_state.modal.button(ui, tr("launch_check.launch_anyway")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::LaunchAnyway), toasts);
+            }
+
+            check_state.modal.suggested_button(ui, tr("launch_check.cancel"));
+        });
+    });
+
+    if let Some(guid) = show_mod {
+        check_state.modal.close();
+        state.current_tab = ManagerTabs::InstalledMods;
+        state.mod_list_state.select_mod(&guid);
+    }
+}
@@ -1,16 +1,22 @@
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 use arc_swap::ArcSwap;
 use dirs::desktop_dir;
-use eframe::egui::{Align2, Button, CollapsingHeader, Color32, ComboBox, Context, Response, RichText, TextEdit, Ui, Vec2, Widget};
+use eframe::egui::{Align2, Button, CollapsingHeader, Color32, ComboBox, Context, CursorIcon, Label, Response, RichText, Sense, TextEdit, Ui, Vec2, Widget};
 use egui_file::FileDialog;
-use egui_toast::Toasts;
+use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
 use strum::IntoEnumIterator;
 use tokio::sync::mpsc::Sender;
 use crate::config::Config;
-use crate::launch::{CinematicTemporalAntiAliasing, Device, DroneCamera, JoinOptions, LaunchOptions, WindowType};
-use crate::manager::ManagerCommand;
+use crate::import::parse_shortcut;
+use crate::locale::{tr, tr_args};
+use crate::launch::{CinematicTemporalAntiAliasing, Device, DroneCamera, JoinOptions, LaunchOptions, LaunchPreset, LaunchProfile, WindowType};
+use crate::install::ModMap;
+use crate::manager::{DataFolderSizes, ManagerCommand};
+use crate::manifest::{GlobalModList, GUID};
 use crate::ui::manager::UIManagerState;
-use crate::utils::{handle_error, optioned_text_field_with_label, text_field_with_label, validation_text_field_with_label};
+use crate::utils::{format_size, handle_error, optioned_text_field_with_label, text_field_with_label, validation_text_field_with_label};
 
 fn mark_changed(state: &mut LauncherState, expr: bool) {
     if expr {
@@ -35,12 +41,38 @@ pub struct LauncherState {
     pub(crate) enable_ctaa: bool,
     data_path_dialog: Option<FileDialog>,
     cache_path_dialog: Option<FileDialog>,
+    import_shortcut_dialog: Option<FileDialog>,
+    /// Name for saving current options as a launch profile
+    new_profile_name: String,
+    /// Instance waiting for stop confirmation, and whether it gets restarted
+    confirm_stop: Option<(u64, bool)>,
+    /// Index of the load order entry being dragged
+    load_order_drag: Option<usize>,
+    confirm_clear_cache: bool,
+}
+
+impl LauncherState {
+    /// Replaces edited options and fills text fields from them
+    pub(crate) fn set_options(&mut self, options: LaunchOptions, changed: bool) {
+        self.enable_owo_str = options.enable_owo.clone().unwrap_or_else(|| "".to_string());
+        self.resolution_width_str = options.resolution_width.clone().map_or_else(|| "".to_string(), |x| x.to_string());
+        self.resolution_height_str = options.resolution_height.clone().map_or_else(|| "".to_string(), |x| x.to_string());
+        self.bootstrap = options.bootstrap.clone().unwrap_or_else(|| "".to_string());
+        self.data_path_str = options.data_path.clone().map_or_else(|| "".to_string(), |x| x.to_string_lossy().to_string());
+        self.cache_path_str = options.cache_path.clone().map_or_else(|| "".to_string(), |x| x.to_string_lossy().to_string());
+        self.watchdog_str = options.watchdog.clone().map_or_else(|| "".to_string(), |x| x.to_string_lossy().to_string());
+        self.config_str = options.config.clone().map_or_else(|| "".to_string(), |x| x.to_string_lossy().to_string());
+        self.enable_ctaa = options.ctaa.is_some();
+        self.temporal_edge_power_str = options.ctaa.as_ref().map_or_else(|| "".to_string(), |x| x.temporal_edge_power.as_ref().map_or_else(|| "".to_string(), |x| x.to_string()));
+        self.aptive_sharpness_str = options.ctaa.as_ref().map_or_else(|| "".to_string(), |x| x.aptive_sharpness.as_ref().map_or_else(|| "".to_string(), |x| x.to_string()));
+        self.cached_launch_options = (options, changed);
+    }
 }
 
 pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
     let launcher_state = &mut state.launcher_state;
 
-    let resp = ComboBox::from_label("Device to launch for")
+    let resp = ComboBox::from_label(tr("launcher.device"))
         .selected_text(launcher_state.cached_launch_options.0.device.to_string())
         .width(200.0)
         .show_ui(ui, |ui| {
@@ -53,19 +85,69 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
 
     ui.add_space(5.0);
 
-    if Button::new(RichText::from("    Launch Neos").size(40.0))
+    if Button::new(RichText::from(format!("    {}", tr("launcher.launch"))).size(40.0))
         .min_size(Vec2::new(300.0, 100.0))
         .ui(ui)
         .clicked() {
+        launch_game(launcher_state, config, toasts, command);
+    }
 
-        save_launch_options(config, launcher_state.cached_launch_options.0.clone());
-        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    ui.horizontal(|ui| {
+        for preset in LaunchPreset::iter() {
+            if ui.button(tr(&format!("launcher.preset.{}", preset.key())))
+                .on_hover_text(tr(&format!("launcher.preset.{}_hint", preset.key())))
+                .clicked() {
+                // Edits are kept like with regular launch, preset itself only applies to this launch
+                save_launch_options(config, launcher_state.cached_launch_options.0.clone());
+                handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+
+                launcher_state.cached_launch_options.1 = false;
+                handle_error(command.blocking_send(ManagerCommand::LaunchNeosPreset(preset)), toasts);
+            }
+        }
+    });
+
+    for instance in &state.game_instances {
+        ui.horizontal(|ui| {
+            let name = instance.profile.clone().unwrap_or_else(|| tr("launcher.main_profile"));
+            ui.label(tr_args("launcher.instance_running", &[("name", name)]));
+
+            // Game could have unsaved work in it, so stopping asks first
+            match launcher_state.confirm_stop {
+                Some((id, restart)) if id == instance.id => {
+                    let question = if restart { "launcher.confirm_restart" } else { "launcher.confirm_stop" };
+                    ui.label(RichText::new(tr(question)).color(Color32::YELLOW));
+
+                    if ui.small_button(tr("launcher.confirm_yes")).clicked() {
+                        handle_error(command.blocking_send(ManagerCommand::StopInstance {
+                            id,
+                            restart,
+                        }), toasts);
+
+                        launcher_state.confirm_stop = None;
+                    }
 
-        launcher_state.cached_launch_options.1 = false;
-        handle_error(command.blocking_send(ManagerCommand::LaunchNeos), toasts);
+                    if ui.small_button(tr("launcher.confirm_no")).clicked() {
+                        launcher_state.confirm_stop = None;
+                    }
+                }
+                _ => {
+                    if ui.small_button(tr("launcher.stop_instance")).clicked() {
+                        launcher_state.confirm_stop = Some((instance.id, false));
+                    }
+
+                    if ui.small_button(tr("launcher.restart_instance")).clicked() {
+                        launcher_state.confirm_stop = Some((instance.id, true));
+                    }
+                }
+            }
+        });
     }
 
-    if Button::new("                                  Make Shortcut")
+    launch_profiles_ui(launcher_state, config, ui, toasts, command);
+    load_order_ui(launcher_state, &state.mod_list, &state.manifest_mods, config, ui, toasts, command);
+
+    if Button::new(format!("                                  {}", tr("launcher.make_shortcut")))
         .min_size(Vec2::new(300.0, 20.0))
         .ui(ui)
         .clicked() {
@@ -85,17 +167,33 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
         launcher_state.shortcut_dialog = Some(dialog);
     }
 
+    if Button::new(format!("                          {}", tr("launcher.import_shortcut")))
+        .min_size(Vec2::new(300.0, 20.0))
+        .ui(ui)
+        .clicked() {
+        let mut dialog = FileDialog::open_file(desktop_dir())
+            .filter(Box::new(|path| path.extension().map_or(false, |x| x.eq_ignore_ascii_case("lnk"))))
+            .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+            .resizable(false)
+            .show_rename(false)
+            .show_new_folder(false);
+
+        dialog.open();
+
+        launcher_state.import_shortcut_dialog = Some(dialog);
+    }
+
     ui.add_space(7.5);
 
-    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.use_mods, "Use mods").changed();
+    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.use_mods, tr("launcher.use_mods")).changed();
     mark_changed(launcher_state, resp);
 
     ui.add_space(7.5);
 
-    CollapsingHeader::new("Display Options")
+    CollapsingHeader::new(tr("launcher.display_options"))
         .default_open(false)
         .show(ui, |ui| {
-            let resp = ComboBox::from_label("Display Mode")
+            let resp = ComboBox::from_label(tr("launcher.display_mode"))
                 .selected_text(launcher_state.cached_launch_options.0.display_mode.to_string())
                 .width(200.0)
                 .show_ui(ui, |ui| {
@@ -108,7 +206,7 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
 
             let resp = validation_text_field_with_label(
                 ui,
-                "Screen width",
+                tr("launcher.screen_width"),
                 200.0,
                 &mut launcher_state.resolution_width_str,
                 &mut launcher_state.cached_launch_options.0.resolution_width
@@ -117,7 +215,7 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
 
             let resp = validation_text_field_with_label(
                 ui,
-                "Screen height",
+                tr("launcher.screen_height"),
                 200.0,
                 &mut launcher_state.resolution_height_str,
                 &mut launcher_state.cached_launch_options.0.resolution_height
@@ -125,13 +223,13 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
             mark_changed(launcher_state, resp);
         });
 
-    CollapsingHeader::new("Data Path Options")
+    CollapsingHeader::new(tr("launcher.data_path_options"))
         .default_open(false)
         .show(ui, |ui| {
             ui.horizontal_top(|ui| {
                 let mut edit = TextEdit::singleline(&mut launcher_state.data_path_str)
                     .desired_width(200.0)
-                    .hint_text("Leave empty to ignore");
+                    .hint_text(tr("common.leave_empty"));
 
                 if edit.ui(ui).changed() {
                     if launcher_state.data_path_str.is_empty() {
@@ -142,7 +240,7 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                     launcher_state.cached_launch_options.1 = true;
                 }
 
-                if ui.button(" Pick location ").clicked() {
+                if ui.button(format!(" {} ", tr("common.pick_location"))).clicked() {
                     let mut dialog = FileDialog::select_folder(launcher_state.cached_launch_options.0.data_path.clone())
                         .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
                         .resizable(false)
@@ -153,13 +251,13 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                     launcher_state.data_path_dialog = Some(dialog);
                 }
 
-                ui.label("Data folder path");
+                ui.label(tr("launcher.data_folder_path"));
             });
 
             ui.horizontal_top(|ui| {
                 let mut edit = TextEdit::singleline(&mut launcher_state.cache_path_str)
                     .desired_width(200.0)
-                    .hint_text("Leave empty to ignore");
+                    .hint_text(tr("common.leave_empty"));
 
                 if edit.ui(ui).changed() {
                     if launcher_state.cache_path_str.is_empty() {
@@ -170,7 +268,7 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                     launcher_state.cached_launch_options.1 = true;
                 }
 
-                if ui.button(" Pick location ").clicked() {
+                if ui.button(format!(" {} ", tr("common.pick_location"))).clicked() {
                     let mut dialog = FileDialog::select_folder(launcher_state.cached_launch_options.0.cache_path.clone())
                         .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
                         .resizable(false)
@@ -181,103 +279,107 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                     launcher_state.cache_path_dialog = Some(dialog);
                 }
 
-                ui.label("Cache folder path");
+                ui.label(tr("launcher.cache_folder_path"));
             });
+
+            ui.add_space(5.0);
+
+            data_folder_sizes_ui(launcher_state, &state.data_folder_sizes, ui, toasts, command);
         });
 
-    CollapsingHeader::new("Misc Options")
+    CollapsingHeader::new(tr("launcher.misc_options"))
         .default_open(false)
         .show(ui, |ui| {
-            let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.invisible, "Autoset status to Invisible").clicked();
+            let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.invisible, tr("launcher.invisible")).clicked();
             mark_changed(launcher_state, resp);
 
-            let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.do_not_auto_load_cloud_home, "Don't Auto Open Cloud Home").clicked();
+            let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.do_not_auto_load_cloud_home, tr("launcher.dont_auto_open_cloud_home")).clicked();
             mark_changed(launcher_state, resp);
 
-            let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.skip_intro_tutorial, "Skip Intro Tutorial").clicked();
+            let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.skip_intro_tutorial, tr("launcher.skip_intro_tutorial")).clicked();
             mark_changed(launcher_state, resp);
         });
 
-    CollapsingHeader::new("Advanced")
+    CollapsingHeader::new(tr("launcher.advanced"))
         .default_open(false)
         .show(ui, |ui| {
-            CollapsingHeader::new("Repair Options")
+            CollapsingHeader::new(tr("launcher.repair_options"))
                 .default_open(false)
                 .show(ui, |ui| {
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.delete_unsynced_cloud_records, "Delete unsynced cloud records").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.delete_unsynced_cloud_records, tr("launcher.delete_unsynced_cloud_records")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_sync_conflicting_cloud_records, "Force sync conflicting cloud records").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_sync_conflicting_cloud_records, tr("launcher.force_sync_conflicting_cloud_records")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.repair_database, "Repair database").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.repair_database, tr("launcher.repair_database")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.reset_dash, "Reset Dash").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.reset_dash, tr("launcher.reset_dash")).clicked();
                     mark_changed(launcher_state, resp);
                 });
 
-            CollapsingHeader::new("OWO Haptic vest")
+            CollapsingHeader::new(tr("launcher.owo_vest"))
                 .default_open(false)
                 .show(ui, |ui| {
-                    let resp = optioned_text_field_with_label(ui, "OWO Vest IP address (enables if specified)", 200.0, &mut launcher_state.enable_owo_str, &mut launcher_state.cached_launch_options.0.enable_owo);
+                    let resp = optioned_text_field_with_label(ui, tr("launcher.owo_vest_address"), 200.0, &mut launcher_state.enable_owo_str, &mut launcher_state.cached_launch_options.0.enable_owo);
                     mark_changed(launcher_state, resp);
                 });
 
-            CollapsingHeader::new("Join Options")
+            CollapsingHeader::new(tr("launcher.join_options"))
                 .default_open(false)
                 .show(ui, |ui| {
-                    let resp = ComboBox::from_label("Auto Join")
+                    let resp = ComboBox::from_label(tr("launcher.auto_join"))
                         .selected_text(match launcher_state.cached_launch_options.0.auto_join {
-                            JoinOptions::None => "None",
-                            JoinOptions::JoinAuto => "Join Auto",
-                            JoinOptions::Join(_) => "Join URL",
-                            JoinOptions::Open(_) => "Open URL",
+                            JoinOptions::None => tr("launcher.join_none"),
+                            JoinOptions::JoinAuto => tr("launcher.join_auto"),
+                            JoinOptions::Join(_) => tr("launcher.join_url"),
+                            JoinOptions::Open(_) => tr("launcher.open_url"),
                         })
                         .width(200.0)
                         .show_ui(ui, |ui| {
-                            ui.selectable_value(&mut launcher_state.cached_launch_options.0.auto_join, JoinOptions::None, "None");
-                            ui.selectable_value(&mut launcher_state.cached_launch_options.0.auto_join, JoinOptions::JoinAuto, "Join Auto");
-                            ui.selectable_value(&mut launcher_state.cached_launch_options.0.auto_join, JoinOptions::Join(format!("")), "Join URL");
-                            ui.selectable_value(&mut launcher_state.cached_launch_options.0.auto_join, JoinOptions::Open(format!("")), "Open URL");
+                            ui.selectable_value(&mut launcher_state.cached_launch_options.0.auto_join, JoinOptions::None, tr("launcher.join_none"));
+                            ui.selectable_value(&mut launcher_state.cached_launch_options.0.auto_join, JoinOptions::JoinAuto, tr("launcher.join_auto"));
+                            ui.selectable_value(&mut launcher_state.cached_launch_options.0.auto_join, JoinOptions::Join(format!("")), tr("launcher.join_url"));
+                            ui.selectable_value(&mut launcher_state.cached_launch_options.0.auto_join, JoinOptions::Open(format!("")), tr("launcher.open_url"));
                         }).inner;
                     mark_changed(launcher_state, resp.is_some());
 
                     let resp = match &mut launcher_state.cached_launch_options.0.auto_join {
                         JoinOptions::None => false,
                         JoinOptions::JoinAuto => false,
-                        JoinOptions::Join(url) => text_field_with_label(ui, "URL", 200.0, url),
-                        JoinOptions::Open(url) => text_field_with_label(ui, "URL", 200.0, url),
+                        JoinOptions::Join(url) => text_field_with_label(ui, tr("launcher.url"), 200.0, url),
+                        JoinOptions::Open(url) => text_field_with_label(ui, tr("launcher.url"), 200.0, url),
                     };
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.announce_home_on_lan, "Announce home on LAN").changed();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.announce_home_on_lan, tr("launcher.announce_home_on_lan")).changed();
                     mark_changed(launcher_state, resp);
 
-                    let resp = text_field_with_label(ui, "Bootstrap class", 200.0, &mut launcher_state.bootstrap);
+                    let resp = text_field_with_label(ui, tr("launcher.bootstrap_class"), 200.0, &mut launcher_state.bootstrap);
                     mark_changed(launcher_state, resp);
                 });
 
-            CollapsingHeader::new("Networking Options")
+            CollapsingHeader::new(tr("launcher.networking_options"))
                 .default_open(false)
                 .show(ui, |ui| {
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_lan, "Force LAN Only").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_lan, tr("launcher.force_lan_only")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_relay, "Force Relay").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_relay, tr("launcher.force_relay")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.use_local_cloud, "Use Local Cloud").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.use_local_cloud, tr("launcher.use_local_cloud")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.use_staging_cloud, "Use Staging Cloud").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.use_staging_cloud, tr("launcher.use_staging_cloud")).clicked();
                     mark_changed(launcher_state, resp);
                 });
 
-            CollapsingHeader::new("Drone Camera Options")
+            CollapsingHeader::new(tr("launcher.drone_camera_options"))
                 .default_open(false)
                 .show(ui, |ui| {
-                    let resp = ComboBox::from_label("Drone Camera Presest")
+                    let resp = ComboBox::from_label(tr("launcher.drone_camera_preset"))
                         .selected_text(launcher_state.cached_launch_options.0.drone_camera.to_string())
                         .width(200.0)
                         .show_ui(ui, |ui| {
@@ -288,21 +390,21 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                         }).inner;
                     mark_changed(launcher_state, resp.is_some());
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.use_neos_camera, "Use Neos Camera").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.use_neos_camera, tr("launcher.use_neos_camera")).clicked();
                     mark_changed(launcher_state, resp);
                 });
 
-            CollapsingHeader::new("Avatar Builder")
+            CollapsingHeader::new(tr("launcher.avatar_builder"))
                 .default_open(false)
                 .show(ui, |ui| {
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_no_voice, "Force No Voice").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_no_voice, tr("launcher.force_no_voice")).clicked();
                     mark_changed(launcher_state, resp);
                 });
 
-            CollapsingHeader::new("Post Processing Options")
+            CollapsingHeader::new(tr("launcher.post_processing_options"))
                 .default_open(false)
                 .show(ui, |ui| {
-                    let resp = ui.checkbox(&mut launcher_state.enable_ctaa, "Enable Cinematic Temporal Anti-Aliasing").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.enable_ctaa, tr("launcher.enable_ctaa")).clicked();
                     mark_changed(launcher_state, resp);
                     if resp {
                         launcher_state.cached_launch_options.0.ctaa = if launcher_state.enable_ctaa {
@@ -313,49 +415,49 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                     }
 
                     if let Some(ctaa) = &mut launcher_state.cached_launch_options.0.ctaa {
-                        let resp = validation_text_field_with_label(ui, "Temporal Edge Power", 200.0, &mut launcher_state.temporal_edge_power_str, &mut ctaa.temporal_edge_power);
+                        let resp = validation_text_field_with_label(ui, tr("launcher.temporal_edge_power"), 200.0, &mut launcher_state.temporal_edge_power_str, &mut ctaa.temporal_edge_power);
                         if resp { launcher_state.cached_launch_options.1 = true; }
 
-                        let resp = validation_text_field_with_label(ui, "Aptive Sharpness", 200.0, &mut launcher_state.aptive_sharpness_str, &mut ctaa.aptive_sharpness);
+                        let resp = validation_text_field_with_label(ui, tr("launcher.aptive_sharpness"), 200.0, &mut launcher_state.aptive_sharpness_str, &mut ctaa.aptive_sharpness);
                         if resp { launcher_state.cached_launch_options.1 = true; }
 
-                        let resp = ui.checkbox(&mut ctaa.sharpness_enabled, "Sharpness Enabled").clicked();
+                        let resp = ui.checkbox(&mut ctaa.sharpness_enabled, tr("launcher.sharpness_enabled")).clicked();
                         if resp { launcher_state.cached_launch_options.1 = true; }
                     }
                 });
 
-            CollapsingHeader::new("Misc Options")
+            CollapsingHeader::new(tr("launcher.misc_options"))
                 .default_open(false)
                 .show(ui, |ui| {
-                    let resp = validation_text_field_with_label(ui, "Watchdog path", 200.0, &mut launcher_state.watchdog_str, &mut launcher_state.cached_launch_options.0.watchdog);
+                    let resp = validation_text_field_with_label(ui, tr("launcher.watchdog_path"), 200.0, &mut launcher_state.watchdog_str, &mut launcher_state.cached_launch_options.0.watchdog);
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.kiosk, "Kiosk").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.kiosk, tr("launcher.kiosk")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.no_ui, "No UI").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.no_ui, tr("launcher.no_ui")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_intro_tutorial, "Force Intro Tutorial").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_intro_tutorial, tr("launcher.force_intro_tutorial")).clicked();
                     mark_changed(launcher_state, resp);
 
-                    let resp = validation_text_field_with_label(ui, "Config path", 200.0, &mut launcher_state.config_str, &mut launcher_state.cached_launch_options.0.config);
+                    let resp = validation_text_field_with_label(ui, tr("launcher.config_path"), 200.0, &mut launcher_state.config_str, &mut launcher_state.cached_launch_options.0.config);
                     mark_changed(launcher_state, resp);
 
-                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_reticle_above_horizon, "Force Reticle Above Horizon").clicked();
+                    let resp = ui.checkbox(&mut launcher_state.cached_launch_options.0.force_reticle_above_horizon, tr("launcher.force_reticle_above_horizon")).clicked();
                     mark_changed(launcher_state, resp);
                 });
 
             ui.add_space(1.0);
 
-            ui.hyperlink_to(RichText::from("Explanation to these options can be found on Neos Wiki").size(12.0), "https://wiki.neos.com/Command_Line_Arguments");
+            ui.hyperlink_to(RichText::from(tr("launcher.wiki_explanation")).size(12.0), "https://wiki.neos.com/Command_Line_Arguments");
         });
 
     ui.add_space(7.5);
 
-    ui.label("Make sure to save changes if you want launch options to persist,\nlaunching the game does save launch options");
+    ui.label(tr("launcher.save_reminder"));
 
-    if ui.add_enabled(launcher_state.cached_launch_options.1, Button::new(" Save changes ")).clicked() {
+    if ui.add_enabled(launcher_state.cached_launch_options.1, Button::new(format!(" {} ", tr("launcher.save_changes")))).clicked() {
         save_launch_options(config, launcher_state.cached_launch_options.0.clone());
         handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
 
@@ -363,6 +465,163 @@ pub fn launcher_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
     }
 }
 
+fn data_folder_sizes_ui(launcher_state: &mut LauncherState, sizes: &Option<DataFolderSizes>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let Some(sizes) = sizes else {
+        ui.label(RichText::new(tr("launcher.measuring_folders")).weak());
+        return;
+    };
+
+    let describe = |folder: &Option<(PathBuf, u64)>| folder.as_ref()
+        .map_or_else(|| tr("launcher.folder_unknown"), |(path, size)| format!("{} ({})", format_size(*size), path.to_string_lossy()));
+
+    ui.label(tr_args("launcher.data_folder_size", &[("size", describe(&sizes.data))]));
+
+    ui.horizontal(|ui| {
+        ui.label(tr_args("launcher.cache_folder_size", &[("size", describe(&sizes.cache))]));
+
+        if sizes.cache.is_none() {
+            return;
+        }
+
+        if launcher_state.confirm_clear_cache {
+            ui.label(RichText::new(tr("launcher.confirm_clear_cache")).color(Color32::YELLOW));
+
+            if ui.small_button(tr("launcher.confirm_yes")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::ClearCache), toasts);
+                launcher_state.confirm_clear_cache = false;
+            }
+
+            if ui.small_button(tr("launcher.confirm_no")).clicked() {
+                launcher_state.confirm_clear_cache = false;
+            }
+        } else if ui.small_button(tr("launcher.clear_cache")).clicked() {
+            launcher_state.confirm_clear_cache = true;
+        }
+    });
+
+    if ui.small_button(tr("launcher.refresh_sizes")).clicked() {
+        handle_error(command.blocking_send(ManagerCommand::MeasureDataFolders), toasts);
+    }
+}
+
+/// Secondary profiles that can run next to the main instance
+fn launch_profiles_ui(launcher_state: &mut LauncherState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    CollapsingHeader::new(tr("launcher.launch_profiles"))
+        .id_source("launch_profiles")
+        .show(ui, |ui| {
+            ui.label(RichText::new(tr("launcher.launch_profiles_hint")).weak());
+
+            let mut remove = None;
+
+            for profile in &config.load().launch_profiles {
+                ui.horizontal(|ui| {
+                    ui.label(&profile.name);
+
+                    if ui.button(tr("launcher.launch_profile")).clicked() {
+                        handle_error(command.blocking_send(ManagerCommand::LaunchNeosProfile(profile.name.clone())), toasts);
+                    }
+
+                    if ui.button(tr("launcher.delete_profile")).clicked() {
+                        remove = Some(profile.name.clone());
+                    }
+                });
+            }
+
+            ui.horizontal(|ui| {
+                TextEdit::singleline(&mut launcher_state.new_profile_name)
+                    .hint_text(tr("launcher.profile_name"))
+                    .desired_width(150.0)
+                    .ui(ui);
+
+                let name = launcher_state.new_profile_name.trim().to_string();
+
+                if ui.add_enabled(!name.is_empty(), Button::new(tr("launcher.save_as_profile"))).clicked() {
+                    let mut config_str = config.load().as_ref().clone();
+                    let profile = LaunchProfile {
+                        name: name.clone(),
+                        options: launcher_state.cached_launch_options.0.clone(),
+                    };
+
+                    // Saving under an existing name replaces that profile
+                    if let Some(existing) = config_str.launch_profiles.iter_mut().find(|x| x.name == name) {
+                        *existing = profile;
+                    } else {
+                        config_str.launch_profiles.push(profile);
+                    }
+
+                    config.swap(Arc::new(config_str));
+                    handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+
+                    launcher_state.new_profile_name.clear();
+                }
+            });
+
+            if let Some(name) = remove {
+                let mut config_str = config.load().as_ref().clone();
+                config_str.launch_profiles.retain(|x| x.name != name);
+
+                config.swap(Arc::new(config_str));
+                handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+            }
+        });
+}
+
+/// Installed mods in load order, rows are reordered by dragging their handle
+fn load_order_ui(launcher_state: &mut LauncherState, mod_list: &ModMap, global_mods: &GlobalModList, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    CollapsingHeader::new(tr("launcher.load_order"))
+        .id_source("load_order")
+        .show(ui, |ui| {
+            ui.label(RichText::new(tr("launcher.load_order_hint")).weak());
+
+            let mut order: Vec<GUID> = mod_list.keys().cloned().collect();
+            config.load().sort_by_load_order(&mut order);
+
+            let mut moved = None;
+
+            for (index, guid) in order.iter().enumerate() {
+                let row = ui.horizontal(|ui| {
+                    let handle = ui.add(Label::new("☰").sense(Sense::drag()))
+                        .on_hover_cursor(CursorIcon::Grab);
+
+                    if handle.drag_started() {
+                        launcher_state.load_order_drag = Some(index);
+                    }
+
+                    let name = global_mods.mod_list.load().get(guid).map_or_else(|| guid.clone(), |x| x.name.clone());
+                    let text = RichText::new(format!("{}. {}", index + 1, name));
+
+                    if launcher_state.load_order_drag == Some(index) {
+                        ui.label(text.strong());
+                    } else {
+                        ui.label(text);
+                    }
+                }).response;
+
+                if let Some(dragged) = launcher_state.load_order_drag {
+                    if dragged != index && ui.rect_contains_pointer(row.rect) {
+                        moved = Some((dragged, index));
+                    }
+                }
+            }
+
+            if let Some((from, to)) = moved {
+                let guid = order.remove(from);
+                order.insert(to, guid);
+                launcher_state.load_order_drag = Some(to);
+
+                let mut config_str = config.load().as_ref().clone();
+                config_str.load_order = order;
+                config.swap(Arc::new(config_str));
+            }
+
+            // Saved once when the row is dropped instead of on every move
+            if launcher_state.load_order_drag.is_some() && ui.input(|x| x.pointer.any_released()) {
+                launcher_state.load_order_drag = None;
+                handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+            }
+        });
+}
+
 pub fn launcher_dialog(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
     if let Some(dialog) = &mut state.launcher_state.shortcut_dialog {
         if dialog.show(ctx).selected() {
@@ -372,6 +631,29 @@ pub fn launcher_dialog(state: &mut UIManagerState, ctx: &Context, toasts: &mut T
         }
     }
 
+    if let Some(dialog) = &mut state.launcher_state.import_shortcut_dialog {
+        if dialog.show(ctx).selected() {
+            if let Some(file) = dialog.path() {
+                let arguments = fs::read(&file).ok()
+                    .and_then(|x| parse_shortcut(&x))
+                    .and_then(|x| x.arguments);
+
+                if let Some(arguments) = arguments {
+                    // Unsaved so user can look over what got imported before keeping it
+                    state.launcher_state.set_options(LaunchOptions::parse_arguments(&arguments), true);
+                } else {
+                    toasts.add(Toast {
+                        kind: ToastKind::Error,
+                        text: tr_args("launcher.import_shortcut_failed", &[("path", file.to_string_lossy().to_string())]).into(),
+                        options: ToastOptions::default()
+                            .show_progress(true)
+                            .duration_in_seconds(5.0),
+                    });
+                }
+            }
+        }
+    }
+
     if let Some(dialog) = &mut state.launcher_state.data_path_dialog {
         if dialog.show(ctx).selected() {
             if let Some(folder) = dialog.path() {
@@ -393,6 +675,15 @@ pub fn launcher_dialog(state: &mut UIManagerState, ctx: &Context, toasts: &mut T
     }
 }
 
+/// Saves currently edited launch options and asks manager to launch the game
+pub fn launch_game(launcher_state: &mut LauncherState, config: &Arc<ArcSwap<Config>>, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    save_launch_options(config, launcher_state.cached_launch_options.0.clone());
+    handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+
+    launcher_state.cached_launch_options.1 = false;
+    handle_error(command.blocking_send(ManagerCommand::LaunchNeos), toasts);
+}
+
 pub fn save_launch_options(config: &Arc<ArcSwap<Config>>, launch_options: LaunchOptions) {
     let mut config_str = config.load().as_ref().clone();
 
@@ -0,0 +1,62 @@
+use eframe::egui::{Rect, Sense, Spinner, Stroke, Ui, vec2};
+use crate::locale::tr;
+
+/// Rows drawn in place of content that hasn't arrived yet
+const SKELETON_ROWS: usize = 6;
+
+/// How far along startup is, tabs that need data from the manager show placeholders until it's there
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadingState {
+    /// Manifests are being downloaded
+    Manifests,
+    /// Mods folder is being scanned
+    Scanning,
+    Ready
+}
+
+impl LoadingState {
+    pub fn is_ready(&self) -> bool {
+        *self == LoadingState::Ready
+    }
+
+    /// Manifests arrived, rescan comes right after them
+    pub fn manifests_loaded(&mut self) {
+        if *self == LoadingState::Manifests {
+            *self = LoadingState::Scanning;
+        }
+    }
+
+    /// First rescan is done, everything is there
+    pub fn scan_finished(&mut self) {
+        if *self == LoadingState::Scanning {
+            *self = LoadingState::Ready;
+        }
+    }
+}
+
+/// Spinner with what's being waited for, followed by grey rows standing in for a list
+pub fn loading_placeholder(ui: &mut Ui, loading: LoadingState) {
+    let text = match loading {
+        LoadingState::Manifests => tr("loading.manifests"),
+        LoadingState::Scanning => tr("loading.scanning"),
+        LoadingState::Ready => return
+    };
+
+    ui.horizontal(|ui| {
+        ui.add(Spinner::new());
+        ui.label(text);
+    });
+
+    ui.add_space(10.0);
+
+    let fill = ui.visuals().widgets.inactive.bg_fill;
+
+    for index in 0..SKELETON_ROWS {
+        // Rows get shorter so it doesn't look like a solid block
+        let width = ui.available_width() * (1.0 - index as f32 * 0.08);
+        let (rect, _) = ui.allocate_exact_size(vec2(ui.available_width(), 36.0), Sense::hover());
+
+        ui.painter().rect(Rect::from_min_size(rect.min, vec2(width, rect.height())), 4.0, fill, Stroke::NONE);
+        ui.add_space(4.0);
+    }
+}
@@ -0,0 +1,115 @@
+use eframe::egui::{ComboBox, Context, TextEdit};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc::Sender;
+use crate::local_mods::LocalModDetails;
+use crate::locale::tr;
+use crate::manager::ManagerCommand;
+use crate::manifest::Category;
+use crate::ui::manager::mod_list::ModEntry;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+
+pub struct LocalDetailsState {
+    modal: Modal,
+    /// Hash of the file details are kept under
+    hash: String,
+    /// Name the file has on disk, shown when no name is given
+    file_name: String,
+    name: String,
+    category: Category,
+    notes: String
+}
+
+impl LocalDetailsState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "local_details_modal"),
+            hash: String::new(),
+            file_name: String::new(),
+            name: String::new(),
+            category: Category::Unknown,
+            notes: String::new(),
+        }
+    }
+
+    pub fn open_with_entry(&mut self, entry: &ModEntry) {
+        let Some(hash) = &entry.local_key else {
+            return;
+        };
+
+        self.hash = hash.clone();
+        self.file_name = entry.key.0.clone();
+        self.name = if entry.name != entry.key.0 { entry.name.clone() } else { String::new() };
+        self.category = entry.category;
+        self.notes = entry.description.clone().unwrap_or_default();
+        self.modal.open();
+    }
+
+    fn details(&self) -> LocalModDetails {
+        let non_empty = |x: &str| Some(x.trim().to_string()).filter(|x| !x.is_empty());
+
+        LocalModDetails {
+            name: non_empty(&self.name),
+            category: Some(self.category).filter(|x| *x != Category::Unknown),
+            notes: non_empty(&self.notes),
+        }
+    }
+}
+
+/// Name, category and notes for a file that isn't in any manifest, so it doesn't show up as a bare file name
+pub fn local_details_modal(state: &mut UIManagerState, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let details_state = &mut state.mod_list_state.local_details;
+    let mut save = false;
+    let mut clear = false;
+
+    details_state.modal.show(|ui| {
+        details_state.modal.title(ui, tr("local_details.title"));
+
+        details_state.modal.frame(ui, |ui| {
+            ui.label(tr("local_details.description"));
+
+            ui.add_space(5.0);
+
+            ui.add(TextEdit::singleline(&mut details_state.name)
+                .hint_text(&details_state.file_name)
+                .desired_width(300.0));
+
+            ComboBox::from_label(tr("local_details.category"))
+                .selected_text(details_state.category.to_string())
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for category in Category::iter() {
+                        ui.selectable_value(&mut details_state.category, category, category.to_string());
+                    }
+                });
+
+            ui.add(TextEdit::multiline(&mut details_state.notes)
+                .hint_text(tr("local_details.notes"))
+                .desired_width(300.0)
+                .desired_rows(4));
+        });
+
+        details_state.modal.buttons(ui, |ui| {
+            if details_state.modal.suggested_button(ui, tr("local_details.save")).clicked() {
+                save = true;
+            }
+
+            if details_state.modal.caution_button(ui, tr("local_details.clear")).clicked() {
+                clear = true;
+            }
+
+            details_state.modal.button(ui, tr("local_details.cancel"));
+        });
+    });
+
+    if save || clear {
+        let details = if clear { LocalModDetails::default() } else { details_state.details() };
+
+        handle_error(command.blocking_send(ManagerCommand::SetLocalModDetails {
+            hash: details_state.hash.clone(),
+            details,
+        }), toasts);
+    }
+}
@@ -0,0 +1,90 @@
+use dirs::desktop_dir;
+use eframe::egui::{Align2, Context, RichText, Ui};
+use egui_file::FileDialog;
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::config::Config;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+
+pub struct LockfileState {
+    modal: Modal,
+    sync_dialog: Option<FileDialog>
+}
+
+impl LockfileState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "lockfile_modal"),
+            sync_dialog: None,
+        }
+    }
+}
+
+pub fn lockfile_button(lockfile_state: &mut LockfileState, ui: &mut Ui) {
+    if ui.button(tr("lockfile.open")).on_hover_text(tr("lockfile.hover")).clicked() {
+        lockfile_state.modal.open();
+    }
+}
+
+/// Syncing to the lockfile the organizer keeps, or to one brought from elsewhere
+pub fn lockfile_modal(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let lockfile_state = &mut state.lockfile_state;
+    let mut sync = false;
+    let mut pick_file = false;
+
+    lockfile_state.modal.show(|ui| {
+        lockfile_state.modal.title(ui, tr("lockfile.title"));
+
+        lockfile_state.modal.frame(ui, |ui| {
+            ui.label(tr("lockfile.description"));
+
+            ui.add_space(5.0);
+
+            let path = Config::lockfile_path();
+
+            if path.exists() {
+                ui.small(RichText::new(tr_args("lockfile.location", &[("path", path.display().to_string())])).weak());
+            } else {
+                ui.small(RichText::new(tr("lockfile.missing")).weak());
+            }
+        });
+
+        lockfile_state.modal.buttons(ui, |ui| {
+            if lockfile_state.modal.suggested_button(ui, tr("lockfile.sync")).clicked() {
+                sync = true;
+            }
+
+            if lockfile_state.modal.button(ui, tr("lockfile.sync_from_file")).clicked() {
+                pick_file = true;
+            }
+
+            lockfile_state.modal.button(ui, tr("lockfile.cancel"));
+        });
+    });
+
+    if sync {
+        handle_error(command.blocking_send(ManagerCommand::SyncToLockfile(None)), toasts);
+    }
+
+    if pick_file {
+        let mut dialog = FileDialog::open_file(desktop_dir())
+            .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+            .resizable(false);
+
+        dialog.open();
+
+        lockfile_state.sync_dialog = Some(dialog);
+    }
+
+    if let Some(dialog) = &mut lockfile_state.sync_dialog {
+        if dialog.show(ctx).selected() {
+            if let Some(file) = dialog.path() {
+                handle_error(command.blocking_send(ManagerCommand::SyncToLockfile(Some(file))), toasts);
+            }
+        }
+    }
+}
@@ -1,28 +1,96 @@
 mod launcher;
 mod tests;
+mod settings;
 pub mod mod_list;
+pub(crate) mod mod_entry;
 mod more_info;
+pub(crate) mod report;
+pub mod tour;
+pub(crate) mod duplicates;
+mod plan_preview;
+mod history;
+mod mod_pack;
+mod lockfile;
+mod cloud_sync;
+mod artifact_sizes;
+mod get_mods;
+mod feed;
+mod notifications;
+mod gallery;
+mod launch_check;
+mod storage;
+mod global_search;
+mod deep_link;
+mod updates;
+mod pre_launch_updates;
+mod compatibility;
+mod stray_mods;
+mod loading;
+mod planner;
+mod resume_queue;
+mod sideload;
+mod github_install;
+mod local_details;
+mod conflicts;
 
+use std::collections::HashMap;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use arc_swap::ArcSwap;
-use eframe::egui::{Button, CentralPanel, CollapsingHeader, Color32, Context, Frame, Margin, RichText, Rounding, ScrollArea, SidePanel, Style, Vec2};
+use eframe::egui::{Align, Button, CentralPanel, CollapsingHeader, Color32, ComboBox, Context, Frame, Key, Layout, Margin, Modifiers, RichText, Rounding, ScrollArea, SidePanel, Style, TextStyle, TopBottomPanel, Vec2};
 use eframe::egui::panel::Side;
 use eframe::egui::WidgetType::SelectableLabel;
 use egui_file::FileDialog;
-use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
+use egui_toast::{ToastKind, Toasts};
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::mpsc::error::TryRecvError;
+use serde::{Serialize, Deserialize};
 use more_info::{MarkdownContent, more_info_modal};
 use crate::config::Config;
-use crate::install::ModMap;
+use crate::history::HistoryEntry;
+use crate::install::{ModConflict, ModMap};
 use crate::launch::{Device, LaunchOptions};
-use crate::manager::{ManagerCommand, ManagerEvent};
-use crate::manifest::GlobalModList;
-use crate::ui::manager::launcher::{launcher_dialog, launcher_ui, LauncherState};
+use crate::manager::{DataFolderSizes, GameInstanceInfo, ManagerCommand, ManagerError, ManagerEvent, OperationStatus, QueueItem};
+use crate::maintenance::FolderReport;
+use crate::local_mods::LocalModInfo;
+use crate::locale::{tr, tr_args};
+use crate::manifest::{find_image_links, GlobalModList};
+use crate::notification::show_notification;
+use crate::trash::TrashEntry;
+use crate::ui::manager::artifact_sizes::ArtifactSizes;
+use crate::ui::manager::compatibility::{compatibility_modal, CompatibilityState};
+use crate::ipc::DeepLink;
+use crate::ui::manager::deep_link::{deep_link_modal, DeepLinkState};
+use crate::ui::manager::duplicates::{duplicates_modal, DuplicatesState};
+use crate::ui::manager::feed::{feed_ui, FeedState};
+use crate::ui::manager::notifications::{notification_center_button, notification_center_ui, NotificationCenterState};
+use crate::ui::manager::get_mods::{get_mods_ui, GetModsState};
+use crate::ui::manager::global_search::{global_search_ui, GlobalSearchState};
+use crate::ui::manager::history::history_ui;
+use crate::ui::manager::launch_check::{launch_check_modal, LaunchCheckState};
+use crate::ui::manager::launcher::{launch_game, launcher_dialog, launcher_ui, LauncherState};
+use crate::ui::manager::loading::{loading_placeholder, LoadingState};
 use crate::ui::manager::mod_list::{mod_list_ui, ModListState};
+use crate::ui::manager::mod_pack::{mod_pack_modal, ModPackState};
+use crate::ui::manager::lockfile::{lockfile_modal, LockfileState};
+use crate::ui::manager::cloud_sync::{cloud_sync_modal, CloudSyncState};
+use crate::ui::manager::planner::{planner_window, PlannerState};
+use crate::ui::manager::plan_preview::{describe_operation, mod_name, plan_preview_modal, PlanPreviewState};
+use crate::ui::manager::pre_launch_updates::{pre_launch_updates_modal, PreLaunchUpdatesState};
+use crate::ui::manager::report::{report_problem_modal, ReportState};
+use crate::ui::manager::resume_queue::{resume_queue_modal, ResumeQueueState};
+use crate::ui::manager::sideload::{sideload_modal, SideloadState};
+use crate::ui::manager::github_install::{github_install_modal, GitHubInstallState};
+use crate::ui::manager::local_details::local_details_modal;
+use crate::ui::manager::conflicts::{conflicts_modal, ConflictsState};
+use crate::ui::manager::settings::{settings_ui, SettingsState};
+use crate::ui::manager::storage::{storage_modal, StorageState};
+use crate::ui::manager::stray_mods::{stray_mods_modal, StrayModsState};
+use crate::ui::manager::tour::{tour_ui, TourState};
 use crate::ui::manager::tests::{test_ui, TestState};
+use crate::ui::manager::updates::{updates_ui, UpdatesState};
 use crate::utils::{handle_error, selectable_value_with_size};
 
 pub struct UIManagerState {
@@ -30,67 +98,342 @@ pub struct UIManagerState {
     pub(crate) launcher_state: LauncherState,
     pub(crate) mod_list_state: ModListState,
     pub(crate) test_state: TestState,
+    pub(crate) report_state: ReportState,
+    pub(crate) tour_state: TourState,
+    pub(crate) settings_state: SettingsState,
+    pub(crate) duplicates_state: DuplicatesState,
+    pub(crate) conflicts_state: ConflictsState,
+    pub(crate) plan_preview_state: PlanPreviewState,
+    pub(crate) launch_check_state: LaunchCheckState,
+    pub(crate) pre_launch_updates_state: PreLaunchUpdatesState,
+    pub(crate) storage_state: StorageState,
+    pub(crate) compatibility_state: CompatibilityState,
+    pub(crate) stray_mods_state: StrayModsState,
+    pub(crate) deep_link_state: DeepLinkState,
+    pub(crate) mod_pack_state: ModPackState,
+    pub(crate) lockfile_state: LockfileState,
+    pub(crate) cloud_sync_state: CloudSyncState,
+    pub(crate) resume_queue_state: ResumeQueueState,
+    pub(crate) sideload_state: SideloadState,
+    pub(crate) github_install_state: GitHubInstallState,
+    pub(crate) actionable_errors: Vec<ManagerError>,
+    pub(crate) operation_queue: Vec<QueueItem>,
+    pub(crate) history: Vec<HistoryEntry>,
+    pub(crate) trash: Vec<TrashEntry>,
+    pub(crate) game_instances: Vec<GameInstanceInfo>,
+    pub(crate) data_folder_sizes: Option<DataFolderSizes>,
+    /// How much cached artifacts take up, None until manager measures it
+    pub(crate) artifact_cache_size: Option<u64>,
+    /// None until folders are inspected from settings
+    pub(crate) maintenance_report: Option<Vec<FolderReport>>,
+    pub(crate) get_mods_state: GetModsState,
+    pub(crate) feed_state: FeedState,
+    pub(crate) planner_state: PlannerState,
+    pub(crate) notification_center: NotificationCenterState,
+    pub(crate) global_search_state: GlobalSearchState,
+    pub(crate) updates_state: UpdatesState,
+    pub(crate) artifact_sizes: ArtifactSizes,
+    /// Mods folder was changed by something else since last scan
+    pub(crate) external_changes: bool,
+    /// Files scanned out of total while rescan is running
+    pub(crate) scan_progress: Option<(usize, usize)>,
+    pub(crate) loading: LoadingState,
+    pub(crate) window_focused: bool,
+    /// Another instance was started, window gets brought to front
+    pub(crate) focus_requested: bool,
+    /// Window is minimized or hidden to tray, updates get announced with system notifications then
+    pub(crate) window_hidden: bool,
+    /// Set from notification thread when the notification gets clicked
+    pub(crate) notification_clicked: Arc<AtomicBool>,
+    /// Updates were announced while window was hidden, Updates tab is opened once it's back
+    show_updates_on_focus: bool,
+    pub(crate) shutdown_complete: bool,
     pub(crate) manifest_mods: GlobalModList,
-    pub(crate) mod_list: ModMap
+    /// Generation of manifest_mods from the last ManifestUpdated
+    pub(crate) manifest_generation: u64,
+    pub(crate) manifest_mod_count: usize,
+    pub(crate) mod_list: ModMap,
+    /// Names and versions recorded for files that aren't in any manifest, by hash
+    pub(crate) local_mods: HashMap<String, LocalModInfo>,
+    /// User's notes about mods, by mod ID
+    pub(crate) notes: HashMap<String, String>,
+    /// Conflicts in installed mods, including suppressed ones
+    pub(crate) conflicts: Vec<ModConflict>
 }
 
-fn handle_events(state: &mut UIManagerState, toasts: &mut Toasts, event_r: &mut Receiver<ManagerEvent>) {
+impl UIManagerState {
+    pub fn from_context(ctx: &Context, manifest_mods: GlobalModList) -> Self {
+        Self {
+            current_tab: ManagerTabs::Launcher,
+            launcher_state: Default::default(),
+            mod_list_state: ModListState::from_context(ctx),
+            test_state: Default::default(),
+            report_state: ReportState::from_context(ctx),
+            tour_state: Default::default(),
+            settings_state: Default::default(),
+            duplicates_state: DuplicatesState::from_context(ctx),
+            conflicts_state: ConflictsState::from_context(ctx),
+            plan_preview_state: PlanPreviewState::from_context(ctx),
+            launch_check_state: LaunchCheckState::from_context(ctx),
+            pre_launch_updates_state: PreLaunchUpdatesState::from_context(ctx),
+            storage_state: StorageState::from_context(ctx),
+            compatibility_state: CompatibilityState::from_context(ctx),
+            stray_mods_state: StrayModsState::from_context(ctx),
+            deep_link_state: DeepLinkState::from_context(ctx),
+            mod_pack_state: ModPackState::from_context(ctx),
+            lockfile_state: LockfileState::from_context(ctx),
+            cloud_sync_state: CloudSyncState::from_context(ctx),
+            resume_queue_state: ResumeQueueState::from_context(ctx),
+            sideload_state: SideloadState::from_context(ctx),
+            github_install_state: GitHubInstallState::from_context(ctx),
+            actionable_errors: vec![],
+            operation_queue: vec![],
+            history: vec![],
+            trash: vec![],
+            game_instances: vec![],
+            data_folder_sizes: None,
+            artifact_cache_size: None,
+            maintenance_report: None,
+            get_mods_state: Default::default(),
+            feed_state: Default::default(),
+            planner_state: Default::default(),
+            notification_center: Default::default(),
+            global_search_state: Default::default(),
+            updates_state: Default::default(),
+            artifact_sizes: Default::default(),
+            external_changes: false,
+            scan_progress: None,
+            loading: LoadingState::Manifests,
+            window_focused: true,
+            focus_requested: false,
+            window_hidden: false,
+            notification_clicked: Default::default(),
+            show_updates_on_focus: false,
+            shutdown_complete: false,
+            manifest_mods,
+            manifest_generation: 0,
+            manifest_mod_count: 0,
+            mod_list: Default::default(),
+            local_mods: Default::default(),
+            notes: Default::default(),
+            conflicts: vec![],
+        }
+    }
+}
+
+fn handle_events(state: &mut UIManagerState, config: &Config, ctx: &Context, toasts: &mut Toasts, event_r: &mut Receiver<ManagerEvent>) {
     match event_r.try_recv() {
         Ok(val) => {
             match val {
                 ManagerEvent::LaunchOptionsState(options) => {
-                    state.launcher_state.enable_owo_str = options.enable_owo.clone().unwrap_or_else(|| "".to_string());
-                    state.launcher_state.resolution_width_str = options.resolution_width.clone().map_or_else(|| "".to_string(), |x| x.to_string());
-                    state.launcher_state.resolution_height_str = options.resolution_height.clone().map_or_else(|| "".to_string(), |x| x.to_string());
-                    state.launcher_state.bootstrap = options.bootstrap.clone().unwrap_or_else(|| "".to_string());
-                    state.launcher_state.data_path_str = options.data_path.clone().map_or_else(|| "".to_string(), |x| x.to_string_lossy().to_string());
-                    state.launcher_state.cache_path_str = options.cache_path.clone().map_or_else(|| "".to_string(), |x| x.to_string_lossy().to_string());
-                    state.launcher_state.watchdog_str = options.watchdog.clone().map_or_else(|| "".to_string(), |x| x.to_string_lossy().to_string());
-                    state.launcher_state.config_str = options.config.clone().map_or_else(|| "".to_string(), |x| x.to_string_lossy().to_string());
-                    state.launcher_state.enable_ctaa = options.ctaa.is_some();
-                    state.launcher_state.temporal_edge_power_str = options.ctaa.as_ref().map_or_else(|| "".to_string(), |x| x.temporal_edge_power.as_ref().map_or_else(|| "".to_string(), |x| x.to_string()));
-                    state.launcher_state.aptive_sharpness_str = options.ctaa.as_ref().map_or_else(|| "".to_string(), |x| x.aptive_sharpness.as_ref().map_or_else(|| "".to_string(), |x| x.to_string()));
-                    state.launcher_state.cached_launch_options = (options, false);
+                    state.launcher_state.set_options(options, false);
                 }
 
                 ManagerEvent::Error(error) => {
-                    toasts.add(Toast {
-                        kind: ToastKind::Error,
-                        text: format!("Manager error\n{}", error).into(),
-                        options: ToastOptions::default()
-                            .show_progress(true)
-                            .duration_in_seconds(30.0),
-                    });
+                    state.report_state.push_error(&error.to_string());
+
+                    state.notification_center.notify(
+                        toasts,
+                        &config.toasts,
+                        ToastKind::Error,
+                        format!("{} {}\n{}", error.icon(), tr(&format!("manager.error.{}", error.category())), error),
+                        true
+                    );
+
+                    if error.retry_command().is_some() || matches!(error, ManagerError::Filesystem(_)) {
+                        state.actionable_errors.push(error);
+                    }
                 }
 
                 ManagerEvent::ModMapChanged(map) => {
                     state.mod_list = map;
+                    state.mod_list_state.invalidate();
+                    state.updates_state.invalidate();
+                    state.external_changes = false;
+                }
+
+                ManagerEvent::ManifestUpdated { count, generation } => {
+                    // Entries show names and updates from the manifest, so they're stale now
+                    state.manifest_generation = generation;
+                    state.manifest_mod_count = count;
+                    state.mod_list_state.invalidate();
+                    state.updates_state.invalidate();
+                    state.loading.manifests_loaded();
+                }
+
+                ManagerEvent::ReleaseFeed(entries) => {
+                    state.feed_state.entries = entries;
+                }
+
+                ManagerEvent::GameInstancesChanged(instances) => {
+                    state.game_instances = instances;
+                }
+
+                ManagerEvent::DataFolderSizes(sizes) => {
+                    state.data_folder_sizes = Some(sizes);
+                }
+
+                ManagerEvent::ArtifactCacheSize(size) => {
+                    state.artifact_cache_size = Some(size);
+                }
+
+                ManagerEvent::MaintenanceReport(reports) => {
+                    state.maintenance_report = Some(reports);
+                }
+
+                ManagerEvent::LaunchProblems(problems) => {
+                    state.launch_check_state.open(problems);
+                }
+
+                ManagerEvent::PreLaunchUpdatesStarted(updates) => {
+                    state.pre_launch_updates_state.start(updates);
+                }
+
+                ManagerEvent::PreLaunchUpdatesFinished(results) => {
+                    state.pre_launch_updates_state.finish(results);
+                }
+
+                ManagerEvent::DeepLink(DeepLink::Show(guid)) => {
+                    state.current_tab = ManagerTabs::InstalledMods;
+                    state.mod_list_state.select_mod(&guid);
+                }
+
+                ManagerEvent::DeepLink(link) => {
+                    state.deep_link_state.open(link);
+                }
+
+                ManagerEvent::FocusRequested => {
+                    state.focus_requested = true;
+                }
+
+                ManagerEvent::UpdatesFound(updates) => {
+                    if state.window_hidden {
+                        let names: Vec<String> = updates.iter()
+                            .map(|((mod_id, _), version)| format!("{} v{}", mod_name(&state.manifest_mods, mod_id), version))
+                            .collect();
+
+                        show_notification(
+                            tr_args("notification.updates_found", &[("count", names.len().to_string())]),
+                            names.join(", "),
+                            state.notification_clicked.clone(),
+                            ctx.clone()
+                        );
+
+                        state.show_updates_on_focus = true;
+                    }
+                }
+
+                ManagerEvent::ConfigReloaded => {
+                    // Settings drafts were made from the old config
+                    state.settings_state = Default::default();
+                    state.updates_state.invalidate();
+                }
+
+                ManagerEvent::CloudSyncConflicts { merged, remote, conflicts, push } => {
+                    state.cloud_sync_state.open(merged, remote, conflicts, push);
+                }
+
+                ManagerEvent::TrashChanged(trash) => {
+                    state.trash = trash;
+                }
+
+                ManagerEvent::ArtifactSizes(sizes) => {
+                    state.artifact_sizes.update(sizes);
+                }
+
+                ManagerEvent::ExternalChangesDetected => {
+                    state.external_changes = true;
+                }
+
+                ManagerEvent::ScanProgress { scanned, total } => {
+                    state.scan_progress = (scanned < total).then_some((scanned, total));
+
+                    if state.scan_progress.is_none() {
+                        state.loading.scan_finished();
+                    }
+                }
+
+                ManagerEvent::UnfinishedQueue(items) => {
+                    state.resume_queue_state.open(items);
+                }
+
+                ManagerEvent::GitHubReleases(repo_url, releases) => {
+                    state.github_install_state.set_releases(repo_url, releases);
+                }
+
+                ManagerEvent::LocalModsChanged(local_mods) => {
+                    state.local_mods = local_mods;
+                    state.mod_list_state.invalidate();
+                }
+
+                ManagerEvent::NotesChanged(notes) => {
+                    state.notes = notes;
+                    state.mod_list_state.invalidate();
+                }
+
+                ManagerEvent::SideloadInspected(file) => {
+                    state.sideload_state.set_inspected(file);
+                }
+
+                ManagerEvent::StrayModsFound(strays) => {
+                    state.stray_mods_state.set_strays(strays);
+                }
+
+                ManagerEvent::ConflictsChanged(conflicts) => {
+                    state.duplicates_state.set_conflicts(&conflicts);
+                    state.conflicts_state.conflicts_changed();
+                    state.conflicts = conflicts;
+                    state.mod_list_state.invalidate();
+                }
+
+                ManagerEvent::ConflictFixesFound(fixes) => {
+                    state.conflicts_state.set_fixes(fixes);
+                }
+
+                ManagerEvent::PlanConflicts(operations, conflicts, untrusted) => {
+                    state.plan_preview_state.open(operations, conflicts, untrusted);
+                }
+
+                ManagerEvent::PlanSimulated(outcome) => {
+                    state.planner_state.set_outcome(outcome);
+                }
+
+                ManagerEvent::QueueChanged(queue) => {
+                    state.operation_queue = queue;
+                }
+
+                ManagerEvent::HistoryChanged(history) => {
+                    state.history = history;
                 }
 
                 ManagerEvent::Notification(kind, message) => {
-                    toasts.add(Toast {
-                        kind,
-                        text: message.into(),
-                        options: ToastOptions::default()
-                            .show_progress(true)
-                            .duration_in_seconds(5.0)
-                    });
+                    state.notification_center.notify(toasts, &config.toasts, kind, message, false);
                 }
 
                 ManagerEvent::LongNotification(kind, message) => {
-                    toasts.add(Toast {
-                        kind,
-                        text: message.into(),
-                        options: ToastOptions::default()
-                            .show_progress(true)
-                            .duration_in_seconds(30.0)
-                    });
+                    if kind == ToastKind::Error {
+                        state.report_state.push_error(&message);
+                    }
+
+                    state.notification_center.notify(toasts, &config.toasts, kind, message, true);
+                }
+                ManagerEvent::ShutdownComplete => {
+                    state.shutdown_complete = true;
                 }
                 ManagerEvent::ReadmeResponse(readme) => {
                     state.mod_list_state.more_info.markdown_content = match readme {
-                        None => MarkdownContent::NoReadme,
-                        Some(content) => MarkdownContent::Markdown(content.trim().to_string())
+                        Ok(None) => MarkdownContent::NoReadme,
+                        Ok(Some(content)) => MarkdownContent::Markdown(content.trim().to_string()),
+                        Err(e) => MarkdownContent::Failed(e)
                     };
+
+                    if let MarkdownContent::Markdown(content) = &state.mod_list_state.more_info.markdown_content {
+                        let links = find_image_links(content);
+                        state.mod_list_state.more_info.gallery.set_links(links);
+                    }
+                }
+                ManagerEvent::ImageLoaded(url, image) => {
+                    state.mod_list_state.more_info.gallery.image_loaded(url, image);
                 }
             }
         }
@@ -98,25 +441,229 @@ fn handle_events(state: &mut UIManagerState, toasts: &mut Toasts, event_r: &mut
             match err {
                 TryRecvError::Empty => {}
                 TryRecvError::Disconnected => {
-                    panic!("Manager is dead!")
+                    if !state.shutdown_complete {
+                        panic!("Manager is dead!")
+                    }
                 }
             }
         }
     }
 }
 
-#[derive(PartialEq)]
+fn handle_shortcuts(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let tab_keys = [
+        (Key::Num1, ManagerTabs::Launcher),
+        (Key::Num2, ManagerTabs::Updates),
+        (Key::Num3, ManagerTabs::ModLoader),
+        (Key::Num4, ManagerTabs::InstalledMods),
+        (Key::Num5, ManagerTabs::GetMods),
+        (Key::Num6, ManagerTabs::Settings),
+        (Key::Num7, ManagerTabs::History),
+        (Key::Num8, ManagerTabs::WhatsNew)
+    ];
+
+    for (key, tab) in tab_keys {
+        if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, key)) {
+            state.current_tab = tab;
+        }
+    }
+
+    if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::L)) {
+        launch_game(&mut state.launcher_state, config, toasts, command);
+    }
+
+    if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::F)) {
+        state.current_tab = ManagerTabs::InstalledMods;
+        state.mod_list_state.focus_search();
+    }
+
+    if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::K)) {
+        state.global_search_state.focus();
+    }
+
+    // Navigation keys shouldn't be stolen from text fields
+    if state.current_tab == ManagerTabs::InstalledMods && ctx.memory(|m| m.focus().is_none()) {
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowDown)) {
+            state.mod_list_state.select_next();
+        }
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowUp)) {
+            state.mod_list_state.select_previous();
+        }
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Enter)) {
+            state.mod_list_state.expand_selected();
+        }
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Space)) {
+            state.mod_list_state.toggle_selected();
+        }
+    }
+}
+
+/// Asks manager to look for changes made by other tools whenever the window gets focus back
+fn check_on_focus(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let focused = ctx.input(|i| i.raw.focused);
+
+    if focused && !state.window_focused {
+        handle_error(command.blocking_send(ManagerCommand::CheckForExternalChanges), toasts);
+
+        if state.show_updates_on_focus {
+            state.show_updates_on_focus = false;
+            state.current_tab = ManagerTabs::Updates;
+        }
+    }
+
+    state.window_focused = focused;
+}
+
+/// Banner offering a rescan when mods folder doesn't match what's shown
+fn external_changes_ui(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    if !state.external_changes {
+        return;
+    }
+
+    TopBottomPanel::top("external_changes")
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("⚠").color(Color32::YELLOW));
+                ui.label(tr("manager.external_changes"));
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.small_button("✖").clicked() {
+                        state.external_changes = false;
+                    }
+
+                    if ui.button(tr("manager.rescan")).clicked() {
+                        handle_error(command.blocking_send(ManagerCommand::RefreshModMap), toasts);
+                        state.external_changes = false;
+                    }
+                });
+            });
+        });
+}
+
+/// Errors that user can do something about, shown at the bottom until dismissed
+fn actionable_errors_ui(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    if state.actionable_errors.is_empty() {
+        return;
+    }
+
+    TopBottomPanel::bottom("actionable_errors")
+        .show(ctx, |ui| {
+            let mut dismissed = None;
+
+            for (index, error) in state.actionable_errors.iter().enumerate().rev().take(3) {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(error.icon()).color(Color32::LIGHT_RED));
+                    ui.label(error.to_string().lines().next().unwrap_or_default());
+
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        if ui.small_button("✖").clicked() {
+                            dismissed = Some(index);
+                        }
+
+                        if let Some(retry) = error.retry_command() {
+                            if ui.button(tr("manager.error.retry")).clicked() {
+                                handle_error(command.blocking_send(retry.clone()), toasts);
+                                dismissed = Some(index);
+                            }
+                        }
+
+                        if let ManagerError::Filesystem(_) = error {
+                            if ui.button(tr("manager.error.open_log")).clicked() {
+                                handle_error(open::that(Config::log_path()), toasts);
+                            }
+                        }
+                    });
+                });
+            }
+
+            if let Some(index) = dismissed {
+                state.actionable_errors.remove(index);
+            }
+        });
+}
+
+/// Small tray at the bottom showing queued operations and how they're going
+fn task_tray_ui(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    if state.operation_queue.is_empty() {
+        return;
+    }
+
+    TopBottomPanel::bottom("task_tray")
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let finished = state.operation_queue.iter().filter(|x| x.status.is_finished()).count();
+
+                ui.label(RichText::new(tr_args("tray.title", &[
+                    ("finished", finished.to_string()),
+                    ("total", state.operation_queue.len().to_string())
+                ])).strong());
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.add_enabled(finished > 0, Button::new(tr("tray.clear"))).clicked() {
+                        handle_error(command.blocking_send(ManagerCommand::ClearFinishedOperations), toasts);
+                    }
+                });
+            });
+
+            ScrollArea::vertical()
+                .id_source("task_tray_scroll")
+                .max_height(100.0)
+                .show(ui, |ui| {
+                    for item in &state.operation_queue {
+                        ui.horizontal(|ui| {
+                            match &item.status {
+                                OperationStatus::Queued => { ui.label("⏳"); }
+                                OperationStatus::Done => { ui.label(RichText::new("✔").color(Color32::LIGHT_GREEN)); }
+                                OperationStatus::Failed(_) => { ui.label(RichText::new("✖").color(Color32::LIGHT_RED)); }
+                                _ => { ui.spinner(); }
+                            }
+
+                            ui.label(describe_operation(&item.operation, &state.manifest_mods));
+
+                            let status = match &item.status {
+                                OperationStatus::Queued => tr("tray.queued"),
+                                OperationStatus::Downloading => tr("tray.downloading"),
+                                OperationStatus::Verifying => tr("tray.verifying"),
+                                OperationStatus::Applying => tr("tray.applying"),
+                                OperationStatus::Done => tr("tray.done"),
+                                OperationStatus::Failed(reason) => format!("{}: {}", tr("tray.failed"), reason.lines().next().unwrap_or_default()),
+                            };
+
+                            ui.small(status);
+                        });
+                    }
+                });
+        });
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub enum ManagerTabs {
+    #[default]
     Launcher,
     Updates,
     ModLoader,
     InstalledMods,
     GetMods,
-    Settings
+    WhatsNew,
+    Settings,
+    History
 }
 
 pub fn manager_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>, event: &mut Receiver<ManagerEvent>) {
-    handle_events(state, toasts, event);
+    handle_events(state, &config.load(), ctx, toasts, event);
+    handle_shortcuts(state, config, ctx, toasts, command);
+    check_on_focus(state, ctx, toasts, command);
+    global_search_ui(state, ctx);
+    external_changes_ui(state, ctx, toasts, command);
+    actionable_errors_ui(state, ctx, toasts, command);
+    task_tray_ui(state, ctx, toasts, command);
+    notification_center_ui(state, ctx);
+
+    state.updates_state.refresh(&state.mod_list, &state.manifest_mods, &config.load());
+    let update_count = state.updates_state.available.len();
 
     CentralPanel::default()
         .show(ctx, |ui| {
@@ -139,28 +686,40 @@ pub fn manager_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ctx
                 })
                 .show_inside(ui, |ui| {
                     ui.vertical_centered_justified(|ui| {
-                        let size = Vec2::new(200.0, 40.0);
-                        let text_size = 16.0;
+                        let text_size = ui.style().text_styles.get(&TextStyle::Body).map_or(16.0, |x| x.size + 1.0);
+                        let size = Vec2::new(ui.available_width(), text_size * 2.5);
 
                         let names = [
-                            (ManagerTabs::Launcher, "🚀 Launcher"),
-                            (ManagerTabs::Updates, "↻ Updates"),
-                            (ManagerTabs::ModLoader, "Ｎ Neos Mod Loader"),
-                            (ManagerTabs::InstalledMods, "📦 Installed Mods"),
-                            (ManagerTabs::GetMods, "⬇ Get More Mods"),
-                            (ManagerTabs::Settings, "🛠 Settings")
+                            (ManagerTabs::Launcher, format!("🚀 {}", tr("tabs.launcher"))),
+                            (ManagerTabs::Updates, if update_count > 0 {
+                                format!("↻ {} ({})", tr("tabs.updates"), update_count)
+                            } else {
+                                format!("↻ {}", tr("tabs.updates"))
+                            }),
+                            (ManagerTabs::ModLoader, format!("Ｎ {}", tr("tabs.mod_loader"))),
+                            (ManagerTabs::InstalledMods, format!("📦 {}", tr("tabs.installed_mods"))),
+                            (ManagerTabs::GetMods, format!("⬇ {}", tr("tabs.get_mods"))),
+                            (ManagerTabs::WhatsNew, format!("✨ {}", tr("tabs.whats_new"))),
+                            (ManagerTabs::Settings, format!("🛠 {}", tr("tabs.settings"))),
+                            (ManagerTabs::History, format!("📜 {}", tr("tabs.history")))
                         ];
 
-                        for (value, name) in names {
-                            selectable_value_with_size(
+                        for (index, (value, name)) in names.into_iter().enumerate() {
+                            let response = selectable_value_with_size(
                                 ui,
                                 size,
                                 &mut state.current_tab,
                                 value,
                                 RichText::new(name).size(text_size)
-                            );
+                            ).on_hover_text(format!("Ctrl+{}", index + 1));
+
+                            state.tour_state.tab_rects.insert(value, response.rect);
                         }
-                    })
+                    });
+
+                    ui.with_layout(Layout::bottom_up(Align::Center).with_cross_justify(true), |ui| {
+                        notification_center_button(&mut state.notification_center, ui);
+                    });
                 });
 
             ScrollArea::vertical()
@@ -170,21 +729,39 @@ pub fn manager_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ctx
                         ManagerTabs::Launcher => {
                             launcher_ui(state, config, ui, ctx, toasts, command);
                         }
+                        ManagerTabs::Updates if !state.loading.is_ready() => {
+                            loading_placeholder(ui, state.loading);
+                        }
                         ManagerTabs::Updates => {
-                            ui.heading("Updates here");
+                            updates_ui(state, config, ui, toasts, command);
                         }
                         ManagerTabs::ModLoader => {
                             ui.heading("modloader");
                         }
+                        // Mods cached by last session are fine to show while rescanning
+                        ManagerTabs::InstalledMods if !state.loading.is_ready() && state.mod_list.is_empty() => {
+                            loading_placeholder(ui, state.loading);
+                        }
                         ManagerTabs::InstalledMods => {
                             mod_list_ui(state, config, ui, ctx, toasts, command);
                         }
-                        ManagerTabs::GetMods => {}
+                        ManagerTabs::GetMods if state.loading == LoadingState::Manifests => {
+                            loading_placeholder(ui, state.loading);
+                        }
+                        ManagerTabs::GetMods => {
+                            get_mods_ui(state, ui, toasts, command);
+                        }
+                        ManagerTabs::WhatsNew if state.loading == LoadingState::Manifests => {
+                            loading_placeholder(ui, state.loading);
+                        }
+                        ManagerTabs::WhatsNew => {
+                            feed_ui(state, ui, toasts, command);
+                        }
                         ManagerTabs::Settings => {
-                            CollapsingHeader::new("Tests")
-                                .show(ui, |ui| {
-                                    test_ui(state, ui, toasts, command, event);
-                                });
+                            settings_ui(state, config, ui, toasts, command, event);
+                        }
+                        ManagerTabs::History => {
+                            history_ui(state, ui, toasts, command);
                         }
                     }
                 });
@@ -192,5 +769,26 @@ pub fn manager_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ctx
         });
 
     launcher_dialog(state, ctx, toasts, command);
-    more_info_modal(state, ctx, toasts, command);
+    more_info_modal(state, config, ctx, toasts, command);
+    local_details_modal(state, toasts, command);
+    report_problem_modal(state, toasts);
+    duplicates_modal(state, toasts, command);
+    conflicts_modal(state, config, toasts, command);
+    plan_preview_modal(state, toasts, command);
+    pre_launch_updates_modal(state, toasts, command);
+    launch_check_modal(state, toasts, command);
+    mod_pack_modal(state, ctx, toasts, command);
+    lockfile_modal(state, ctx, toasts, command);
+    cloud_sync_modal(state, toasts, command);
+    storage_modal(state);
+    compatibility_modal(state);
+    stray_mods_modal(state, config.load().neos_exe_location.parent().unwrap_or(Path::new("")), toasts, command);
+    deep_link_modal(state, toasts, command);
+    planner_window(state, ctx, toasts, command);
+    resume_queue_modal(state, toasts, command);
+    sideload_modal(state, config, ctx, toasts, command);
+    github_install_modal(state, config, toasts, command);
+
+    state.artifact_sizes.request_pending(toasts, command);
+    tour_ui(state, config, ctx, toasts, command);
 }
\ No newline at end of file
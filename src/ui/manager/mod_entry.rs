@@ -0,0 +1,631 @@
+use eframe::egui::{Align2, Button, Color32, FontFamily, FontId, Pos2, Rect, Response, Sense, Stroke, TextFormat, TextStyle, Ui, Vec2, vec2, Widget, WidgetInfo, WidgetType};
+use eframe::egui::text::LayoutJob;
+use eframe::epaint::text::TextWrapping;
+use crate::locale::{tr, tr_args};
+use crate::ui::manager::mod_list::ModEntry;
+use crate::ui::manager::plan_preview::severity_color;
+use crate::utils::{format_size, lerp_color, lerp_f32, time_ago};
+use crate::version::SimplifiedReq;
+
+/// Sizes the entry is built from, everything scales with text size
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModEntryStyle {
+    pub text_size: f32,
+    /// Space between an expanded entry and its neighbours
+    pub gap: f32,
+    /// Space around buttons of expanded entry
+    pub padding: f32
+}
+
+impl ModEntryStyle {
+    pub fn from_text_size(text_size: f32) -> Self {
+        Self {
+            text_size,
+            gap: 10.0,
+            padding: 5.0,
+        }
+    }
+
+    pub fn element_height(&self) -> f32 {
+        self.text_size * 4.0
+    }
+
+    pub fn button_size(&self) -> Vec2 {
+        vec2(self.text_size * 6.5, self.text_size + 5.0)
+    }
+
+    pub fn arrow_width(&self) -> f32 {
+        self.text_size * 2.0
+    }
+
+    /// Height that expanded part grows to, description goes above the buttons if there's one
+    pub fn expanded_height(&self, description_height: Option<f32>) -> f32 {
+        match description_height {
+            Some(height) => height + self.button_size().y + 25.0 + self.gap,
+            None => self.button_size().y + 10.0 + self.gap
+        }
+    }
+}
+
+/// Where every part of the entry goes, worked out without a Ui so it can be tested
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModEntryGeometry {
+    /// Clickable header part
+    pub element: Rect,
+    /// Background, including the part that's shown when expanded
+    pub background: Rect,
+    pub checkbox: Rect,
+    pub arrow: Pos2,
+    /// Title and GUID get clipped to this
+    pub text_container: Rect,
+    pub more_info_button: Rect,
+    pub uninstall_button: Rect,
+    pub update_button: Rect
+}
+
+impl ModEntryGeometry {
+    /// Expanded height is how far the expanded part currently is, it's animated
+    pub fn compute(style: &ModEntryStyle, origin: Pos2, width: f32, expanded_height: f32, expanded: bool) -> Self {
+        let element_height = style.element_height();
+        let arrow_width = style.arrow_width();
+        let button_size = style.button_size();
+
+        let element = Rect::from_min_size(origin, vec2(width, element_height));
+        let background = Rect::from_min_size(origin, vec2(width, element_height + expanded_height - style.gap));
+
+        // Arrow dips a bit when it points down
+        let arrow = origin + vec2(width - arrow_width / 2.0 - 10.0, element_height / 2.0 + if expanded { 1.0 } else { 0.0 });
+
+        let checkbox_size = element_height / 2.0;
+        let checkbox_offset = element_height / 2.0 - checkbox_size / 2.0;
+        let checkbox = Rect::from_min_max(
+            origin + vec2(width - arrow_width - element_height + checkbox_offset, checkbox_offset),
+            origin + vec2(width - arrow_width - checkbox_offset, element_height - checkbox_offset)
+        );
+
+        let text_container = Rect::from_min_size(origin, vec2(width - element_height - arrow_width, element_height));
+
+        // Buttons go from right to left along the bottom of the background
+        let button_step = vec2(style.padding + button_size.x, 0.0);
+        let more_info_pos = background.right_bottom() - vec2(style.padding, style.padding) - button_size;
+        let uninstall_pos = more_info_pos - button_step;
+        let update_pos = uninstall_pos - button_step;
+
+        Self {
+            element,
+            background,
+            checkbox,
+            arrow,
+            text_container,
+            more_info_button: Rect::from_min_size(more_info_pos, button_size),
+            uninstall_button: Rect::from_min_size(uninstall_pos, button_size),
+            update_button: Rect::from_min_size(update_pos, button_size),
+        }
+    }
+
+    /// Top left corner of the description, which sits right above the buttons
+    pub fn description_pos(&self, style: &ModEntryStyle, description_height: f32) -> Pos2 {
+        self.background.left_bottom() + vec2(10.0, -13.0 - style.button_size().y - description_height)
+    }
+}
+
+/// What user did with the entry
+#[derive(Clone, Copy)]
+pub enum DrawModEntryResponse {
+    Nothing,
+    ToggleExpand,
+    ToggleEnabled,
+    MoreInfo,
+    Uninstall,
+    Update,
+    OpenFolder,
+    CopyGuid,
+    OpenSource,
+    TogglePinned,
+    /// Name, category and notes for mods that aren't in any manifest
+    EditDetails
+}
+
+/// Installed mod in the mod list, with a checkbox to enable it and buttons that show up when it's expanded
+pub struct ModEntryWidget<'a> {
+    entry: &'a ModEntry,
+    action: &'a mut DrawModEntryResponse,
+    first_one: bool,
+    expanded: bool,
+    selected: bool,
+    scroll_to: bool
+}
+
+impl<'a> ModEntryWidget<'a> {
+    /// Action is set to whatever was clicked
+    pub(super) fn new(entry: &'a ModEntry, action: &'a mut DrawModEntryResponse) -> Self {
+        Self {
+            entry,
+            action,
+            first_one: false,
+            expanded: false,
+            selected: false,
+            scroll_to: false,
+        }
+    }
+
+    /// First entry of a list doesn't get a gap above it when expanded
+    pub fn first_one(mut self, first_one: bool) -> Self {
+        self.first_one = first_one;
+        self
+    }
+
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// Entry picked with keyboard
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn scroll_to(mut self, scroll_to: bool) -> Self {
+        self.scroll_to = scroll_to;
+        self
+    }
+}
+
+impl Widget for ModEntryWidget<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self { entry, action, first_one, expanded, selected, scroll_to } = self;
+
+        let normal_text = ui.style().text_styles.get(&TextStyle::Body).cloned().unwrap_or_else(|| FontId { size: 15.0, family: FontFamily::Proportional });
+        let small_text = ui.style().text_styles.get(&TextStyle::Small).cloned().unwrap_or_else(|| FontId { size: 12.0, family: FontFamily::Proportional });
+
+        let style = ModEntryStyle::from_text_size(normal_text.size);
+
+        // Gap above expanded entry
+        let target_prefix = if expanded && !first_one {
+            style.gap
+        } else {
+            0.0
+        };
+
+        // Ids don't depend on position in the list, so animations don't jump when the list changes
+        let id = entry.stable_id();
+
+        let animated_prefix = ui.ctx().animate_value_with_time(id.with("prefix"), target_prefix, 0.1);
+
+        ui.add_space(animated_prefix);
+
+        let element_width = ui.max_rect().width();
+        let origin = ui.next_widget_position();
+
+        let description_galley = if expanded {
+            expanded_description(entry).map(|x| {
+                ui.painter().layout(x, small_text.clone(), Color32::BLACK, element_width - 20.0)
+            })
+        } else {
+            None
+        };
+
+        let target_height = if expanded {
+            style.expanded_height(description_galley.as_ref().map(|x| x.rect.height()))
+        } else {
+            0.0
+        };
+
+        let animated_spacer = ui.ctx().animate_value_with_time(id.with("expanded"), target_height, 0.1);
+        let geometry = ModEntryGeometry::compute(&style, origin, element_width, animated_spacer, expanded);
+
+        // Responses
+        let (element_rect, _) = ui.allocate_exact_size(geometry.element.size(), Sense::hover());
+        let mut element_response = ui.interact(element_rect, id, Sense::click());
+
+        let mut context_action = None;
+        element_response = element_response.context_menu(|ui| {
+            context_action = mod_entry_context_menu(ui, entry);
+        });
+
+        let checkbox_response = ui.interact(geometry.checkbox, id.with("checkbox"), Sense::click());
+
+        // Painted by hand, so screen readers have to be told what these are
+        let accessible_name = entry_accessible_name(entry);
+        element_response.widget_info(|| WidgetInfo::selected(WidgetType::CollapsingHeader, expanded, &accessible_name));
+        checkbox_response.widget_info(|| WidgetInfo::selected(WidgetType::Checkbox, entry.enabled, tr_args("mod_list.enabled_checkbox", &[("name", entry.name.clone())])));
+
+        // Tabbing through the list keeps focused entry in view
+        if scroll_to || element_response.gained_focus() || checkbox_response.gained_focus() {
+            ui.scroll_to_rect(element_rect, None);
+        }
+
+        let mut additional_responses = if animated_spacer > 0.1 {
+            Some((
+                ui.interact(geometry.more_info_button, id.with("more_info"), Sense::click()),
+                ui.interact(geometry.uninstall_button, id.with("uninstall"), Sense::click()),
+                ui.interact(geometry.update_button, id.with("update"), Sense::click()),
+            ))
+        } else {
+            None
+        };
+
+        ui.add_space(animated_spacer);
+
+        if ui.is_rect_visible(element_rect) {
+            let is_latest = entry.is_latest();
+
+            let title_galley = ui.painter().layout(
+                entry_title(entry),
+                normal_text.clone(),
+                Color32::BLACK,
+                geometry.text_container.width()
+            );
+
+            let id_galley = entry_subtitle(entry, &small_text, geometry.text_container.width())
+                .map(|x| ui.ctx().fonts(|f| f.layout_job(x)));
+
+            let title_height = title_galley.rect.height();
+            let id_height = id_galley.as_ref().map_or(0.0, |x| x.rect.height());
+
+            // Main element background
+            let element_visuals = if (element_response.is_pointer_button_down_on() || element_response.has_focus()) && !checkbox_response.is_pointer_button_down_on() {
+                ui.style().visuals.widgets.active
+            } else if element_response.hovered() || element_response.highlighted() {
+                ui.style().visuals.widgets.hovered
+            } else {
+                ui.style().visuals.widgets.inactive
+            };
+
+            let fg_rect = element_rect.expand(element_visuals.expansion);
+
+            ui.painter()
+                .rect(geometry.background, 4.0, ui.visuals().widgets.noninteractive.bg_fill, ui.visuals().widgets.noninteractive.bg_stroke);
+
+            // Expanded part
+            if let Some((ref mut more_info, ref mut uninstall, ref mut update)) = &mut additional_responses {
+                if let Some(description_galley) = description_galley {
+                    let description_pos = geometry.description_pos(&style, description_galley.rect.height());
+                    ui.painter().galley_with_color(
+                        description_pos,
+                        description_galley,
+                        Color32::LIGHT_GRAY
+                    );
+                }
+
+                draw_button(ui, &tr("mod_list.more_info"), normal_text.clone(), more_info, true);
+                draw_button(ui, &tr("mod_list.uninstall"), normal_text.clone(), uninstall, true);
+                draw_button(ui, &tr("mod_list.update"), normal_text.clone(), update, !is_latest);
+            }
+
+            // Mod button
+            let fg_stroke = if selected {
+                ui.visuals().selection.stroke
+            } else {
+                element_visuals.bg_stroke
+            };
+
+            ui.painter()
+                .rect(fg_rect, 4.0, element_visuals.bg_fill, fg_stroke);
+
+            let arrow_font_id = FontId { size: style.text_size * 1.6, family: FontFamily::Proportional };
+
+            ui.painter().text(geometry.arrow, Align2::CENTER_CENTER, if expanded {
+                "⏷"
+            } else {
+                "⏵"
+            }, arrow_font_id, element_visuals.text_color());
+
+            draw_checkbox(ui, entry, geometry.checkbox, &checkbox_response);
+
+            if let Some(severity) = entry.conflict {
+                ui.painter().text(geometry.checkbox.left_center() - vec2(style.padding, 0.0), Align2::RIGHT_CENTER, "⚠", normal_text.clone(), severity_color(severity));
+            }
+
+            // Title with GUID and version under it, centered vertically
+            let text_height = 2.0 + title_height + id_height;
+
+            let title_pos = origin + vec2(10.0, style.element_height() / 2.0 - text_height / 2.0);
+            let id_pos = title_pos + vec2(0.0, title_height + 2.0);
+
+            let text_painter = ui.painter_at(geometry.text_container);
+
+            text_painter.galley_with_color(
+                title_pos,
+                title_galley,
+                element_visuals.text_color(),
+            );
+
+            if let Some(id_galley) = id_galley {
+                text_painter.galley(
+                    id_pos,
+                    id_galley
+                );
+            }
+        }
+
+        *action = if let Some(context_action) = context_action {
+            context_action
+        } else if let Some((more_info, uninstall, update)) = &additional_responses {
+            if more_info.clicked() {
+                DrawModEntryResponse::MoreInfo
+            } else if uninstall.clicked() {
+                DrawModEntryResponse::Uninstall
+            } else if update.clicked() {
+                DrawModEntryResponse::Update
+            } else {
+                DrawModEntryResponse::Nothing
+            }
+        } else {
+            DrawModEntryResponse::Nothing
+        };
+
+        if let DrawModEntryResponse::Nothing = action {
+            if checkbox_response.clicked() {
+                *action = DrawModEntryResponse::ToggleEnabled;
+            } else if element_response.clicked() {
+                *action = DrawModEntryResponse::ToggleExpand;
+            }
+        }
+
+        element_response.union(checkbox_response)
+    }
+}
+
+/// Name on a single line, long names get cut off
+fn entry_title(entry: &ModEntry) -> String {
+    let no_new_line_name = entry.name.replace('\n', "\\n");
+
+    if no_new_line_name.len() > 80 {
+        format!("{}...", no_new_line_name.chars().take(80).collect::<String>())
+    } else {
+        no_new_line_name
+    }
+}
+
+/// GUID and version, with the latest version if there's a newer one and state of the mod's files
+fn entry_subtitle(entry: &ModEntry, small_text: &FontId, max_width: f32) -> Option<LayoutJob> {
+    let id = entry.id.as_ref()?.replace('\n', "\\n");
+
+    let id = if id.len() > 55 {
+        format!("{}...", id.chars().take(55).collect::<String>())
+    } else {
+        id
+    };
+
+    let mut job = LayoutJob {
+        wrap: TextWrapping {
+            max_width,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut append = |text: &str, color: Color32| {
+        job.append(text, 0.0, TextFormat {
+            font_id: small_text.clone(),
+            color,
+            ..Default::default()
+        });
+    };
+
+    append(&format!("{} ", id), Color32::GRAY);
+
+    if let Some(version) = &entry.version {
+        if entry.is_latest() {
+            append(&format!("v{}", version), Color32::GRAY);
+        } else {
+            append(&format!("v{}", version), Color32::LIGHT_RED);
+            append(", ", Color32::GRAY);
+
+            if let Some(latest_version) = &entry.latest_version {
+                append(&format!("{} v{}", tr("mod_list.latest_is"), latest_version), Color32::LIGHT_GREEN);
+            }
+        }
+    }
+
+    // Partial states of multi-file mods
+    let status_text = if !entry.status.is_complete() {
+        Some((tr_args("mod_list.files_present", &[
+            ("present", entry.status.present.to_string()),
+            ("expected", entry.status.expected.to_string())
+        ]), Color32::LIGHT_RED))
+    } else if entry.status.is_partially_enabled() {
+        Some((tr_args("mod_list.files_enabled", &[
+            ("enabled", entry.status.enabled.to_string()),
+            ("present", entry.status.present.to_string())
+        ]), Color32::YELLOW))
+    } else {
+        None
+    };
+
+    if let Some((text, color)) = status_text {
+        append(&format!(", {}", text), color);
+    }
+
+    Some(job)
+}
+
+/// Box that fills in when mod is enabled, half way when only some of its files are
+fn draw_checkbox(ui: &mut Ui, entry: &ModEntry, rect: Rect, response: &Response) {
+    let selected_visuals = ui.style().interact_selectable(response, true);
+    let visuals = ui.style().interact_selectable(response, entry.enabled);
+
+    let target = if entry.enabled {
+        1.0
+    } else if entry.status.is_partially_enabled() {
+        0.5
+    } else {
+        0.0
+    };
+
+    let t = ui.ctx().animate_value_with_time(response.id, target, 0.2);
+    let lerped_color = lerp_color(&ui.style().visuals.panel_fill, &selected_visuals.bg_fill, t);
+    let lerped_transparency_color = lerp_color(&Color32::TRANSPARENT, &selected_visuals.bg_fill, t);
+
+    let current_rect = rect.shrink(lerp_f32(4.0, 0.0, t));
+
+    let inner_box_size = lerp_f32(3.0, rect.width() - 7.0, t);
+    let inner_box_rect = Rect::from_center_size(current_rect.center(), vec2(inner_box_size, inner_box_size));
+
+    ui.painter().rect(
+        current_rect.expand(visuals.expansion + 1.0),
+        4.0,
+        Color32::TRANSPARENT,
+        visuals.bg_stroke
+    );
+
+    ui.painter().rect(
+        current_rect,
+        2.0,
+        Color32::TRANSPARENT,
+        Stroke::new(3.0, lerped_color)
+    );
+
+    ui.painter().rect(
+        inner_box_rect,
+        2.0,
+        lerped_transparency_color,
+        Stroke::new(0.0, Color32::TRANSPARENT)
+    );
+}
+
+fn draw_button(ui: &mut Ui, text: &str, font_id: FontId, response: &mut Response, enabled: bool) {
+    let rect = response.rect;
+
+    response.widget_info(|| WidgetInfo {
+        enabled,
+        ..WidgetInfo::labeled(WidgetType::Button, text)
+    });
+
+    let visuals = if enabled {
+        ui.style().interact(response)
+    } else {
+        &ui.style().visuals.widgets.noninteractive
+    };
+
+    // Button background
+    ui.painter()
+        .rect(rect, 4.0, visuals.bg_fill, visuals.bg_stroke);
+
+    // Text
+    ui.painter()
+        .text(rect.center(), Align2::CENTER_CENTER, text, font_id, visuals.text_color());
+}
+
+/// Description shown in expanded entry, along with user's notes and mods that need this one
+fn expanded_description(entry: &ModEntry) -> Option<String> {
+    let mut required_by = (!entry.required_by.is_empty())
+        .then(|| tr_args("mod_list.required_by", &[("mods", entry.required_by.join(", "))]));
+
+    let combined_requirement = entry.combined_requirement.as_ref().map(|x| match x {
+        SimplifiedReq::Satisfiable(requirement) => tr_args("mod_list.combined_requirement", &[("requirement", requirement.to_string())]),
+        SimplifiedReq::Unsatisfiable => tr("mod_list.unsatisfiable_requirements")
+    });
+
+    if let (Some(required_by), Some(combined_requirement)) = (&mut required_by, combined_requirement) {
+        required_by.push('\n');
+        required_by.push_str(&combined_requirement);
+    }
+
+    let mut details = if entry.installed_at != 0 {
+        tr_args("mod_list.file_details", &[
+            ("ago", time_ago(entry.installed_at)),
+            ("size", format_size(entry.size))
+        ])
+    } else {
+        format_size(entry.size)
+    };
+
+    if let Some(assembly_version) = &entry.assembly_version {
+        details.push('\n');
+        details.push_str(&tr_args("mod_list.assembly_version", &[("version", assembly_version.clone())]));
+    }
+
+    let notes = entry.notes.as_ref().map(|x| tr_args("mod_list.notes", &[("notes", x.clone())]));
+
+    let sections: Vec<String> = [entry.description.clone(), notes, required_by, Some(details)].into_iter()
+        .flatten()
+        .filter(|x| !x.is_empty())
+        .collect();
+
+    Some(sections.join("\n\n"))
+}
+
+/// Same actions as the expanded entry has and a few more, without having to expand it
+fn mod_entry_context_menu(ui: &mut Ui, entry: &ModEntry) -> Option<DrawModEntryResponse> {
+    let mut action = None;
+
+    let toggle_text = if entry.enabled { tr("mod_list.disable") } else { tr("mod_list.enable") };
+
+    if ui.button(toggle_text).clicked() {
+        action = Some(DrawModEntryResponse::ToggleEnabled);
+    }
+
+    if ui.add_enabled(!entry.is_latest(), Button::new(tr("mod_list.update"))).clicked() {
+        action = Some(DrawModEntryResponse::Update);
+    }
+
+    if ui.button(tr("mod_list.uninstall")).clicked() {
+        action = Some(DrawModEntryResponse::Uninstall);
+    }
+
+    if ui.button(tr("mod_list.more_info")).clicked() {
+        action = Some(DrawModEntryResponse::MoreInfo);
+    }
+
+    if entry.local_key.is_some() && ui.button(tr("mod_list.edit_details")).clicked() {
+        action = Some(DrawModEntryResponse::EditDetails);
+    }
+
+    let pin_text = if entry.pinned { tr("mod_list.unpin") } else { tr("mod_list.pin") };
+
+    if ui.button(pin_text).clicked() {
+        action = Some(DrawModEntryResponse::TogglePinned);
+    }
+
+    ui.separator();
+
+    if ui.add_enabled(entry.folder.is_some(), Button::new(tr("mod_list.open_folder"))).clicked() {
+        action = Some(DrawModEntryResponse::OpenFolder);
+    }
+
+    if ui.add_enabled(entry.id.is_some(), Button::new(tr("mod_list.copy_guid"))).clicked() {
+        action = Some(DrawModEntryResponse::CopyGuid);
+    }
+
+    if ui.add_enabled(entry.source_location.is_some(), Button::new(tr("mod_list.open_source"))).clicked() {
+        action = Some(DrawModEntryResponse::OpenSource);
+    }
+
+    if action.is_some() {
+        ui.close_menu();
+    }
+
+    action
+}
+
+/// What screen readers announce for an entry, everything that's otherwise only shown with colors
+fn entry_accessible_name(entry: &ModEntry) -> String {
+    let mut name = entry.name.clone();
+
+    if let Some(version) = &entry.version {
+        name.push_str(&format!(" v{}", version));
+    }
+
+    if !entry.is_latest() {
+        if let Some(latest_version) = &entry.latest_version {
+            name.push_str(&format!(", {} v{}", tr("mod_list.latest_is"), latest_version));
+        }
+    }
+
+    if !entry.enabled {
+        name.push_str(&format!(", {}", tr("mod_list.disabled")));
+    }
+
+    if entry.conflict.is_some() {
+        name.push_str(&format!(", {}", tr("mod_list.has_conflicts")));
+    }
+
+    if entry.pinned {
+        name.push_str(&format!(", {}", tr("mod_list.pinned")));
+    }
+
+    name
+}
@@ -1,32 +1,56 @@
 use std::cmp::max;
-use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use arc_swap::ArcSwap;
-use eframe::egui::{Align2, Area, Color32, ComboBox, Context, FontFamily, FontId, Frame, Margin, Pos2, pos2, Rect, Resize, Response, RichText, ScrollArea, Sense, Stroke, TextEdit, TextFormat, TextStyle, Ui, Vec2, vec2, Widget};
-use eframe::egui::text::LayoutJob;
-use eframe::epaint::text::TextWrapping;
+use eframe::egui::{Area, Color32, ComboBox, Context, Frame, Id, Margin, Pos2, pos2, ProgressBar, Resize, RichText, ScrollArea, TextEdit, Ui, vec2, Widget};
 use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
 use egui_modal::Modal;
 use egui_toast::Toasts;
 use futures::StreamExt;
 use tokio::sync::mpsc::Sender;
 use crate::config::Config;
-use crate::install::ModMap;
+use crate::install::{artifact_install_location, ArtifactStatus, ConflictSeverity, IDVersion, ModConflict, ModInstallOperations, ModMap};
 use crate::manager::ManagerCommand;
-use crate::manifest::{Category, GlobalModList, Mod};
+use crate::locale::{tr, tr_args};
+use crate::manifest::{Category, GlobalModList, GUID};
+use crate::resolver::{find_dependents, latest_update_for};
+use crate::ui::manager::mod_entry::{DrawModEntryResponse, ModEntryWidget};
+use crate::ui::manager::mod_pack::mod_pack_buttons;
+use crate::ui::manager::lockfile::lockfile_button;
 use crate::ui::manager::more_info::InfoModalState;
+use crate::ui::manager::local_details::LocalDetailsState;
+use crate::ui::manager::compatibility::compatibility_button;
+use crate::ui::manager::stray_mods::deep_scan_button;
+use crate::ui::manager::planner::planner_button;
+use crate::ui::manager::conflicts::conflicts_button;
+use crate::ui::manager::sideload::sideload_button;
+use crate::ui::manager::github_install::github_install_button;
+use crate::local_mods::{local_key_of, LocalModInfo};
+use crate::ui::manager::report::mod_report_button;
+use crate::ui::manager::storage::storage_button;
 use crate::ui::manager::UIManagerState;
-use crate::utils::{get_next_id, handle_error, lerp_color, lerp_f32};
-use crate::version::Version;
+use crate::utils::{append_relative_path, handle_error};
+use crate::version::{SimplifiedReq, Version, VersionReq};
 
 pub struct ModListState {
     mod_view: ModView,
     filter: String,
     last_mod_count: usize,
-    expanded_entry: u64,
-    pub more_info: InfoModalState
+    /// Stable ID of the expanded entry
+    expanded_entry: Option<Id>,
+    /// Stable ID of the selected entry, so selection follows the mod when the list changes
+    selected_entry: Option<Id>,
+    /// Stable IDs of entries in the order they were drawn last frame
+    visible_entries: Vec<Id>,
+    /// Row each mod from manifest was drawn at last frame
+    rows: HashMap<GUID, usize>,
+    /// Stays on until the selected entry gets drawn, list might be rebuilding when selection is made
+    scroll_to_selected: bool,
+    focus_search: bool,
+    key_action: Option<DrawModEntryResponse>,
+    pub more_info: InfoModalState,
+    pub local_details: LocalDetailsState
 }
 
 impl ModListState {
@@ -35,26 +59,107 @@ impl ModListState {
             mod_view: Default::default(),
             filter: "".to_string(),
             last_mod_count: 0,
-            expanded_entry: 0,
+            expanded_entry: None,
+            selected_entry: None,
+            visible_entries: vec![],
+            rows: HashMap::new(),
+            scroll_to_selected: false,
+            focus_search: false,
+            key_action: None,
             more_info: InfoModalState::from_context(ctx),
+            local_details: LocalDetailsState::from_context(ctx),
         }
     }
+
+    fn selected_row(&self) -> Option<usize> {
+        self.selected_entry.and_then(|id| self.visible_entries.iter().position(|x| *x == id))
+    }
+
+    pub fn select_next(&mut self) {
+        if self.visible_entries.is_empty() {
+            return;
+        }
+
+        let row = self.selected_row().map_or(0, |x| (x + 1).min(self.visible_entries.len() - 1));
+
+        self.selected_entry = Some(self.visible_entries[row]);
+        self.scroll_to_selected = true;
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.visible_entries.is_empty() {
+            return;
+        }
+
+        let row = self.selected_row().map_or(0, |x| x.saturating_sub(1));
+
+        self.selected_entry = Some(self.visible_entries[row]);
+        self.scroll_to_selected = true;
+    }
+
+    /// Selects the mod and scrolls the list to it, search filter is cleared if it hides the mod
+    pub fn select_mod(&mut self, guid: &str) {
+        if !self.rows.contains_key(guid) && !self.filter.is_empty() {
+            self.filter.clear();
+            self.invalidate();
+        }
+
+        self.selected_entry = Some(entry_id(guid));
+        self.scroll_to_selected = true;
+    }
+
+    pub fn expand_selected(&mut self) {
+        if self.selected_entry.is_some() {
+            self.key_action = Some(DrawModEntryResponse::ToggleExpand);
+        }
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if self.selected_entry.is_some() {
+            self.key_action = Some(DrawModEntryResponse::ToggleEnabled);
+        }
+    }
+
+    pub fn focus_search(&mut self) {
+        self.focus_search = true;
+    }
+
+    /// Forces entries to be rebuilt from the mod map on next frame
+    pub fn invalidate(&mut self) {
+        self.last_mod_count = usize::MAX;
+    }
 }
 
 pub enum ModView {
     NotInitialized,
     Category(Vec<(String, Vec<ModEntry>)>),
+    /// Grouped by scan location files were found in
+    Location(Vec<(String, Vec<ModEntry>)>),
     All(Vec<ModEntry>)
 }
 
 impl ModView {
     pub fn variant(&self) -> String {
         match self {
-            ModView::Category(_) => format!("Category"),
-            ModView::NotInitialized | ModView::All(_) => format!("Alphabetic")
+            ModView::Category(_) => tr("mod_list.sort_category"),
+            ModView::Location(_) => tr("mod_list.sort_location"),
+            ModView::NotInitialized | ModView::All(_) => tr("mod_list.sort_alphabetic")
         }
     }
 
+    /// Same kind of view made from new entries
+    fn with_entries(&self, mods: Vec<ModEntry>) -> ModView {
+        match self {
+            ModView::NotInitialized | ModView::Category(_) => ModView::Category(split_by_categories(mods)),
+            ModView::Location(_) => ModView::Location(split_by_locations(mods)),
+            ModView::All(_) => ModView::All(mods)
+        }
+    }
+
+    pub fn is_location(&self) -> bool {
+        matches!(self, ModView::Location(_))
+    }
+
     pub fn is_category(&self) -> bool {
         if let ModView::Category(_) = self {
             true
@@ -78,46 +183,116 @@ impl Default for ModView {
     }
 }
 
-#[derive(Debug, Hash)]
+#[derive(Debug)]
 pub struct ModEntry {
-    category: Category,
+    pub(crate) category: Category,
     pub(crate) name: String,
     pub(crate) id: Option<String>,
-    version: Option<Version>,
-    latest_version: Option<Version>,
-    description: Option<String>,
-    enabled: bool
+    pub(crate) version: Option<Version>,
+    pub(crate) latest_version: Option<Version>,
+    pub(crate) description: Option<String>,
+    pub(crate) enabled: bool,
+    /// Mod ID and version as they are in mod map
+    pub(crate) key: IDVersion,
+    pub(crate) status: ArtifactStatus,
+    /// Installed mods that depend on this one, with versions they need
+    pub(crate) required_by: Vec<String>,
+    /// What all of the dependents need from the mod together, only when there's more than one
+    pub(crate) combined_requirement: Option<SimplifiedReq>,
+    /// Total size of the mod's files in bytes
+    pub(crate) size: u64,
+    /// Latest modification time of the mod's files, seconds since unix epoch
+    pub(crate) installed_at: u64,
+    /// Assembly version found in the mod's dll
+    pub(crate) assembly_version: Option<String>,
+    /// Scan location the files were found in, relative to Neos folder
+    location: Option<PathBuf>,
+    /// Files aren't in the folder manifest says they should be installed to
+    misplaced: bool,
+    /// Folder the first file of the mod is in
+    pub(crate) folder: Option<PathBuf>,
+    pub(crate) source_location: Option<String>,
+    /// Hash of the file user details are kept under, only for mods that aren't in any manifest
+    pub(crate) local_key: Option<String>,
+    /// What user wrote about the mod
+    pub(crate) notes: Option<String>,
+    /// Worst conflict the mod is involved in that wasn't suppressed
+    pub(crate) conflict: Option<ConflictSeverity>,
+    /// Shown above the other mods of its group
+    pub(crate) pinned: bool
+}
+
+impl ModEntry {
+    /// Stays the same across refreshes, so toggling or updating the mod doesn't reset its UI state.
+    /// Mods from manifest go by GUID, unknown ones by where their file is
+    pub(crate) fn stable_id(&self) -> Id {
+        match &self.id {
+            Some(guid) => entry_id(guid),
+            None => Id::new(("mod_entry", &self.folder, &self.key.0))
+        }
+    }
+
+    /// Installed version is the latest one, or there's nothing to compare with
+    pub(crate) fn is_latest(&self) -> bool {
+        self.version.as_ref()
+            .zip(self.latest_version.as_ref())
+            .map_or(true, |(version, latest)| version >= latest)
+    }
 }
 
 pub fn mod_list_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
     let mod_map = &state.mod_list;
     let global_mods = &state.manifest_mods;
+    let local_mods = &state.local_mods;
+    let notes = &state.notes;
+    let conflicts = &state.conflicts;
+
+    if let Some((scanned, total)) = state.scan_progress {
+        ProgressBar::new(scanned as f32 / total as f32)
+            .desired_width(ui.available_width())
+            .text(RichText::new(tr_args("mod_list.scanning", &[
+                ("scanned", scanned.to_string()),
+                ("total", total.to_string())
+            ])).small())
+            .animate(true)
+            .ui(ui);
+    }
 
     ui.horizontal(|ui| {
-        if TextEdit::singleline(&mut state.mod_list_state.filter)
-            .hint_text("Search")
+        let search_response = TextEdit::singleline(&mut state.mod_list_state.filter)
+            .hint_text(tr("mod_list.search"))
             .desired_width(250.0)
-            .ui(ui).changed() {
-            let mut mods = build_entries(mod_map, global_mods);
+            .ui(ui);
+
+        if state.mod_list_state.focus_search {
+            search_response.request_focus();
+            state.mod_list_state.focus_search = false;
+        }
+
+        if search_response.changed() {
+            state.mod_list_state.selected_entry = None;
+            state.mod_list_state.scroll_to_selected = false;
+
+            let mut mods = build_entries(mod_map, global_mods, local_mods, notes, conflicts, &config.load());
 
             if !state.mod_list_state.filter.is_empty() {
                 mods.retain(|x| filter_entry(&state.mod_list_state.filter, x))
             }
 
             match &state.mod_list_state.mod_view {
-                ModView::Category(_) => state.mod_list_state.mod_view = ModView::Category(split_by_categories(mods)),
-                ModView::NotInitialized | ModView::All(_) => state.mod_list_state.mod_view = ModView::All(mods)
+                ModView::NotInitialized => state.mod_list_state.mod_view = ModView::All(mods),
+                view => state.mod_list_state.mod_view = view.with_entries(mods)
             }
         }
 
         ui.separator();
 
-        ComboBox::from_label("Sort by")
+        ComboBox::from_label(tr("mod_list.sort_by"))
             .selected_text(state.mod_list_state.mod_view.variant())
             .width(120.0)
             .show_ui(ui, |ui| {
                 { // Category
-                    let mut response = ui.selectable_label(state.mod_list_state.mod_view.is_category(), "Category");
+                    let mut response = ui.selectable_label(state.mod_list_state.mod_view.is_category(), tr("mod_list.sort_category"));
                     if response.clicked() && !state.mod_list_state.mod_view.is_category() {
                         state.mod_list_state.last_mod_count = 0;
                         state.mod_list_state.mod_view = ModView::Category(vec![]);
@@ -125,8 +300,17 @@ pub fn mod_list_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                     }
                 }
 
+                { // Location
+                    let mut response = ui.selectable_label(state.mod_list_state.mod_view.is_location(), tr("mod_list.sort_location"));
+                    if response.clicked() && !state.mod_list_state.mod_view.is_location() {
+                        state.mod_list_state.last_mod_count = 0;
+                        state.mod_list_state.mod_view = ModView::Location(vec![]);
+                        response.mark_changed();
+                    }
+                }
+
                 { // All
-                    let mut response = ui.selectable_label(state.mod_list_state.mod_view.is_all(), "Alphabetic");
+                    let mut response = ui.selectable_label(state.mod_list_state.mod_view.is_all(), tr("mod_list.sort_alphabetic"));
                     if response.clicked() && !state.mod_list_state.mod_view.is_all() {
                         state.mod_list_state.last_mod_count = 0;
                         state.mod_list_state.mod_view = ModView::All(vec![]);
@@ -134,6 +318,20 @@ pub fn mod_list_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                     }
                 }
             });
+
+        ui.separator();
+
+        mod_pack_buttons(&mut state.mod_pack_state, mod_map, ui);
+        lockfile_button(&mut state.lockfile_state, ui);
+        storage_button(&mut state.storage_state, ui);
+        compatibility_button(&mut state.compatibility_state, &config.load(), ui);
+        deep_scan_button(&mut state.stray_mods_state, ui, toasts, command);
+        conflicts_button(&mut state.conflicts_state, &state.conflicts, &config.load(), ui);
+        planner_button(&mut state.planner_state, ui);
+        sideload_button(&mut state.sideload_state, ui);
+        github_install_button(&mut state.github_install_state, ui);
+        let loaded_config = config.load();
+        mod_report_button(&mut state.report_state, mod_map, global_mods, loaded_config.neos_exe_location.parent().unwrap_or(Path::new("")), &mut state.notification_center, &loaded_config.toasts, ui, toasts);
     });
 
     ui.separator();
@@ -141,13 +339,19 @@ pub fn mod_list_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
     let mod_list_state = &mut state.mod_list_state;
 
     ScrollArea::vertical()
+        .id_source("mod_list_scroll")
         .show(ui, |ui| {
             ui.scope(|ui| {
                 ui.spacing_mut().item_spacing = vec2(8.0, 4.0);
 
+                let mut visible_entries = vec![];
+                let mut rows = HashMap::new();
+                let mut drawn = false;
+                let by_location = mod_list_state.mod_view.is_location();
+
                 match &mut mod_list_state.mod_view {
                     ModView::NotInitialized => {
-                        let mut mods = build_entries(mod_map, global_mods);
+                        let mut mods = build_entries(mod_map, global_mods, local_mods, notes, conflicts, &config.load());
                         mod_list_state.last_mod_count = mods.len();
 
                         if !mod_list_state.filter.is_empty() {
@@ -156,87 +360,98 @@ pub fn mod_list_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
 
                         mod_list_state.mod_view = ModView::Category(split_by_categories(mods))
                     }
-                    ModView::Category(mods) => {
+                    ModView::Category(mods) | ModView::Location(mods) => {
                         if mod_list_state.last_mod_count == mod_map.len() {
+                            drawn = true;
+
                             for (category, category_mods) in mods {
                                 ui.heading(category);
 
+                                if by_location {
+                                    misplaced_mods_fix(ui, category_mods, toasts, command);
+                                }
+
                                 ui.add_space(2.0);
 
                                 let mut first_one = true;
 
                                 for mod_item in category_mods {
-                                    let mut hasher = DefaultHasher::new();
-                                    mod_item.hash(&mut hasher);
-                                    let hash = hasher.finish();
-
-                                    match draw_mod_entry(ui, mod_item, first_one, mod_list_state.expanded_entry == hash) {
-                                        DrawModEntryResponse::Nothing => {}
-                                        DrawModEntryResponse::ToggleExpand => {
-                                            if mod_list_state.expanded_entry == hash {
-                                                mod_list_state.expanded_entry = 0;
-                                            } else {
-                                                mod_list_state.expanded_entry = hash;
-                                            }
-                                        }
-                                        DrawModEntryResponse::ToggleEnabled => {
-                                            mod_item.enabled = !mod_item.enabled;
-                                        }
-                                        DrawModEntryResponse::MoreInfo => {
-                                            mod_list_state.more_info.open_with_entry_data(mod_item, global_mods, toasts, command);
+                                    let id = mod_item.stable_id();
+
+                                    if let Some(guid) = &mod_item.id {
+                                        rows.insert(guid.clone(), visible_entries.len());
+                                    }
+                                    visible_entries.push(id);
+
+                                    let selected = mod_list_state.selected_entry == Some(id);
+
+                                    let mut response = DrawModEntryResponse::Nothing;
+
+                                    ui.add(ModEntryWidget::new(mod_item, &mut response)
+                                        .first_one(first_one)
+                                        .expanded(mod_list_state.expanded_entry == Some(id))
+                                        .selected(selected)
+                                        .scroll_to(selected && mod_list_state.scroll_to_selected));
+
+                                    if selected {
+                                        if let Some(action) = mod_list_state.key_action.take() {
+                                            response = action;
                                         }
-                                        DrawModEntryResponse::Uninstall => {}
-                                        DrawModEntryResponse::Update => {}
                                     }
 
+                                    handle_entry_response(response, mod_item, id, &mut mod_list_state.expanded_entry, &mut mod_list_state.more_info, &mut mod_list_state.local_details, &mut mod_list_state.last_mod_count, global_mods, config, ui, toasts, command);
+
                                     first_one = false;
                                 }
 
                                 ui.add_space(10.0);
                             }
                         } else {
-                            let mut mods = build_entries(mod_map, global_mods);
+                            let mut mods = build_entries(mod_map, global_mods, local_mods, notes, conflicts, &config.load());
                             mod_list_state.last_mod_count = mods.len();
 
                             if !mod_list_state.filter.is_empty() {
                                 mods.retain(|x| filter_entry(&mod_list_state.filter, x))
                             }
 
-                            mod_list_state.mod_view = ModView::Category(split_by_categories(mods))
+                            mod_list_state.mod_view = mod_list_state.mod_view.with_entries(mods)
                         }
                     }
                     ModView::All(mods) => {
                         if mod_list_state.last_mod_count == mod_map.len() {
+                            drawn = true;
                             let mut first_one = true;
 
                             for mod_item in mods {
-                                let mut hasher = DefaultHasher::new();
-                                mod_item.hash(&mut hasher);
-                                let hash = hasher.finish();
-
-                                match draw_mod_entry(ui, mod_item, first_one, mod_list_state.expanded_entry == hash) {
-                                    DrawModEntryResponse::Nothing => {}
-                                    DrawModEntryResponse::ToggleExpand => {
-                                        if mod_list_state.expanded_entry == hash {
-                                            mod_list_state.expanded_entry = 0;
-                                        } else {
-                                            mod_list_state.expanded_entry = hash;
-                                        }
-                                    }
-                                    DrawModEntryResponse::ToggleEnabled => {
-                                        mod_item.enabled = !mod_item.enabled;
-                                    }
-                                    DrawModEntryResponse::MoreInfo => {
-                                        mod_list_state.more_info.open_with_entry_data(mod_item, global_mods, toasts, command);
+                                let id = mod_item.stable_id();
+
+                                if let Some(guid) = &mod_item.id {
+                                    rows.insert(guid.clone(), visible_entries.len());
+                                }
+                                visible_entries.push(id);
+
+                                let selected = mod_list_state.selected_entry == Some(id);
+
+                                let mut response = DrawModEntryResponse::Nothing;
+
+                                ui.add(ModEntryWidget::new(mod_item, &mut response)
+                                    .first_one(first_one)
+                                    .expanded(mod_list_state.expanded_entry == Some(id))
+                                    .selected(selected)
+                                    .scroll_to(selected && mod_list_state.scroll_to_selected));
+
+                                if selected {
+                                    if let Some(action) = mod_list_state.key_action.take() {
+                                        response = action;
                                     }
-                                    DrawModEntryResponse::Uninstall => {}
-                                    DrawModEntryResponse::Update => {}
                                 }
 
+                                handle_entry_response(response, mod_item, id, &mut mod_list_state.expanded_entry, &mut mod_list_state.more_info, &mut mod_list_state.local_details, &mut mod_list_state.last_mod_count, global_mods, config, ui, toasts, command);
+
                                 first_one = false;
                             }
                         } else {
-                            let mut mods = build_entries(mod_map, global_mods);
+                            let mut mods = build_entries(mod_map, global_mods, local_mods, notes, conflicts, &config.load());
                             mod_list_state.last_mod_count = mods.len();
 
                             if !mod_list_state.filter.is_empty() {
@@ -247,386 +462,255 @@ pub fn mod_list_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui
                         }
                     }
                 }
-            });
-        });
-}
-
-fn draw_mod_entry(ui: &mut Ui, entry: &ModEntry, first_one: bool, expanded: bool) -> DrawModEntryResponse {
-    let inter_mod_gap = 10_f32;
-
-    // Prefix
-    let target_prefix = if expanded && !first_one {
-        inter_mod_gap
-    } else {
-        0.0
-    };
-
-    let animated_prefix_id = get_next_id(ui);
-    let animated_prefix = ui.ctx().animate_value_with_time(animated_prefix_id, target_prefix, 0.1);
-
-    ui.add_space(animated_prefix);
-
-    // The actual component starts here
-    let normal_text = ui.style().text_styles.get(&TextStyle::Body).cloned().unwrap_or_else(|| FontId { size: 15.0, family: FontFamily::Proportional });
-    let small_text = ui.style().text_styles.get(&TextStyle::Small).cloned().unwrap_or_else(|| FontId { size: 12.0, family: FontFamily::Proportional });
-
-    let max_rect = ui.max_rect();
-    let element_width = max_rect.width();
-    let element_left_top = ui.next_widget_position();
-    let element_height = 60.0_f32;
-
-    let button_width = 100_f32;
-    let button_height = 20_f32;
-
-    // Arrow calculations
-    let arrow_font_id = FontId { size: 25.0, family: FontFamily::Proportional };
-    let arrow_width = 30.0_f32;
-    let arrow_point = element_left_top + vec2(element_width - arrow_width / 2.0 - 10.0, element_height / 2.0 + if expanded {
-        1.0
-    } else {
-        0.0
-    });
 
-    // Checkbox calculation
-    const CHECKBOX_MAX_SIZE: f32 = 30.0;
+                mod_list_state.key_action = None;
 
-    let checkbox_offset = element_height / 2.0 - CHECKBOX_MAX_SIZE / 2.0;
-    let checkbox_starting_pos = element_left_top + Vec2::new(element_width - arrow_width - element_height + checkbox_offset, checkbox_offset);
-    let checkbox_end_pos = element_left_top + Vec2::new(element_width - arrow_width - checkbox_offset, element_height - checkbox_offset);
-    let checkbox_rect = Rect::from([checkbox_starting_pos, checkbox_end_pos]);
+                // Entries weren't drawn while the view was being rebuilt, selection is kept for the next frame
+                if drawn {
+                    if mod_list_state.selected_entry.map_or(false, |x| !visible_entries.contains(&x)) {
+                        mod_list_state.selected_entry = None;
+                    }
 
-    // Text container
-    let text_container_width = element_width - element_height - arrow_width;
+                    mod_list_state.scroll_to_selected = false;
+                    mod_list_state.visible_entries = visible_entries;
+                    mod_list_state.rows = rows;
+                }
+            });
+        });
+}
 
-    // Expand calculations
-    let mut description_galley = if expanded {
-        entry.description.as_ref().map(|x| {
-            ui.painter().layout(x.clone(), small_text.clone(), Color32::BLACK, element_width - 20.0)
-        })
-    } else {
-        None
-    };
+/// Stable ID of the entry for mod from manifest
+pub(crate) fn entry_id(guid: &str) -> Id {
+    Id::new(("mod_entry", guid))
+}
 
-    let target_height = if expanded {
-        if let Some(galley) = &description_galley {
-            galley.rect.height() + button_height + 25.0 + inter_mod_gap
-        } else {
-            button_height + 10.0 + inter_mod_gap
+/// Carries out what was picked on the entry, both views share it
+fn handle_entry_response(response: DrawModEntryResponse, mod_item: &mut ModEntry, id: Id, expanded_entry: &mut Option<Id>, more_info: &mut InfoModalState, local_details: &mut LocalDetailsState, last_mod_count: &mut usize, global_mods: &GlobalModList, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    match response {
+        DrawModEntryResponse::Nothing => {}
+        DrawModEntryResponse::ToggleExpand => {
+            if *expanded_entry == Some(id) {
+                *expanded_entry = None;
+            } else {
+                *expanded_entry = Some(id);
+            }
         }
-    } else {
-        0.0
-    };
-
-    let animated_spacer = ui.ctx().animate_value_with_time(ui.next_auto_id(), target_height, 0.1);
-    let expanded_rect = Rect::from_min_size(element_left_top, vec2(element_width, element_height + animated_spacer - inter_mod_gap));
-
-    // Responses
-    let (element_rect, mut element_response) = ui.allocate_exact_size(Vec2::new(element_width, element_height), Sense::click());
-    let checkbox_id = get_next_id(ui);
-    let mut checkbox_response = ui.interact(checkbox_rect.clone(), checkbox_id, Sense::click());
-
-    let more_info_id = get_next_id(ui);
-    let uninstall_id = get_next_id(ui);
-    let update_id = get_next_id(ui);
-
-    let mut additional_responses = if animated_spacer > 0.1 {
-        let more_info_pos = expanded_rect.right_bottom() - vec2(5.0 + button_width, 5.0 + button_height);
-        let uninstall_pos = more_info_pos - vec2(5.0 + button_width, 0.0);
-        let update_pos = uninstall_pos - vec2(5.0 + button_width, 0.0);
-
-        let more_info_rect = Rect::from_min_size(more_info_pos, vec2(button_width, button_height));
-        let uninstall_rect = Rect::from_min_size(uninstall_pos, vec2(button_width, button_height));
-        let update_rect = Rect::from_min_size(update_pos, vec2(button_width, button_height));
-
-        Some((
-            ui.interact(more_info_rect, more_info_id, Sense::click()),
-            ui.interact(uninstall_rect, uninstall_id, Sense::click()),
-            ui.interact(update_rect, update_id, Sense::click()),
-        ))
-    } else {
-        None
-    };
-
-    ui.add_space(animated_spacer);
-
-    // Actually painting
-    if ui.is_rect_visible(element_rect) {
-        // Latest version test
-        let is_latest = entry.version.as_ref().and_then(|x| {
-            let latest = entry.latest_version.as_ref()?;
-            Some(x >= latest)
-        }).unwrap_or(true);
-
-        // Fixing title text
-        let no_new_line_name = entry.name.replace('\n', "\\n");
-
-        let title = if no_new_line_name.len() > 80 {
-            format!("{}...", no_new_line_name.chars().take(80).collect::<String>())
-        } else {
-            no_new_line_name
-        };
-
-        // Calculating text sizes
-        let title_galley = ui.painter().layout(
-            title,
-            normal_text.clone(),
-            Color32::BLACK,
-            text_container_width
-        );
-
-        let id_version_text = entry.id.as_ref().map(|x| {
-            let id = x.replace('\n', "\\n");
-
-            let id = if id.len() > 55 {
-                format!("{}...", id.chars().take(55).collect::<String>())
+        DrawModEntryResponse::ToggleEnabled => {
+            let operation = if mod_item.enabled {
+                ModInstallOperations::DisableMod(mod_item.key.clone())
             } else {
-                id
-            };
-
-            let mut job = LayoutJob {
-                wrap: TextWrapping {
-                    max_width: text_container_width,
-                    ..Default::default()
-                },
-                ..Default::default()
+                ModInstallOperations::EnableMod(mod_item.key.clone())
             };
 
-            job.append(&format!("{} ", id), 0.0, TextFormat {
-                font_id: small_text.clone(),
-                color: Color32::GRAY,
-                ..Default::default()
-            });
-
-            if is_latest {
-                if let Some(version) = &entry.version {
-                    job.append(&format!("v{}", version), 0.0, TextFormat {
-                        font_id: small_text.clone(),
-                        color: Color32::GRAY,
-                        ..Default::default()
-                    });
-                }
-            } else {
-                if let Some(version) = &entry.version {
-                    job.append(&format!("v{}", version), 0.0, TextFormat {
-                        font_id: small_text.clone(),
-                        color: Color32::LIGHT_RED,
-                        ..Default::default()
-                    });
-
-                    job.append(", ", 0.0, TextFormat {
-                        font_id: small_text.clone(),
-                        color: Color32::GRAY,
-                        ..Default::default()
-                    });
-
-                    if let Some(latest_version) = &entry.latest_version {
-                        job.append(&format!("latest is v{}", latest_version), 0.0, TextFormat {
-                            font_id: small_text.clone(),
-                            color: Color32::LIGHT_GREEN,
-                            ..Default::default()
-                        });
-                    }
-                }
+            mod_item.enabled = !mod_item.enabled;
+            handle_error(command.blocking_send(ManagerCommand::PreviewOperations(vec![operation])), toasts);
+        }
+        DrawModEntryResponse::MoreInfo => {
+            more_info.open_with_entry_data(mod_item, global_mods, toasts, command);
+        }
+        DrawModEntryResponse::Uninstall => {
+            handle_error(command.blocking_send(ManagerCommand::PreviewOperations(vec![ModInstallOperations::UninstallMod(mod_item.key.clone())])), toasts);
+        }
+        DrawModEntryResponse::Update => {
+            if let (false, Some(latest)) = (mod_item.is_latest(), &mod_item.latest_version) {
+                handle_error(command.blocking_send(ManagerCommand::InstallMod {
+                    guid: mod_item.key.0.clone(),
+                    version_req: VersionReq::at_least(latest),
+                }), toasts);
             }
-
-            job
-        });
-
-        let id_galley = id_version_text.map(|x| ui.ctx().fonts(|f| f.layout_job(x)));
-
-        let title_height = title_galley.rect.height();
-        let id_height = id_galley.as_ref().map_or(0.0, |x| x.rect.height());
-
-        // Drawing the main element background
-        let element_visuals = if (element_response.is_pointer_button_down_on() || element_response.has_focus()) && !checkbox_response.is_pointer_button_down_on() {
-            ui.style().visuals.widgets.active
-        } else if element_response.hovered() || element_response.highlighted() {
-            ui.style().visuals.widgets.hovered
-        } else {
-            ui.style().visuals.widgets.inactive
-        };
-
-        let bg_rect = expanded_rect.clone();
-        let fg_rect = element_rect.expand(element_visuals.expansion);
-
-        ui.painter()
-            .rect(bg_rect, 4.0, ui.visuals().widgets.noninteractive.bg_fill, ui.visuals().widgets.noninteractive.bg_stroke);
-
-        // Drawing additional options here
-        if let Some((ref mut more_info, ref mut uninstall, ref mut update)) = &mut additional_responses {
-            let element_bottom_pos = expanded_rect.left_bottom();
-
-            if let Some(description_galley) = description_galley {
-                let description_pos = element_bottom_pos + vec2(10.0, -13.0 - button_height - description_galley.rect.height());
-                ui.painter().galley_with_color(
-                    description_pos,
-                    description_galley,
-                    Color32::LIGHT_GRAY
-                );
+        }
+        DrawModEntryResponse::OpenFolder => {
+            if let Some(folder) = &mod_item.folder {
+                handle_error(open::that(folder), toasts);
             }
-
-            draw_button(ui, "More Info", normal_text.clone(), more_info, true);
-            draw_button(ui, "Uninstall", normal_text.clone(), uninstall, true);
-            draw_button(ui, "Update", normal_text.clone(), update, !is_latest);
         }
-
-        // Drawing the mod button
-        ui.painter()
-            .rect(fg_rect, 4.0, element_visuals.bg_fill, element_visuals.bg_stroke);
-
-        // Drawing the arrow
-        ui.painter().text(arrow_point, Align2::CENTER_CENTER, if expanded {
-            format!("⏷")
-        } else {
-            format!("⏵")
-        }, arrow_font_id.clone(), element_visuals.text_color());
-
-        // Drawing the checkbox
-        let checkbox_selected_visuals = ui.style().interact_selectable(&checkbox_response, true);
-        let checkbox_visuals = ui.style().interact_selectable(&checkbox_response, entry.enabled);
-
-        let target = if entry.enabled { 1.0 } else { 0.0 };
-
-        let t = ui.ctx().animate_value_with_time(checkbox_id, target, 0.2);
-        let lerped_color = lerp_color(&ui.style().visuals.panel_fill, &checkbox_selected_visuals.bg_fill, t);
-        let lerped_transparency_color = lerp_color(&Color32::TRANSPARENT, &checkbox_selected_visuals.bg_fill, t);
-
-        let checkbox_shrink = lerp_f32(4.0, 0.0, t);
-        let checkbox_current_rect = checkbox_rect.shrink(checkbox_shrink);
-
-        let inner_box_size = lerp_f32(3.0, 23.0, t);
-        let inner_box_rect = Rect::from_center_size(checkbox_current_rect.center(), vec2(inner_box_size, inner_box_size));
-
-        ui.painter().rect(
-            checkbox_current_rect.expand(checkbox_visuals.expansion + 1.0),
-            4.0,
-            Color32::TRANSPARENT,
-            checkbox_visuals.bg_stroke
-        );
-
-        ui.painter().rect(
-            checkbox_current_rect,
-            2.0,
-            Color32::TRANSPARENT,
-            Stroke::new(3.0, lerped_color)
-        );
-
-        ui.painter().rect(
-            inner_box_rect,
-            2.0,
-            lerped_transparency_color,
-            Stroke::new(0.0, Color32::TRANSPARENT)
-        );
-
-        // Calculating text position
-        let text_height = 2.0 + title_height + id_height;
-
-        let title_pos = element_left_top + vec2(10.0, element_height / 2.0 - text_height / 2.0);
-        let id_pos = title_pos + vec2(0.0, title_height + 2.0);
-
-        // Drawing text in separate clipped painter
-        let text_painter = ui.painter_at(Rect::from_min_size(element_left_top.clone(), vec2(text_container_width, element_height)));
-
-        text_painter.galley_with_color(
-            title_pos,
-            title_galley,
-            element_visuals.text_color(),
-        );
-
-        if let Some(id_galley) = id_galley {
-            text_painter.galley(
-                id_pos,
-                id_galley
-            );
+        DrawModEntryResponse::CopyGuid => {
+            if let Some(id) = &mod_item.id {
+                ui.output_mut(|x| x.copied_text = id.clone());
+            }
         }
-    }
-
-    if let Some((more_info, uninstall, update)) = additional_responses {
-        if more_info.clicked() {
-            return DrawModEntryResponse::MoreInfo;
-        } else if uninstall.clicked() {
-            return DrawModEntryResponse::Uninstall;
-        } else if update.clicked() {
-            return DrawModEntryResponse::Update;
+        DrawModEntryResponse::OpenSource => {
+            if let Some(source_location) = &mod_item.source_location {
+                handle_error(open::that(source_location), toasts);
+            }
+        }
+        DrawModEntryResponse::TogglePinned => {
+            let mut config_str = config.load().as_ref().clone();
+            config_str.toggle_pinned(&mod_item.key.0);
+            config.swap(Arc::new(config_str));
+            handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+
+            // Entries get built again, so the mod moves to its new place
+            *last_mod_count = 0;
+        }
+        DrawModEntryResponse::EditDetails => {
+            local_details.open_with_entry(mod_item);
         }
     }
-
-    if checkbox_response.clicked() {
-        DrawModEntryResponse::ToggleEnabled
-    } else if element_response.clicked() {
-        DrawModEntryResponse::ToggleExpand
-    } else {
-        DrawModEntryResponse::Nothing
-    }
-}
-
-fn draw_button(ui: &mut Ui, text: &str, font_id: FontId, mut response: &mut Response, enabled: bool) {
-    let rect = response.rect;
-
-    let visuals = if enabled {
-        ui.style().interact(&response)
-    } else {
-        &ui.style().visuals.widgets.noninteractive
-    };
-
-    // Button background
-    ui.painter()
-        .rect(rect.clone(), 4.0, visuals.bg_fill, visuals.bg_stroke);
-
-    // Text
-    ui.painter()
-        .text(rect.center(), Align2::CENTER_CENTER, text, font_id, visuals.text_color());
-}
-
-enum DrawModEntryResponse {
-    Nothing,
-    ToggleExpand,
-    ToggleEnabled,
-    MoreInfo,
-    Uninstall,
-    Update
 }
 
-fn build_entries(mod_map: &ModMap, global_mods: &GlobalModList) -> Vec<ModEntry> {
-    let mut mod_iter = mod_map.iter()
-        .filter(|(_, l)| l.len() > 0);
-
+fn build_entries(mod_map: &ModMap, global_mods: &GlobalModList, local_mods: &HashMap<String, LocalModInfo>, notes: &HashMap<String, String>, conflicts: &[ModConflict], config: &Config) -> Vec<ModEntry> {
     let global_modlist = global_mods.mod_list.load();
+    let neos_folder = config.neos_exe_location.parent().unwrap_or(Path::new(""));
     let mut mods = vec![];
 
-    while let Some((mod_id, versions)) = mod_iter.next() {
-        let (version, file) = versions.iter().next().unwrap();
+    for mod_id in mod_map.keys() {
+        let Some((version, file)) = mod_map.installed_version(mod_id).and_then(|x| Some((x, mod_map.file(mod_id, x)?))) else {
+            continue;
+        };
+        let key = (mod_id.clone(), version.clone());
+        let folder = file.files.first().and_then(|x| x.file_path.parent());
+        let location = folder.and_then(|x| scan_location_of(x, neos_folder, config));
+        let conflict = conflicts.iter()
+            .filter(|x| x.involves(mod_id) && !config.is_conflict_suppressed(x))
+            .map(|x| x.severity())
+            .max();
+
+        let dependents = find_dependents(mod_id, mod_map, &global_modlist);
+
+        let combined_requirement = dependents.iter()
+            .map(|(_, requirement)| requirement.clone())
+            .reduce(|a, b| a.intersect(&b))
+            .filter(|_| dependents.len() > 1)
+            .map(|x| x.simplify());
+
+        let required_by = dependents.into_iter()
+            .map(|((dependent_id, dependent_version), requirement)| {
+                let name = global_modlist.get(&dependent_id).map_or(dependent_id, |x| x.name.clone());
+                format!("{} v{} ({})", name, dependent_version, requirement)
+            })
+            .collect();
 
         if let Some(manifest_mod) = global_modlist.get(mod_id) {
+            let expected = manifest_mod.versions.get(version).map_or(file.files.len(), |x| x.artifacts.len());
+            let expected_folder = manifest_mod.versions.get(version)
+                .and_then(|x| x.artifacts.first())
+                .map(|x| artifact_install_location(x, manifest_mod.install_category(), None, &config.install_profile.default_locations))
+                .and_then(|x| {
+                    let mut path = neos_folder.to_path_buf();
+                    append_relative_path(&mut path, x).ok().map(|_| path)
+                });
+
             mods.push(ModEntry {
                 category: manifest_mod.category,
                 name: manifest_mod.name.clone(),
                 id: Some(mod_id.to_string()),
                 version: Some(version.clone()),
-                latest_version: manifest_mod.versions.iter().map(|(v, _)| v).max().cloned(),
+                latest_version: latest_update_for(version, manifest_mod, config.update_channel_for(mod_id)).cloned(),
                 description: Some(manifest_mod.description.clone()),
-                enabled: file.files.iter().all(|x| !x.disabled),
+                enabled: file.is_enabled(),
+                key,
+                status: file.status(expected),
+                required_by,
+                combined_requirement,
+                size: file.total_size(),
+                installed_at: file.installed_at(),
+                assembly_version: file.assembly_version(),
+                location,
+                misplaced: expected_folder.is_some() && folder != expected_folder.as_deref(),
+                folder: folder.map(|x| x.to_path_buf()),
+                source_location: manifest_mod.source_location.clone(),
+                local_key: None,
+                notes: notes.get(mod_id).cloned(),
+                conflict,
+                pinned: config.is_pinned(mod_id),
             })
         } else {
+            let local_key = local_key_of(local_mods, file);
+            let local_info = local_key.as_ref().and_then(|x| local_mods.get(x));
+
             mods.push(ModEntry {
-                category: Category::Unknown,
-                name: mod_id.clone(),
+                category: local_info.and_then(|x| x.category).unwrap_or(Category::Unknown),
+                name: local_info.and_then(|x| x.name.clone()).unwrap_or_else(|| mod_id.clone()),
                 id: None,
-                version: None,
+                version: local_info.and_then(|x| x.version.clone()),
                 latest_version: None,
-                description: None,
-                enabled: file.files.iter().all(|x| !x.disabled),
+                description: local_info.and_then(|x| x.notes.clone()),
+                enabled: file.is_enabled(),
+                key,
+                status: file.status(file.files.len()),
+                required_by,
+                combined_requirement,
+                size: file.total_size(),
+                installed_at: file.installed_at(),
+                assembly_version: file.assembly_version(),
+                location,
+                misplaced: false,
+                folder: folder.map(|x| x.to_path_buf()),
+                source_location: local_info.and_then(|x| x.source.clone()),
+                local_key,
+                notes: notes.get(mod_id).cloned(),
+                conflict,
+                pinned: config.is_pinned(mod_id),
             })
         }
     }
 
     mods.sort_by(|a, b| {
-        a.name.cmp(&b.name)
+        b.pinned.cmp(&a.pinned).then_with(|| a.name.cmp(&b.name))
     });
 
     mods
 }
 
+/// Scan location that has the folder in it, the most specific one if they're nested
+fn scan_location_of(folder: &Path, neos_folder: &Path, config: &Config) -> Option<PathBuf> {
+    config.install_profile.scan_locations.iter()
+        .filter_map(|x| {
+            let mut path = neos_folder.to_path_buf();
+            append_relative_path(&mut path, &x.path).ok()?;
+
+            folder.starts_with(&path).then(|| (path.components().count(), x.path.clone()))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, location)| location)
+}
+
+fn split_by_locations(entries: Vec<ModEntry>) -> Vec<(String, Vec<ModEntry>)> {
+    let mut locations: Vec<(String, Vec<ModEntry>)> = entries.into_iter()
+        .fold(HashMap::new(), |mut map: HashMap<String, Vec<ModEntry>>, item| {
+            let location = item.location.as_ref()
+                .map_or_else(|| tr("mod_list.other_location"), |x| x.to_string_lossy().to_string());
+
+            map.entry(location)
+                .or_insert(vec![])
+                .push(item);
+
+            map
+        })
+        .into_iter()
+        .collect();
+
+    locations.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    locations
+}
+
+/// Warning under location heading about mods that were installed to a wrong folder, reinstalling puts them where they belong
+fn misplaced_mods_fix(ui: &mut Ui, entries: &[ModEntry], toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let misplaced: Vec<&ModEntry> = entries.iter().filter(|x| x.misplaced).collect();
+
+    if misplaced.is_empty() {
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        let names = misplaced.iter().map(|x| x.name.as_str()).collect::<Vec<&str>>().join(", ");
+        ui.label(RichText::new(tr_args("mod_list.misplaced", &[("mods", names)])).color(Color32::GOLD));
+
+        if ui.small_button(tr("mod_list.move_to_correct_folder")).clicked() {
+            let operations = misplaced.iter()
+                .flat_map(|x| [
+                    ModInstallOperations::UninstallMod(x.key.clone()),
+                    ModInstallOperations::InstallMod(x.key.clone())
+                ])
+                .collect();
+
+            handle_error(command.blocking_send(ManagerCommand::PreviewOperations(operations)), toasts);
+        }
+    });
+}
+
 fn split_by_categories(entries: Vec<ModEntry>) -> Vec<(String, Vec<ModEntry>)> {
     let mut categories: Vec<(Category, Vec<ModEntry>)> = entries.into_iter()
         .fold(HashMap::new(), |mut map, item| {
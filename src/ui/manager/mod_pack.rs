@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use dirs::desktop_dir;
+use eframe::egui::{Align2, Button, Context, ScrollArea, Ui};
+use egui_file::FileDialog;
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::install::{IDVersion, ModMap};
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+
+pub struct ModPackState {
+    modal: Modal,
+    /// Mods that will go into the exported pack
+    selected: HashSet<IDVersion>,
+    export_dialog: Option<FileDialog>,
+    import_dialog: Option<FileDialog>
+}
+
+impl ModPackState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "mod_pack_modal"),
+            selected: Default::default(),
+            export_dialog: None,
+            import_dialog: None,
+        }
+    }
+}
+
+/// Export and import buttons for the mod list header
+pub fn mod_pack_buttons(pack_state: &mut ModPackState, mod_map: &ModMap, ui: &mut Ui) {
+    if ui.button(tr("mod_pack.export")).clicked() {
+        // Everything that's enabled is what people usually want to share
+        pack_state.selected = mod_map.iter_versions()
+            .filter(|(_, _, file)| file.is_enabled())
+            .map(|(mod_id, version, _)| (mod_id.clone(), version.clone()))
+            .collect();
+
+        pack_state.modal.open();
+    }
+
+    if ui.button(tr("mod_pack.import")).clicked() {
+        let mut dialog = FileDialog::open_file(desktop_dir())
+            .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+            .resizable(false);
+
+        dialog.open();
+
+        pack_state.import_dialog = Some(dialog);
+    }
+}
+
+pub fn mod_pack_modal(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let pack_state = &mut state.mod_pack_state;
+    let global_mods = &state.manifest_mods;
+
+    let mods = state.mod_list.id_versions();
+
+    pack_state.modal.show(|ui| {
+        pack_state.modal.title(ui, tr("mod_pack.title"));
+
+        pack_state.modal.frame(ui, |ui| {
+            ui.label(tr("mod_pack.description"));
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("mod_pack_scroll")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for id in &mods {
+                        let name = global_mods.mod_list.load().get(&id.0)
+                            .map_or_else(|| id.0.clone(), |x| x.name.clone());
+
+                        let mut checked = pack_state.selected.contains(id);
+
+                        if ui.checkbox(&mut checked, format!("{} v{}", name, id.1)).changed() {
+                            if checked {
+                                pack_state.selected.insert(id.clone());
+                            } else {
+                                pack_state.selected.remove(id);
+                            }
+                        }
+                    }
+                });
+        });
+
+        pack_state.modal.buttons(ui, |ui| {
+            let label = tr_args("mod_pack.export_selected", &[("count", pack_state.selected.len().to_string())]);
+
+            if ui.add_enabled(!pack_state.selected.is_empty(), Button::new(label)).clicked() {
+                let mut dialog = FileDialog::save_file(desktop_dir())
+                    .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+                    .resizable(false)
+                    .show_rename(false);
+
+                dialog.open();
+
+                pack_state.export_dialog = Some(dialog);
+                pack_state.modal.close();
+            }
+
+            pack_state.modal.button(ui, tr("mod_pack.cancel"));
+        });
+    });
+
+    if let Some(dialog) = &mut pack_state.export_dialog {
+        if dialog.show(ctx).selected() {
+            if let Some(file) = dialog.path() {
+                let mut mods: Vec<IDVersion> = pack_state.selected.iter().cloned().collect();
+                mods.sort();
+
+                handle_error(command.blocking_send(ManagerCommand::ExportModPack {
+                    mods,
+                    path: file.with_extension("zip"),
+                }), toasts);
+            }
+        }
+    }
+
+    if let Some(dialog) = &mut pack_state.import_dialog {
+        if dialog.show(ctx).selected() {
+            if let Some(file) = dialog.path() {
+                handle_error(command.blocking_send(ManagerCommand::ImportModPack(file)), toasts);
+            }
+        }
+    }
+}
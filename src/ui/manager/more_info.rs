@@ -1,29 +1,53 @@
-use eframe::egui::{Align2, Area, CollapsingHeader, Color32, Context, FontFamily, FontId, Frame, Margin, Rect, ScrollArea, Sense, Separator, Stroke, TextStyle, Ui, vec2, Widget};
+use std::fmt::Display;
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use eframe::egui::{Align, Align2, Area, Button, CollapsingHeader, Color32, ComboBox, Context, FontFamily, FontId, Frame, Layout, Margin, Rect, RichText, ScrollArea, Sense, Separator, Stroke, TextEdit, TextStyle, Ui, vec2, Widget, WidgetInfo, WidgetType};
 use egui_toast::Toasts;
 use tokio::sync::mpsc::Sender;
 use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
 use egui_modal::Modal;
+use strum::IntoEnumIterator;
+use crate::config::{Config, UpdateChannel};
+use crate::locale::{tr, tr_args};
 use crate::manager::ManagerCommand;
-use crate::manifest::{Category, GlobalModList, Mod, ModVersion};
+use crate::install::ModMap;
+use crate::manifest::{Change, compare_versions, GlobalModList, Mod, ModVersion};
+use crate::resolver::{dependency_status, DependencyStatus};
+use crate::ui::manager::gallery::{gallery_ui, GalleryState};
 use crate::ui::manager::mod_list::ModEntry;
+use crate::ui::manager::report::{build_mod_issue_url, build_mod_report, new_issue_url, system_info};
 use crate::ui::manager::UIManagerState;
 use crate::utils::{get_next_id, handle_error};
-use crate::version::Version;
+use crate::version::{Version, VersionReq};
 
 pub enum MarkdownContent {
     Loading,
     NoReadme,
+    /// Fetching failed, with the reason
+    Failed(String),
     Markdown(String)
 }
 
 pub struct InfoModalState {
     modal: Modal,
     pub id: Option<String>,
+    /// Installed version, None for mods opened from the catalog
+    pub version: Option<Version>,
     pub info: Option<Mod>,
     pub versions: Vec<(Version, ModVersion)>,
+    pub required_by: Vec<String>,
     pub tab: InfoModalTabs,
+    /// Versions picked for comparison in Versions tab
+    compare_from: Option<Version>,
+    compare_to: Option<Version>,
     cache: CommonMarkCache,
-    pub markdown_content: MarkdownContent
+    pub markdown_content: MarkdownContent,
+    pub gallery: GalleryState,
+    /// ID of the mod in mod map, notes are kept under it
+    mod_id: String,
+    notes: String,
+    /// Notes as they were last saved, to tell if there's anything to save
+    saved_notes: String
 }
 
 impl InfoModalState {
@@ -32,11 +56,19 @@ impl InfoModalState {
             modal: Modal::new(ctx, "more_info_modal")
                 .with_close_on_outside_click(true),
             id: None,
+            version: None,
             info: None,
             versions: vec![],
+            required_by: vec![],
             tab: InfoModalTabs::Readme,
+            compare_from: None,
+            compare_to: None,
             cache: CommonMarkCache::default(),
             markdown_content: MarkdownContent::Loading,
+            gallery: GalleryState::default(),
+            mod_id: String::new(),
+            notes: String::new(),
+            saved_notes: String::new(),
         }
     }
 
@@ -45,16 +77,22 @@ impl InfoModalState {
             .get(&x).cloned()).unwrap_or_else(|| Mod {
             name: mod_entry.name.clone(),
             color: None,
-            description: "File that wasn't recognized".to_string(),
+            description: mod_entry.description.clone().unwrap_or_else(|| "File that wasn't recognized".to_string()),
             authors: Default::default(),
-            source_location: None,
+            source_location: mod_entry.source_location.clone(),
             website: None,
             tags: None,
-            category: Category::Unknown,
+            category: mod_entry.category,
             flags: None,
+            download_count: None,
             versions: Default::default(),
         }));
         self.id = mod_entry.id.clone();
+        self.version = mod_entry.version.clone();
+        self.mod_id = mod_entry.key.0.clone();
+        self.notes = mod_entry.notes.clone().unwrap_or_default();
+        self.saved_notes = self.notes.clone();
+        self.required_by = mod_entry.required_by.clone();
 
         self.versions.clear();
 
@@ -68,12 +106,19 @@ impl InfoModalState {
                 b_v.cmp(a_v)
             });
         }
+
+        // Installed version against the newest one is what's interesting most of the time
+        self.compare_to = self.versions.first().map(|(x, _)| x.clone());
+        self.compare_from = self.version.clone()
+            .filter(|x| self.versions.iter().any(|(v, _)| v == x))
+            .or_else(|| self.versions.get(1).map(|(x, _)| x.clone()));
     }
 
     pub(crate) fn open_with_entry_data(&mut self, mod_entry: &ModEntry, global_mods: &GlobalModList, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
         self.fill_in_info(mod_entry, global_mods);
         self.tab = InfoModalTabs::Readme;
         self.markdown_content = MarkdownContent::Loading;
+        self.gallery.set_links(vec![]);
         self.modal.open();
 
         match &mod_entry.id {
@@ -90,11 +135,17 @@ impl InfoModalState {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum InfoModalTabs {
     Readme,
-    Versions
+    Versions,
+    Gallery,
+    RequiredBy,
+    Notes
 }
 
-pub fn more_info_modal(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+pub fn more_info_modal(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
     let info_modal_state = &mut state.mod_list_state.more_info;
+    let mod_map = &state.mod_list;
+    let global_mods = &state.manifest_mods;
+    let mut channel_changed = false;
 
     info_modal_state.modal.show(|ui| {
         let pos = ui.next_widget_position();
@@ -115,6 +166,27 @@ pub fn more_info_modal(state: &mut UIManagerState, ctx: &Context, toasts: &mut T
                 MoreInfoHeaderResponse::OpenSource => {
                     handle_error(open::that(mod_info.source_location.as_ref().unwrap()), toasts);
                 }
+                MoreInfoHeaderResponse::CopyGuid => {
+                    if let Some(id) = &info_modal_state.id {
+                        ui.output_mut(|x| x.copied_text = id.clone());
+                    }
+                }
+                MoreInfoHeaderResponse::ReportIssue => {
+                    let neos_folder = config.load().neos_exe_location.parent().map(|x| x.to_path_buf()).unwrap_or_default();
+                    let mod_report = build_mod_report(mod_map, global_mods, None);
+
+                    let url = build_mod_issue_url(
+                        mod_info.source_location.as_ref().unwrap(),
+                        &mod_info.name,
+                        info_modal_state.version.as_ref(),
+                        &system_info(&neos_folder),
+                        &mod_report
+                    );
+
+                    if let Some(url) = url {
+                        handle_error(open::that(url.as_str()), toasts);
+                    }
+                }
             }
 
             match info_modal_state.tab {
@@ -138,6 +210,21 @@ pub fn more_info_modal(state: &mut UIManagerState, ctx: &Context, toasts: &mut T
                                         ui.heading("No README file")
                                     });
                                 }
+                                MarkdownContent::Failed(error) => {
+                                    let mut retry = false;
+
+                                    ui.vertical_centered(|ui| {
+                                        ui.heading(tr("more_info.readme_failed"));
+                                        ui.label(RichText::new(error).color(Color32::LIGHT_RED));
+                                        ui.add_space(5.0);
+                                        retry = ui.button(tr("more_info.readme_retry")).clicked();
+                                    });
+
+                                    if let (true, Some(guid)) = (retry, &info_modal_state.id) {
+                                        handle_error(command.blocking_send(ManagerCommand::FindReadmeFor(guid.clone())), toasts);
+                                        info_modal_state.markdown_content = MarkdownContent::Loading;
+                                    }
+                                }
                                 MarkdownContent::Markdown(md) => {
                                     ScrollArea::vertical()
                                         .id_source("more_info_readme_scroll")
@@ -162,6 +249,14 @@ pub fn more_info_modal(state: &mut UIManagerState, ctx: &Context, toasts: &mut T
                             bottom: 0.0,
                         })
                         .show(ui, |ui| {
+                            if let Some(guid) = &info_modal_state.id {
+                                channel_changed = update_channel_ui(ui, guid, config, toasts, command);
+                            }
+
+                            if info_modal_state.versions.len() > 1 {
+                                version_comparison_ui(ui, &info_modal_state.versions, &mut info_modal_state.compare_from, &mut info_modal_state.compare_to);
+                            }
+
                             if info_modal_state.versions.len() > 0 {
                                 ScrollArea::vertical()
                                     .id_source("more_info_version_scroll")
@@ -169,7 +264,7 @@ pub fn more_info_modal(state: &mut UIManagerState, ctx: &Context, toasts: &mut T
                                     .max_height(500.0)
                                     .show(ui, |ui| {
                                         for (version, version_info) in &info_modal_state.versions {
-                                            more_info_version(ui, version, version_info);
+                                            more_info_version(ui, info_modal_state.id.as_deref(), version, version_info, mod_map, toasts, command);
                                         }
                                     });
                             } else {
@@ -179,12 +274,245 @@ pub fn more_info_modal(state: &mut UIManagerState, ctx: &Context, toasts: &mut T
                             }
                         });
                 }
+
+                InfoModalTabs::Gallery => {
+                    Frame::default()
+                        .outer_margin(Margin {
+                            left: 0.0,
+                            right: 0.0,
+                            top: 5.0,
+                            bottom: 0.0,
+                        })
+                        .show(ui, |ui| {
+                            ui.set_height(500.0);
+                            gallery_ui(&mut info_modal_state.gallery, ui, toasts, command);
+                        });
+                }
+
+                InfoModalTabs::RequiredBy => {
+                    Frame::default()
+                        .outer_margin(Margin {
+                            left: 0.0,
+                            right: 0.0,
+                            top: 5.0,
+                            bottom: 0.0,
+                        })
+                        .show(ui, |ui| {
+                            if info_modal_state.required_by.len() > 0 {
+                                ScrollArea::vertical()
+                                    .id_source("more_info_required_by_scroll")
+                                    .auto_shrink([false; 2])
+                                    .max_height(500.0)
+                                    .show(ui, |ui| {
+                                        ui.label(tr("more_info.required_by"));
+
+                                        for dependent in &info_modal_state.required_by {
+                                            ui.label(format!("• {}", dependent));
+                                        }
+                                    });
+                            } else {
+                                ui.centered_and_justified(|ui| {
+                                    ui.heading(tr("more_info.not_required"));
+                                });
+                            }
+                        });
+                }
+
+                InfoModalTabs::Notes => {
+                    Frame::default()
+                        .outer_margin(Margin {
+                            left: 0.0,
+                            right: 0.0,
+                            top: 5.0,
+                            bottom: 0.0,
+                        })
+                        .show(ui, |ui| {
+                            notes_ui(ui, &info_modal_state.mod_id, &mut info_modal_state.notes, &mut info_modal_state.saved_notes, toasts, command);
+                        });
+                }
             }
         }
     });
+
+    // Latest versions shown in the list depend on the channel
+    if channel_changed {
+        state.mod_list_state.invalidate();
+        state.updates_state.invalidate();
+    }
+
+}
+
+/// Text area for user's own notes about the mod, saved only when asked to
+fn notes_ui(ui: &mut Ui, mod_id: &str, notes: &mut String, saved_notes: &mut String, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    ui.label(tr("more_info.notes_description"));
+
+    ui.add_space(5.0);
+
+    ScrollArea::vertical()
+        .id_source("more_info_notes_scroll")
+        .max_height(440.0)
+        .show(ui, |ui| {
+            ui.add(TextEdit::multiline(notes)
+                .hint_text(tr("more_info.notes_hint"))
+                .desired_width(f32::INFINITY)
+                .desired_rows(12));
+        });
+
+    ui.add_space(5.0);
+
+    let changed = notes != saved_notes;
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(changed, Button::new(tr("more_info.notes_save"))).clicked() {
+            handle_error(command.blocking_send(ManagerCommand::SetModNotes {
+                mod_id: mod_id.to_string(),
+                notes: notes.clone(),
+            }), toasts);
+
+            *saved_notes = notes.clone();
+        }
+
+        if ui.add_enabled(changed, Button::new(tr("more_info.notes_revert"))).clicked() {
+            *notes = saved_notes.clone();
+        }
+    });
+}
+
+/// Picks channel of the mod, removing the override makes it follow the global one again. True if it was changed
+fn update_channel_ui(ui: &mut Ui, guid: &str, config: &Arc<ArcSwap<Config>>, toasts: &mut Toasts, command: &Sender<ManagerCommand>) -> bool {
+    let global_channel = config.load().update_channel;
+    let current = config.load().mod_update_channels.get(guid).copied();
+
+    let default_text = tr_args("update_channel.default", &[("channel", tr(global_channel.title_key()))]);
+    let selected_text = current.map_or_else(|| default_text.clone(), |x| tr(x.title_key()));
+
+    let mut picked = None;
+
+    ComboBox::from_label(tr("update_channel.label"))
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(current.is_none(), &default_text).clicked() {
+                picked = Some(None);
+            }
+
+            for channel in UpdateChannel::iter() {
+                if ui.selectable_label(current == Some(channel), tr(channel.title_key())).clicked() {
+                    picked = Some(Some(channel));
+                }
+            }
+        });
+
+    if let Some(channel) = picked.filter(|x| *x != current) {
+        let mut config_str = config.load().as_ref().clone();
+
+        match channel {
+            Some(channel) => config_str.mod_update_channels.insert(guid.to_string(), channel),
+            None => config_str.mod_update_channels.remove(guid)
+        };
+
+        config.swap(Arc::new(config_str));
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+
+        return true;
+    }
+
+    false
+}
+
+fn version_combo_box(ui: &mut Ui, id: &str, selected: &mut Option<Version>, versions: &[(Version, ModVersion)]) {
+    ComboBox::from_id_source(id)
+        .selected_text(selected.as_ref().map_or_else(String::new, |x| format!("v{}", x)))
+        .show_ui(ui, |ui| {
+            for (version, _) in versions {
+                ui.selectable_value(selected, Some(version.clone()), format!("v{}", version));
+            }
+        });
+}
+
+fn change_label<T: Display>(ui: &mut Ui, name: &str, change: &Change<T>) {
+    match change {
+        Change::Added(new) => ui.label(RichText::new(format!("+ {} {}", name, new)).color(Color32::LIGHT_GREEN)),
+        Change::Removed(old) => ui.label(RichText::new(format!("- {} {}", name, old)).color(Color32::LIGHT_RED)),
+        Change::Changed(old, new) => ui.label(RichText::new(format!("~ {} {} → {}", name, old, new)).color(Color32::YELLOW))
+    };
+}
+
+/// Two versions picked by user and what changed between them
+fn version_comparison_ui(ui: &mut Ui, versions: &[(Version, ModVersion)], compare_from: &mut Option<Version>, compare_to: &mut Option<Version>) {
+    CollapsingHeader::new(tr("more_info.compare"))
+        .id_source("more_info_compare")
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                version_combo_box(ui, "more_info_compare_from", compare_from, versions);
+                ui.label("→");
+                version_combo_box(ui, "more_info_compare_to", compare_to, versions);
+            });
+
+            let find = |version: &Option<Version>| version.as_ref()
+                .and_then(|version| versions.iter().find(|(x, _)| x == version));
+
+            let (Some((from, from_info)), Some((to, to_info))) = (find(compare_from), find(compare_to)) else {
+                return;
+            };
+
+            if from == to {
+                ui.label(RichText::new(tr("more_info.compare_same")).weak());
+                return;
+            }
+
+            let diff = compare_versions(from_info, to_info);
+
+            ui.add_space(5.0);
+
+            if diff.is_empty() {
+                ui.label(RichText::new(tr("more_info.compare_no_changes")).weak());
+            }
+
+            if let Some(change) = &diff.neos_version_compatibility {
+                change_label(ui, &tr("more_info.compare_neos"), change);
+            }
+
+            if let Some(change) = &diff.modloader_version_compatibility {
+                change_label(ui, &tr("more_info.compare_mod_loader"), change);
+            }
+
+            for (title, changes) in [(tr("more_info.compare_dependencies"), &diff.dependencies), (tr("more_info.compare_conflicts"), &diff.conflicts)] {
+                if !changes.is_empty() {
+                    ui.strong(title);
+
+                    for (name, change) in changes {
+                        change_label(ui, name, change);
+                    }
+                }
+            }
+
+            if !diff.artifacts.is_empty() {
+                ui.strong(tr("more_info.compare_files"));
+
+                for (name, change) in &diff.artifacts {
+                    // Hashes don't say much, so only whether the file is new, gone or different
+                    let (text, color) = match change {
+                        Change::Added(_) => (format!("+ {}", name), Color32::LIGHT_GREEN),
+                        Change::Removed(_) => (format!("- {}", name), Color32::LIGHT_RED),
+                        Change::Changed(_, _) => (format!("~ {}", name), Color32::YELLOW)
+                    };
+
+                    ui.label(RichText::new(text).color(color));
+                }
+            }
+
+            ui.add_space(5.0);
+
+            ui.columns(2, |columns| {
+                for (ui, (version, version_info)) in columns.iter_mut().zip([(from, from_info), (to, to_info)]) {
+                    ui.strong(format!("v{}", version));
+                    ui.label(version_info.changelog.as_deref().unwrap_or("- Empty changelog -"));
+                }
+            });
+        });
 }
 
-fn more_info_version(ui: &mut Ui, version: &Version, version_info: &ModVersion) {
+fn more_info_version(ui: &mut Ui, guid: Option<&str>, version: &Version, version_info: &ModVersion, mod_map: &ModMap, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
     Frame::default()
         .fill(ui.visuals().widgets.inactive.bg_fill)
         .outer_margin(5.0)
@@ -194,7 +522,31 @@ fn more_info_version(ui: &mut Ui, version: &Version, version_info: &ModVersion)
             let pos = ui.next_widget_position();
             ui.expand_to_include_rect(Rect::from_min_size(pos, vec2(ui.max_rect().width(), 20.0)));
 
-            ui.heading(format!("v{}", version));
+            ui.horizontal(|ui| {
+                ui.heading(format!("v{}", version));
+
+                if ui.small_button("📋").on_hover_text(tr("more_info.copy_version")).clicked() {
+                    ui.output_mut(|x| x.copied_text = version.to_string());
+                }
+
+                let Some(guid) = guid else {
+                    return;
+                };
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    let installed = mod_map.is_installed(guid, version);
+
+                    if installed {
+                        ui.add_enabled(false, Button::new(tr("more_info.version_installed")));
+                    } else if ui.button(tr("more_info.install_version")).clicked() {
+                        // Anything else installed gets replaced, so this is how to go back to an older version
+                        handle_error(command.blocking_send(ManagerCommand::InstallMod {
+                            guid: guid.to_string(),
+                            version_req: VersionReq::exact(version),
+                        }), toasts);
+                    }
+                });
+            });
 
             if let Some(changelog) = &version_info.changelog {
                 ui.label(changelog);
@@ -215,7 +567,33 @@ fn more_info_version(ui: &mut Ui, version: &Version, version_info: &ModVersion)
                     .id_source(get_next_id(ui))
                     .show(ui, |ui| {
                         for (guid, dependency) in dependencies {
-                            ui.label(format!("• {} {}", guid, dependency.version));
+                            ui.horizontal(|ui| {
+                                let status = dependency_status(guid, &dependency.version, mod_map);
+
+                                match &status {
+                                    DependencyStatus::Satisfied(_) => ui.label(RichText::new("✔").color(Color32::LIGHT_GREEN)),
+                                    DependencyStatus::WrongVersion(_) | DependencyStatus::Missing => ui.label(RichText::new("✖").color(Color32::LIGHT_RED))
+                                };
+
+                                ui.label(format!("{} {}", guid, dependency.version));
+
+                                match status {
+                                    DependencyStatus::Satisfied(installed) => {
+                                        ui.small(tr_args("more_info.dependency_installed", &[("version", installed.to_string())]));
+                                    }
+                                    DependencyStatus::WrongVersion(installed) => {
+                                        ui.small(RichText::new(tr_args("more_info.dependency_wrong_version", &[("version", installed.to_string())])).color(Color32::LIGHT_RED));
+                                    }
+                                    DependencyStatus::Missing => {
+                                        if ui.small_button(tr("more_info.install_dependency")).clicked() {
+                                            handle_error(command.blocking_send(ManagerCommand::InstallMod {
+                                                guid: guid.clone(),
+                                                version_req: dependency.version.clone(),
+                                            }), toasts);
+                                        }
+                                    }
+                                }
+                            });
                         }
                     });
             }
@@ -239,6 +617,8 @@ enum MoreInfoHeaderResponse {
     ChangeTab(InfoModalTabs),
     OpenWebsite,
     OpenSource,
+    ReportIssue,
+    CopyGuid,
 }
 
 fn more_info_header(ui: &mut Ui, mod_info: &Mod, id: &Option<String>, current_tab: &InfoModalTabs) -> MoreInfoHeaderResponse {
@@ -282,6 +662,7 @@ fn more_info_header(ui: &mut Ui, mod_info: &Mod, id: &Option<String>, current_ta
 
     let close_button_id = get_next_id(ui);
     let close_button_response = ui.interact(close_button_rect, close_button_id, Sense::click());
+    close_button_response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, tr("more_info.close")));
 
     let close_button_visuals = ui.style().interact(&close_button_response);
 
@@ -309,14 +690,38 @@ fn more_info_header(ui: &mut Ui, mod_info: &Mod, id: &Option<String>, current_ta
     let text_painter = ui.painter_at(actual_text_bounds);
     text_painter.galley_with_color(text_start_position, title_galley, header_visuals.text_color());
 
+    let mut copy_guid_response = None;
+
     if let Some(id_galley) = id_galley {
-        text_painter.galley_with_color(text_start_position + vec2(0.0, title_height + 3.0), id_galley, Color32::GRAY);
+        let id_position = text_start_position + vec2(0.0, title_height + 3.0);
+        let copy_size = id_height + 6.0;
+        let copy_rect = Rect::from_center_size(id_position + vec2(id_galley.rect.width() + 6.0 + copy_size / 2.0, id_height / 2.0), vec2(copy_size, copy_size));
+
+        text_painter.galley_with_color(id_position, id_galley, Color32::GRAY);
+
+        // Small button right after the GUID
+        let copy_id = get_next_id(ui);
+        let copy_response = ui.interact(copy_rect, copy_id, Sense::click())
+            .on_hover_text(tr("more_info.copy_guid"));
+        copy_response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, tr("more_info.copy_guid")));
+        let copy_visuals = ui.style().interact(&copy_response);
+
+        if copy_response.hovered() || copy_response.has_focus() {
+            ui.painter().rect(copy_rect, 4.0, copy_visuals.bg_fill, copy_visuals.bg_stroke);
+        }
+
+        ui.painter().text(copy_rect.center(), Align2::CENTER_CENTER, "📋", small_text.clone(), copy_visuals.text_color());
+
+        copy_guid_response = Some(copy_response);
     }
 
     // Tab buttons
     let tab_buttons = [
         ("README", InfoModalTabs::Readme),
-        ("Versions", InfoModalTabs::Versions)
+        ("Versions", InfoModalTabs::Versions),
+        ("Screenshots", InfoModalTabs::Gallery),
+        ("Required by", InfoModalTabs::RequiredBy),
+        ("Notes", InfoModalTabs::Notes)
     ];
 
     let mut offset = tabs_gap;
@@ -331,6 +736,7 @@ fn more_info_header(ui: &mut Ui, mod_info: &Mod, id: &Option<String>, current_ta
         offset += tabs_width + tabs_gap;
 
         let tab_response = ui.interact(tab_rect, tab_id, Sense::click());
+        tab_response.widget_info(|| WidgetInfo::selected(WidgetType::SelectableLabel, tab_selected, tab_name));
 
         let tab_visuals = ui.style().interact_selectable(&tab_response, tab_selected);
 
@@ -343,22 +749,25 @@ fn more_info_header(ui: &mut Ui, mod_info: &Mod, id: &Option<String>, current_ta
     }
 
     let site_buttons = [
-        if mod_info.website.is_some() { Some(("🌐", MoreInfoHeaderResponse::OpenWebsite)) } else { None },
-        if mod_info.source_location.is_some() { Some(("", MoreInfoHeaderResponse::OpenSource)) } else { None }
+        if mod_info.website.is_some() { Some(("🌐", "more_info.open_website", MoreInfoHeaderResponse::OpenWebsite)) } else { None },
+        if mod_info.source_location.is_some() { Some(("", "more_info.open_source", MoreInfoHeaderResponse::OpenSource)) } else { None },
+        if mod_info.source_location.as_deref().and_then(new_issue_url).is_some() { Some(("🐛", "more_info.report_issue", MoreInfoHeaderResponse::ReportIssue)) } else { None }
     ];
 
     let mut offset = element_width - tabs_height;
     for button in site_buttons {
         let tab_id = get_next_id(ui);
 
-        if let Some((tab_label, action)) = button {
+        if let Some((tab_label, description, action)) = button {
             let tab_start_pos = element_rect.left_bottom() + vec2(offset, -tabs_height);
             let tab_rect = Rect::from_min_size(tab_start_pos, vec2(tabs_height, tabs_height))
                 .shrink(tabs_gap);
 
             offset -= tabs_height;
 
-            let tab_response = ui.interact(tab_rect, tab_id, Sense::click());
+            let tab_response = ui.interact(tab_rect, tab_id, Sense::click())
+                .on_hover_text(tr(description));
+            tab_response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, tr(description)));
 
             let tab_visuals = ui.style().interact(&tab_response);
 
@@ -375,5 +784,9 @@ fn more_info_header(ui: &mut Ui, mod_info: &Mod, id: &Option<String>, current_ta
         return MoreInfoHeaderResponse::CloseRequested;
     }
 
+    if copy_guid_response.map_or(false, |x| x.clicked()) {
+        return MoreInfoHeaderResponse::CopyGuid;
+    }
+
     MoreInfoHeaderResponse::Nothing
 }
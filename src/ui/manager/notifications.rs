@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+use eframe::egui::{Align, Color32, Context, Layout, RichText, ScrollArea, Ui, Window};
+use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
+use crate::config::ToastSettings;
+use crate::locale::{tr, tr_args};
+use crate::ui::manager::UIManagerState;
+use crate::utils::{time_ago, unix_timestamp};
+
+/// Notifications older than this many are dropped from the center
+const MAX_NOTIFICATIONS: usize = 200;
+
+pub struct NotificationEntry {
+    pub kind: ToastKind,
+    pub message: String,
+    /// Seconds since unix epoch
+    pub timestamp: u64
+}
+
+/// Everything the manager announced, so toasts that went by too fast can be read again
+#[derive(Default)]
+pub struct NotificationCenterState {
+    pub open: bool,
+    entries: VecDeque<NotificationEntry>,
+    /// Notifications that came in since the center was last opened
+    unread: usize,
+    /// Only notifications of this kind are shown, all of them if None
+    filter: Option<ToastKind>
+}
+
+impl NotificationCenterState {
+    /// Records the notification and shows it as a toast, unless settings say not to
+    pub fn notify(&mut self, toasts: &mut Toasts, settings: &ToastSettings, kind: ToastKind, message: String, long: bool) {
+        if kind != ToastKind::Success || settings.show_success {
+            toasts.add(Toast {
+                kind,
+                text: message.clone().into(),
+                options: ToastOptions::default()
+                    .show_progress(true)
+                    .duration_in_seconds(if long { settings.long_duration } else { settings.duration } as f64),
+            });
+        }
+
+        self.entries.push_front(NotificationEntry {
+            kind,
+            message,
+            timestamp: unix_timestamp(),
+        });
+        self.entries.truncate(MAX_NOTIFICATIONS);
+
+        if !self.open {
+            self.unread += 1;
+        }
+    }
+}
+
+fn kind_icon(kind: ToastKind) -> RichText {
+    match kind {
+        ToastKind::Error => RichText::new("✖").color(Color32::LIGHT_RED),
+        ToastKind::Warning => RichText::new("⚠").color(Color32::YELLOW),
+        ToastKind::Success => RichText::new("✔").color(Color32::LIGHT_GREEN),
+        _ => RichText::new("ℹ")
+    }
+}
+
+/// Button that opens the center, shows how many notifications weren't seen yet
+pub fn notification_center_button(state: &mut NotificationCenterState, ui: &mut Ui) {
+    let text = if state.unread > 0 {
+        format!("🔔 {} ({})", tr("notifications.title"), state.unread)
+    } else {
+        format!("🔔 {}", tr("notifications.title"))
+    };
+
+    if ui.button(text).clicked() {
+        state.open = !state.open;
+    }
+}
+
+pub fn notification_center_ui(state: &mut UIManagerState, ctx: &Context) {
+    let center = &mut state.notification_center;
+
+    if !center.open {
+        return;
+    }
+
+    center.unread = 0;
+
+    let mut open = true;
+
+    Window::new(tr("notifications.title"))
+        .open(&mut open)
+        .default_width(400.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let filters = [
+                    (None, tr("notifications.all")),
+                    (Some(ToastKind::Error), tr("notifications.errors")),
+                    (Some(ToastKind::Warning), tr("notifications.warnings")),
+                    (Some(ToastKind::Info), tr("notifications.info")),
+                    (Some(ToastKind::Success), tr("notifications.success"))
+                ];
+
+                for (filter, name) in filters {
+                    ui.selectable_value(&mut center.filter, filter, name);
+                }
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.button(tr("notifications.clear")).clicked() {
+                        center.entries.clear();
+                    }
+                });
+            });
+
+            ui.separator();
+
+            let filter = center.filter;
+            let mut shown = center.entries.iter()
+                .filter(|x| filter.map_or(true, |filter| x.kind == filter))
+                .peekable();
+
+            if shown.peek().is_none() {
+                ui.label(RichText::new(tr("notifications.empty")).weak());
+                return;
+            }
+
+            ScrollArea::vertical()
+                .id_source("notification_center_scroll")
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    for entry in shown {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(kind_icon(entry.kind));
+                            ui.small(RichText::new(time_ago(entry.timestamp)).weak());
+                            ui.label(&entry.message);
+                        });
+                        ui.add_space(3.0);
+                    }
+                });
+
+            ui.small(RichText::new(tr_args("notifications.count", &[("count", center.entries.len().to_string())])).weak());
+        });
+
+    center.open = open;
+}
@@ -0,0 +1,192 @@
+use eframe::egui::{Color32, Context, RichText, ScrollArea};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::install::{ConflictSeverity, ModConflict, ModInstallOperations};
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::manifest::{GlobalModList, GUID};
+use crate::ui::manager::artifact_sizes::ArtifactSizes;
+use crate::ui::manager::UIManagerState;
+use crate::utils::{format_size, handle_error};
+
+pub struct PlanPreviewState {
+    modal: Modal,
+    operations: Vec<ModInstallOperations>,
+    conflicts: Vec<ModConflict>,
+    /// Mods coming from sources that aren't trusted, with URL of the source
+    untrusted: Vec<(GUID, String)>
+}
+
+impl PlanPreviewState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "plan_preview_modal"),
+            operations: vec![],
+            conflicts: vec![],
+            untrusted: vec![],
+        }
+    }
+
+    pub fn open(&mut self, operations: Vec<ModInstallOperations>, conflicts: Vec<ModConflict>, untrusted: Vec<(GUID, String)>) {
+        self.operations = operations;
+        self.conflicts = conflicts;
+        self.untrusted = untrusted;
+        self.modal.open();
+    }
+}
+
+pub fn mod_name(global_mods: &GlobalModList, mod_id: &str) -> String {
+    global_mods.mod_list.load().get(mod_id)
+        .map_or_else(|| mod_id.to_string(), |x| x.name.clone())
+}
+
+pub fn describe_operation(operation: &ModInstallOperations, global_mods: &GlobalModList) -> String {
+    let (key, (mod_id, version)) = match operation {
+        ModInstallOperations::InstallMod(id) | ModInstallOperations::InstallModAt(id, _) => ("plan.install", id),
+        ModInstallOperations::UninstallMod(id) => ("plan.uninstall", id),
+        ModInstallOperations::EnableMod(id) => ("plan.enable", id),
+        ModInstallOperations::DisableMod(id) => ("plan.disable", id),
+    };
+
+    tr_args(key, &[("name", mod_name(global_mods, mod_id)), ("version", version.to_string())])
+}
+
+/// Total size of artifacts the operations would download, None if some sizes aren't known yet
+pub fn download_size(operations: &[ModInstallOperations], global_mods: &GlobalModList, artifact_sizes: &mut ArtifactSizes) -> Option<u64> {
+    let mod_list = global_mods.mod_list.load();
+
+    artifact_sizes.total(operations.iter()
+        .filter_map(|x| match x {
+            ModInstallOperations::InstallMod(id) | ModInstallOperations::InstallModAt(id, _) => Some(id),
+            _ => None
+        })
+        .filter_map(|(mod_id, version)| mod_list.get(mod_id)?.versions.get(version))
+        .flat_map(|x| x.artifacts.iter()))
+}
+
+pub fn severity_color(severity: ConflictSeverity) -> Color32 {
+    match severity {
+        ConflictSeverity::Warning => Color32::GOLD,
+        ConflictSeverity::Error => Color32::LIGHT_RED
+    }
+}
+
+pub fn describe_conflict(conflict: &ModConflict, global_mods: &GlobalModList) -> String {
+    match conflict {
+        ModConflict::VersionConflict(mod_id) => {
+            tr_args("conflict.version", &[("name", mod_name(global_mods, mod_id))])
+        }
+        ModConflict::DirectConflict { this, conflict_with } => {
+            tr_args("conflict.direct", &[
+                ("name", mod_name(global_mods, &this.0)),
+                ("other", format!("{} v{}", mod_name(global_mods, &conflict_with.0), conflict_with.1))
+            ])
+        }
+        ModConflict::DependencyMissing { this, needs } => {
+            tr_args("conflict.dependency_missing", &[
+                ("name", mod_name(global_mods, &this.0)),
+                ("other", format!("{} {}", mod_name(global_mods, &needs.0), needs.1))
+            ])
+        }
+        ModConflict::DependencyMismatch { this, needs, found_versions } => {
+            tr_args("conflict.dependency_mismatch", &[
+                ("name", mod_name(global_mods, &this.0)),
+                ("other", format!("{} {}", mod_name(global_mods, &needs.0), needs.1)),
+                ("found", found_versions.iter().map(|x| format!("v{}", x)).collect::<Vec<String>>().join(", "))
+            ])
+        }
+        ModConflict::IncompleteInstall { this, missing_file } => {
+            tr_args("conflict.incomplete", &[
+                ("name", mod_name(global_mods, &this.0)),
+                ("file", missing_file.clone())
+            ])
+        }
+        ModConflict::FileConflict { this, already_exists } => {
+            tr_args("conflict.file", &[
+                ("name", mod_name(global_mods, &this.0)),
+                ("file", already_exists.to_string_lossy().to_string())
+            ])
+        }
+        ModConflict::IncompatibleRequirements { this, requirements } => {
+            tr_args("conflict.incompatible_requirements", &[
+                ("name", mod_name(global_mods, this)),
+                ("requirements", requirements.iter()
+                    .map(|((mod_id, _), requirement)| format!("{} ({})", mod_name(global_mods, mod_id), requirement))
+                    .collect::<Vec<String>>()
+                    .join(", "))
+            ])
+        }
+    }
+}
+
+pub fn plan_preview_modal(state: &mut UIManagerState, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let preview_state = &mut state.plan_preview_state;
+    let global_mods = &state.manifest_mods;
+    let mut cancelled = false;
+
+    let download_size = if preview_state.modal.is_open() {
+        download_size(&preview_state.operations, global_mods, &mut state.artifact_sizes)
+    } else {
+        None
+    };
+
+    preview_state.modal.show(|ui| {
+        preview_state.modal.title(ui, tr("plan.title"));
+
+        preview_state.modal.frame(ui, |ui| {
+            if preview_state.conflicts.is_empty() {
+                ui.label(tr("plan.description_untrusted"));
+            } else {
+                ui.label(tr("plan.description"));
+            }
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("plan_preview_scroll")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for conflict in &preview_state.conflicts {
+                        ui.label(RichText::new(format!("⚠ {}", describe_conflict(conflict, global_mods))).color(severity_color(conflict.severity())));
+                    }
+
+                    for (mod_id, source) in &preview_state.untrusted {
+                        ui.label(RichText::new(format!("🔓 {}", tr_args("plan.untrusted", &[
+                            ("name", mod_name(global_mods, mod_id)),
+                            ("source", source.clone())
+                        ]))).color(Color32::GOLD));
+                    }
+
+                    ui.add_space(5.0);
+                    ui.label(tr("plan.operations"));
+
+                    for operation in &preview_state.operations {
+                        ui.small(describe_operation(operation, global_mods));
+                    }
+                });
+
+            if let Some(size) = download_size.filter(|x| *x > 0) {
+                ui.add_space(5.0);
+                ui.label(tr_args("plan.download_size", &[("size", format_size(size))]));
+            }
+        });
+
+        preview_state.modal.buttons(ui, |ui| {
+            let apply_label = if preview_state.conflicts.is_empty() { tr("plan.install_untrusted") } else { tr("plan.apply_anyway") };
+
+            if preview_state.modal.button(ui, apply_label).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::ApplyOperations(preview_state.operations.clone())), toasts);
+            }
+
+            if preview_state.modal.suggested_button(ui, tr("plan.abort")).clicked() {
+                cancelled = true;
+            }
+        });
+    });
+
+    // Mod list might be showing what user tried to do, going back to what's actually installed
+    if cancelled {
+        state.mod_list_state.invalidate();
+    }
+}
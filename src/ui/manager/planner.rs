@@ -0,0 +1,274 @@
+use std::str::FromStr;
+use eframe::egui::{Button, Color32, ComboBox, Context, RichText, ScrollArea, Spinner, TextEdit, Ui, Window};
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::install::IDVersion;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::manifest::GUID;
+use crate::planner::{PlanOutcome, PlanStep};
+use crate::ui::manager::plan_preview::{describe_conflict, describe_operation, download_size, mod_name};
+use crate::ui::manager::UIManagerState;
+use crate::utils::{format_size, handle_error};
+use crate::version::VersionReq;
+
+/// How many mods matching the search are offered for installing
+const SEARCH_RESULTS: usize = 8;
+
+#[derive(Default)]
+pub struct PlannerState {
+    open: bool,
+    steps: Vec<PlanStep>,
+    /// None while the manager is simulating current steps
+    outcome: Option<PlanOutcome>,
+    search: String,
+    /// Installed mod version picked for uninstalling
+    uninstall: Option<IDVersion>
+}
+
+impl PlannerState {
+    /// Outcomes of steps that were changed since are dropped, a newer one is on the way
+    pub fn set_outcome(&mut self, outcome: PlanOutcome) {
+        if outcome.steps == self.steps {
+            self.outcome = Some(outcome);
+        }
+    }
+
+    fn steps_changed(&mut self, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+        self.outcome = None;
+
+        if !self.steps.is_empty() {
+            handle_error(command.blocking_send(ManagerCommand::SimulatePlan(self.steps.clone())), toasts);
+        }
+    }
+
+    fn discard(&mut self) {
+        self.steps.clear();
+        self.outcome = None;
+        self.open = false;
+    }
+}
+
+pub fn planner_button(planner_state: &mut PlannerState, ui: &mut Ui) {
+    if ui.button(tr("planner.open")).on_hover_text(tr("planner.hover")).clicked() {
+        planner_state.open = true;
+    }
+}
+
+fn describe_step(step: &PlanStep, state: &UIManagerState) -> String {
+    match step {
+        PlanStep::Install(mod_id, _) => tr_args("planner.step_install", &[("name", mod_name(&state.manifest_mods, mod_id))]),
+        PlanStep::Uninstall((mod_id, version)) => tr_args("planner.step_uninstall", &[
+            ("name", mod_name(&state.manifest_mods, mod_id)),
+            ("version", version.to_string())
+        ])
+    }
+}
+
+/// Mods from manifest matching the search, by name
+fn search_mods(state: &UIManagerState, search: &str) -> Vec<(GUID, String)> {
+    let search = search.to_lowercase();
+    let mod_list = state.manifest_mods.mod_list.load();
+
+    let mut results: Vec<(GUID, String)> = mod_list.iter()
+        .filter(|(guid, info)| info.name.to_lowercase().contains(&search) || guid.to_lowercase().contains(&search))
+        .map(|(guid, info)| (guid.clone(), info.name.clone()))
+        .collect();
+
+    results.sort_by_cached_key(|(_, name)| name.to_lowercase());
+    results.truncate(SEARCH_RESULTS);
+    results
+}
+
+fn add_steps_ui(state: &mut UIManagerState, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut added = None;
+
+    ui.add(TextEdit::singleline(&mut state.planner_state.search)
+        .hint_text(tr("planner.search"))
+        .desired_width(f32::INFINITY));
+
+    if !state.planner_state.search.is_empty() {
+        for (guid, name) in search_mods(state, &state.planner_state.search) {
+            ui.horizontal(|ui| {
+                if ui.small_button(tr("planner.add_install")).clicked() {
+                    added = Some(PlanStep::Install(guid.clone(), VersionReq::from_str("*").unwrap()));
+                }
+
+                ui.label(name);
+                ui.small(RichText::new(&guid).weak());
+            });
+        }
+    }
+
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        let installed = state.mod_list.id_versions();
+        let planner_state = &mut state.planner_state;
+
+        let selected_text = planner_state.uninstall.as_ref()
+            .map_or_else(|| tr("planner.pick_installed"), |(mod_id, version)| format!("{} v{}", mod_name(&state.manifest_mods, mod_id), version));
+
+        ComboBox::from_id_source("planner_uninstall")
+            .selected_text(selected_text)
+            .width(250.0)
+            .show_ui(ui, |ui| {
+                for id in installed {
+                    let label = format!("{} v{}", mod_name(&state.manifest_mods, &id.0), id.1);
+                    ui.selectable_value(&mut planner_state.uninstall, Some(id), label);
+                }
+            });
+
+        if ui.add_enabled(planner_state.uninstall.is_some(), Button::new(tr("planner.add_uninstall"))).clicked() {
+            added = planner_state.uninstall.take().map(PlanStep::Uninstall);
+        }
+    });
+
+    if let Some(step) = added {
+        if !state.planner_state.steps.contains(&step) {
+            state.planner_state.steps.push(step);
+            state.planner_state.steps_changed(toasts, command);
+        }
+    }
+}
+
+fn steps_ui(state: &mut UIManagerState, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut removed = None;
+
+    for (index, step) in state.planner_state.steps.iter().enumerate() {
+        let failure = state.planner_state.outcome.as_ref()
+            .and_then(|x| x.failed_steps.iter().find(|(failed, _)| *failed == index))
+            .map(|(_, reason)| reason.clone());
+
+        ui.horizontal(|ui| {
+            if ui.small_button("✖").on_hover_text(tr("planner.remove_step")).clicked() {
+                removed = Some(index);
+            }
+
+            match failure {
+                Some(reason) => {
+                    ui.label(RichText::new(describe_step(step, state)).color(Color32::LIGHT_RED))
+                        .on_hover_text(reason);
+                }
+                None => {
+                    ui.label(describe_step(step, state));
+                }
+            }
+        });
+    }
+
+    if let Some(index) = removed {
+        state.planner_state.steps.remove(index);
+        state.planner_state.steps_changed(toasts, command);
+    }
+}
+
+fn outcome_ui(state: &mut UIManagerState, ui: &mut Ui) {
+    let Some(outcome) = &state.planner_state.outcome else {
+        ui.horizontal(|ui| {
+            ui.add(Spinner::new());
+            ui.label(tr("planner.simulating"));
+        });
+        return;
+    };
+
+    let global_mods = &state.manifest_mods;
+
+    if outcome.conflicts.is_empty() {
+        ui.label(RichText::new(tr("planner.no_conflicts")).color(Color32::LIGHT_GREEN));
+    }
+
+    for conflict in &outcome.conflicts {
+        ui.label(RichText::new(format!("⚠ {}", describe_conflict(conflict, global_mods))).color(Color32::LIGHT_RED));
+    }
+
+    for (mod_id, source) in &outcome.untrusted {
+        ui.label(RichText::new(format!("🔓 {}", tr_args("plan.untrusted", &[
+            ("name", mod_name(global_mods, mod_id)),
+            ("source", source.clone())
+        ]))).color(Color32::GOLD));
+    }
+
+    ui.add_space(5.0);
+    ui.label(tr("plan.operations"));
+
+    for operation in &outcome.operations {
+        ui.small(describe_operation(operation, global_mods));
+    }
+
+    match download_size(&outcome.operations, global_mods, &mut state.artifact_sizes) {
+        Some(size) if size > 0 => {
+            ui.add_space(5.0);
+            ui.label(tr_args("plan.download_size", &[("size", format_size(size))]));
+        }
+        Some(_) => {}
+        None => {
+            ui.add_space(5.0);
+            ui.label(RichText::new(tr("planner.size_unknown")).weak());
+        }
+    }
+}
+
+/// Window where changes are queued up and checked together before any of them get applied
+pub fn planner_window(state: &mut UIManagerState, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut open = state.planner_state.open;
+
+    if !open {
+        return;
+    }
+
+    Window::new(tr("planner.title"))
+        .open(&mut open)
+        .default_width(450.0)
+        .show(ctx, |ui| {
+            ui.label(RichText::new(tr("planner.description")).weak());
+
+            ui.add_space(5.0);
+
+            add_steps_ui(state, ui, toasts, command);
+
+            ui.separator();
+
+            ScrollArea::vertical()
+                .id_source("planner_scroll")
+                .max_height(350.0)
+                .show(ui, |ui| {
+                    if state.planner_state.steps.is_empty() {
+                        ui.label(RichText::new(tr("planner.empty")).weak());
+                        return;
+                    }
+
+                    ui.strong(tr("planner.steps"));
+                    steps_ui(state, ui, toasts, command);
+
+                    ui.add_space(5.0);
+                    ui.strong(tr("planner.result"));
+                    outcome_ui(state, ui);
+                });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                let operations = state.planner_state.outcome.as_ref()
+                    .map(|x| x.operations.clone())
+                    .filter(|x| !x.is_empty());
+
+                if ui.add_enabled(operations.is_some(), Button::new(tr("planner.apply"))).clicked() {
+                    if let Some(operations) = operations {
+                        handle_error(command.blocking_send(ManagerCommand::ApplyOperations(operations)), toasts);
+                    }
+
+                    state.planner_state.discard();
+                }
+
+                if ui.button(tr("planner.discard")).clicked() {
+                    state.planner_state.discard();
+                }
+            });
+        });
+
+    // Closing the window keeps the plan around for later
+    if !open {
+        state.planner_state.open = false;
+    }
+}
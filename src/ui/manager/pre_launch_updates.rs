@@ -0,0 +1,84 @@
+use eframe::egui::{Color32, Context, RichText, ScrollArea, Spinner};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::install::IDVersion;
+use crate::locale::tr;
+use crate::manager::ManagerCommand;
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+use crate::version::Version;
+
+pub struct PreLaunchUpdatesState {
+    modal: Modal,
+    updates: Vec<(IDVersion, Version)>,
+    /// Whether each update went through, None while they're still being applied
+    results: Option<Vec<(IDVersion, Version, bool)>>
+}
+
+impl PreLaunchUpdatesState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "pre_launch_updates_modal"),
+            updates: vec![],
+            results: None,
+        }
+    }
+
+    pub fn start(&mut self, updates: Vec<(IDVersion, Version)>) {
+        self.updates = updates;
+        self.results = None;
+        self.modal.open();
+    }
+
+    pub fn finish(&mut self, results: Vec<(IDVersion, Version, bool)>) {
+        self.results = Some(results);
+    }
+}
+
+/// Progress of updates applied on launch, then what came out of them before the game starts
+pub fn pre_launch_updates_modal(state: &mut UIManagerState, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let updates_state = &mut state.pre_launch_updates_state;
+    let global_mods = &state.manifest_mods;
+
+    updates_state.modal.show(|ui| {
+        updates_state.modal.title(ui, tr("pre_launch_updates.title"));
+
+        updates_state.modal.frame(ui, |ui| {
+            let description = if updates_state.results.is_some() { "pre_launch_updates.finished" } else { "pre_launch_updates.applying" };
+            ui.label(tr(description));
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("pre_launch_updates_scroll")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for (index, ((mod_id, installed), newest)) in updates_state.updates.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            match updates_state.results.as_ref().and_then(|x| x.get(index)) {
+                                Some((_, _, true)) => ui.label(RichText::new("✔").color(Color32::LIGHT_GREEN)),
+                                Some((_, _, false)) => ui.label(RichText::new("✖").color(Color32::LIGHT_RED)),
+                                None => ui.add(Spinner::new())
+                            };
+
+                            ui.label(format!("{} v{} → v{}", mod_name(global_mods, mod_id), installed, newest));
+                        });
+                    }
+                });
+        });
+
+        if updates_state.results.is_none() {
+            return;
+        }
+
+        updates_state.modal.buttons(ui, |ui| {
+            if updates_state.modal.suggested_button(ui, tr("pre_launch_updates.launch")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::ContinueLaunch), toasts);
+            }
+
+            updates_state.modal.button(ui, tr("pre_launch_updates.cancel"));
+        });
+    });
+}
@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use dirs::home_dir;
+use eframe::egui::{Context, ScrollArea, TextEdit, Ui, Widget};
+use egui_modal::Modal;
+use egui_toast::{ToastKind, Toasts};
+use reqwest::Url;
+use crate::config::ToastSettings;
+use crate::install::ModMap;
+use crate::launch::{mod_loader_version, neos_version};
+use crate::locale::{tr, tr_args};
+use crate::manifest::GlobalModList;
+use crate::ui::manager::notifications::NotificationCenterState;
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+use crate::version::Version;
+
+pub const ISSUES_URL: &str = "https://github.com/TheJebForge/neos-mod-organizer/issues/new";
+
+/// How many of the last errors are kept around for reports
+const MAX_RECENT_ERRORS: usize = 10;
+
+/// Errors longer than that get cut off, to keep the issue URL at sane length
+const MAX_ERROR_LENGTH: usize = 300;
+
+/// Hashes in mod report are shortened to this many characters, enough to tell files apart
+const REPORT_HASH_LENGTH: usize = 12;
+
+/// Mod list is left out of issues that would get longer than that, browsers and GitHub cut off long URLs
+const MAX_MOD_ISSUE_BODY_LENGTH: usize = 6000;
+
+pub struct ReportState {
+    modal: Modal,
+    pub(crate) recent_errors: VecDeque<String>,
+    description: String,
+    include_errors: bool,
+    /// Mod report starts with OS and mod loader version
+    include_system_info: bool,
+}
+
+impl ReportState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "report_problem_modal")
+                .with_close_on_outside_click(true),
+            recent_errors: Default::default(),
+            description: "".to_string(),
+            include_errors: true,
+            include_system_info: true,
+        }
+    }
+
+    pub fn push_error(&mut self, error: &str) {
+        if self.recent_errors.len() >= MAX_RECENT_ERRORS {
+            self.recent_errors.pop_front();
+        }
+
+        self.recent_errors.push_back(sanitize_error(error));
+    }
+
+    pub fn open(&mut self) {
+        self.modal.open();
+    }
+}
+
+/// Removes user specific info from the error, like path to user's home folder
+pub fn sanitize_error(error: &str) -> String {
+    let mut error = error.to_string();
+
+    if let Some(home) = home_dir() {
+        let home = home.to_string_lossy().to_string();
+
+        if !home.is_empty() {
+            error = error.replace(&home, "~");
+        }
+    }
+
+    if error.chars().count() > MAX_ERROR_LENGTH {
+        error = format!("{}...", error.chars().take(MAX_ERROR_LENGTH).collect::<String>());
+    }
+
+    error
+}
+
+pub fn build_issue_body(description: &str, errors: &[String]) -> String {
+    let mut body = format!(
+        "**Describe the problem**\n{}\n\n**Environment**\n- Organizer version: {}\n- OS: {} ({})\n",
+        if description.is_empty() { "-" } else { description },
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+
+    if !errors.is_empty() {
+        body.push_str("\n**Recent errors**\n```\n");
+
+        for error in errors {
+            body.push_str(error);
+            body.push('\n');
+        }
+
+        body.push_str("```\n");
+    }
+
+    body
+}
+
+pub fn build_issue_url(description: &str, errors: &[String]) -> Url {
+    let title = description.lines()
+        .next()
+        .map(|x| x.chars().take(80).collect::<String>())
+        .unwrap_or_default();
+
+    Url::parse_with_params(ISSUES_URL, &[
+        ("title", title),
+        ("body", build_issue_body(description, errors))
+    ]).expect("Issues URL is invalid")
+}
+
+/// New issue page of the mod's repository, only GitHub lets issues be filled in through the URL
+pub fn new_issue_url(source_location: &str) -> Option<Url> {
+    let url = Url::parse(source_location.trim()).ok()?;
+
+    if url.host_str()? != "github.com" {
+        return None;
+    }
+
+    let mut segments = url.path_segments()?.filter(|x| !x.is_empty());
+    let owner = segments.next()?;
+    let repository = segments.next()?.trim_end_matches(".git");
+
+    Url::parse(&format!("https://github.com/{}/{}/issues/new", owner, repository)).ok()
+}
+
+/// Issue about the mod filled in with its version and the environment, installed mods are included if they fit
+pub fn build_mod_issue_url(source_location: &str, name: &str, version: Option<&Version>, system_info: &str, mod_report: &str) -> Option<Url> {
+    let mut url = new_issue_url(source_location)?;
+
+    let mut body = format!(
+        "**Describe the problem**\n-\n\n**Mod**\n{}{}\n\n**Environment**\n{}",
+        name,
+        version.map_or_else(String::new, |x| format!(" v{}", x)),
+        system_info
+    );
+
+    if body.len() + mod_report.len() < MAX_MOD_ISSUE_BODY_LENGTH {
+        body.push('\n');
+        body.push_str(mod_report);
+    }
+
+    url.query_pairs_mut().append_pair("body", &body);
+
+    Some(url)
+}
+
+/// OS, organizer, Neos and mod loader versions for the top of the mod report
+pub fn system_info(neos_folder: &Path) -> String {
+    format!(
+        "- Organizer version: {}\n- OS: {} ({})\n- Neos version: {}\n- NeosModLoader version: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        neos_version(neos_folder).unwrap_or_else(|| "unknown".to_string()),
+        mod_loader_version(neos_folder).unwrap_or_else(|| "not installed".to_string())
+    )
+}
+
+/// Markdown table of installed mods to paste when asking for help
+pub fn build_mod_report(mod_map: &ModMap, global_mods: &GlobalModList, system_info: Option<String>) -> String {
+    // Pipes would split table cells
+    let escape = |x: &str| x.replace('|', "\\|");
+
+    let mut rows: Vec<(String, String, String, bool, String)> = mod_map.iter_versions()
+        .map(|(mod_id, version, file)| (
+            mod_name(global_mods, mod_id),
+            mod_id.clone(),
+            version.to_string(),
+            file.is_enabled(),
+            file.files.iter()
+                .map(|x| x.file_hash.chars().take(REPORT_HASH_LENGTH).collect::<String>())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ))
+        .collect();
+
+    rows.sort_by_cached_key(|(name, mod_id, version, _, _)| (name.to_lowercase(), mod_id.clone(), version.clone()));
+
+    let mut report = system_info.map_or_else(String::new, |x| format!("{}\n", x));
+
+    report.push_str(&format!("**Installed mods ({})**\n\n", rows.len()));
+    report.push_str("| Name | GUID | Version | Enabled | Hash |\n|---|---|---|---|---|\n");
+
+    for (name, mod_id, version, enabled, hash) in rows {
+        report.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            escape(&name),
+            escape(&mod_id),
+            version,
+            if enabled { "Yes" } else { "No" },
+            hash
+        ));
+    }
+
+    report
+}
+
+/// Copies the mod report to clipboard, with system info if user wants it
+pub fn mod_report_button(report_state: &mut ReportState, mod_map: &ModMap, global_mods: &GlobalModList, neos_folder: &Path, notification_center: &mut NotificationCenterState, toast_settings: &ToastSettings, ui: &mut Ui, toasts: &mut Toasts) {
+    ui.menu_button(tr("report.mod_report"), |ui| {
+        ui.checkbox(&mut report_state.include_system_info, tr("report.include_system_info"));
+
+        if ui.button(tr("report.copy_mod_report")).clicked() {
+            let system_info = report_state.include_system_info.then(|| system_info(neos_folder));
+
+            ui.output_mut(|x| x.copied_text = build_mod_report(mod_map, global_mods, system_info));
+            ui.close_menu();
+
+            notification_center.notify(toasts, toast_settings, ToastKind::Success, tr("report.mod_report_copied"), false);
+        }
+    });
+}
+
+pub fn report_problem_modal(state: &mut UIManagerState, toasts: &mut Toasts) {
+    let report_state = &mut state.report_state;
+
+    report_state.modal.show(|ui| {
+        report_state.modal.title(ui, tr("settings.report_problem"));
+
+        report_state.modal.frame(ui, |ui| {
+            ui.label(tr("report.describe"));
+
+            TextEdit::multiline(&mut report_state.description)
+                .desired_width(450.0)
+                .desired_rows(6)
+                .ui(ui);
+
+            ui.checkbox(&mut report_state.include_errors, tr_args("report.include_errors", &[("count", report_state.recent_errors.len().to_string())]));
+
+            if report_state.include_errors && !report_state.recent_errors.is_empty() {
+                ScrollArea::vertical()
+                    .id_source("report_problem_errors_scroll")
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        for error in &report_state.recent_errors {
+                            ui.small(error);
+                        }
+                    });
+            }
+
+            ui.small(format!("Organizer v{}, {} ({})", env!("CARGO_PKG_VERSION"), std::env::consts::OS, std::env::consts::ARCH));
+        });
+
+        report_state.modal.buttons(ui, |ui| {
+            if report_state.modal.suggested_button(ui, tr("report.open_issue")).clicked() {
+                let errors = if report_state.include_errors {
+                    report_state.recent_errors.iter().cloned().collect::<Vec<String>>()
+                } else {
+                    vec![]
+                };
+
+                let url = build_issue_url(&report_state.description, &errors);
+
+                handle_error(open::that(url.as_str()), toasts);
+                report_state.description.clear();
+            }
+
+            report_state.modal.button(ui, tr("common.cancel"));
+        });
+    });
+}
@@ -0,0 +1,67 @@
+use eframe::egui::{Context, ScrollArea};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::locale::{tr, tr_args};
+use crate::manager::{ManagerCommand, QueueItem};
+use crate::ui::manager::plan_preview::describe_operation;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+
+pub struct ResumeQueueState {
+    modal: Modal,
+    items: Vec<QueueItem>
+}
+
+impl ResumeQueueState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "resume_queue_modal"),
+            items: vec![],
+        }
+    }
+
+    pub fn open(&mut self, items: Vec<QueueItem>) {
+        self.items = items;
+        self.modal.open();
+    }
+}
+
+/// Asks what to do with operations that were left unfinished when the organizer closed
+pub fn resume_queue_modal(state: &mut UIManagerState, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let resume_state = &mut state.resume_queue_state;
+    let global_mods = &state.manifest_mods;
+
+    if !resume_state.modal.is_open() {
+        return;
+    }
+
+    resume_state.modal.show(|ui| {
+        resume_state.modal.title(ui, tr("resume_queue.title"));
+
+        resume_state.modal.frame(ui, |ui| {
+            ui.label(tr_args("resume_queue.description", &[("count", resume_state.items.len().to_string())]));
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("resume_queue_scroll")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for item in &resume_state.items {
+                        ui.small(describe_operation(&item.operation, global_mods));
+                    }
+                });
+        });
+
+        resume_state.modal.buttons(ui, |ui| {
+            if resume_state.modal.suggested_button(ui, tr("resume_queue.resume")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::ResumeSavedQueue), toasts);
+            }
+
+            if resume_state.modal.caution_button(ui, tr("resume_queue.discard")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::DiscardSavedQueue), toasts);
+            }
+        });
+    });
+}
@@ -0,0 +1,980 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use eframe::egui::{Align, Button, CollapsingHeader, Color32, ComboBox, DragValue, Grid, RichText, TextEdit, Ui, Widget};
+use egui_toast::Toasts;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use tokio::sync::mpsc::{Receiver, Sender};
+use crate::config::{clamp_ui_scale, CloudSyncSettings, Config, DefaultLocations, SourceTrust, InstallProfile, InstallType, MirrorRule, NetworkSettings, ProxyKind, ScanLocation, SyncTarget, UpdateChannel};
+use crate::maintenance::MaintenanceFolder;
+use crate::locale::{available_languages, set_language, tr, tr_args};
+use crate::manager::{ManagerCommand, ManagerEvent};
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::tests::test_ui;
+use crate::ui::manager::UIManagerState;
+use crate::utils::{format_size, handle_error, unix_timestamp};
+
+/// Limit that's set when it gets turned on, in KiB/s
+const DEFAULT_BANDWIDTH_LIMIT: u32 = 1024;
+
+/// Scales offered in settings, picking from a list avoids rescaling the window while a slider is dragged
+const UI_SCALES: [f32; 8] = [0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0];
+
+#[derive(Default)]
+pub struct SettingsState {
+    new_scan_location: String,
+    /// Public keys of manifest sources being edited, by URL
+    source_keys: HashMap<String, String>,
+    /// Default mods and libraries folders being edited
+    default_locations: Option<(String, String, String)>,
+    /// Network settings being edited, only saved once applied
+    network: Option<NetworkSettings>,
+    ca_bundle: String,
+    new_mirror_prefix: String,
+    new_mirror_replacement: String,
+    /// Cloud sync settings being edited, only saved once applied
+    cloud_sync: Option<CloudSyncSettings>,
+    /// Section to open and scroll to on next frame, set by global search
+    jump_to: Option<SettingsSection>
+}
+
+impl SettingsState {
+    pub fn jump_to(&mut self, section: SettingsSection) {
+        self.jump_to = Some(section);
+    }
+}
+
+/// Collapsible sections of the settings tab
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum SettingsSection {
+    ScanLocations,
+    DefaultLocations,
+    ManifestSources,
+    ConflictPreference,
+    Trash,
+    ArtifactCache,
+    Maintenance,
+    Network,
+    CloudSync,
+    Notifications
+}
+
+impl SettingsSection {
+    pub fn title_key(&self) -> &'static str {
+        match self {
+            SettingsSection::ScanLocations => "settings.scan_locations",
+            SettingsSection::DefaultLocations => "settings.default_locations",
+            SettingsSection::ManifestSources => "settings.manifest_sources",
+            SettingsSection::ConflictPreference => "settings.conflict_preference",
+            SettingsSection::Trash => "settings.trash",
+            SettingsSection::ArtifactCache => "settings.artifact_cache",
+            SettingsSection::Maintenance => "settings.maintenance",
+            SettingsSection::Network => "settings.network",
+            SettingsSection::CloudSync => "settings.cloud_sync",
+            SettingsSection::Notifications => "settings.notifications",
+        }
+    }
+
+    /// Labels of settings inside the section, so they can be searched for
+    pub fn setting_keys(&self) -> &'static [&'static str] {
+        match self {
+            SettingsSection::ScanLocations => &["settings.install_type", "settings.add_scan_location"],
+            SettingsSection::DefaultLocations => &["settings.default_mods_location", "settings.default_libraries_location", "settings.default_plugins_location"],
+            SettingsSection::ManifestSources => &["settings.source_trusted", "settings.source_public_key"],
+            SettingsSection::ConflictPreference => &[],
+            SettingsSection::Trash => &["settings.trash_enabled", "settings.trash_retention", "settings.empty_trash"],
+            SettingsSection::ArtifactCache => &["settings.artifact_cache_enabled", "settings.artifact_cache_max_size", "settings.artifact_cache_prune", "settings.artifact_cache_clear"],
+            SettingsSection::Maintenance => &["settings.staging_retention", "settings.backup_retention", "settings.cache_retention", "settings.inspect_folders", "settings.clean_up"],
+            SettingsSection::Network => &["settings.use_proxy", "settings.proxy_kind", "settings.proxy_address", "settings.ca_bundle", "settings.bandwidth_limit", "settings.mirrors"],
+            SettingsSection::CloudSync => &["settings.sync_target", "settings.github_token", "settings.git_url", "settings.git_branch", "settings.sync_pull", "settings.sync_push"],
+            SettingsSection::Notifications => &["settings.show_success_toasts", "settings.toast_duration", "settings.long_toast_duration"],
+        }
+    }
+}
+
+/// Collapsible header of a section, opened and scrolled to if global search jumped to it
+fn section_header(ui: &mut Ui, section: SettingsSection, jump_to: Option<SettingsSection>, add_contents: impl FnOnce(&mut Ui)) {
+    let jumped = jump_to == Some(section);
+
+    let response = CollapsingHeader::new(tr(section.title_key()))
+        .default_open(false)
+        .open(jumped.then_some(true))
+        .show(ui, add_contents);
+
+    if jumped {
+        response.header_response.scroll_to_me(Some(Align::TOP));
+    }
+}
+
+pub fn settings_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>, event: &mut Receiver<ManagerEvent>) {
+    let current_language = config.load().language.clone();
+    let jump_to = state.settings_state.jump_to.take();
+
+    ComboBox::from_label(tr("settings.language"))
+        .selected_text(&current_language)
+        .width(120.0)
+        .show_ui(ui, |ui| {
+            for language in available_languages() {
+                if ui.selectable_label(language == current_language, &language).clicked() && language != current_language {
+                    if handle_error(set_language(&language), toasts).is_some() {
+                        let mut config_str = config.load().as_ref().clone();
+                        config_str.language = language;
+                        config.swap(Arc::new(config_str));
+
+                        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+                    }
+                }
+            }
+        });
+
+    let current_scale = clamp_ui_scale(config.load().ui_scale);
+
+    ComboBox::from_label(tr("settings.ui_scale"))
+        .selected_text(format!("{:.0}%", current_scale * 100.0))
+        .width(120.0)
+        .show_ui(ui, |ui| {
+            for scale in UI_SCALES {
+                if ui.selectable_label(scale == current_scale, format!("{:.0}%", scale * 100.0)).clicked() && scale != current_scale {
+                    let mut config_str = config.load().as_ref().clone();
+                    config_str.ui_scale = clamp_ui_scale(scale);
+                    config.swap(Arc::new(config_str));
+
+                    handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+                }
+            }
+        });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::ScanLocations, jump_to, |ui| {
+        scan_locations_ui(&mut state.settings_state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::DefaultLocations, jump_to, |ui| {
+        default_locations_ui(&mut state.settings_state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::ManifestSources, jump_to, |ui| {
+        manifest_sources_ui(&mut state.settings_state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::ConflictPreference, jump_to, |ui| {
+        conflict_preference_ui(state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::Trash, jump_to, |ui| {
+        trash_ui(state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::ArtifactCache, jump_to, |ui| {
+        artifact_cache_ui(state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::Maintenance, jump_to, |ui| {
+        maintenance_ui(state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::Network, jump_to, |ui| {
+        network_ui(&mut state.settings_state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::CloudSync, jump_to, |ui| {
+        cloud_sync_ui(&mut state.settings_state, config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    section_header(ui, SettingsSection::Notifications, jump_to, |ui| {
+        toast_settings_ui(config, ui, toasts, command);
+    });
+
+    ui.add_space(5.0);
+
+    if cfg!(target_os = "windows") {
+        let mut minimize_to_tray = config.load().minimize_to_tray;
+
+        if ui.checkbox(&mut minimize_to_tray, tr("settings.minimize_to_tray")).changed() {
+            let mut config_str = config.load().as_ref().clone();
+            config_str.minimize_to_tray = minimize_to_tray;
+            config.swap(Arc::new(config_str));
+
+            handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+        }
+
+        ui.add_space(5.0);
+    }
+
+    let mut update_notifications = config.load().update_notifications;
+
+    if ui.checkbox(&mut update_notifications, tr("settings.update_notifications")).changed() {
+        let mut config_str = config.load().as_ref().clone();
+        config_str.update_notifications = update_notifications;
+        config.swap(Arc::new(config_str));
+
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    }
+
+    ui.add_space(5.0);
+
+    let mut update_before_launch = config.load().update_before_launch;
+
+    if ui.checkbox(&mut update_before_launch, tr("settings.update_before_launch")).changed() {
+        let mut config_str = config.load().as_ref().clone();
+        config_str.update_before_launch = update_before_launch;
+        config.swap(Arc::new(config_str));
+
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    }
+
+    ui.add_space(5.0);
+
+    let current_channel = config.load().update_channel;
+
+    ComboBox::from_label(tr("settings.update_channel"))
+        .selected_text(tr(current_channel.title_key()))
+        .width(120.0)
+        .show_ui(ui, |ui| {
+            for channel in UpdateChannel::iter() {
+                if ui.selectable_label(channel == current_channel, tr(channel.title_key())).clicked() && channel != current_channel {
+                    let mut config_str = config.load().as_ref().clone();
+                    config_str.update_channel = channel;
+                    config.swap(Arc::new(config_str));
+
+                    handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+                    state.mod_list_state.invalidate();
+                    state.updates_state.invalidate();
+                }
+            }
+        });
+
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        let key = if Config::is_portable() { "settings.data_folder_portable" } else { "settings.data_folder" };
+        ui.label(tr_args(key, &[("path", Config::data_folder().to_string_lossy().to_string())]));
+
+        if ui.small_button(tr("settings.open_data_folder")).clicked() {
+            handle_error(open::that(Config::data_folder()), toasts);
+        }
+    });
+
+    ui.add_space(5.0);
+
+    if ui.button(tr("settings.report_problem")).clicked() {
+        state.report_state.open();
+    }
+
+    ui.add_space(5.0);
+
+    CollapsingHeader::new("Tests")
+        .show(ui, |ui| {
+            test_ui(state, ui, toasts, command, event);
+        });
+}
+
+fn scan_locations_ui(settings_state: &mut SettingsState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut profile = config.load().install_profile.clone();
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ComboBox::from_label(tr("settings.install_type"))
+            .selected_text(profile.install_type.to_string())
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for variant in InstallType::iter() {
+                    let label = variant.to_string();
+                    changed |= ui.selectable_value(&mut profile.install_type, variant, label).changed();
+                }
+            });
+
+        if ui.button(tr("settings.reset_scan_locations")).clicked() {
+            profile = InstallProfile {
+                default_locations: profile.default_locations.clone(),
+                ..InstallProfile::for_install_type(profile.install_type)
+            };
+            changed = true;
+        }
+    });
+
+    let mut removed = None;
+
+    for (index, location) in profile.scan_locations.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(&mut location.enabled, location.path.to_string_lossy().to_string()).changed();
+
+            if ui.small_button("✖").clicked() {
+                removed = Some(index);
+            }
+        });
+    }
+
+    if let Some(index) = removed {
+        profile.scan_locations.remove(index);
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        TextEdit::singleline(&mut settings_state.new_scan_location)
+            .hint_text("/nml_mods")
+            .desired_width(200.0)
+            .ui(ui);
+
+        if ui.add_enabled(!settings_state.new_scan_location.is_empty(), Button::new(tr("settings.add_scan_location"))).clicked() {
+            profile.scan_locations.push(ScanLocation::new(PathBuf::from(settings_state.new_scan_location.trim())));
+            settings_state.new_scan_location.clear();
+            changed = true;
+        }
+    });
+
+    if changed {
+        let mut config_str = config.load().as_ref().clone();
+        config_str.install_profile = profile;
+        config.swap(Arc::new(config_str));
+
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+        handle_error(command.blocking_send(ManagerCommand::RefreshModMap), toasts);
+    }
+}
+
+fn default_locations_ui(settings_state: &mut SettingsState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let current = config.load().install_profile.default_locations.clone();
+
+    let (mods, libraries, plugins) = settings_state.default_locations.get_or_insert_with(|| (
+        current.mods.to_string_lossy().to_string(),
+        current.libraries.to_string_lossy().to_string(),
+        current.plugins.to_string_lossy().to_string()
+    ));
+
+    ui.label(RichText::new(tr("settings.default_locations_hint")).weak());
+
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.default_mods_location"));
+        TextEdit::singleline(mods)
+            .hint_text("/nml_mods")
+            .desired_width(200.0)
+            .ui(ui);
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.default_libraries_location"));
+        TextEdit::singleline(libraries)
+            .hint_text("/nml_libs")
+            .desired_width(200.0)
+            .ui(ui);
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.default_plugins_location"));
+        TextEdit::singleline(plugins)
+            .hint_text("/Libraries")
+            .desired_width(200.0)
+            .ui(ui);
+    });
+
+    let edited = DefaultLocations {
+        mods: PathBuf::from(mods.trim()),
+        libraries: PathBuf::from(libraries.trim()),
+        plugins: PathBuf::from(plugins.trim()),
+    };
+
+    let valid = !mods.trim().is_empty() && !libraries.trim().is_empty() && !plugins.trim().is_empty();
+    let mut reverted = false;
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(valid && edited != current, Button::new(tr("settings.apply_default_locations"))).clicked() {
+            let mut config_str = config.load().as_ref().clone();
+            config_str.install_profile.default_locations = edited.clone();
+            config.swap(Arc::new(config_str));
+
+            handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+            handle_error(command.blocking_send(ManagerCommand::RefreshModMap), toasts);
+        }
+
+        if ui.button(tr("settings.reset_default_locations")).clicked() {
+            reverted = true;
+        }
+    });
+
+    if reverted {
+        let defaults = DefaultLocations::default();
+
+        settings_state.default_locations = Some((
+            defaults.mods.to_string_lossy().to_string(),
+            defaults.libraries.to_string_lossy().to_string(),
+            defaults.plugins.to_string_lossy().to_string()
+        ));
+    }
+}
+
+fn manifest_sources_ui(settings_state: &mut SettingsState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let current = config.load_full();
+    let mut changed: Option<(String, SourceTrust)> = None;
+
+    ui.label(RichText::new(tr("settings.manifest_sources_description")).weak());
+
+    for url in &current.manifest_links {
+        let mut trust = current.source_trust(url);
+
+        ui.add_space(5.0);
+        ui.label(url);
+
+        if !url.starts_with("https://") {
+            ui.small(RichText::new(tr("settings.source_not_https")).color(Color32::LIGHT_RED));
+        }
+
+        if ui.checkbox(&mut trust.trusted, tr("settings.source_trusted")).changed() {
+            changed = Some((url.clone(), trust.clone()));
+        }
+
+        let key = settings_state.source_keys.entry(url.clone())
+            .or_insert_with(|| trust.public_key.clone().unwrap_or_default());
+
+        ui.horizontal(|ui| {
+            TextEdit::singleline(key)
+                .hint_text(tr("settings.source_public_key"))
+                .desired_width(300.0)
+                .ui(ui);
+
+            let edited = Some(key.trim().to_string()).filter(|x| !x.is_empty());
+
+            if ui.add_enabled(edited != trust.public_key, Button::new(tr("settings.source_save_key"))).clicked() {
+                changed = Some((url.clone(), SourceTrust {
+                    public_key: edited,
+                    ..trust.clone()
+                }));
+            }
+        });
+    }
+
+    if let Some((url, trust)) = changed {
+        let mut config_str = current.as_ref().clone();
+        config_str.manifest_trust.insert(url, trust);
+        config.swap(Arc::new(config_str));
+
+        // Signatures are checked when manifests get downloaded
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+        handle_error(command.blocking_send(ManagerCommand::RefreshManifests), toasts);
+    }
+}
+
+fn network_ui(settings_state: &mut SettingsState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let current = config.load().network.clone();
+
+    let network = settings_state.network.get_or_insert_with(|| {
+        settings_state.ca_bundle = current.ca_bundle.as_ref().map_or_else(String::new, |x| x.to_string_lossy().to_string());
+        current.clone()
+    });
+
+    ui.checkbox(&mut network.proxy.enabled, tr("settings.use_proxy"));
+
+    ui.add_enabled_ui(network.proxy.enabled, |ui| {
+        ComboBox::from_label(tr("settings.proxy_kind"))
+            .selected_text(network.proxy.kind.to_string())
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for variant in ProxyKind::iter() {
+                    let label = variant.to_string();
+                    ui.selectable_value(&mut network.proxy.kind, variant, label);
+                }
+            });
+
+        ui.horizontal(|ui| {
+            TextEdit::singleline(&mut network.proxy.address)
+                .hint_text("proxy.local:8080")
+                .desired_width(200.0)
+                .ui(ui);
+            ui.label(tr("settings.proxy_address"));
+        });
+
+        ui.horizontal(|ui| {
+            TextEdit::singleline(&mut network.proxy.username)
+                .desired_width(200.0)
+                .ui(ui);
+            ui.label(tr("settings.proxy_username"));
+        });
+
+        ui.horizontal(|ui| {
+            TextEdit::singleline(&mut network.proxy.password)
+                .password(true)
+                .desired_width(200.0)
+                .ui(ui);
+            ui.label(tr("settings.proxy_password"));
+        });
+    });
+
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        TextEdit::singleline(&mut settings_state.ca_bundle)
+            .hint_text("/etc/ssl/certs/company.pem")
+            .desired_width(200.0)
+            .ui(ui);
+        ui.label(tr("settings.ca_bundle"));
+    });
+
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        let mut limited = network.bandwidth_limit.is_some();
+
+        if ui.checkbox(&mut limited, tr("settings.bandwidth_limit")).changed() {
+            network.bandwidth_limit = limited.then_some(DEFAULT_BANDWIDTH_LIMIT);
+        }
+
+        if let Some(limit) = &mut network.bandwidth_limit {
+            DragValue::new(limit)
+                .clamp_range(16..=u32::MAX)
+                .speed(16.0)
+                .suffix(" KiB/s")
+                .ui(ui);
+        }
+    });
+
+    ui.add_space(5.0);
+
+    ui.label(tr("settings.mirrors"));
+    ui.small(tr("settings.mirrors_description"));
+
+    let mut removed = None;
+
+    for (index, mirror) in network.mirrors.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut mirror.enabled, "");
+            TextEdit::singleline(&mut mirror.prefix)
+                .desired_width(200.0)
+                .ui(ui);
+            ui.label("→");
+            TextEdit::singleline(&mut mirror.replacement)
+                .desired_width(200.0)
+                .ui(ui);
+
+            if ui.small_button("✖").clicked() {
+                removed = Some(index);
+            }
+        });
+    }
+
+    if let Some(index) = removed {
+        network.mirrors.remove(index);
+    }
+
+    ui.horizontal(|ui| {
+        TextEdit::singleline(&mut settings_state.new_mirror_prefix)
+            .hint_text("https://raw.githubusercontent.com/")
+            .desired_width(200.0)
+            .ui(ui);
+        ui.label("→");
+        TextEdit::singleline(&mut settings_state.new_mirror_replacement)
+            .hint_text("https://mirror.example.com/raw/")
+            .desired_width(200.0)
+            .ui(ui);
+
+        let can_add = !settings_state.new_mirror_prefix.trim().is_empty() && !settings_state.new_mirror_replacement.trim().is_empty();
+
+        if ui.add_enabled(can_add, Button::new(tr("settings.add_mirror"))).clicked() {
+            network.mirrors.push(MirrorRule::new(
+                settings_state.new_mirror_prefix.trim().to_string(),
+                settings_state.new_mirror_replacement.trim().to_string()
+            ));
+
+            settings_state.new_mirror_prefix.clear();
+            settings_state.new_mirror_replacement.clear();
+        }
+    });
+
+    let ca_bundle = settings_state.ca_bundle.trim();
+    network.ca_bundle = (!ca_bundle.is_empty()).then(|| PathBuf::from(ca_bundle));
+
+    let changed = *network != current;
+    let mut reverted = false;
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(changed, Button::new(tr("settings.apply_network"))).clicked() {
+            let mut config_str = config.load().as_ref().clone();
+            config_str.network = network.clone();
+            config.swap(Arc::new(config_str));
+
+            handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+            handle_error(command.blocking_send(ManagerCommand::ReloadNetworkSettings), toasts);
+        }
+
+        reverted = ui.add_enabled(changed, Button::new(tr("settings.revert_network"))).clicked();
+    });
+
+    if reverted {
+        settings_state.network = None;
+    }
+}
+
+fn sync_target_label(target: &SyncTarget) -> String {
+    match target {
+        SyncTarget::Disabled => tr("settings.sync_disabled"),
+        SyncTarget::Gist { .. } => tr("settings.sync_gist"),
+        SyncTarget::Git { .. } => tr("settings.sync_git")
+    }
+}
+
+fn cloud_sync_ui(settings_state: &mut SettingsState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let current = config.load().cloud_sync.clone();
+    let cloud_sync = settings_state.cloud_sync.get_or_insert_with(|| current.clone());
+
+    ui.small(tr("settings.cloud_sync_description"));
+
+    ComboBox::from_label(tr("settings.sync_target"))
+        .selected_text(sync_target_label(&cloud_sync.target))
+        .width(120.0)
+        .show_ui(ui, |ui| {
+            let targets = [
+                SyncTarget::Disabled,
+                SyncTarget::Gist { id: String::new() },
+                SyncTarget::Git { url: String::new(), branch: "main".to_string() }
+            ];
+
+            for target in targets {
+                let selected = std::mem::discriminant(&target) == std::mem::discriminant(&cloud_sync.target);
+
+                if ui.selectable_label(selected, sync_target_label(&target)).clicked() && !selected {
+                    cloud_sync.target = target;
+                }
+            }
+        });
+
+    match &mut cloud_sync.target {
+        SyncTarget::Disabled => {}
+        SyncTarget::Gist { id } => {
+            ui.horizontal(|ui| {
+                TextEdit::singleline(id)
+                    .hint_text(tr("settings.gist_id_hint"))
+                    .desired_width(200.0)
+                    .ui(ui);
+                ui.label(tr("settings.gist_id"));
+            });
+
+            ui.horizontal(|ui| {
+                TextEdit::singleline(&mut cloud_sync.token)
+                    .password(true)
+                    .desired_width(200.0)
+                    .ui(ui);
+                ui.label(tr("settings.github_token"));
+            });
+        }
+        SyncTarget::Git { url, branch } => {
+            ui.horizontal(|ui| {
+                TextEdit::singleline(url)
+                    .hint_text("git@github.com:user/neos-setup.git")
+                    .desired_width(200.0)
+                    .ui(ui);
+                ui.label(tr("settings.git_url"));
+            });
+
+            ui.horizontal(|ui| {
+                TextEdit::singleline(branch)
+                    .desired_width(200.0)
+                    .ui(ui);
+                ui.label(tr("settings.git_branch"));
+            });
+        }
+    }
+
+    let changed = *cloud_sync != current;
+    let mut reverted = false;
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(changed, Button::new(tr("settings.apply_cloud_sync"))).clicked() {
+            let mut config_str = config.load().as_ref().clone();
+            config_str.cloud_sync = cloud_sync.clone();
+            config.swap(Arc::new(config_str));
+
+            handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+        }
+
+        reverted = ui.add_enabled(changed, Button::new(tr("settings.revert_cloud_sync"))).clicked();
+    });
+
+    if reverted {
+        settings_state.cloud_sync = None;
+    }
+
+    // Syncing goes by saved settings, so unapplied edits can't be what gets used
+    let can_sync = !changed && current.target != SyncTarget::Disabled;
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(can_sync, Button::new(tr("settings.sync_pull"))).clicked() {
+            handle_error(command.blocking_send(ManagerCommand::CloudSync { push: false }), toasts);
+        }
+
+        if ui.add_enabled(can_sync, Button::new(tr("settings.sync_push"))).clicked() {
+            handle_error(command.blocking_send(ManagerCommand::CloudSync { push: true }), toasts);
+        }
+    });
+}
+
+fn conflict_preference_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut preference = config.load().conflict_preference.clone();
+    let mut changed = false;
+
+    ui.label(RichText::new(tr("settings.conflict_preference_hint")).weak());
+
+    let mut swap = None;
+    let mut remove = None;
+
+    for (index, guid) in preference.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("{}. {}", index + 1, mod_name(&state.manifest_mods, guid)));
+
+            if ui.add_enabled(index > 0, Button::new("⬆").small()).clicked() {
+                swap = Some((index - 1, index));
+            }
+
+            if ui.add_enabled(index + 1 < preference.len(), Button::new("⬇").small()).clicked() {
+                swap = Some((index, index + 1));
+            }
+
+            if ui.small_button("✖").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+
+    if let Some((a, b)) = swap {
+        preference.swap(a, b);
+        changed = true;
+    }
+
+    if let Some(index) = remove {
+        preference.remove(index);
+        changed = true;
+    }
+
+    let mut candidates: Vec<(String, String)> = state.mod_list.keys()
+        .filter(|x| !preference.contains(x))
+        .map(|x| (mod_name(&state.manifest_mods, x), x.clone()))
+        .collect();
+    candidates.sort();
+
+    ComboBox::from_id_source("conflict_preference_add")
+        .selected_text(tr("settings.conflict_preference_add"))
+        .width(200.0)
+        .show_ui(ui, |ui| {
+            for (name, guid) in candidates {
+                if ui.selectable_label(false, name).clicked() {
+                    preference.push(guid);
+                    changed = true;
+                }
+            }
+        });
+
+    if changed {
+        let mut config_str = config.load().as_ref().clone();
+        config_str.conflict_preference = preference;
+        config.swap(Arc::new(config_str));
+
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    }
+}
+
+fn trash_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut trash = config.load().trash.clone();
+    let mut changed = false;
+
+    changed |= ui.checkbox(&mut trash.enabled, tr("settings.trash_enabled")).changed();
+
+    ui.horizontal(|ui| {
+        changed |= DragValue::new(&mut trash.retention_days)
+            .clamp_range(1..=365)
+            .ui(ui)
+            .changed();
+        ui.label(tr("settings.trash_retention"));
+    });
+
+    if changed {
+        let mut config_str = config.load().as_ref().clone();
+        config_str.trash = trash;
+        config.swap(Arc::new(config_str));
+
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    }
+
+    ui.add_space(5.0);
+
+    if state.trash.is_empty() {
+        ui.label(RichText::new(tr("settings.trash_empty")).weak());
+        return;
+    }
+
+    for entry in state.trash.iter().rev() {
+        let name = state.manifest_mods.mod_list.load().get(&entry.mod_id.0)
+            .map_or_else(|| entry.mod_id.0.clone(), |x| x.name.clone());
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} v{}", name, entry.mod_id.1));
+
+            if ui.button(tr("settings.trash_restore")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::RestoreFromTrash(entry.id)), toasts);
+            }
+
+            if ui.small_button("✖").on_hover_text(tr("settings.trash_delete")).clicked() {
+                handle_error(command.blocking_send(ManagerCommand::DeleteFromTrash(entry.id)), toasts);
+            }
+        });
+    }
+
+    if ui.button(tr("settings.empty_trash")).clicked() {
+        handle_error(command.blocking_send(ManagerCommand::EmptyTrash), toasts);
+    }
+}
+
+fn artifact_cache_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut cache = config.load().artifact_cache.clone();
+    let mut changed = false;
+
+    ui.label(RichText::new(tr("settings.artifact_cache_description")).weak());
+
+    changed |= ui.checkbox(&mut cache.enabled, tr("settings.artifact_cache_enabled")).changed();
+
+    ui.horizontal(|ui| {
+        changed |= DragValue::new(&mut cache.max_size_mb)
+            .clamp_range(16..=65536)
+            .suffix(" MB")
+            .ui(ui)
+            .changed();
+        ui.label(tr("settings.artifact_cache_max_size"));
+    });
+
+    if changed {
+        let mut config_str = config.load().as_ref().clone();
+        config_str.artifact_cache = cache;
+        config.swap(Arc::new(config_str));
+
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    }
+
+    ui.add_space(5.0);
+
+    if let Some(size) = state.artifact_cache_size {
+        ui.label(tr_args("settings.artifact_cache_size", &[("size", format_size(size))]));
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button(tr("settings.artifact_cache_prune")).clicked() {
+            handle_error(command.blocking_send(ManagerCommand::PruneArtifactCache), toasts);
+        }
+
+        if ui.button(tr("settings.artifact_cache_clear")).clicked() {
+            handle_error(command.blocking_send(ManagerCommand::ClearArtifactCache), toasts);
+        }
+    });
+}
+
+fn folder_label(folder: MaintenanceFolder) -> String {
+    tr(match folder {
+        MaintenanceFolder::Staging => "settings.folder_staging",
+        MaintenanceFolder::Trash => "settings.trash",
+        MaintenanceFolder::Backups => "settings.folder_backups",
+        MaintenanceFolder::ArtifactCache => "settings.artifact_cache",
+    })
+}
+
+fn maintenance_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut maintenance = config.load().maintenance.clone();
+    let mut changed = false;
+
+    for (value, label) in [
+        (&mut maintenance.staging_retention_days, "settings.staging_retention"),
+        (&mut maintenance.backup_retention_days, "settings.backup_retention"),
+        (&mut maintenance.cache_retention_days, "settings.cache_retention")
+    ] {
+        ui.horizontal(|ui| {
+            changed |= DragValue::new(value)
+                .clamp_range(1..=365)
+                .ui(ui)
+                .changed();
+            ui.label(tr(label));
+        });
+    }
+
+    ui.label(RichText::new(tr("settings.trash_retention_note")).weak());
+
+    if changed {
+        let mut config_str = config.load().as_ref().clone();
+        config_str.maintenance = maintenance;
+        config.swap(Arc::new(config_str));
+
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    }
+
+    ui.add_space(5.0);
+
+    if let Some(reports) = &state.maintenance_report {
+        let now = unix_timestamp();
+
+        Grid::new("maintenance_report").striped(true).show(ui, |ui| {
+            for report in reports {
+                ui.label(folder_label(report.folder));
+                ui.label(tr_args("settings.folder_items", &[("count", report.items.to_string())]));
+                ui.label(format_size(report.size));
+
+                match report.oldest {
+                    Some(oldest) => ui.label(tr_args("settings.folder_oldest", &[("days", (now.saturating_sub(oldest) / (24 * 60 * 60)).to_string())])),
+                    None => ui.label("")
+                };
+
+                ui.end_row();
+            }
+        });
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button(tr("settings.inspect_folders")).clicked() {
+            handle_error(command.blocking_send(ManagerCommand::InspectFolders), toasts);
+        }
+
+        if ui.button(tr("settings.clean_up")).on_hover_text(tr("settings.clean_up_hover")).clicked() {
+            handle_error(command.blocking_send(ManagerCommand::CleanUpFolders), toasts);
+        }
+    });
+}
+
+fn toast_settings_ui(config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut settings = config.load().toasts.clone();
+    let mut changed = false;
+
+    changed |= ui.checkbox(&mut settings.show_success, tr("settings.show_success_toasts")).changed();
+
+    for (value, label) in [(&mut settings.duration, "settings.toast_duration"), (&mut settings.long_duration, "settings.long_toast_duration")] {
+        ui.horizontal(|ui| {
+            changed |= DragValue::new(value)
+                .clamp_range(1.0..=120.0)
+                .suffix(" s")
+                .ui(ui)
+                .changed();
+            ui.label(tr(label));
+        });
+    }
+
+    if changed {
+        let mut config_str = config.load().as_ref().clone();
+        config_str.toasts = settings;
+        config.swap(Arc::new(config_str));
+
+        handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use dirs::desktop_dir;
+use eframe::egui::{Align2, Button, Color32, ComboBox, Context, RichText, Spinner, TextEdit, Ui};
+use egui_file::FileDialog;
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::config::Config;
+use crate::install::SideloadFile;
+use crate::manifest::GlobalModList;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::UIManagerState;
+use crate::utils::{format_size, handle_error};
+
+pub struct SideloadState {
+    modal: Modal,
+    /// Path or URL typed in or picked
+    source: String,
+    /// Scan location the file goes into
+    location: Option<PathBuf>,
+    inspecting: bool,
+    inspected: Option<SideloadFile>,
+    file_dialog: Option<FileDialog>
+}
+
+impl SideloadState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "sideload_modal"),
+            source: String::new(),
+            location: None,
+            inspecting: false,
+            inspected: None,
+            file_dialog: None,
+        }
+    }
+
+    pub fn set_inspected(&mut self, file: Option<SideloadFile>) {
+        self.inspecting = false;
+        self.inspected = file;
+    }
+
+    fn inspect(&mut self, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+        self.inspecting = true;
+        self.inspected = None;
+        handle_error(command.blocking_send(ManagerCommand::InspectSideload(self.source.trim().to_string())), toasts);
+    }
+}
+
+pub fn sideload_button(sideload_state: &mut SideloadState, ui: &mut Ui) {
+    if ui.button(tr("sideload.open")).on_hover_text(tr("sideload.hover")).clicked() {
+        sideload_state.source.clear();
+        sideload_state.inspected = None;
+        sideload_state.inspecting = false;
+        sideload_state.modal.open();
+    }
+}
+
+fn inspected_ui(global_mods: &GlobalModList, file: &SideloadFile, ui: &mut Ui) {
+    ui.label(format!("{} ({})", file.file_name, format_size(file.size)));
+    ui.small(RichText::new(&file.hash).weak());
+
+    ui.add_space(5.0);
+
+    match &file.identified {
+        Some((mod_id, version)) => {
+            ui.label(RichText::new(tr_args("sideload.identified", &[
+                ("name", mod_name(global_mods, mod_id)),
+                ("version", version.to_string())
+            ])).color(Color32::LIGHT_GREEN));
+        }
+        None => {
+            ui.label(RichText::new(format!("⚠ {}", tr("sideload.unidentified"))).color(Color32::GOLD));
+        }
+    }
+}
+
+/// Installs a DLL that isn't necessarily in any manifest, from disk or a direct link
+pub fn sideload_modal(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let locations: Vec<PathBuf> = config.load().install_profile.enabled_locations().cloned().collect();
+    let sideload_state = &mut state.sideload_state;
+    let global_mods = &state.manifest_mods;
+    let mut browse = false;
+    let mut check = false;
+
+    if sideload_state.location.as_ref().map_or(true, |x| !locations.contains(x)) {
+        sideload_state.location = locations.first().cloned();
+    }
+
+    sideload_state.modal.show(|ui| {
+        sideload_state.modal.title(ui, tr("sideload.title"));
+
+        sideload_state.modal.frame(ui, |ui| {
+            ui.label(tr("sideload.description"));
+
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                let response = ui.add(TextEdit::singleline(&mut sideload_state.source)
+                    .hint_text(tr("sideload.source"))
+                    .desired_width(300.0));
+
+                if response.changed() {
+                    sideload_state.inspected = None;
+                }
+
+                if ui.button(tr("sideload.browse")).clicked() {
+                    browse = true;
+                }
+            });
+
+            ComboBox::from_label(tr("sideload.location"))
+                .selected_text(sideload_state.location.as_ref().map_or(String::new(), |x| x.to_string_lossy().to_string()))
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for location in &locations {
+                        ui.selectable_value(&mut sideload_state.location, Some(location.clone()), location.to_string_lossy());
+                    }
+                });
+
+            ui.add_space(5.0);
+
+            if sideload_state.inspecting {
+                ui.horizontal(|ui| {
+                    ui.add(Spinner::new());
+                    ui.label(tr("sideload.inspecting"));
+                });
+            } else if let Some(file) = &sideload_state.inspected {
+                inspected_ui(global_mods, file, ui);
+            }
+        });
+
+        sideload_state.modal.buttons(ui, |ui| {
+            let source = sideload_state.source.trim().to_string();
+            let ready = sideload_state.inspected.as_ref().filter(|x| x.source == source).is_some();
+
+            if ready {
+                if let Some(location) = sideload_state.location.clone() {
+                    if sideload_state.modal.suggested_button(ui, tr("sideload.install")).clicked() {
+                        handle_error(command.blocking_send(ManagerCommand::InstallSideload {
+                            source,
+                            location,
+                        }), toasts);
+                    }
+                }
+            } else if ui.add_enabled(!source.is_empty() && !sideload_state.inspecting, Button::new(tr("sideload.check"))).clicked() {
+                check = true;
+            }
+
+            sideload_state.modal.button(ui, tr("sideload.cancel"));
+        });
+    });
+
+    if check {
+        sideload_state.inspect(toasts, command);
+    }
+
+    if browse {
+        let mut dialog = FileDialog::open_file(desktop_dir())
+            .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+            .resizable(false);
+
+        dialog.open();
+
+        sideload_state.file_dialog = Some(dialog);
+    }
+
+    if let Some(dialog) = &mut sideload_state.file_dialog {
+        if dialog.show(ctx).selected() {
+            if let Some(file) = dialog.path() {
+                sideload_state.source = file.to_string_lossy().to_string();
+                sideload_state.inspect(toasts, command);
+            }
+
+            sideload_state.file_dialog = None;
+        }
+    }
+}
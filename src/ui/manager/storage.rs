@@ -0,0 +1,107 @@
+use eframe::egui::{Context, Grid, RichText, ScrollArea, Ui};
+use egui_modal::Modal;
+use crate::install::{IDVersion, ModMap};
+use crate::locale::{tr, tr_args};
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::UIManagerState;
+use crate::utils::format_size;
+
+#[derive(PartialEq, Clone, Copy)]
+enum StorageSort {
+    Size,
+    Name
+}
+
+pub struct StorageState {
+    modal: Modal,
+    sort: StorageSort
+}
+
+impl StorageState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "storage_modal"),
+            sort: StorageSort::Size,
+        }
+    }
+}
+
+/// Size of every installed mod version along with how many files it has
+pub fn mod_sizes(mod_map: &ModMap) -> Vec<(IDVersion, usize, u64)> {
+    mod_map.iter_versions()
+        .map(|(mod_id, version, file)| ((mod_id.clone(), version.clone()), file.files.len(), file.total_size()))
+        .collect()
+}
+
+pub fn storage_button(storage_state: &mut StorageState, ui: &mut Ui) {
+    if ui.button(tr("storage.open")).clicked() {
+        storage_state.modal.open();
+    }
+}
+
+pub fn storage_modal(state: &mut UIManagerState) {
+    let storage_state = &mut state.storage_state;
+    let global_mods = &state.manifest_mods;
+
+    if !storage_state.modal.is_open() {
+        return;
+    }
+
+    let mut sizes: Vec<(String, IDVersion, usize, u64)> = mod_sizes(&state.mod_list).into_iter()
+        .map(|(id, files, size)| (mod_name(global_mods, &id.0), id, files, size))
+        .collect();
+
+    match storage_state.sort {
+        StorageSort::Size => sizes.sort_by(|a, b| b.3.cmp(&a.3).then_with(|| a.0.cmp(&b.0))),
+        StorageSort::Name => sizes.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()).then_with(|| a.1.cmp(&b.1))),
+    }
+
+    let total: u64 = sizes.iter().map(|x| x.3).sum();
+
+    storage_state.modal.show(|ui| {
+        storage_state.modal.title(ui, tr("storage.title"));
+
+        storage_state.modal.frame(ui, |ui| {
+            ui.label(tr_args("storage.total", &[
+                ("size", format_size(total)),
+                ("count", sizes.len().to_string())
+            ]));
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("storage_scroll")
+                .max_height(350.0)
+                .show(ui, |ui| {
+                    Grid::new("storage_grid")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            // Clicking column headers changes sorting
+                            if ui.selectable_label(storage_state.sort == StorageSort::Name, RichText::new(tr("storage.name")).strong()).clicked() {
+                                storage_state.sort = StorageSort::Name;
+                            }
+
+                            ui.label(RichText::new(tr("storage.files")).strong());
+
+                            if ui.selectable_label(storage_state.sort == StorageSort::Size, RichText::new(tr("storage.size")).strong()).clicked() {
+                                storage_state.sort = StorageSort::Size;
+                            }
+
+                            ui.end_row();
+
+                            for (name, (_, version), files, size) in &sizes {
+                                ui.label(format!("{} v{}", name, version));
+                                ui.label(files.to_string());
+                                ui.label(format_size(*size));
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+
+        storage_state.modal.buttons(ui, |ui| {
+            storage_state.modal.button(ui, tr("storage.close"));
+        });
+    });
+}
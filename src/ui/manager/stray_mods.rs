@@ -0,0 +1,97 @@
+use std::path::Path;
+use eframe::egui::{Context, ScrollArea, Spinner, Ui};
+use egui_modal::Modal;
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::install::StrayMod;
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+
+pub struct StrayModsState {
+    modal: Modal,
+    /// None while the scan is running
+    strays: Option<Vec<StrayMod>>
+}
+
+impl StrayModsState {
+    pub(crate) fn from_context(ctx: &Context) -> Self {
+        Self {
+            modal: Modal::new(ctx, "stray_mods_modal"),
+            strays: None,
+        }
+    }
+
+    pub fn set_strays(&mut self, strays: Vec<StrayMod>) {
+        self.strays = Some(strays);
+    }
+}
+
+/// Scanning whole game folder takes a while, so it only happens when asked for
+pub fn deep_scan_button(stray_mods_state: &mut StrayModsState, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    if ui.button(tr("stray_mods.open")).on_hover_text(tr("stray_mods.hover")).clicked() {
+        stray_mods_state.strays = None;
+        stray_mods_state.modal.open();
+        handle_error(command.blocking_send(ManagerCommand::FindStrayMods), toasts);
+    }
+}
+
+pub fn stray_mods_modal(state: &mut UIManagerState, neos_folder: &Path, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let stray_mods_state = &mut state.stray_mods_state;
+    let global_mods = &state.manifest_mods;
+
+    if !stray_mods_state.modal.is_open() {
+        return;
+    }
+
+    stray_mods_state.modal.show(|ui| {
+        stray_mods_state.modal.title(ui, tr("stray_mods.title"));
+
+        stray_mods_state.modal.frame(ui, |ui| {
+            let Some(strays) = &stray_mods_state.strays else {
+                ui.horizontal(|ui| {
+                    ui.add(Spinner::new());
+                    ui.label(tr("stray_mods.scanning"));
+                });
+                return;
+            };
+
+            if strays.is_empty() {
+                ui.label(tr("stray_mods.none"));
+                return;
+            }
+
+            ui.label(tr_args("stray_mods.found", &[("count", strays.len().to_string())]));
+
+            ui.add_space(5.0);
+
+            ScrollArea::vertical()
+                .id_source("stray_mods_scroll")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for stray in strays {
+                        let path = stray.file_path.strip_prefix(neos_folder).unwrap_or(&stray.file_path);
+
+                        ui.label(format!("{} v{}", mod_name(global_mods, &stray.mod_id), stray.version));
+                        ui.small(tr_args("stray_mods.location", &[
+                            ("path", path.to_string_lossy().to_string()),
+                            ("expected", stray.expected_location.to_string_lossy().to_string())
+                        ]));
+                        ui.add_space(3.0);
+                    }
+                });
+        });
+
+        stray_mods_state.modal.buttons(ui, |ui| {
+            if let Some(strays) = stray_mods_state.strays.as_ref().filter(|x| !x.is_empty()) {
+                if stray_mods_state.modal.suggested_button(ui, tr("stray_mods.move")).clicked() {
+                    handle_error(command.blocking_send(ManagerCommand::MoveStrayMods(strays.clone())), toasts);
+                }
+            }
+
+            stray_mods_state.modal.button(ui, tr("stray_mods.close"));
+        });
+    });
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use eframe::egui::{Align2, Button, Context, Id, LayerId, Order, Rect, Stroke, vec2, Window};
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::config::Config;
+use crate::locale::tr;
+use crate::manager::ManagerCommand;
+use crate::ui::manager::{ManagerTabs, UIManagerState};
+use crate::utils::handle_error;
+
+struct TourStep {
+    tab: ManagerTabs,
+    highlight: Option<ManagerTabs>,
+    title: &'static str,
+    text: &'static str
+}
+
+const TOUR_STEPS: [TourStep; 5] = [
+    TourStep {
+        tab: ManagerTabs::Launcher,
+        highlight: Some(ManagerTabs::Launcher),
+        title: "tour.launcher.title",
+        text: "tour.launcher.text",
+    },
+    TourStep {
+        tab: ManagerTabs::InstalledMods,
+        highlight: Some(ManagerTabs::InstalledMods),
+        title: "tour.installed_mods.title",
+        text: "tour.installed_mods.text",
+    },
+    TourStep {
+        tab: ManagerTabs::InstalledMods,
+        highlight: None,
+        title: "tour.enable_toggle.title",
+        text: "tour.enable_toggle.text",
+    },
+    TourStep {
+        tab: ManagerTabs::Updates,
+        highlight: Some(ManagerTabs::Updates),
+        title: "tour.updates.title",
+        text: "tour.updates.text",
+    },
+    TourStep {
+        tab: ManagerTabs::GetMods,
+        highlight: Some(ManagerTabs::GetMods),
+        title: "tour.get_mods.title",
+        text: "tour.get_mods.text",
+    },
+];
+
+#[derive(Default)]
+pub struct TourState {
+    step: Option<usize>,
+    shown_step: Option<usize>,
+    pub(crate) tab_rects: HashMap<ManagerTabs, Rect>
+}
+
+impl TourState {
+    pub fn new(active: bool) -> Self {
+        Self {
+            step: if active { Some(0) } else { None },
+            shown_step: None,
+            tab_rects: Default::default(),
+        }
+    }
+}
+
+enum TourAction {
+    Back,
+    Next,
+    Finish
+}
+
+pub fn tour_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ctx: &Context, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let tour_state = &mut state.tour_state;
+
+    let Some(step_index) = tour_state.step else {
+        return;
+    };
+
+    let step = &TOUR_STEPS[step_index];
+
+    // Only switching tabs when step changes, so user is still free to look around
+    if tour_state.shown_step != Some(step_index) {
+        tour_state.shown_step = Some(step_index);
+        state.current_tab = step.tab;
+    }
+
+    if let Some(rect) = step.highlight.and_then(|x| tour_state.tab_rects.get(&x)) {
+        ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("tour_highlight")))
+            .rect_stroke(rect.expand(3.0), 4.0, Stroke::new(2.0, ctx.style().visuals.selection.stroke.color));
+    }
+
+    let is_last = step_index + 1 >= TOUR_STEPS.len();
+    let mut action = None;
+
+    Window::new(tr("tour.title"))
+        .id(Id::new("tour_window"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_BOTTOM, vec2(0.0, -20.0))
+        .show(ctx, |ui| {
+            ui.set_max_width(400.0);
+
+            ui.heading(tr(step.title));
+            ui.label(tr(step.text));
+
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                ui.small(format!("{}/{}", step_index + 1, TOUR_STEPS.len()));
+
+                if ui.add_enabled(step_index > 0, Button::new(tr("tour.back"))).clicked() {
+                    action = Some(TourAction::Back);
+                }
+
+                if ui.button(if is_last { tr("tour.finish") } else { tr("tour.next") }).clicked() {
+                    action = Some(if is_last { TourAction::Finish } else { TourAction::Next });
+                }
+
+                if ui.button(tr("tour.skip")).clicked() {
+                    action = Some(TourAction::Finish);
+                }
+            });
+        });
+
+    match action {
+        None => {}
+        Some(TourAction::Back) => {
+            tour_state.step = Some(step_index.saturating_sub(1));
+        }
+        Some(TourAction::Next) => {
+            tour_state.step = Some(step_index + 1);
+        }
+        Some(TourAction::Finish) => {
+            tour_state.step = None;
+
+            let mut config_str = config.load().as_ref().clone();
+            config_str.tour_completed = true;
+            config.swap(Arc::new(config_str));
+
+            handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use eframe::egui::{Align, Layout, RichText, Ui};
+use egui_toast::Toasts;
+use tokio::sync::mpsc::Sender;
+use crate::config::Config;
+use crate::install::{IDVersion, ModMap};
+use crate::locale::{tr, tr_args};
+use crate::manager::ManagerCommand;
+use crate::manifest::GlobalModList;
+use crate::resolver::available_updates;
+use crate::ui::manager::plan_preview::mod_name;
+use crate::ui::manager::UIManagerState;
+use crate::utils::handle_error;
+use crate::version::{Version, VersionReq};
+
+pub struct UpdatesState {
+    /// Installed mods with newer versions that weren't skipped, along with the newest version
+    pub available: Vec<(IDVersion, Version)>,
+    /// Mod map, manifests or update settings changed since updates were last looked for
+    outdated: bool
+}
+
+impl Default for UpdatesState {
+    fn default() -> Self {
+        Self {
+            available: vec![],
+            outdated: true,
+        }
+    }
+}
+
+impl UpdatesState {
+    /// Updates are looked for again on next refresh
+    pub fn invalidate(&mut self) {
+        self.outdated = true;
+    }
+
+    /// Looks for updates if something changed since the last time
+    pub fn refresh(&mut self, mod_map: &ModMap, global_mods: &GlobalModList, config: &Config) {
+        if !self.outdated {
+            return;
+        }
+
+        self.outdated = false;
+        self.available = available_updates(mod_map, &global_mods.mod_list.load(), |x| config.update_channel_for(x));
+        self.available.retain(|((mod_id, _), newest)| !config.is_update_skipped(mod_id, newest));
+    }
+}
+
+fn skip_update(updates_state: &mut UpdatesState, config: &Arc<ArcSwap<Config>>, mod_id: &str, version: Option<&Version>, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    let mut config_str = config.load().as_ref().clone();
+
+    match version {
+        Some(version) => config_str.skipped_updates.insert(mod_id.to_string(), version.clone()),
+        None => config_str.skipped_updates.remove(mod_id)
+    };
+
+    config.swap(Arc::new(config_str));
+    handle_error(command.blocking_send(ManagerCommand::SaveConfig), toasts);
+    updates_state.invalidate();
+}
+
+pub fn updates_ui(state: &mut UIManagerState, config: &Arc<ArcSwap<Config>>, ui: &mut Ui, toasts: &mut Toasts, command: &Sender<ManagerCommand>) {
+    ui.heading(tr("tabs.updates"));
+
+    ui.add_space(5.0);
+
+    if state.updates_state.available.is_empty() {
+        ui.label(RichText::new(tr("updates.up_to_date")).weak());
+    }
+
+    let mut skip = None;
+
+    for ((mod_id, installed), newest) in &state.updates_state.available {
+        ui.horizontal(|ui| {
+            ui.label(mod_name(&state.manifest_mods, mod_id));
+            ui.small(format!("v{} → v{}", installed, newest));
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui.button(tr_args("updates.skip", &[("version", newest.to_string())])).clicked() {
+                    skip = Some((mod_id.clone(), newest.clone()));
+                }
+
+                if ui.button(tr("updates.update")).clicked() {
+                    handle_error(command.blocking_send(ManagerCommand::InstallMod {
+                        guid: mod_id.clone(),
+                        version_req: VersionReq::at_least(newest),
+                    }), toasts);
+                }
+            });
+        });
+    }
+
+    if let Some((mod_id, version)) = skip {
+        skip_update(&mut state.updates_state, config, &mod_id, Some(&version), toasts, command);
+    }
+
+    let mut skipped: Vec<(String, Version)> = config.load().skipped_updates.iter()
+        .map(|(mod_id, version)| (mod_id.clone(), version.clone()))
+        .collect();
+
+    if skipped.is_empty() {
+        return;
+    }
+
+    skipped.sort_by_cached_key(|(mod_id, _)| mod_name(&state.manifest_mods, mod_id).to_lowercase());
+
+    ui.add_space(10.0);
+
+    ui.collapsing(tr("updates.skipped"), |ui| {
+        for (mod_id, version) in &skipped {
+            ui.horizontal(|ui| {
+                ui.label(tr_args("updates.skipped_version", &[
+                    ("name", mod_name(&state.manifest_mods, mod_id)),
+                    ("version", version.to_string())
+                ]));
+
+                if ui.small_button(tr("updates.unskip")).clicked() {
+                    skip_update(&mut state.updates_state, config, mod_id, None, toasts, command);
+                }
+            });
+        }
+    });
+}
@@ -4,6 +4,7 @@ use std::io;
 use std::ops::{Add, Mul, Sub};
 use std::path::{Component, Path, PathBuf, StripPrefixError};
 use std::str::FromStr;
+use std::time::Duration;
 use async_recursion::async_recursion;
 use eframe::egui::{Color32, Id, InnerResponse, Rect, Response, SelectableLabel, TextEdit, Ui, Vec2, Widget, WidgetText};
 use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
@@ -11,6 +12,8 @@ use sha2::{Sha256, Digest};
 use sha2::digest::FixedOutput;
 use tokio::fs;
 use tokio::fs::File;
+use tokio::time::{Instant, sleep};
+use crate::locale::{tr, tr_args};
 
 #[inline]
 pub fn place_in_middle<R>(ui: &mut Ui, desired_size: Vec2, add_contents: impl FnOnce(&mut Ui) -> R) -> InnerResponse<R> {
@@ -157,9 +160,8 @@ pub async fn get_all_files_of_extension(location: PathBuf, extensions: &[&str])
             files.extend(get_all_files_of_extension(entry_path.clone(), extensions).await?);
         } else {
             if let Some(extension) = entry_path.extension() {
-                let lossy_extension = extension.to_string_lossy().to_string();
-
-                if extensions.iter().any(|x| lossy_extension == *x) {
+                // Windows doesn't care about case, so neither should we
+                if extensions.iter().any(|x| extension.eq_ignore_ascii_case(x)) {
                     files.push(entry_path);
                 }
             }
@@ -169,14 +171,176 @@ pub async fn get_all_files_of_extension(location: PathBuf, extensions: &[&str])
     Ok(files)
 }
 
+/// Total size of files in the folder and its subfolders, links aren't followed
+#[async_recursion::async_recursion]
+pub async fn folder_size(location: PathBuf) -> Result<u64, io::Error> {
+    let mut size = 0;
+
+    let mut directory = fs::read_dir(location).await?;
+
+    while let Some(entry) = directory.next_entry().await? {
+        let entry_type = entry.file_type().await?;
+
+        if entry_type.is_dir() {
+            size += folder_size(entry.path()).await?;
+        } else if entry_type.is_file() {
+            size += entry.metadata().await?.len();
+        }
+    }
+
+    Ok(size)
+}
+
+/// Deletes everything inside of the folder, but keeps the folder itself
+pub async fn clear_folder(location: &Path) -> Result<(), io::Error> {
+    let mut directory = fs::read_dir(location).await?;
+
+    while let Some(entry) = directory.next_entry().await? {
+        if entry.file_type().await?.is_dir() {
+            fs::remove_dir_all(entry.path()).await?;
+        } else {
+            fs::remove_file(entry.path()).await?;
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn sha256_file(path: impl AsRef<Path>) -> Result<String, io::Error> {
     let data = fs::read(path).await?;
 
+    Ok(sha256_bytes(&data))
+}
+
+pub fn sha256_bytes(data: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(data);
     let hash = hasher.finalize();
 
-    Ok(hex::encode(hash))
+    hex::encode(hash)
+}
+
+/// Token bucket for limiting download speed, holds at most a second worth of bytes
+pub struct TokenBucket {
+    bytes_per_second: f64,
+    tokens: f64,
+    last_refill: Instant
+}
+
+impl TokenBucket {
+    pub fn new(bytes_per_second: u64) -> Self {
+        let bytes_per_second = bytes_per_second.max(1) as f64;
+
+        Self {
+            bytes_per_second,
+            tokens: bytes_per_second,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes tokens for the amount of bytes, returns how long to wait before they can be used
+    pub fn take_at(&mut self, amount: usize, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+
+        self.tokens = (self.tokens + elapsed * self.bytes_per_second).min(self.bytes_per_second);
+        self.tokens -= amount as f64;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.bytes_per_second)
+        }
+    }
+
+    pub async fn throttle(&mut self, amount: usize) {
+        let wait = self.take_at(amount, Instant::now());
+
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+}
+
+/// Size in bytes as something readable, like "1.5 MB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Seconds since unix epoch
+pub fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |x| x.as_secs())
+}
+
+/// How long ago the timestamp was, timestamp is seconds since unix epoch
+pub fn time_ago(timestamp: u64) -> String {
+    let seconds = unix_timestamp().saturating_sub(timestamp);
+
+    match seconds {
+        0..=59 => tr("time.just_now"),
+        60..=3599 => tr_args("time.minutes_ago", &[("count", (seconds / 60).to_string())]),
+        3600..=86399 => tr_args("time.hours_ago", &[("count", (seconds / 3600).to_string())]),
+        86400..=1209599 => tr_args("time.days_ago", &[("count", (seconds / 86400).to_string())]),
+        _ => tr_args("time.weeks_ago", &[("count", (seconds / 604800).to_string())])
+    }
+}
+
+/// Version .NET assembly reports in its version resource, "Assembly Version" string is preferred over file version
+pub fn detect_assembly_version(data: &[u8]) -> Option<String> {
+    if !data.starts_with(b"MZ") {
+        return None;
+    }
+
+    let utf16 = |text: &str| text.encode_utf16().chain([0]).flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>();
+    let is_version = |x: &str| !x.is_empty() && x.chars().all(|x| x.is_ascii_digit() || x == '.');
+
+    // Value of a version info string goes right after its null terminated key, aligned to 4 bytes
+    let key = utf16("Assembly Version");
+
+    if let Some(position) = data.windows(key.len()).position(|x| x == key.as_slice()) {
+        let mut offset = position + key.len();
+
+        while offset % 4 != 0 && data.get(offset..offset + 2) == Some(&[0, 0]) {
+            offset += 2;
+        }
+
+        let units: Vec<u16> = data.get(offset..)?
+            .chunks_exact(2)
+            .map(|x| u16::from_le_bytes([x[0], x[1]]))
+            .take_while(|x| *x != 0)
+            .take(64)
+            .collect();
+
+        let version = String::from_utf16_lossy(&units);
+
+        if is_version(&version) {
+            return Some(version);
+        }
+    }
+
+    // VS_FIXEDFILEINFO starts with this signature, followed by struct version and file version
+    let signature = 0xFEEF04BDu32.to_le_bytes();
+    let position = data.windows(4).position(|x| x == signature)?;
+    let read = |offset: usize| Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?));
+
+    let most = read(position + 8)?;
+    let least = read(position + 12)?;
+
+    Some(format!("{}.{}.{}.{}", most >> 16, most & 0xFFFF, least >> 16, least & 0xFFFF))
 }
 
 pub fn append_relative_path(target: &mut PathBuf, path: impl AsRef<Path>) -> Result<(), StripPrefixError> {
@@ -191,6 +355,51 @@ pub fn append_relative_path(target: &mut PathBuf, path: impl AsRef<Path>) -> Res
     Ok(())
 }
 
+/// Checks that path coming from a manifest can't point outside of the folder it gets appended to.
+/// Both separators are checked since manifests are shared between platforms
+pub fn is_safe_relative_path(path: &Path) -> bool {
+    let path_str = path.to_string_lossy();
+
+    let segments_safe = path_str.split(['/', '\\'])
+        .all(|x| x != ".." && !x.contains(':'));
+
+    let components_safe = path.components()
+        .all(|x| matches!(x, Component::RootDir | Component::CurDir | Component::Normal(_)));
+
+    segments_safe && components_safe
+}
+
+/// File name has to be a single path component, so it can't move the file into another folder
+pub fn is_safe_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\', ':', '\0'])
+}
+
+/// Checks if path stays inside of root after resolving symlinks, path itself doesn't have to exist yet
+pub fn is_within(root: &Path, path: &Path) -> Result<bool, io::Error> {
+    let root = root.canonicalize()?;
+
+    // Closest parent that exists is what links could be resolved for
+    let mut existing = path;
+
+    while !existing.exists() {
+        // ".." in the part that doesn't exist yet can't be resolved
+        if existing.file_name().is_none() {
+            return Ok(false);
+        }
+
+        let Some(parent) = existing.parent() else {
+            return Ok(false);
+        };
+
+        existing = parent;
+    }
+
+    Ok(existing.canonicalize()?.starts_with(root))
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct Colorf32 {
     r: f32,
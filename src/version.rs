@@ -1,12 +1,13 @@
 use std::cmp::Ordering;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::num::ParseIntError;
 use std::str::FromStr;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Visitor;
 
-#[derive(Debug, Hash, Clone)]
+#[derive(Debug, Clone)]
 pub struct Version {
     major: u16,
     minor: Option<u16>,
@@ -103,6 +104,21 @@ impl Version {
     pub fn major(&self) -> u16 {
         self.major
     }
+
+    /// Same version with missing parts filled with zeros, so comparators treat it as exact
+    fn filled(&self) -> Self {
+        Self {
+            major: self.major,
+            minor: Some(self.minor()),
+            patch: Some(self.patch()),
+            revision: Some(self.revision()),
+            suffix: self.suffix.clone(),
+        }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.suffix.is_some()
+    }
 }
 
 impl Default for Version {
@@ -147,7 +163,18 @@ impl PartialEq for Version {
             && self.minor.unwrap_or_else(|| 0) == other.minor.unwrap_or_else(|| 0)
             && self.patch.unwrap_or_else(|| 0) == other.patch.unwrap_or_else(|| 0)
             && self.revision.unwrap_or_else(|| 0) == other.revision.unwrap_or_else(|| 0)
-            && self.suffix == other.suffix
+            && compare_suffixes(&self.suffix, &other.suffix) == Ordering::Equal
+    }
+}
+
+/// Hashes what equality looks at, so versions that are equal but written differently land in the same spot of a map
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.major.hash(state);
+        self.minor.unwrap_or(0).hash(state);
+        self.patch.unwrap_or(0).hash(state);
+        self.revision.unwrap_or(0).hash(state);
+        self.suffix.as_deref().map(split_suffix).hash(state);
     }
 }
 
@@ -186,8 +213,67 @@ impl Ord for Version {
             return lhs_revision.cmp(&rhs_revision)
         }
 
-        self.suffix.cmp(&other.suffix)
+        compare_suffixes(&self.suffix, &other.suffix)
+    }
+}
+
+/// Piece of the suffix, numbers are compared as numbers and everything else as text
+#[derive(Debug, Eq, PartialEq, Hash)]
+enum SuffixPart<'a> {
+    Numeric(u64),
+    Text(&'a str)
+}
+
+fn split_suffix(suffix: &str) -> Vec<SuffixPart> {
+    let mut parts = vec![];
+
+    for identifier in suffix.split(|c: char| c == '.' || c == '-').filter(|x| !x.is_empty()) {
+        let mut start = 0;
+        let mut chars = identifier.char_indices().peekable();
+
+        // Splitting on digit/non-digit boundaries, so beta10 becomes beta and 10
+        while let Some((index, char)) = chars.next() {
+            let boundary = chars.peek().map_or(true, |(_, next)| next.is_ascii_digit() != char.is_ascii_digit());
+
+            if boundary {
+                let piece = &identifier[start..index + char.len_utf8()];
+
+                parts.push(match piece.parse::<u64>() {
+                    Ok(number) => SuffixPart::Numeric(number),
+                    Err(_) => SuffixPart::Text(piece)
+                });
+
+                start = index + char.len_utf8();
+            }
+        }
+    }
+
+    parts
+}
+
+/// Prerelease style ordering, version without a suffix is newer than the one with it
+fn compare_suffixes(lhs: &Option<String>, rhs: &Option<String>) -> Ordering {
+    let (lhs, rhs) = match (lhs, rhs) {
+        (None, None) => return Ordering::Equal,
+        (None, Some(_)) => return Ordering::Greater,
+        (Some(_), None) => return Ordering::Less,
+        (Some(lhs), Some(rhs)) => (split_suffix(lhs), split_suffix(rhs))
+    };
+
+    for (lhs_part, rhs_part) in lhs.iter().zip(rhs.iter()) {
+        let ordering = match (lhs_part, rhs_part) {
+            (SuffixPart::Numeric(a), SuffixPart::Numeric(b)) => a.cmp(b),
+            (SuffixPart::Numeric(_), SuffixPart::Text(_)) => Ordering::Less,
+            (SuffixPart::Text(_), SuffixPart::Numeric(_)) => Ordering::Greater,
+            (SuffixPart::Text(a), SuffixPart::Text(b)) => a.cmp(b),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
     }
+
+    lhs.len().cmp(&rhs.len())
 }
 
 fn find_suffix(ver: &str) -> Option<usize> {
@@ -205,6 +291,9 @@ impl FromStr for Version {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (ver, suffix) = if let Some(index) = find_suffix(s) {
+            // Dot before the suffix, like in 1.0.0.beta, separates the suffix rather than starting an empty part
+            let index = if s[..index].ends_with('.') { index - 1 } else { index };
+
             (s[..index].to_string(), Some(s[index..].to_string()))
         } else {
             (s.to_string(), None)
@@ -218,9 +307,25 @@ impl FromStr for Version {
             return Err(VersionError::MissingMajorVersion)
         };
 
-        let minor = pieces.next().map_or_else(|| None, |v| v.parse().ok());
-        let patch = pieces.next().map_or_else(|| None, |v| v.parse().ok());
-        let revision = pieces.next().map_or_else(|| None, |v| v.parse().ok());
+        // Wildcards stand for missing parts, and can only be followed by more wildcards, 1.2.*.4 doesn't mean anything
+        let mut parts = [None; 3];
+        let mut seen_wildcard = false;
+
+        for (index, piece) in pieces.enumerate() {
+            if index >= parts.len() {
+                return Err(VersionError::TooManyParts(s.to_string()));
+            }
+
+            if piece == "*" {
+                seen_wildcard = true;
+            } else if seen_wildcard {
+                return Err(VersionError::InvalidWildcard(s.to_string()));
+            } else {
+                parts[index] = Some(piece.parse::<u16>()?);
+            }
+        }
+
+        let [minor, patch, revision] = parts;
 
         Ok(Self {
             major,
@@ -232,35 +337,274 @@ impl FromStr for Version {
     }
 }
 
+/// Groups are separated with `||` and match if any of them matches,
+/// comparators inside of a group are separated with commas or spaces and all of them have to match
 #[derive(Debug, Hash, Clone, Eq, PartialEq)]
 pub struct VersionReq {
-    comparators: Vec<Comparator>
+    groups: Vec<Vec<Comparator>>
 }
 
 impl VersionReq {
     pub fn matches(&self, version: &Version) -> bool {
-        self.comparators.iter()
-            .all(|x| x.matches(version))
+        self.groups.iter()
+            .any(|group| group.iter().all(|x| x.matches(version)))
+    }
+
+    /// Matches the version and anything newer, prereleases included
+    pub fn at_least(version: &Version) -> Self {
+        Self {
+            groups: vec![vec![Comparator {
+                version: version.clone(),
+                op: VersionOp::GreaterEq,
+            }]]
+        }
+    }
+
+    /// Matches only this version, missing parts are filled with zeros so nothing like 1.2.3.1 matches 1.2.3
+    pub fn exact(version: &Version) -> Self {
+        Self {
+            groups: vec![vec![Comparator {
+                version: version.filled(),
+                op: VersionOp::Exact,
+            }]]
+        }
+    }
+
+    /// Matches versions that both requirements match, every group of one is paired with every group of the other
+    pub fn intersect(&self, other: &VersionReq) -> VersionReq {
+        let mut groups = vec![];
+
+        for lhs in &self.groups {
+            for rhs in &other.groups {
+                let mut group = lhs.clone();
+                group.extend(rhs.iter().filter(|x| !lhs.contains(x)).cloned());
+
+                if !groups.contains(&group) {
+                    groups.push(group);
+                }
+            }
+        }
+
+        Self {
+            groups
+        }
+    }
+
+    /// Same requirement with the least comparators it can be written with, overlapping groups are joined together.
+    /// Comparators that already cover a whole group are kept as they are, so ^1.2 doesn't turn into >=1.2, <2
+    pub fn simplify(&self) -> SimplifiedReq {
+        let originals: Vec<(&Comparator, VersionRange)> = self.groups.iter()
+            .flatten()
+            .map(|x| (x, x.range()))
+            .collect();
+
+        let mut ranges: Vec<VersionRange> = self.groups.iter()
+            .map(|group| group.iter()
+                .map(|x| x.range())
+                .fold(VersionRange::any(), |a, b| a.intersect(&b)))
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        if ranges.is_empty() {
+            return SimplifiedReq::Unsatisfiable;
+        }
+
+        ranges.sort_by(|a, b| compare_lower(&a.lower, &b.lower));
+
+        let mut merged: Vec<VersionRange> = vec![];
+
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if last.touches(&range) => {
+                    if compare_upper(&range.upper, &last.upper) == Ordering::Greater {
+                        last.upper = range.upper;
+                    }
+                }
+                _ => merged.push(range)
+            }
+        }
+
+        SimplifiedReq::Satisfiable(Self {
+            groups: merged.into_iter()
+                .map(|range| match originals.iter().find(|(_, x)| *x == range) {
+                    Some((comparator, _)) => vec![(*comparator).clone()],
+                    None => range.comparators()
+                })
+                .collect()
+        })
+    }
+}
+
+/// Requirement after simplifying, or nothing if no version can match it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SimplifiedReq {
+    Satisfiable(VersionReq),
+    Unsatisfiable
+}
+
+/// Edge of a range, version itself is in the range only if it's inclusive
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Bound {
+    version: Version,
+    inclusive: bool
+}
+
+impl Bound {
+    fn inclusive(version: Version) -> Option<Self> {
+        Some(Self { version, inclusive: true })
+    }
+
+    fn exclusive(version: Version) -> Option<Self> {
+        Some(Self { version, inclusive: false })
+    }
+}
+
+/// Lower bound that lets in less goes later
+fn compare_lower(a: &Option<Bound>, b: &Option<Bound>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(a), Some(b)) => a.version.cmp(&b.version).then(b.inclusive.cmp(&a.inclusive))
+    }
+}
+
+/// Upper bound that lets in more goes later
+fn compare_upper(a: &Option<Bound>, b: &Option<Bound>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.version.cmp(&b.version).then(a.inclusive.cmp(&b.inclusive))
+    }
+}
+
+/// Versions between the bounds, every comparator matches exactly one of these, None means there's no limit
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct VersionRange {
+    lower: Option<Bound>,
+    upper: Option<Bound>
+}
+
+impl VersionRange {
+    fn any() -> Self {
+        Self { lower: None, upper: None }
+    }
+
+    /// From lower version up to, but not including, upper one
+    fn between(lower: Version, upper: Version) -> Self {
+        Self { lower: Bound::inclusive(lower), upper: Bound::exclusive(upper) }
+    }
+
+    fn intersect(&self, other: &VersionRange) -> VersionRange {
+        Self {
+            lower: if compare_lower(&self.lower, &other.lower) == Ordering::Less { other.lower.clone() } else { self.lower.clone() },
+            upper: if compare_upper(&self.upper, &other.upper) == Ordering::Greater { other.upper.clone() } else { self.upper.clone() },
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match (&self.lower, &self.upper) {
+            (Some(lower), Some(upper)) => match lower.version.cmp(&upper.version) {
+                Ordering::Greater => true,
+                Ordering::Equal => !(lower.inclusive && upper.inclusive),
+                Ordering::Less => false
+            },
+            _ => false
+        }
+    }
+
+    /// Other range starts before this one ends or right where it ends, so they can be joined. Other one can't start earlier
+    fn touches(&self, other: &VersionRange) -> bool {
+        match (&self.upper, &other.lower) {
+            (None, _) | (_, None) => true,
+            (Some(upper), Some(lower)) => match lower.version.cmp(&upper.version) {
+                Ordering::Less => true,
+                Ordering::Equal => upper.inclusive || lower.inclusive,
+                Ordering::Greater => false
+            }
+        }
+    }
+
+    fn comparators(&self) -> Vec<Comparator> {
+        let comparator = |version: Version, op: VersionOp| Comparator { version, op };
+
+        match (&self.lower, &self.upper) {
+            (None, None) => vec![comparator(Default::default(), VersionOp::WildcardAny)],
+            (Some(lower), Some(upper)) if lower.version == upper.version => vec![comparator(lower.version.filled(), VersionOp::Exact)],
+            (lower, upper) => {
+                let lower = lower.as_ref().map(|x| match x.inclusive {
+                    true => comparator(x.version.clone(), VersionOp::GreaterEq),
+                    false => comparator(x.version.filled(), VersionOp::Greater)
+                });
+
+                let upper = upper.as_ref().map(|x| match x.inclusive {
+                    true => comparator(x.version.filled(), VersionOp::LessEq),
+                    false => comparator(x.version.clone(), VersionOp::Less)
+                });
+
+                lower.into_iter().chain(upper).collect()
+            }
+        }
     }
 }
 
 impl Display for VersionReq {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.comparators.iter()
-            .map(|x| x.to_string())
+        write!(f, "{}", self.groups.iter()
+            .map(|group| group.iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(", "))
             .collect::<Vec<String>>()
-            .join(", "))
+            .join(" || "))
     }
 }
 
+/// Splits comparator group into separate comparators, operators separated from their version with spaces are glued back
+fn split_comparators(group: &str) -> Vec<String> {
+    let mut comparators = vec![];
+
+    for piece in group.split(',') {
+        let mut pending_op = String::new();
+
+        for token in piece.split_whitespace() {
+            if token.chars().all(|x| "=<>~^".contains(x)) {
+                pending_op.push_str(token);
+            } else {
+                comparators.push(format!("{}{}", pending_op, token));
+                pending_op.clear();
+            }
+        }
+
+        if !pending_op.is_empty() {
+            comparators.push(pending_op);
+        }
+    }
+
+    comparators
+}
+
 impl FromStr for VersionReq {
     type Err = VersionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let groups = s.split("||")
+            .map(|group| {
+                let comparators = split_comparators(group);
+
+                if comparators.is_empty() {
+                    return Err(VersionError::EmptyRequirement);
+                }
+
+                comparators.iter()
+                    .map(|x| Comparator::from_str(x))
+                    .collect::<Result<Vec<Comparator>, Self::Err>>()
+            })
+            .collect::<Result<Vec<Vec<Comparator>>, Self::Err>>()?;
+
         Ok(Self {
-            comparators: s.split(",")
-                .map(|x| Comparator::from_str(x.trim()))
-                .collect::<Result<Vec<Comparator>, Self::Err>>()?
+            groups
         })
     }
 }
@@ -332,7 +676,7 @@ impl Comparator {
             VersionOp::LessEq => {
                 match () {
                     _ if self.version.has_revision() => {
-                        version < &self.version
+                        version <= &self.version
                     }
 
                     _ if self.version.has_patch() => {
@@ -380,18 +724,19 @@ impl Comparator {
             }
 
             VersionOp::Caret => {
+                // Missing parts count as allowed to change, so ^0.0 is same as =0.0 and not =0.0.0.0
                 match () {
-                    _ if self.version.major() > 0 => {
+                    _ if self.version.major() > 0 || !self.version.has_minor() => {
                         version >= &self.version
                             && version < &Version::from_major(self.version.major() + 1)
                     }
 
-                    _ if self.version.minor() > 0 => {
+                    _ if self.version.minor() > 0 || !self.version.has_patch() => {
                         version >= &self.version
                             && version < &Version::from_minor(0, self.version.minor() + 1)
                     }
 
-                    _ if self.version.patch() > 0 => {
+                    _ if self.version.patch() > 0 || !self.version.has_revision() => {
                         version >= &self.version
                             && version < &Version::from_patch(0, 0, self.version.patch() + 1)
                     }
@@ -405,13 +750,84 @@ impl Comparator {
             VersionOp::WildcardAny => true,
         }
     }
+
+    /// Versions the comparator matches, has to agree with matches
+    fn range(&self) -> VersionRange {
+        let version = &self.version;
+        let (major, minor, patch) = (version.major, version.minor(), version.patch());
+
+        // Version right after everything that starts with the given parts, like 1.3 for 1.2
+        let after_given = || match () {
+            _ if version.has_patch() => Version::from_patch(major, minor, patch + 1),
+            _ if version.has_minor() => Version::from_minor(major, minor + 1),
+            _ => Version::from_major(major + 1)
+        };
+
+        // Everything that starts with the given parts
+        let given_parts = || match () {
+            _ if version.has_patch() => VersionRange::between(Version::from_patch(major, minor, patch), after_given()),
+            _ if version.has_minor() => VersionRange::between(Version::from_minor(major, minor), after_given()),
+            _ => VersionRange::between(Version::from_major(major), after_given())
+        };
+
+        match self.op {
+            VersionOp::Exact | VersionOp::Wildcard => {
+                if version.has_revision() {
+                    VersionRange { lower: Bound::inclusive(version.clone()), upper: Bound::inclusive(version.clone()) }
+                } else {
+                    given_parts()
+                }
+            }
+
+            VersionOp::Greater => {
+                if version.has_revision() {
+                    VersionRange { lower: Bound::exclusive(version.clone()), upper: None }
+                } else {
+                    VersionRange { lower: Bound::inclusive(after_given()), upper: None }
+                }
+            }
+
+            VersionOp::GreaterEq => VersionRange { lower: Bound::inclusive(version.clone()), upper: None },
+
+            VersionOp::Less => VersionRange { lower: None, upper: Bound::exclusive(version.clone()) },
+
+            VersionOp::LessEq => {
+                if version.has_revision() {
+                    VersionRange { lower: None, upper: Bound::inclusive(version.clone()) }
+                } else {
+                    VersionRange { lower: None, upper: Bound::exclusive(after_given()) }
+                }
+            }
+
+            VersionOp::Tilde => {
+                match () {
+                    _ if version.has_revision() => VersionRange::between(Version::from_revision(major, minor, patch, version.revision()), Version::from_minor(major, minor + 1)),
+                    _ if version.has_patch() => VersionRange::between(Version::from_patch(major, minor, patch), Version::from_minor(major, minor + 1)),
+                    _ => given_parts()
+                }
+            }
+
+            VersionOp::Caret => {
+                match () {
+                    _ if major > 0 || !version.has_minor() => VersionRange::between(version.clone(), Version::from_major(major + 1)),
+                    _ if minor > 0 || !version.has_patch() => VersionRange::between(version.clone(), Version::from_minor(0, minor + 1)),
+                    _ if patch > 0 || !version.has_revision() => VersionRange::between(version.clone(), Version::from_patch(0, 0, patch + 1)),
+                    _ => VersionRange { lower: Bound::inclusive(version.clone()), upper: Bound::inclusive(version.clone()) }
+                }
+            }
+
+            VersionOp::WildcardAny => VersionRange::any(),
+        }
+    }
 }
 
 impl FromStr for Comparator {
     type Err = VersionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s == "*" {
+        let s = s.trim();
+
+        if s.split('.').all(|x| x == "*") {
             return Ok(Self {
                 version: Default::default(),
                 op: VersionOp::WildcardAny,
@@ -421,49 +837,49 @@ impl FromStr for Comparator {
         match () {
             _ if s.starts_with('=') => {
                 Ok(Self {
-                    version: s[1..].parse()?,
+                    version: s[1..].trim().parse()?,
                     op: VersionOp::Exact,
                 })
             }
 
             _ if s.starts_with(">=") => {
                 Ok(Self {
-                    version: s[2..].parse()?,
+                    version: s[2..].trim().parse()?,
                     op: VersionOp::GreaterEq,
                 })
             }
 
             _ if s.starts_with('>') => {
                 Ok(Self {
-                    version: s[1..].parse()?,
+                    version: s[1..].trim().parse()?,
                     op: VersionOp::Greater,
                 })
             }
 
             _ if s.starts_with("<=") => {
                 Ok(Self {
-                    version: s[2..].parse()?,
+                    version: s[2..].trim().parse()?,
                     op: VersionOp::LessEq,
                 })
             }
 
             _ if s.starts_with('<') => {
                 Ok(Self {
-                    version: s[1..].parse()?,
+                    version: s[1..].trim().parse()?,
                     op: VersionOp::Less,
                 })
             }
 
             _ if s.starts_with('~') => {
                 Ok(Self {
-                    version: s[1..].parse()?,
+                    version: s[1..].trim().parse()?,
                     op: VersionOp::Tilde,
                 })
             }
 
             _ if s.starts_with('^') => {
                 Ok(Self {
-                    version: s[1..].parse()?,
+                    version: s[1..].trim().parse()?,
                     op: VersionOp::Caret,
                 })
             }
@@ -553,9 +969,10 @@ pub enum VersionOp {
     /// - `A.I.P.*` - same as `=A.I.P`
     /// - `A.I.*` or `A.I.*.*` - same as `=A.I`
     /// - `A.*` or `A.*.*` or `A.*.*.*` - same as `=A`
+    /// - `A.I.*.R` and similar are rejected, wildcard can only be followed by wildcards
     Wildcard,
 
-    /// - `*` - same as `>=0.0.0.0`
+    /// - `*` or `*.*` - same as `>=0.0.0.0`
     WildcardAny,
 }
 
@@ -563,6 +980,12 @@ pub enum VersionOp {
 #[derive(Debug)]
 pub enum VersionError {
     MissingMajorVersion,
+    /// Wildcard is followed by a number, like `1.*.2`
+    InvalidWildcard(String),
+    /// More than major, minor, patch and revision, like `1.2.3.4.5`
+    TooManyParts(String),
+    /// Requirement or one of its `||` groups has no comparators
+    EmptyRequirement,
     ParseIntError(ParseIntError)
 }
 